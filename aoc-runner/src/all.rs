@@ -0,0 +1,205 @@
+use crate::args::Args;
+use crate::{meta, workspace};
+use aoclib::fmt::duration;
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// `aoc all [--year YYYY] [--example [K]] [--salted-hash SALT] [--jobs J] [--tag T,...]
+/// [--skip-slow]`: runs every day crate and prints their answers in day order.
+///
+/// `--tag` keeps only the days whose metadata (see [`meta::DayMeta`]) lists one of the
+/// comma-separated tags, and `--skip-slow` drops the days marked slow, so a change to
+/// one part of aoclib can be checked against just the solutions that use it.
+///
+/// The days are built once up front and then run `J` at a time, by default one per
+/// available core. Each day runs in its own process and stays single-threaded unless
+/// it parallelises itself. A day's output is buffered until it finishes and printed
+/// in one piece once every earlier day has been printed, so the log reads the same as
+/// a sequential run. A failing day is reported and the rest still run.
+pub fn all(args: &Args) -> Result<(), Box<dyn Error>> {
+    let year: Option<u32> = args.parse_value("--year")?;
+    let jobs = match args.parse_value::<usize>("--jobs")? {
+        Some(0) => return Err("Option '--jobs' needs at least one job".into()),
+        Some(jobs) => jobs,
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+
+    let root = workspace::root()?;
+    let names = select_days(&root, workspace::days(&root, year)?, args)?;
+    if names.is_empty() {
+        return Err("No day crates match the filters".into());
+    }
+    workspace::build_days(&root, &names)?;
+
+    let mut day_args = Vec::new();
+    if let Some(index) = crate::run::example_index(args)? {
+        day_args.push("--example".to_string());
+        day_args.push(index.to_string());
+    }
+    if let Some(salt) = crate::run::salted_hash(args)? {
+        day_args.push("--salted-hash".to_string());
+        day_args.push(salt.to_string());
+    }
+    let arg_refs: Vec<&str> = day_args.iter().map(String::as_str).collect();
+
+    let start = Instant::now();
+    let mut failed = Vec::new();
+    run_in_order(
+        names.len(),
+        jobs,
+        |i| {
+            let started = Instant::now();
+            let output = workspace::run_day(&root, &names[i], &arg_refs).map_err(|e| e.to_string());
+            (output, started.elapsed())
+        },
+        |i, (output, elapsed): (Result<String, String>, Duration)| {
+            println!("== {} ({}) ==", names[i], duration(elapsed));
+            match output {
+                Ok(output) => print!("{}", output),
+                Err(e) => {
+                    println!("{}", e);
+                    failed.push(names[i].as_str());
+                }
+            }
+        },
+    );
+    eprintln!(
+        "Ran {} day{} in {} with {} job{}",
+        names.len(),
+        if names.len() == 1 { "" } else { "s" },
+        duration(start.elapsed()),
+        jobs,
+        if jobs == 1 { "" } else { "s" }
+    );
+
+    if !failed.is_empty() {
+        return Err(format!("{} failed: {}", failed.len(), failed.join(", ")).into());
+    }
+    Ok(())
+}
+
+/// Keeps the days that pass the `--tag` and `--skip-slow` filters.
+fn select_days(
+    root: &std::path::Path,
+    names: Vec<String>,
+    args: &Args,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let tags: Option<Vec<&str>> = match args.value("--tag") {
+        Some(tags) if !tags.starts_with("--") => Some(tags.split(',').map(str::trim).collect()),
+        _ if args.flag("--tag") => return Err("Option '--tag' requires a value".into()),
+        _ => None,
+    };
+    let skip_slow = args.flag("--skip-slow");
+    if tags.is_none() && !skip_slow {
+        return Ok(names);
+    }
+
+    let mut selected = Vec::new();
+    for name in names {
+        let meta = meta::read(root, &name)?;
+        let tagged = tags.as_ref().is_none_or(|tags| meta.has_any_tag(tags));
+        if tagged && skip_slow && meta.slow {
+            eprintln!("Skipping {} (slow)", name);
+        } else if tagged {
+            selected.push(name);
+        }
+    }
+    Ok(selected)
+}
+
+/// Runs `work` for every index below `count` on `jobs` threads, handing each result
+/// to `report` on the calling thread in index order as soon as it and all earlier
+/// results are ready.
+fn run_in_order<T, W, R>(count: usize, jobs: usize, work: W, mut report: R)
+where
+    T: Send,
+    W: Fn(usize) -> T + Sync,
+    R: FnMut(usize, T),
+{
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(count) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= count || sender.send((i, work(i))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending: Vec<Option<T>> = (0..count).map(|_| None).collect();
+        let mut printed = 0;
+        for (i, result) in receiver {
+            pending[i] = Some(result);
+            while let Some(result) = pending.get_mut(printed).and_then(Option::take) {
+                report(printed, result);
+                printed += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_run_in_order_reports_in_index_order() {
+        // Earlier indices take longest, so they finish last
+        let finished = Mutex::new(Vec::new());
+        let mut reported = Vec::new();
+        run_in_order(
+            6,
+            3,
+            |i| {
+                thread::sleep(Duration::from_millis(10 * (6 - i) as u64));
+                finished.lock().unwrap().push(i);
+                i * i
+            },
+            |i, square| reported.push((i, square)),
+        );
+        assert_eq!(reported, (0..6).map(|i| (i, i * i)).collect::<Vec<_>>());
+        assert_ne!(finished.into_inner().unwrap(), (0..6).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_select_days() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap();
+        let names = workspace::days(root, Some(2025)).unwrap();
+        let select = |s: &str| {
+            select_days(
+                root,
+                names.clone(),
+                &Args::new(s.split_whitespace().map(str::to_string)),
+            )
+        };
+        assert_eq!(select("").unwrap(), names);
+        assert_eq!(select("--tag GRID").unwrap(), vec!["day04_2025"]);
+        assert_eq!(
+            select("--tag grid,math --skip-slow").unwrap(),
+            vec!["day02_2025", "day04_2025"]
+        );
+        assert!(select("--tag no-such-tag").unwrap().is_empty());
+        assert!(select("--tag").is_err());
+        assert!(select("--tag --skip-slow").is_err());
+    }
+
+    #[test]
+    fn test_run_in_order_edge_cases() {
+        let mut reported = Vec::new();
+        run_in_order(0, 4, |i| i, |i, _| reported.push(i));
+        assert!(reported.is_empty());
+        run_in_order(3, 10, |i| i, |i, _| reported.push(i));
+        assert_eq!(reported, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,113 @@
+use std::error::Error;
+use std::str::FromStr;
+
+/// Minimal command-line argument access for the runner's subcommands.
+///
+/// Options are written as `--name value` and switches as `--name`. Everything else is
+/// a positional argument.
+#[derive(Debug, Default)]
+pub struct Args {
+    raw: Vec<String>,
+}
+
+impl Args {
+    /// Wraps the arguments that follow the subcommand name.
+    pub fn new<I: IntoIterator<Item = String>>(args: I) -> Self {
+        Args {
+            raw: args.into_iter().collect(),
+        }
+    }
+
+    /// Returns true if the switch `name` is present.
+    pub fn flag(&self, name: &str) -> bool {
+        self.raw.iter().any(|arg| arg == name)
+    }
+
+    /// Returns the value following the option `name`, if present.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.raw
+            .iter()
+            .position(|arg| arg == name)
+            .and_then(|i| self.raw.get(i + 1))
+            .map(String::as_str)
+    }
+
+    /// Parses the value following the option `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the option is present without a value or the value fails
+    /// to parse.
+    pub fn parse_value<T>(&self, name: &str) -> Result<Option<T>, Box<dyn Error>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        if !self.flag(name) {
+            return Ok(None);
+        }
+        let value = self
+            .value(name)
+            .ok_or_else(|| format!("Option '{}' requires a value", name))?;
+        value
+            .parse()
+            .map(Some)
+            .map_err(|e| format!("Invalid value '{}' for '{}': {}", value, name, e).into())
+    }
+
+    /// Returns the positional arguments, skipping the values of the given options.
+    pub fn positional(&self, options: &[&str]) -> Vec<&str> {
+        let mut result = Vec::new();
+        let mut skip_next = false;
+        for arg in &self.raw {
+            if skip_next {
+                skip_next = false;
+            } else if options.contains(&arg.as_str()) {
+                skip_next = true;
+            } else if !arg.starts_with("--") {
+                result.push(arg.as_str());
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Args {
+        Args::new(s.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn test_flag() {
+        let args = args("--day 2 --example");
+        assert!(args.flag("--example"));
+        assert!(!args.flag("--bench"));
+    }
+
+    #[test]
+    fn test_value() {
+        let args = args("--day 2 --year 2025");
+        assert_eq!(args.value("--day"), Some("2"));
+        assert_eq!(args.value("--year"), Some("2025"));
+        assert_eq!(args.value("--runs"), None);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let args = args("--day 7 --year abc --runs");
+        assert_eq!(args.parse_value::<u32>("--day").unwrap(), Some(7));
+        assert_eq!(args.parse_value::<u32>("--missing").unwrap(), None);
+        assert!(args.parse_value::<u32>("--year").is_err());
+        assert!(args.parse_value::<u32>("--runs").is_err());
+    }
+
+    #[test]
+    fn test_positional() {
+        let args = args("2025 --day 3 --example 4");
+        assert_eq!(args.positional(&["--day"]), vec!["2025", "4"]);
+        assert_eq!(args.positional(&["--day", "--example"]), vec!["2025"]);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::args::Args;
+use crate::workspace;
+use aoclib::fmt::{duration, thousands};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_RUNS: usize = 50;
+pub(crate) const DEFAULT_OUTPUT: &str = "benchmarks.tsv";
+const HEADER: &str = "timestamp_ms\tcrate\truns\tphase\tmin_ns\tmedian_ns\tmean_ns";
+
+/// Timings of one phase as reported by a day binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PhaseTiming {
+    phase: String,
+    min: Duration,
+    median: Duration,
+    mean: Duration,
+}
+
+/// `aoc bench --day N [--year YYYY] [--runs R] [--output FILE]`: times a day's parse,
+/// part 1 and part 2 separately.
+///
+/// Results are printed as a table and appended to the output file (by default
+/// `benchmarks.tsv` in the workspace root). When the file already holds results for
+/// the same day, the median is compared against the most recent previous run.
+pub fn bench(args: &Args) -> Result<(), Box<dyn Error>> {
+    let day: u32 = args
+        .parse_value("--day")?
+        .ok_or("Missing required option '--day'")?;
+    let year: Option<u32> = args.parse_value("--year")?;
+    let runs: usize = args.parse_value("--runs")?.unwrap_or(DEFAULT_RUNS);
+
+    let root = workspace::root()?;
+    let name = workspace::find_day(&root, year, day)?;
+    let output = workspace::run_day(&root, &name, &["--bench", &runs.to_string()])?;
+    let timings = parse_timings(&output).map_err(|e| {
+        format!(
+            "{} did not report benchmark timings ({}); is its main wired to aoclib::run?",
+            name, e
+        )
+    })?;
+
+    let output_path = root.join(args.value("--output").unwrap_or(DEFAULT_OUTPUT));
+    let previous = fs::read_to_string(&output_path)
+        .map(|content| previous_medians(&content, &name))
+        .unwrap_or_default();
+
+    println!("{} ({} runs)", name, thousands(runs));
+    println!(
+        "{:<8}{:>14}{:>14}{:>14}{:>12}",
+        "phase", "min", "median", "mean", "vs last"
+    );
+    for timing in &timings {
+        let change = previous
+            .iter()
+            .find(|(phase, _)| *phase == timing.phase)
+            .map(|(_, last)| relative_change(*last, timing.median))
+            .unwrap_or_default();
+        println!(
+            "{:<8}{:>14}{:>14}{:>14}{:>12}",
+            timing.phase,
+            duration(timing.min),
+            duration(timing.median),
+            duration(timing.mean),
+            change
+        );
+    }
+
+    append_results(&output_path, &name, runs, &timings)?;
+    println!("Results appended to {}", output_path.display());
+    Ok(())
+}
+
+/// Parses the `phase min median mean` lines printed by `aoclib::run` in bench mode.
+fn parse_timings(output: &str) -> Result<Vec<PhaseTiming>, String> {
+    let timings: Vec<PhaseTiming> = output
+        .lines()
+        .map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 4 {
+                return Err(format!("unexpected line '{}'", line));
+            }
+            let nanos = |s: &str| {
+                s.parse::<u64>()
+                    .map(Duration::from_nanos)
+                    .map_err(|e| format!("invalid timing '{}': {}", s, e))
+            };
+            Ok(PhaseTiming {
+                phase: fields[0].to_string(),
+                min: nanos(fields[1])?,
+                median: nanos(fields[2])?,
+                mean: nanos(fields[3])?,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    if timings.is_empty() {
+        return Err("no output".to_string());
+    }
+    Ok(timings)
+}
+
+/// Returns the median of each phase from the most recent run of `name` in a results
+/// file.
+pub(crate) fn previous_medians(content: &str, name: &str) -> Vec<(String, Duration)> {
+    let rows: Vec<Vec<&str>> = content
+        .lines()
+        .skip(1)
+        .map(|line| line.split('\t').collect::<Vec<_>>())
+        .filter(|fields| fields.len() == 7 && fields[1] == name)
+        .collect();
+    let Some(latest) = rows.last().map(|fields| fields[0]) else {
+        return Vec::new();
+    };
+
+    rows.iter()
+        .filter(|fields| fields[0] == latest)
+        .filter_map(|fields| {
+            let median = fields[5].parse().ok().map(Duration::from_nanos)?;
+            Some((fields[3].to_string(), median))
+        })
+        .collect()
+}
+
+/// Formats the change from `before` to `after` as a signed percentage.
+fn relative_change(before: Duration, after: Duration) -> String {
+    if before.is_zero() {
+        return String::new();
+    }
+    let change = (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+    format!("{:+.1}%", change)
+}
+
+/// Appends one row per phase to the results file, writing a header if it is new.
+fn append_results(
+    path: &Path,
+    name: &str,
+    runs: usize,
+    timings: &[PhaseTiming],
+) -> Result<(), Box<dyn Error>> {
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{}", HEADER)?;
+    }
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+    for t in timings {
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            timestamp,
+            name,
+            runs,
+            t.phase,
+            t.min.as_nanos(),
+            t.median.as_nanos(),
+            t.mean.as_nanos()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timings() {
+        let timings = parse_timings("parse\t10\t20\t30\npart1\t1\t2\t3\n").unwrap();
+        assert_eq!(timings.len(), 2);
+        assert_eq!(
+            timings[0],
+            PhaseTiming {
+                phase: "parse".to_string(),
+                min: Duration::from_nanos(10),
+                median: Duration::from_nanos(20),
+                mean: Duration::from_nanos(30),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_timings_rejects_answers() {
+        assert!(parse_timings("Part 1: 1078\nPart 2: 6412\n").is_err());
+        assert!(parse_timings("").is_err());
+        assert!(parse_timings("parse\tx\t1\t1").is_err());
+    }
+
+    #[test]
+    fn test_previous_medians_uses_latest_run() {
+        let content = format!(
+            "{}\n\
+             100\tday01_2025\t50\tparse\t1\t10\t1\n\
+             100\tday01_2025\t50\tpart1\t1\t20\t1\n\
+             200\tday02_2025\t50\tparse\t1\t99\t1\n\
+             300\tday01_2025\t50\tparse\t1\t15\t1\n",
+            HEADER
+        );
+        assert_eq!(
+            previous_medians(&content, "day01_2025"),
+            vec![("parse".to_string(), Duration::from_nanos(15))]
+        );
+        assert!(previous_medians(&content, "day03_2025").is_empty());
+    }
+
+    #[test]
+    fn test_relative_change() {
+        let ms = Duration::from_millis;
+        assert_eq!(relative_change(ms(100), ms(150)), "+50.0%");
+        assert_eq!(relative_change(ms(200), ms(100)), "-50.0%");
+        assert_eq!(relative_change(Duration::ZERO, ms(1)), "");
+    }
+}
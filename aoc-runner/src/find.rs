@@ -0,0 +1,150 @@
+use crate::args::Args;
+use crate::meta::DayMeta;
+use crate::{meta, workspace};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// `aoc find QUERY [--year YYYY]`: lists the solutions whose title, tags or use of
+/// aoclib match `QUERY`, across every year in the workspace.
+///
+/// Matching is a case-insensitive substring search, so `aoc find dijkstra` finds the
+/// days tagged `dijkstra` as well as those calling `aoclib::search::dijkstra`. Each
+/// hit says what matched.
+pub fn find(args: &Args) -> Result<(), Box<dyn Error>> {
+    let query = args.positional(&["--year"]).join(" ");
+    if query.is_empty() {
+        return Err("Missing search term, e.g. 'aoc find dijkstra'".into());
+    }
+    let year: Option<u32> = args.parse_value("--year")?;
+
+    let root = workspace::root()?;
+    let mut found = 0;
+    for name in workspace::days(&root, year)? {
+        let meta = meta::read(&root, &name)?;
+        let uses = aoclib_uses(&root.join(&name).join("src"))?;
+        let reasons = matches(&query, &meta, &uses);
+        if reasons.is_empty() {
+            continue;
+        }
+        found += 1;
+        println!(
+            "{}  {}",
+            name,
+            meta.title.as_deref().unwrap_or("(untitled)")
+        );
+        for reason in reasons {
+            println!("    {}", reason);
+        }
+    }
+    if found == 0 {
+        eprintln!("No solutions match '{}'", query);
+    }
+    Ok(())
+}
+
+/// Describes each part of a day that matches `query`, ignoring case.
+fn matches(query: &str, meta: &DayMeta, uses: &[String]) -> Vec<String> {
+    let query = query.to_lowercase();
+    let hit = |text: &str| text.to_lowercase().contains(&query);
+    let mut reasons = Vec::new();
+    if let Some(title) = meta.title.as_deref().filter(|title| hit(title)) {
+        reasons.push(format!("title: {}", title));
+    }
+    for tag in meta.tags.iter().filter(|tag| hit(tag)) {
+        reasons.push(format!("tag: {}", tag));
+    }
+    for path in uses.iter().filter(|path| hit(path)) {
+        reasons.push(format!("uses: {}", path));
+    }
+    reasons
+}
+
+/// Collects the aoclib paths named in the Rust sources directly inside `dir`, sorted
+/// and without duplicates.
+fn aoclib_uses(dir: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut uses = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            uses.extend(aoclib_paths(&fs::read_to_string(&path)?));
+        }
+    }
+    uses.sort();
+    uses.dedup();
+    Ok(uses)
+}
+
+/// Finds every `aoclib::...` path in `source`, expanding one level of braces, so
+/// `use aoclib::{math::lcm, Grid};` names `aoclib::math::lcm` and `aoclib::Grid`.
+fn aoclib_paths(source: &str) -> Vec<String> {
+    let is_path_char = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+    let mut paths = Vec::new();
+    for (start, _) in source.match_indices("aoclib::") {
+        let rest = &source[start..];
+        let head: &str = &rest[..rest.find(|c| !is_path_char(c)).unwrap_or(rest.len())];
+        match rest[head.len()..].strip_prefix('{') {
+            Some(group) => {
+                let group = &group[..group.find('}').unwrap_or(group.len())];
+                for item in group.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+                    let item = &item[..item.find(|c| !is_path_char(c)).unwrap_or(item.len())];
+                    paths.push(format!("{}{}", head, item));
+                }
+            }
+            None => paths.push(head.trim_end_matches(':').to_string()),
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aoclib_paths() {
+        let source = "\
+use aoclib::{math::lcm, Grid};
+use aoclib::search::dijkstra;
+fn main() {
+    aoclib::run(&Day);
+    let ids = aoclib::examples!();
+}";
+        assert_eq!(
+            aoclib_paths(source),
+            vec![
+                "aoclib::math::lcm",
+                "aoclib::Grid",
+                "aoclib::search::dijkstra",
+                "aoclib::run",
+                "aoclib::examples",
+            ]
+        );
+        assert!(aoclib_paths("no library here").is_empty());
+    }
+
+    #[test]
+    fn test_matches() {
+        let meta = DayMeta {
+            title: Some("Printing Department".to_string()),
+            tags: vec!["grid".to_string(), "sim".to_string()],
+            slow: false,
+        };
+        let uses = vec!["aoclib::Point2".to_string()];
+        assert_eq!(matches("GRID", &meta, &uses), vec!["tag: grid"]);
+        assert_eq!(
+            matches("print", &meta, &uses),
+            vec!["title: Printing Department"]
+        );
+        assert_eq!(matches("point", &meta, &uses), vec!["uses: aoclib::Point2"]);
+        assert!(matches("dijkstra", &meta, &uses).is_empty());
+    }
+
+    #[test]
+    fn test_workspace_days_are_searchable() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let uses = aoclib_uses(&root.join("day02_2025").join("src")).unwrap();
+        assert!(uses.contains(&"aoclib::math::factorize".to_string()));
+        assert!(uses.contains(&"aoclib::collections::RangeSet".to_string()));
+    }
+}
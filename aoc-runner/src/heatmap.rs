@@ -0,0 +1,269 @@
+use crate::args::Args;
+use crate::bench::{previous_medians, DEFAULT_OUTPUT};
+use crate::workspace;
+use aoclib::fmt::duration;
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::time::Duration;
+
+const DEFAULT_BUDGET_MS: u64 = 1_000;
+
+/// How a day's runtime compares with the budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    /// Under a tenth of the budget.
+    Fast,
+    /// Within the budget.
+    Within,
+    /// Over the budget.
+    Over,
+}
+
+impl Level {
+    fn of(total: Duration, budget: Duration) -> Level {
+        if total >= budget {
+            Level::Over
+        } else if total * 10 >= budget {
+            Level::Within
+        } else {
+            Level::Fast
+        }
+    }
+
+    fn ansi(self) -> &'static str {
+        match self {
+            Level::Fast => "\x1b[30;42m",
+            Level::Within => "\x1b[30;43m",
+            Level::Over => "\x1b[1;37;41m",
+        }
+    }
+
+    fn svg_fill(self) -> &'static str {
+        match self {
+            Level::Fast => "#7bc96f",
+            Level::Within => "#f2c84b",
+            Level::Over => "#e05d44",
+        }
+    }
+}
+
+/// `aoc heatmap [--year YYYY] [--input FILE] [--budget MS] [--svg FILE]`: draws the
+/// year's days as a 5x5 calendar coloured by runtime.
+///
+/// A day's runtime is the sum of the parse, part 1 and part 2 medians from its latest
+/// `aoc bench` run in the results file (by default `benchmarks.tsv`). Days under a
+/// tenth of the budget are green, days within it yellow and days over it red, and the
+/// days over budget are listed underneath. The year defaults to the latest one with
+/// results. Colours are left out when `NO_COLOR` is set, and `--svg` writes the
+/// calendar as an SVG image instead of printing it.
+pub fn heatmap(args: &Args) -> Result<(), Box<dyn Error>> {
+    let root = workspace::root()?;
+    let path = root.join(args.value("--input").unwrap_or(DEFAULT_OUTPUT));
+    let content = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "Cannot read '{}' ({}); run 'aoc bench' first",
+            path.display(),
+            e
+        )
+    })?;
+    let budget = Duration::from_millis(args.parse_value("--budget")?.unwrap_or(DEFAULT_BUDGET_MS));
+
+    let year = match args.parse_value("--year")? {
+        Some(year) => year,
+        None => latest_year(&content).ok_or("No benchmark results to draw")?,
+    };
+    let totals = day_totals(&content, year);
+
+    if let Some(svg) = args.value("--svg") {
+        let svg_path = root.join(svg);
+        fs::write(&svg_path, render_svg(year, &totals, budget))?;
+        println!("Wrote {}", svg_path.display());
+    } else {
+        let color = env::var_os("NO_COLOR").is_none();
+        print!("{}", render_terminal(year, &totals, budget, color));
+    }
+
+    let over: Vec<String> = totals
+        .iter()
+        .filter(|&(_, &total)| total >= budget)
+        .map(|(day, &total)| format!("day {} ({})", day, duration(total)))
+        .collect();
+    if over.is_empty() {
+        println!("Every benchmarked day is within {}", duration(budget));
+    } else {
+        println!("Over {}: {}", duration(budget), over.join(", "));
+    }
+    Ok(())
+}
+
+/// Returns the most recent year with results in a benchmark file.
+fn latest_year(content: &str) -> Option<u32> {
+    crate_names(content)
+        .filter_map(workspace::parse_crate_name)
+        .map(|(year, _)| year)
+        .max()
+}
+
+/// Returns each day's total runtime in `year`, from its latest benchmark run.
+fn day_totals(content: &str, year: u32) -> BTreeMap<u32, Duration> {
+    let mut totals = BTreeMap::new();
+    for name in crate_names(content) {
+        if let Some((crate_year, day)) = workspace::parse_crate_name(name) {
+            if crate_year == year {
+                let phases = previous_medians(content, name);
+                totals.insert(day, phases.iter().map(|(_, median)| *median).sum());
+            }
+        }
+    }
+    totals
+}
+
+/// Iterates over the crate column of a benchmark file, skipping the header.
+fn crate_names(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split('\t').nth(1))
+}
+
+/// Draws the calendar as text, one row of five days per line.
+fn render_terminal(
+    year: u32,
+    totals: &BTreeMap<u32, Duration>,
+    budget: Duration,
+    color: bool,
+) -> String {
+    let mut out = format!("{} runtimes, budget {}\n", year, duration(budget));
+    for week in 0..5 {
+        for day in week * 5 + 1..=week * 5 + 5 {
+            let cell = match totals.get(&day) {
+                Some(&total) => {
+                    let level = Level::of(total, budget);
+                    let mark = if level == Level::Over { '!' } else { ' ' };
+                    let text = format!(" {:>2} {:>9}{}", day, duration(total), mark);
+                    if color {
+                        format!("{}{}\x1b[0m", level.ansi(), text)
+                    } else {
+                        text
+                    }
+                }
+                None => format!(" {:>2} {:>9} ", day, "-"),
+            };
+            out.push_str(&cell);
+            out.push(' ');
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+    }
+    out
+}
+
+/// Draws the calendar as a standalone SVG image.
+fn render_svg(year: u32, totals: &BTreeMap<u32, Duration>, budget: Duration) -> String {
+    const CELL: (u32, u32) = (120, 64);
+    const TOP: u32 = 40;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         font-family=\"monospace\">\n",
+        CELL.0 * 5,
+        TOP + CELL.1 * 5
+    );
+    svg.push_str(&format!(
+        "  <text x=\"8\" y=\"26\" font-size=\"18\">{} runtimes, budget {}</text>\n",
+        year,
+        duration(budget)
+    ));
+    for day in 1..=25 {
+        let (x, y) = ((day - 1) % 5 * CELL.0, TOP + (day - 1) / 5 * CELL.1);
+        let (fill, label) = match totals.get(&day) {
+            Some(&total) => (Level::of(total, budget).svg_fill(), duration(total)),
+            None => ("#e4e4e4", "-".to_string()),
+        };
+        svg.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" \
+             stroke=\"#ffffff\" stroke-width=\"2\"/>\n",
+            x, y, CELL.0, CELL.1, fill
+        ));
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" font-size=\"14\">{}</text>\n",
+            x + 8,
+            y + 22,
+            day
+        ));
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" font-size=\"16\">{}</text>\n",
+            x + 8,
+            y + 48,
+            label
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESULTS: &str = "\
+timestamp_ms\tcrate\truns\tphase\tmin_ns\tmedian_ns\tmean_ns
+100\tday01_2024\t50\tparse\t1\t5000\t1
+100\tday01_2025\t50\tparse\t1\t400000000\t1
+200\tday01_2025\t50\tparse\t1\t1000\t1
+200\tday01_2025\t50\tpart1\t1\t2000\t1
+200\tday01_2025\t50\tpart2\t1\t3000\t1
+300\tday03_2025\t50\tparse\t1\t200000000\t1
+300\tday03_2025\t50\tpart2\t1\t1300000000\t1
+400\tday04_2025\t50\tpart1\t1\t150000000\t1
+";
+
+    #[test]
+    fn test_day_totals_use_latest_run() {
+        let totals = day_totals(RESULTS, 2025);
+        let ns = Duration::from_nanos;
+        assert_eq!(totals.get(&1), Some(&ns(6000)));
+        assert_eq!(totals.get(&3), Some(&ns(1_500_000_000)));
+        assert_eq!(totals.len(), 3);
+        assert_eq!(day_totals(RESULTS, 2024).get(&1), Some(&ns(5000)));
+        assert_eq!(latest_year(RESULTS), Some(2025));
+        assert_eq!(latest_year("timestamp_ms\tcrate\n"), None);
+    }
+
+    #[test]
+    fn test_levels() {
+        let ms = Duration::from_millis;
+        assert_eq!(Level::of(ms(99), ms(1000)), Level::Fast);
+        assert_eq!(Level::of(ms(100), ms(1000)), Level::Within);
+        assert_eq!(Level::of(ms(1000), ms(1000)), Level::Over);
+    }
+
+    #[test]
+    fn test_render_terminal() {
+        let totals = day_totals(RESULTS, 2025);
+        let plain = render_terminal(2025, &totals, Duration::from_secs(1), false);
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "2025 runtimes, budget 1.00 s");
+        assert!(lines[1].starts_with("  1   6.00 µs"));
+        assert!(lines[1].contains("  3    1.50 s!"));
+        assert!(lines[1].contains("  4    150 ms "));
+        assert!(lines[5].ends_with(" 25         -"));
+
+        let colored = render_terminal(2025, &totals, Duration::from_secs(1), true);
+        assert!(colored.contains("\x1b[1;37;41m  3    1.50 s!\x1b[0m"));
+        assert!(colored.contains("\x1b[30;43m  4    150 ms \x1b[0m"));
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let totals = day_totals(RESULTS, 2025);
+        let svg = render_svg(2025, &totals, Duration::from_secs(1));
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect ").count(), 25);
+        assert_eq!(svg.matches("#e05d44").count(), 1);
+        assert!(svg.contains(">1.50 s</text>"));
+    }
+}
@@ -0,0 +1,359 @@
+use crate::args::Args;
+use crate::workspace;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+
+/// Maximum number of rows shown in a line-length histogram.
+const HISTOGRAM_ROWS: usize = 8;
+
+/// Width of the longest bar in a line-length histogram.
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Structural statistics of a puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Profile {
+    lines: usize,
+    blank_lines: usize,
+    /// Number of lines of each length.
+    line_lengths: BTreeMap<usize, usize>,
+    /// Distinct characters, excluding line breaks.
+    charset: Vec<char>,
+    numbers: NumberStats,
+}
+
+/// Statistics of the integer tokens found in an input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct NumberStats {
+    count: usize,
+    /// Smallest and largest token that fits in an `i128`.
+    range: Option<(i128, i128)>,
+    negatives: usize,
+    max_digits: usize,
+    /// Tokens too large for an `i128`.
+    oversized: usize,
+}
+
+/// `aoc inspect YEAR DAY [--example [K]]` (or `--day N [--year YYYY]`): prints
+/// structural statistics of a day's input and how they differ from its example.
+pub fn inspect(args: &Args) -> Result<(), Box<dyn Error>> {
+    let positional = args.positional(&["--day", "--year", "--example"]);
+    let (year, day) = match positional.as_slice() {
+        [year, day] => (Some(year.parse()?), day.parse()?),
+        [] => (
+            args.parse_value("--year")?,
+            args.parse_value("--day")?
+                .ok_or("Expected 'YEAR DAY' or '--day N'")?,
+        ),
+        _ => return Err("Expected 'YEAR DAY' or '--day N'".into()),
+    };
+    let example = args
+        .value("--example")
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or(1);
+
+    let root = workspace::root()?;
+    let name = workspace::find_day(&root, year, day)?;
+    let dir = root.join(&name);
+
+    let input = profile(&fs::read_to_string(dir.join("input.txt"))?);
+    println!("{} input.txt", name);
+    print_profile(&input);
+
+    let example_file = aoclib::example_file(example);
+    match fs::read_to_string(dir.join(&example_file)) {
+        Ok(content) => {
+            let example = profile(&content);
+            println!();
+            println!("{} {}", name, example_file);
+            print_profile(&example);
+            println!();
+            let diffs = differences(&input, &example);
+            if diffs.is_empty() {
+                println!("No structural surprises compared with {}", example_file);
+            } else {
+                println!("Input differs from {}:", example_file);
+                for diff in diffs {
+                    println!("  ! {}", diff);
+                }
+            }
+        }
+        Err(_) => println!("\n{} has no {} to compare against", name, example_file),
+    }
+    Ok(())
+}
+
+/// Computes the structural statistics of `text`.
+fn profile(text: &str) -> Profile {
+    let mut line_lengths = BTreeMap::new();
+    let mut blank_lines = 0;
+    let mut lines = 0;
+
+    for line in text.lines() {
+        lines += 1;
+        if line.trim().is_empty() {
+            blank_lines += 1;
+        }
+        *line_lengths.entry(line.chars().count()).or_insert(0) += 1;
+    }
+
+    let mut charset: Vec<char> = text.chars().filter(|&c| c != '\n' && c != '\r').collect();
+    charset.sort_unstable();
+    charset.dedup();
+
+    Profile {
+        lines,
+        blank_lines,
+        line_lengths,
+        charset,
+        numbers: number_stats(text),
+    }
+}
+
+/// Scans `text` for integer tokens.
+///
+/// A `-` directly before a digit counts as a sign only when it does not follow a
+/// letter or digit, so ranges like `11-22` yield two positive numbers.
+fn number_stats(text: &str) -> NumberStats {
+    let mut stats = NumberStats::default();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        let negative = start > 0
+            && chars[start - 1] == '-'
+            && (start < 2 || !chars[start - 2].is_alphanumeric());
+        let digits: String = chars[start..i].iter().collect();
+
+        stats.count += 1;
+        stats.max_digits = stats
+            .max_digits
+            .max(digits.trim_start_matches('0').len().max(1));
+        if negative {
+            stats.negatives += 1;
+        }
+        match digits.parse::<i128>() {
+            Ok(value) => {
+                let value = if negative { -value } else { value };
+                stats.range = Some(match stats.range {
+                    Some((lo, hi)) => (lo.min(value), hi.max(value)),
+                    None => (value, value),
+                });
+            }
+            Err(_) => stats.oversized += 1,
+        }
+    }
+
+    stats
+}
+
+/// Lists the ways in which the real input is structurally different from the example.
+fn differences(input: &Profile, example: &Profile) -> Vec<String> {
+    let mut diffs = Vec::new();
+    let max_len = |p: &Profile| p.line_lengths.keys().last().copied().unwrap_or(0);
+    let min_len = |p: &Profile| p.line_lengths.keys().next().copied().unwrap_or(0);
+
+    if max_len(input) > max_len(example) {
+        diffs.push(format!(
+            "longer lines: up to {} chars (example: {})",
+            max_len(input),
+            max_len(example)
+        ));
+    }
+    if input.line_lengths.len() > 1 && example.line_lengths.len() == 1 {
+        diffs.push(format!(
+            "varying line lengths {}..={} (example lines are all {} chars)",
+            min_len(input),
+            max_len(input),
+            min_len(example)
+        ));
+    }
+    if input.blank_lines > 0 && example.blank_lines == 0 {
+        diffs.push(format!(
+            "{} blank lines (example has none)",
+            input.blank_lines
+        ));
+    }
+
+    // Digits are covered by the number statistics below
+    let extra: String = input
+        .charset
+        .iter()
+        .filter(|c| !c.is_ascii_digit() && !example.charset.contains(c))
+        .collect();
+    if !extra.is_empty() {
+        diffs.push(format!("characters not in the example: {:?}", extra));
+    }
+
+    let (ni, ne) = (&input.numbers, &example.numbers);
+    if ni.negatives > 0 && ne.negatives == 0 {
+        diffs.push(format!(
+            "{} negative numbers (example has none)",
+            ni.negatives
+        ));
+    }
+    if ni.oversized > 0 {
+        diffs.push(format!("{} numbers do not fit in an i128", ni.oversized));
+    }
+    if let (Some((_, input_max)), Some((_, example_max))) = (ni.range, ne.range) {
+        for (limit, name) in [(i32::MAX as i128, "i32"), (i64::MAX as i128, "i64")] {
+            if input_max > limit && example_max <= limit {
+                diffs.push(format!(
+                    "numbers up to {} overflow {} (example max: {})",
+                    input_max, name, example_max
+                ));
+                break;
+            }
+        }
+    }
+    if ni.max_digits > ne.max_digits {
+        diffs.push(format!(
+            "numbers with up to {} digits (example: {})",
+            ni.max_digits, ne.max_digits
+        ));
+    }
+
+    diffs
+}
+
+/// Prints a profile as an indented report.
+fn print_profile(p: &Profile) {
+    println!("  lines:       {} ({} blank)", p.lines, p.blank_lines);
+    if let (Some(min), Some(max)) = (p.line_lengths.keys().next(), p.line_lengths.keys().last()) {
+        println!("  line length: {}..={}", min, max);
+        for (label, count) in histogram(&p.line_lengths) {
+            let peak = p.line_lengths.values().max().copied().unwrap_or(1);
+            let bar = "#".repeat(
+                (count * HISTOGRAM_WIDTH)
+                    .div_ceil(peak.max(1))
+                    .min(HISTOGRAM_WIDTH),
+            );
+            println!("    {:>11} | {} {}", label, bar, count);
+        }
+    }
+    let charset: String = p.charset.iter().collect();
+    println!(
+        "  charset:     {:?} ({} distinct)",
+        charset,
+        p.charset.len()
+    );
+
+    let n = &p.numbers;
+    match n.range {
+        Some((lo, hi)) => println!(
+            "  numbers:     {} tokens in {}..={} ({} negative, up to {} digits)",
+            n.count, lo, hi, n.negatives, n.max_digits
+        ),
+        None if n.count > 0 => println!(
+            "  numbers:     {} tokens (up to {} digits)",
+            n.count, n.max_digits
+        ),
+        None => println!("  numbers:     none"),
+    }
+}
+
+/// Groups line lengths into at most `HISTOGRAM_ROWS` labelled buckets.
+fn histogram(lengths: &BTreeMap<usize, usize>) -> Vec<(String, usize)> {
+    if lengths.len() <= HISTOGRAM_ROWS {
+        return lengths
+            .iter()
+            .map(|(len, count)| (len.to_string(), *count))
+            .collect();
+    }
+
+    let min = *lengths.keys().next().unwrap();
+    let max = *lengths.keys().last().unwrap();
+    let width = (max - min + 1).div_ceil(HISTOGRAM_ROWS);
+    let mut buckets: Vec<(String, usize)> = (0..HISTOGRAM_ROWS)
+        .map(|i| {
+            let lo = min + i * width;
+            (format!("{}..={}", lo, lo + width - 1), 0)
+        })
+        .collect();
+    for (len, count) in lengths {
+        buckets[(len - min) / width].1 += count;
+    }
+    buckets.retain(|(_, count)| *count > 0);
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_lines_and_charset() {
+        let p = profile("ab\n\nabc\n");
+        assert_eq!(p.lines, 3);
+        assert_eq!(p.blank_lines, 1);
+        assert_eq!(p.line_lengths, BTreeMap::from([(0, 1), (2, 1), (3, 1)]));
+        assert_eq!(p.charset, vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    fn test_number_stats_ranges_are_not_negative() {
+        let n = number_stats("11-22,95-115");
+        assert_eq!(n.count, 4);
+        assert_eq!(n.negatives, 0);
+        assert_eq!(n.range, Some((11, 115)));
+        assert_eq!(n.max_digits, 3);
+    }
+
+    #[test]
+    fn test_number_stats_negatives() {
+        let n = number_stats("p=3,-4 v=-17,5\nx -2");
+        assert_eq!(n.negatives, 3);
+        assert_eq!(n.range, Some((-17, 5)));
+    }
+
+    #[test]
+    fn test_number_stats_oversized() {
+        let n = number_stats(&"9".repeat(50));
+        assert_eq!(n.count, 1);
+        assert_eq!(n.oversized, 1);
+        assert_eq!(n.range, None);
+        assert_eq!(n.max_digits, 50);
+    }
+
+    #[test]
+    fn test_differences_flags_surprises() {
+        let example = profile("1,2\n3,4\n");
+        let input = profile("1,-2\n3000000000,4,x\n");
+        let diffs = differences(&input, &example);
+        assert!(diffs.iter().any(|d| d.starts_with("longer lines")));
+        assert!(diffs.iter().any(|d| d.starts_with("varying line lengths")));
+        assert!(diffs.iter().any(|d| d.contains("\"-x\"")));
+        assert!(diffs.iter().any(|d| d.contains("negative numbers")));
+        assert!(diffs.iter().any(|d| d.contains("overflow i32")));
+    }
+
+    #[test]
+    fn test_differences_identical_structure() {
+        let example = profile("12\n34\n");
+        let input = profile("56\n78\n90\n");
+        assert!(differences(&input, &example).is_empty());
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let few = BTreeMap::from([(3, 2), (5, 1)]);
+        assert_eq!(
+            histogram(&few),
+            vec![("3".to_string(), 2), ("5".to_string(), 1)]
+        );
+
+        let many: BTreeMap<usize, usize> = (0..16).map(|len| (len, 1)).collect();
+        let buckets = histogram(&many);
+        assert_eq!(buckets.len(), HISTOGRAM_ROWS);
+        assert_eq!(buckets[0], ("0..=1".to_string(), 2));
+    }
+}
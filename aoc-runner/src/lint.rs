@@ -0,0 +1,359 @@
+use crate::args::Args;
+use crate::workspace;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Integer types a digit-by-digit fold overflows quickly in.
+const NARROW_INTS: [&str; 6] = ["usize", "u64", "u32", "isize", "i64", "i32"];
+
+/// A problem found in one of a day's source files.
+#[derive(Debug, PartialEq)]
+struct Finding {
+    /// Path relative to the day crate, e.g. `src/lib.rs`.
+    file: String,
+    /// 1-based line number, or `None` for a finding about the whole file.
+    line: Option<usize>,
+    message: String,
+}
+
+/// `aoc lint [--year YYYY]`: checks every day crate for pitfalls this workspace has
+/// hit before, and fails if any are found.
+///
+/// The checks are textual heuristics over `src/*.rs`, not a compiler pass:
+///
+/// * a `part1`/`part2` function that prints instead of returning its answer
+/// * a hard-coded `input.txt` path, which bypasses `--example` and embedded examples
+/// * a digit-by-digit `fold` into a 64-bit or smaller integer, which overflows once
+///   the number passes 19 digits
+/// * no test that runs the example input, or no `example.txt` at all
+pub fn lint(args: &Args) -> Result<(), Box<dyn Error>> {
+    let year: Option<u32> = args.parse_value("--year")?;
+    let root = workspace::root()?;
+
+    let mut total = 0;
+    let mut crates = 0;
+    for name in workspace::days(&root, year)? {
+        let findings = lint_day(&root.join(&name))?;
+        if findings.is_empty() {
+            continue;
+        }
+        crates += 1;
+        total += findings.len();
+        println!("{}", name);
+        for finding in findings {
+            match finding.line {
+                Some(line) => println!("    {}:{}: {}", finding.file, line, finding.message),
+                None => println!("    {}: {}", finding.file, finding.message),
+            }
+        }
+    }
+
+    if total > 0 {
+        return Err(format!(
+            "{} finding{} in {} crate{}",
+            total,
+            if total == 1 { "" } else { "s" },
+            crates,
+            if crates == 1 { "" } else { "s" }
+        )
+        .into());
+    }
+    eprintln!("No findings");
+    Ok(())
+}
+
+/// Lints one day crate.
+fn lint_day(dir: &Path) -> Result<Vec<Finding>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir.join("src"))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            files.push((format!("src/{}", name), fs::read_to_string(&path)?));
+        }
+    }
+    files.sort();
+
+    let mut findings: Vec<Finding> = files
+        .iter()
+        .flat_map(|(file, source)| lint_source(file, source))
+        .collect();
+    let lib = if files.iter().any(|(file, _)| file == "src/lib.rs") {
+        "src/lib.rs"
+    } else {
+        "src/main.rs"
+    };
+    if !dir.join("example.txt").is_file() {
+        findings.push(Finding {
+            file: "example.txt".to_string(),
+            line: None,
+            message: "missing; save the puzzle's example input so it can be tested".to_string(),
+        });
+    } else if !files.iter().any(|(_, source)| tests_use_example(source)) {
+        findings.push(Finding {
+            file: lib.to_string(),
+            line: None,
+            message: "no test runs the example input; add one with \
+                      include_str!(\"../example.txt\")"
+                .to_string(),
+        });
+    }
+    Ok(findings)
+}
+
+/// Runs the per-file checks on `source`, which was read from `file`.
+fn lint_source(file: &str, source: &str) -> Vec<Finding> {
+    let code = without_comments(source);
+    let tests_start = code
+        .lines()
+        .position(|line| line.trim() == "#[cfg(test)]")
+        .unwrap_or(usize::MAX);
+    let mut findings = Vec::new();
+    let mut report = |offset: usize, message: String| {
+        findings.push(Finding {
+            file: file.to_string(),
+            line: Some(line_of(&code, offset)),
+            message,
+        });
+    };
+
+    for (offset, _) in code.match_indices("input.txt\"") {
+        if line_of(&code, offset) <= tests_start {
+            report(
+                offset,
+                "hard-coded input path; read the input with aoclib::run or \
+                 aoclib::load_input so --example works"
+                    .to_string(),
+            );
+        }
+    }
+
+    for function in functions(&code) {
+        if line_of(&code, function.offset) > tests_start {
+            break;
+        }
+        if ["part1", "part2", "part_1", "part_2"].contains(&function.name) {
+            for print in ["println!", "print!", "eprintln!", "eprint!", "dbg!"] {
+                for (at, _) in word_matches(function.body, print) {
+                    report(
+                        function.body_offset + at,
+                        format!(
+                            "{} prints with {}; return the answer so the runner can \
+                             print, check and benchmark it",
+                            function.name, print
+                        ),
+                    );
+                }
+            }
+        }
+        let Some(int) = NARROW_INTS
+            .iter()
+            .find(|int| function.signature.ends_with(&format!("-> {}", int)))
+        else {
+            continue;
+        };
+        for (at, _) in function.body.match_indices(".fold(") {
+            let call = &function.body[at..];
+            let call = &call[..closing(call, '(', ')').unwrap_or(call.len())];
+            if call.contains("* 10 +") {
+                report(
+                    function.body_offset + at,
+                    format!(
+                        "{} folds digits into a {}, which overflows past 19 digits; \
+                         use u128 or checked_mul",
+                        function.name, int
+                    ),
+                );
+            }
+        }
+    }
+    findings
+}
+
+/// Whether the tests in `source` read an example input, from disk or embedded.
+fn tests_use_example(source: &str) -> bool {
+    source
+        .split_once("#[cfg(test)]")
+        .is_some_and(|(_, tests)| tests.contains("example") && tests.contains(".txt"))
+        || source.contains(".examples()")
+}
+
+/// A function definition in a source file.
+struct Function<'a> {
+    name: &'a str,
+    /// Everything between `fn` and the opening brace, trimmed.
+    signature: &'a str,
+    /// Byte offset of the `fn` keyword.
+    offset: usize,
+    body: &'a str,
+    /// Byte offset of `body`.
+    body_offset: usize,
+}
+
+/// Finds every function with a body in `code`, including methods and nested ones.
+fn functions(code: &str) -> Vec<Function<'_>> {
+    let mut found = Vec::new();
+    for (offset, _) in word_matches(code, "fn") {
+        let rest = &code[offset + 2..];
+        let name = rest.trim_start();
+        let name = &name[..name
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(name.len())];
+        let Some(open) = rest.find(['{', ';']) else {
+            continue;
+        };
+        if name.is_empty() || !rest[open..].starts_with('{') {
+            continue;
+        }
+        let body = &rest[open..];
+        let end = closing(body, '{', '}').unwrap_or(body.len());
+        found.push(Function {
+            name,
+            signature: rest[..open].trim(),
+            offset,
+            body: &body[..end],
+            body_offset: offset + 2 + open,
+        });
+    }
+    found
+}
+
+/// Returns the length of the bracketed group `text` starts with, up to and including
+/// its closing bracket.
+fn closing(text: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Finds `word` in `code` where it is not part of a longer identifier.
+fn word_matches<'a>(code: &'a str, word: &'a str) -> impl Iterator<Item = (usize, &'a str)> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    code.match_indices(word).filter(move |&(i, _)| {
+        let before = code[..i].chars().next_back();
+        let after = code[i + word.len()..].chars().next();
+        let starts_word = !before.is_some_and(is_ident);
+        let ends_word = !word.ends_with(is_ident) || !after.is_some_and(is_ident);
+        starts_word && ends_word
+    })
+}
+
+/// Blanks out `//` comments, doc comments included, keeping every byte offset and
+/// line number intact.
+fn without_comments(source: &str) -> String {
+    source
+        .lines()
+        .map(|line| match line.find("//") {
+            Some(i) => format!("{}{}", &line[..i], " ".repeat(line.len() - i)),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the 1-based line number of byte `offset` in `text`.
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<(usize, String)> {
+        lint_source("src/lib.rs", source)
+            .into_iter()
+            .map(|f| (f.line.unwrap(), f.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_print_in_part() {
+        let source = "\
+fn part1(input: &str) -> usize {
+    let n = input.len();
+    println!(\"{}\", n);
+    n
+}
+fn helper() {
+    println!(\"fine\");
+}";
+        let found = messages(source);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 3);
+        assert!(found[0].1.starts_with("part1 prints with println!"));
+    }
+
+    #[test]
+    fn test_hard_coded_input() {
+        let source = "\
+// Reads \"input.txt\" in a comment, which is fine
+fn main() {
+    let input = std::fs::read_to_string(\"./input.txt\").unwrap();
+}
+#[cfg(test)]
+mod tests {
+    const INPUT: &str = include_str!(\"../input.txt\");
+}";
+        let found = messages(source);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 3);
+        assert!(found[0].1.starts_with("hard-coded input path"));
+    }
+
+    #[test]
+    fn test_narrow_fold() {
+        let source = "\
+pub fn joltage(digits: &[u8]) -> usize {
+    digits
+        .iter()
+        .fold(0, |acc, &d| acc * 10 + d as usize)
+}
+pub fn wide(digits: &[u8]) -> u128 {
+    digits.iter().fold(0, |acc, &d| acc * 10 + d as u128)
+}
+pub fn count(digits: &[u8]) -> usize {
+    digits.iter().fold(0, |acc, _| acc + 1)
+}";
+        let found = messages(source);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 4);
+        assert!(found[0].1.starts_with("joltage folds digits into a usize"));
+    }
+
+    #[test]
+    fn test_tests_use_example() {
+        let with = "fn a() {}\n#[cfg(test)]\nmod tests {\n    include_str!(\"../example.txt\");\n}";
+        assert!(tests_use_example(with));
+        assert!(!tests_use_example("fn a() {}\n#[cfg(test)]\nmod tests {}"));
+        assert!(!tests_use_example("const EXAMPLE: &str = \"example.txt\";"));
+    }
+
+    #[test]
+    fn test_functions() {
+        let code = "impl S {\n    fn part1(&self) -> Answer { if x { 1 } else { 2 } }\n}\nfn f();";
+        let found = functions(code);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "part1");
+        assert_eq!(found[0].signature, "part1(&self) -> Answer");
+        assert_eq!(found[0].body, "{ if x { 1 } else { 2 } }");
+    }
+
+    #[test]
+    fn test_workspace_template_is_clean() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let template = fs::read_to_string(root.join("templates/lib.rs.tmpl")).unwrap();
+        assert!(lint_source("src/lib.rs", &template).is_empty());
+        assert!(tests_use_example(&template));
+    }
+}
@@ -0,0 +1,63 @@
+mod all;
+mod args;
+mod bench;
+mod find;
+mod heatmap;
+mod inspect;
+mod lint;
+mod meta;
+mod run;
+mod scaffold;
+mod workspace;
+
+use args::Args;
+use std::env;
+use std::error::Error;
+use std::process;
+
+const USAGE: &str = "\
+Usage: aoc <command> [options]
+
+Commands:
+  run --day N [--year YYYY] [--example [K]] [--json] [--salted-hash SALT] [--repeat R]
+                               Run a day against its input or example input,
+                               optionally R times to check the answers never change;
+                               --salted-hash prints hashes instead of the answers
+  all [--year YYYY] [--example [K]] [--salted-hash SALT] [--jobs J] [--tag T,...]
+      [--skip-slow]            Run every day, J at a time, printing answers in order;
+                               optionally only days tagged T or not marked slow
+  find QUERY [--year YYYY]     List the days whose title, tags or aoclib use match
+  inspect YEAR DAY [--example [K]]
+                               Compare the structure of a day's input and example
+  lint [--year YYYY]           Check the day crates for common pitfalls
+  new --day N [--year YYYY]    Create a new day crate from the template
+  bench --day N [--year YYYY] [--runs R] [--output FILE]
+                               Time parse, part 1 and part 2 separately
+  heatmap [--year YYYY] [--input FILE] [--budget MS] [--svg FILE]
+                               Draw the calendar coloured by benchmarked runtime";
+
+fn main() {
+    let mut argv = env::args().skip(1);
+    let command = argv.next();
+    let args = Args::new(argv);
+
+    let result: Result<(), Box<dyn Error>> = match command.as_deref() {
+        Some("run") => run::run(&args),
+        Some("all") => all::all(&args),
+        Some("find") => find::find(&args),
+        Some("inspect") => inspect::inspect(&args),
+        Some("lint") => lint::lint(&args),
+        Some("new") => scaffold::new(&args),
+        Some("bench") => bench::bench(&args),
+        Some("heatmap") => heatmap::heatmap(&args),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
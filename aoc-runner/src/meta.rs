@@ -0,0 +1,164 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// What a day crate says about itself in the `[package.metadata.aoc]` table of its
+/// `Cargo.toml`.
+///
+/// ```toml
+/// [package.metadata.aoc]
+/// title = "Printing Department"
+/// tags = ["grid", "sim"]
+/// slow = false
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DayMeta {
+    /// The puzzle's title, if recorded.
+    pub title: Option<String>,
+    /// Free-form labels, such as the aoclib modules the solution depends on.
+    pub tags: Vec<String>,
+    /// Whether the solution takes long enough to leave out of quick runs.
+    pub slow: bool,
+}
+
+impl DayMeta {
+    /// Returns true if the day has any of `tags`, ignoring case.
+    pub fn has_any_tag(&self, tags: &[&str]) -> bool {
+        self.tags
+            .iter()
+            .any(|tag| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+}
+
+/// Reads the metadata of the day crate `name` in the workspace at `root`. A crate
+/// without an `[package.metadata.aoc]` table has no tags and is not slow.
+///
+/// # Errors
+///
+/// Returns an error if the manifest cannot be read or its metadata is malformed.
+pub fn read(root: &Path, name: &str) -> Result<DayMeta, Box<dyn Error>> {
+    let path = root.join(name).join("Cargo.toml");
+    let manifest = fs::read_to_string(&path)?;
+    parse(&manifest).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+/// Parses the `[package.metadata.aoc]` table out of a `Cargo.toml`.
+///
+/// Only the keys that table uses are understood: `title` as a string, `tags` as a
+/// one-line array of strings and `slow` as a boolean. Every other table is skipped.
+///
+/// # Errors
+///
+/// Returns an error naming the line of an unknown key or a malformed value.
+pub fn parse(manifest: &str) -> Result<DayMeta, String> {
+    let mut meta = DayMeta::default();
+    let mut in_table = false;
+    for (i, raw) in manifest.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.starts_with('[') {
+            in_table = line == "[package.metadata.aoc]";
+            continue;
+        }
+        if !in_table || line.is_empty() {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected 'key = value', got '{}'", i + 1, raw))?;
+        let value = value.trim();
+        match key.trim() {
+            "title" => {
+                let title = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .ok_or_else(|| format!("line {}: title must be a string", i + 1))?;
+                meta.title = Some(title.to_string()).filter(|t| !t.is_empty());
+            }
+            "tags" => {
+                let items = value
+                    .strip_prefix('[')
+                    .and_then(|v| v.strip_suffix(']'))
+                    .ok_or_else(|| format!("line {}: tags must be an array of strings", i + 1))?;
+                meta.tags = items
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(|tag| {
+                        tag.strip_prefix('"')
+                            .and_then(|t| t.strip_suffix('"'))
+                            .map(str::to_string)
+                            .ok_or_else(|| format!("line {}: tag {} is not a string", i + 1, tag))
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            "slow" => {
+                meta.slow = value
+                    .parse()
+                    .map_err(|_| format!("line {}: slow must be true or false", i + 1))?;
+            }
+            other => return Err(format!("line {}: unknown key '{}'", i + 1, other)),
+        }
+    }
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "\
+[package]
+name = \"day04_2025\"
+
+[package.metadata.aoc]
+# Used by aoc all
+title = \"Printing Department\"
+tags = [\"grid\", \"sim\"]
+slow = true
+
+[dependencies]
+slow = \"not ours\"
+";
+
+    #[test]
+    fn test_parse() {
+        let meta = parse(MANIFEST).unwrap();
+        assert_eq!(meta.title.as_deref(), Some("Printing Department"));
+        assert_eq!(meta.tags, vec!["grid", "sim"]);
+        assert!(meta.slow);
+        assert!(meta.has_any_tag(&["Grid"]));
+        assert!(meta.has_any_tag(&["math", "sim"]));
+        assert!(!meta.has_any_tag(&["math"]));
+        assert_eq!(
+            parse("[package]\nname = \"x\"\n").unwrap(),
+            DayMeta::default()
+        );
+        assert!(parse("[package.metadata.aoc]\ntags = []\n")
+            .unwrap()
+            .tags
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let table = "[package.metadata.aoc]\n";
+        assert!(parse(&format!("{}tags = \"grid\"", table))
+            .unwrap_err()
+            .contains("line 2"));
+        assert!(parse(&format!("{}tags = [grid]", table))
+            .unwrap_err()
+            .contains("not a string"));
+        assert!(parse(&format!("{}slow = yes", table)).is_err());
+        assert!(parse(&format!("{}title = Lobby", table)).is_err());
+        assert!(parse(&format!("{}fast = true", table))
+            .unwrap_err()
+            .contains("unknown key"));
+    }
+
+    #[test]
+    fn test_workspace_days_have_metadata() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let meta = read(root, "day04_2025").unwrap();
+        assert!(meta.has_any_tag(&["grid"]));
+    }
+}
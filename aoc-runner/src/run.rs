@@ -0,0 +1,176 @@
+use crate::args::Args;
+use crate::workspace;
+use std::error::Error;
+
+/// `aoc run --day N [--year YYYY] [--example [K]] [--json] [--salted-hash SALT]
+/// [--repeat R]`: runs a day and prints its answers.
+///
+/// With `--example` the day runs against `example.txt` (or `exampleK.txt`) from its
+/// crate directory instead of `input.txt`. With `--salted-hash` the day prints a hash
+/// of each answer salted with `SALT`, for sharing a run without spoiling it. With
+/// `--repeat` the day runs `R` times, each in a fresh process, and fails unless every
+/// run prints the same answers. This catches answers that depend on `HashMap`
+/// iteration order, which is seeded differently in every process, or on thread
+/// scheduling.
+pub fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let day: u32 = args
+        .parse_value("--day")?
+        .ok_or("Missing required option '--day'")?;
+    let year: Option<u32> = args.parse_value("--year")?;
+
+    let root = workspace::root()?;
+    let name = workspace::find_day(&root, year, day)?;
+    let day_args = day_args(args)?;
+
+    if let Some(index) = example_index(args)? {
+        let file = aoclib::example_file(index);
+        if !root.join(&name).join(&file).is_file() {
+            return Err(format!("{} has no {}", name, file).into());
+        }
+    }
+
+    let repeat: usize = args.parse_value("--repeat")?.unwrap_or(1);
+    if repeat == 0 {
+        return Err("Option '--repeat' needs at least one run".into());
+    }
+
+    let arg_refs: Vec<&str> = day_args.iter().map(String::as_str).collect();
+    let outputs = (0..repeat)
+        .map(|_| workspace::run_day(&root, &name, &arg_refs))
+        .collect::<Result<Vec<_>, _>>()?;
+    check_identical(&outputs)?;
+    print!("{}", outputs[0]);
+    if repeat > 1 {
+        eprintln!("Answers identical across {} runs", repeat);
+    }
+    Ok(())
+}
+
+/// Checks that every run printed the same output, naming the first run that differs
+/// and the lines that changed.
+fn check_identical(outputs: &[String]) -> Result<(), String> {
+    let Some(first) = outputs.first() else {
+        return Ok(());
+    };
+    let Some((run, other)) = outputs
+        .iter()
+        .enumerate()
+        .find(|(_, output)| *output != first)
+    else {
+        return Ok(());
+    };
+    let mut message = format!(
+        "Nondeterministic answers: run {} differs from run 1",
+        run + 1
+    );
+    let (a, b): (Vec<&str>, Vec<&str>) = (first.lines().collect(), other.lines().collect());
+    for i in 0..a.len().max(b.len()) {
+        let (before, after) = (a.get(i).copied(), b.get(i).copied());
+        if before != after {
+            message += &format!(
+                "\n  run 1: {}\n  run {}: {}",
+                before.unwrap_or("<missing>"),
+                run + 1,
+                after.unwrap_or("<missing>")
+            );
+        }
+    }
+    Err(message)
+}
+
+/// Returns the requested example number, if `--example` was given.
+pub(crate) fn example_index(args: &Args) -> Result<Option<usize>, Box<dyn Error>> {
+    if !args.flag("--example") {
+        return Ok(None);
+    }
+    match args.value("--example").filter(|v| !v.starts_with("--")) {
+        Some(index) => {
+            Ok(Some(index.parse().map_err(|_| {
+                format!("Invalid example number '{}'", index)
+            })?))
+        }
+        None => Ok(Some(1)),
+    }
+}
+
+/// Returns the salt given with `--salted-hash`, if any.
+pub(crate) fn salted_hash(args: &Args) -> Result<Option<&str>, Box<dyn Error>> {
+    if !args.flag("--salted-hash") {
+        return Ok(None);
+    }
+    match args.value("--salted-hash").filter(|v| !v.starts_with("--")) {
+        Some(salt) => Ok(Some(salt)),
+        None => Err("Option '--salted-hash' requires a salt".into()),
+    }
+}
+
+/// Builds the arguments forwarded to the day binary.
+fn day_args(args: &Args) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut forwarded = Vec::new();
+    if let Some(index) = example_index(args)? {
+        forwarded.push("--example".to_string());
+        forwarded.push(index.to_string());
+    }
+    if args.flag("--json") {
+        forwarded.push("--json".to_string());
+    }
+    if let Some(salt) = salted_hash(args)? {
+        forwarded.push("--salted-hash".to_string());
+        forwarded.push(salt.to_string());
+    }
+    Ok(forwarded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Args {
+        Args::new(s.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn test_example_index() {
+        assert_eq!(example_index(&args("--day 2")).unwrap(), None);
+        assert_eq!(example_index(&args("--day 2 --example")).unwrap(), Some(1));
+        assert_eq!(example_index(&args("--example --day 2")).unwrap(), Some(1));
+        assert_eq!(
+            example_index(&args("--example 2 --day 2")).unwrap(),
+            Some(2)
+        );
+        assert!(example_index(&args("--example two")).is_err());
+    }
+
+    #[test]
+    fn test_check_identical() {
+        let same = vec!["Part 1: 3\nPart 2: 4\n".to_string(); 3];
+        assert_eq!(check_identical(&same), Ok(()));
+        assert_eq!(check_identical(&[]), Ok(()));
+
+        let mut differing = same.clone();
+        differing[2] = "Part 1: 3\nPart 2: 5\n".to_string();
+        assert_eq!(
+            check_identical(&differing).unwrap_err(),
+            "Nondeterministic answers: run 3 differs from run 1\n  \
+             run 1: Part 2: 4\n  run 3: Part 2: 5"
+        );
+        let truncated = vec!["a\nb\n".to_string(), "a\n".to_string()];
+        assert!(check_identical(&truncated)
+            .unwrap_err()
+            .ends_with("run 2: <missing>"));
+    }
+
+    #[test]
+    fn test_day_args() {
+        assert!(day_args(&args("--day 2")).unwrap().is_empty());
+        assert_eq!(
+            day_args(&args("--day 2 --example --json")).unwrap(),
+            vec!["--example", "1", "--json"]
+        );
+        assert_eq!(
+            day_args(&args("--salted-hash pepper --day 2")).unwrap(),
+            vec!["--salted-hash", "pepper"]
+        );
+        assert!(day_args(&args("--day 2 --salted-hash")).is_err());
+    }
+}
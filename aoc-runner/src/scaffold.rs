@@ -0,0 +1,123 @@
+use crate::args::Args;
+use crate::workspace;
+use std::error::Error;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/Cargo.toml.tmpl");
+const LIB_TEMPLATE: &str = include_str!("../templates/lib.rs.tmpl");
+const MAIN_TEMPLATE: &str = include_str!("../templates/main.rs.tmpl");
+
+/// `aoc new --day N [--year YYYY]`: creates a new day crate and registers it in the
+/// workspace.
+///
+/// The crate contains a `Cargo.toml`, a `src/lib.rs` implementing the `Solver` trait
+/// with an example test module, a thin `src/main.rs`, an empty `input.txt` and an empty
+/// `example.txt`.
+pub fn new(args: &Args) -> Result<(), Box<dyn Error>> {
+    let day: u32 = args
+        .parse_value("--day")?
+        .ok_or("Missing required option '--day'")?;
+    let year: u32 = args.parse_value("--year")?.unwrap_or_else(current_year);
+    if !(1..=25).contains(&day) {
+        return Err(format!("Day must be between 1 and 25, got {}", day).into());
+    }
+
+    let root = workspace::root()?;
+    let name = workspace::crate_name(year, day);
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Err(format!("'{}' already exists", dir.display()).into());
+    }
+
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(dir.join("Cargo.toml"), render(CARGO_TEMPLATE, year, day))?;
+    fs::write(dir.join("src/lib.rs"), render(LIB_TEMPLATE, year, day))?;
+    fs::write(dir.join("src/main.rs"), render(MAIN_TEMPLATE, year, day))?;
+    fs::write(dir.join("input.txt"), "")?;
+    fs::write(dir.join("example.txt"), "")?;
+    workspace::register_member(&root, &name)?;
+
+    println!("Created {}", dir.display());
+    Ok(())
+}
+
+/// Fills in the `{{crate}}`, `{{solver}}`, `{{year}}` and `{{day}}` placeholders.
+fn render(template: &str, year: u32, day: u32) -> String {
+    template
+        .replace("{{crate}}", &workspace::crate_name(year, day))
+        .replace("{{solver}}", &format!("Day{:02}", day))
+        .replace("{{year}}", &year.to_string())
+        .replace("{{day}}", &day.to_string())
+}
+
+/// Returns the current calendar year (UTC).
+fn current_year() -> u32 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    year_from_days((secs / 86_400) as i64)
+}
+
+/// Converts days since 1970-01-01 into a proleptic Gregorian year.
+fn year_from_days(days: i64) -> u32 {
+    // Shift the epoch to 0000-03-01 so leap days fall at the end of each cycle
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let year = year_of_era + era * 400;
+
+    // Months are counted from March, so January and February belong to the next year
+    if month_index >= 10 {
+        (year + 1) as u32
+    } else {
+        year as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_cargo_template() {
+        let rendered = render(CARGO_TEMPLATE, 2025, 5);
+        assert!(rendered.contains("name = \"day05_2025\""));
+        assert!(rendered.contains("aoclib = { path = \"../aoclib\" }"));
+    }
+
+    #[test]
+    fn test_render_main_template() {
+        let rendered = render(MAIN_TEMPLATE, 2024, 12);
+        assert!(rendered.contains("aoclib::run(&day12_2024::Day12)"));
+        assert!(!rendered.contains("{{"));
+    }
+
+    #[test]
+    fn test_render_lib_template() {
+        let rendered = render(LIB_TEMPLATE, 2024, 12);
+        assert!(rendered.contains("pub struct Day12;"));
+        assert!(rendered.contains("impl Solver for Day12"));
+        assert!(rendered.contains("Day12.solve(input)"));
+        assert!(rendered.contains("Advent of Code 2024, day 12"));
+        assert!(rendered.contains("include_str!(\"../example.txt\")"));
+        assert!(!rendered.contains("{{"));
+    }
+
+    #[test]
+    fn test_year_from_days() {
+        assert_eq!(year_from_days(0), 1970);
+        assert_eq!(year_from_days(364), 1970);
+        assert_eq!(year_from_days(365), 1971);
+        // 2024-12-01 and 2025-01-01
+        assert_eq!(year_from_days(20_058), 2024);
+        assert_eq!(year_from_days(20_089), 2025);
+        // 2000-02-29 (leap day)
+        assert_eq!(year_from_days(11_016), 2000);
+    }
+}
@@ -0,0 +1,260 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Returns the crate name used for a given puzzle, e.g. `day03_2025`.
+pub fn crate_name(year: u32, day: u32) -> String {
+    format!("day{:02}_{}", day, year)
+}
+
+/// Splits a day crate name such as `day03_2025` into its year and day.
+pub fn parse_crate_name(name: &str) -> Option<(u32, u32)> {
+    let (day, year) = name.strip_prefix("day")?.split_once('_')?;
+    Some((year.parse().ok()?, day.parse().ok()?))
+}
+
+/// Finds the workspace root by walking up from the current directory until a
+/// `Cargo.toml` containing a `[workspace]` table is found.
+///
+/// # Errors
+///
+/// Returns an error if no workspace manifest exists above the current directory.
+pub fn root() -> Result<PathBuf, Box<dyn Error>> {
+    let mut dir = env::current_dir()?;
+    loop {
+        let manifest = dir.join("Cargo.toml");
+        if manifest.is_file() && fs::read_to_string(&manifest)?.contains("[workspace]") {
+            return Ok(dir);
+        }
+        if !dir.pop() {
+            return Err("Could not find the workspace Cargo.toml".into());
+        }
+    }
+}
+
+/// Resolves the crate directory name for `day`.
+///
+/// When `year` is not given, the most recent year that has a crate for that day is
+/// used.
+///
+/// # Errors
+///
+/// Returns an error if no matching day crate exists in the workspace.
+pub fn find_day(root: &Path, year: Option<u32>, day: u32) -> Result<String, Box<dyn Error>> {
+    if let Some(year) = year {
+        let name = crate_name(year, day);
+        if !root.join(&name).join("Cargo.toml").is_file() {
+            return Err(format!("No crate '{}' in the workspace", name).into());
+        }
+        return Ok(name);
+    }
+
+    let prefix = format!("day{:02}_", day);
+    let mut candidates: Vec<String> = fs::read_dir(root)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with(&prefix) && root.join(name).join("Cargo.toml").is_file())
+        .collect();
+    candidates.sort();
+    candidates
+        .pop()
+        .ok_or_else(|| format!("No crate for day {} in the workspace", day).into())
+}
+
+/// Lists the day crates in the workspace, optionally only those for `year`, ordered
+/// by year and then day.
+///
+/// # Errors
+///
+/// Returns an error if the workspace directory cannot be read.
+pub fn days(root: &Path, year: Option<u32>) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut days: Vec<(u32, u32, String)> = fs::read_dir(root)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| root.join(name).join("Cargo.toml").is_file())
+        .filter_map(|name| {
+            let (crate_year, day) = parse_crate_name(&name)?;
+            year.is_none_or(|y| y == crate_year)
+                .then_some((crate_year, day, name))
+        })
+        .collect();
+    days.sort();
+    Ok(days.into_iter().map(|(_, _, name)| name).collect())
+}
+
+/// Builds the given day crates in release mode with a single cargo invocation, so
+/// that running them afterwards does not compile anything.
+///
+/// # Errors
+///
+/// Returns an error if cargo cannot be started or the build fails.
+pub fn build_days(root: &Path, names: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut command = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()));
+    command.args(["build", "--quiet", "--release"]);
+    for name in names {
+        command.args(["-p", name]);
+    }
+    let output = command.current_dir(root).output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Build failed:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Runs a day crate in release mode from its own directory, passing `args` through to
+/// the day binary, and returns what it printed.
+///
+/// # Errors
+///
+/// Returns an error if cargo cannot be started or the day exits unsuccessfully.
+pub fn run_day(root: &Path, name: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+    let output = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["run", "--quiet", "--release", "-p", name, "--"])
+        .args(args)
+        .current_dir(root.join(name))
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed:\n{}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        )
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Adds `member` to the workspace `Cargo.toml` located in `root`.
+///
+/// # Errors
+///
+/// Returns an error if the manifest cannot be read or written, or has no members list.
+pub fn register_member(root: &Path, member: &str) -> Result<(), Box<dyn Error>> {
+    let manifest = root.join("Cargo.toml");
+    let content = fs::read_to_string(&manifest)?;
+    fs::write(&manifest, add_member(&content, member)?)?;
+    Ok(())
+}
+
+/// Returns `manifest` with `member` inserted into its `members = [...]` array.
+///
+/// The members are rewritten one per line in sorted order, so day crates stay grouped
+/// chronologically. Adding an existing member leaves the list unchanged.
+fn add_member(manifest: &str, member: &str) -> Result<String, Box<dyn Error>> {
+    let start = manifest
+        .find("members = [")
+        .ok_or("Workspace Cargo.toml has no 'members = [' list")?;
+    let list_start = start + "members = [".len();
+    let list_end = list_start
+        + manifest[list_start..]
+            .find(']')
+            .ok_or("Unterminated workspace members list")?;
+
+    let mut members: Vec<String> = manifest[list_start..list_end]
+        .split(',')
+        .map(|m| m.trim().trim_matches('"').to_string())
+        .filter(|m| !m.is_empty())
+        .collect();
+    if !members.iter().any(|m| m == member) {
+        members.push(member.to_string());
+    }
+    members.sort();
+
+    let mut list = String::from("\n");
+    for m in &members {
+        list.push_str(&format!("    \"{}\",\n", m));
+    }
+
+    Ok(format!(
+        "{}{}{}",
+        &manifest[..list_start],
+        list,
+        &manifest[list_end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "\
+[package]
+name = \"adventofcode\"
+
+[workspace]
+members = [
+    \"aoclib\",
+    \"day01_2025\",
+    \"day03_2025\",
+]
+";
+
+    #[test]
+    fn test_crate_name() {
+        assert_eq!(crate_name(2025, 3), "day03_2025");
+        assert_eq!(crate_name(2024, 25), "day25_2024");
+    }
+
+    #[test]
+    fn test_parse_crate_name() {
+        assert_eq!(parse_crate_name("day03_2025"), Some((2025, 3)));
+        assert_eq!(parse_crate_name(&crate_name(2024, 25)), Some((2024, 25)));
+        assert_eq!(parse_crate_name("aoclib"), None);
+        assert_eq!(parse_crate_name("day_x"), None);
+    }
+
+    #[test]
+    fn test_find_day() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        assert_eq!(find_day(root, None, 1).unwrap(), "day01_2025");
+        assert_eq!(find_day(root, Some(2025), 3).unwrap(), "day03_2025");
+        assert!(find_day(root, Some(1999), 3).is_err());
+        assert!(find_day(root, None, 26).is_err());
+    }
+
+    #[test]
+    fn test_days() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let days = days(root, None).unwrap();
+        assert_eq!(&days[..2], ["day01_2025", "day02_2025"]);
+        assert!(!days.iter().any(|d| d == "aoclib" || d == "regression"));
+        assert_eq!(super::days(root, Some(2025)).unwrap(), days);
+        assert!(super::days(root, Some(1999)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_member_sorted() {
+        let updated = add_member(MANIFEST, "day02_2025").unwrap();
+        assert!(updated.contains(
+            "members = [\n    \"aoclib\",\n    \"day01_2025\",\n    \"day02_2025\",\n    \"day03_2025\",\n]"
+        ));
+        assert!(updated.starts_with("[package]\nname = \"adventofcode\""));
+    }
+
+    #[test]
+    fn test_add_member_existing() {
+        let updated = add_member(MANIFEST, "day01_2025").unwrap();
+        assert_eq!(updated, MANIFEST);
+    }
+
+    #[test]
+    fn test_add_member_single_line_list() {
+        let updated = add_member("[workspace]\nmembers = [\"aoclib\"]\n", "day01_2025").unwrap();
+        assert_eq!(
+            updated,
+            "[workspace]\nmembers = [\n    \"aoclib\",\n    \"day01_2025\",\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_add_member_missing_list() {
+        assert!(add_member("[package]\nname = \"x\"\n", "day01_2025").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! Compares the ways aoclib can read a very large input: copying it whole onto the heap
+//! with `read_input`, mapping it with `read_input_mmap`, and streaming it line by line
+//! with `parse_lines_iter`.
+//!
+//! ```text
+//! cargo run --release -p aoclib --features mmap --example read_large -- [FILE | --generate MB]
+//! ```
+//!
+//! With `--generate MB` (256 by default), a file of that many megabytes of numbers is
+//! written to the temporary directory and removed afterwards. Each method sums every
+//! number, and is timed together with how much heap (anonymous memory) it grew by.
+
+use aoclib::bench::time;
+use aoclib::fmt::{duration, thousands};
+use aoclib::{parse_lines_iter, read_input, read_input_mmap};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (path, generated) = match args.first().map(String::as_str) {
+        None | Some("--generate") => {
+            let megabytes = match args.get(1) {
+                Some(mb) => mb.parse().map_err(|_| format!("Invalid size '{}'", mb))?,
+                None => 256,
+            };
+            (generate(megabytes)?, true)
+        }
+        Some(path) => (PathBuf::from(path), false),
+    };
+
+    let size = fs::metadata(&path)?.len();
+    println!("{}: {} MB", path.display(), thousands(size / 1_000_000));
+    println!("method                  time   heap growth  sum");
+
+    let sum = |input: &str| -> u64 {
+        input
+            .lines()
+            .map(|line| line.parse::<u64>().unwrap_or(0))
+            .sum()
+    };
+    let report = |name: &str, result: Result<(u64, u64), Box<dyn Error>>, elapsed| match result {
+        Ok((total, heap)) => println!(
+            "{:<18} {:>9}  {:>9} MB  {}",
+            name,
+            duration(elapsed),
+            thousands(heap / 1_000_000),
+            total
+        ),
+        Err(e) => println!("{:<18} failed: {}", name, e),
+    };
+
+    let (result, elapsed) = time(|| -> Result<_, Box<dyn Error>> {
+        let before = heap_bytes();
+        let input = read_input(&path)?;
+        let grown = heap_bytes().saturating_sub(before);
+        Ok((sum(&input), grown))
+    });
+    report("read_input", result, elapsed);
+
+    let (result, elapsed) = time(|| -> Result<_, Box<dyn Error>> {
+        let before = heap_bytes();
+        // SAFETY: nothing else writes to the file while it is mapped
+        let input = unsafe { read_input_mmap(&path)? };
+        let total = sum(&input);
+        Ok((total, heap_bytes().saturating_sub(before)))
+    });
+    report("read_input_mmap", result, elapsed);
+
+    let (result, elapsed) = time(|| -> Result<_, Box<dyn Error>> {
+        let before = heap_bytes();
+        let mut total = 0;
+        for n in parse_lines_iter::<u64, _>(&path)? {
+            total += n?;
+        }
+        Ok((total, heap_bytes().saturating_sub(before)))
+    });
+    report("parse_lines_iter", result, elapsed);
+
+    if generated {
+        fs::remove_file(&path)?;
+    }
+    Ok(())
+}
+
+/// Writes about `megabytes` MB of pseudo-random numbers, one per line, to a file in the
+/// temporary directory and returns its path.
+fn generate(megabytes: u64) -> Result<PathBuf, Box<dyn Error>> {
+    let path = std::env::temp_dir().join("aoclib_read_large.txt");
+    let mut out = BufWriter::new(File::create(&path)?);
+    let mut seed = 2025u64;
+    let mut written = 0;
+    while written < megabytes * 1_000_000 {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let line = format!("{}\n", seed >> 40);
+        out.write_all(line.as_bytes())?;
+        written += line.len() as u64;
+    }
+    out.flush()?;
+    Ok(path)
+}
+
+/// The process's current anonymous (heap) memory in bytes, from `/proc/self/status`,
+/// or 0 where that is not available.
+fn heap_bytes() -> u64 {
+    let status = fs::read_to_string(Path::new("/proc/self/status")).unwrap_or_default();
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("RssAnon:"))
+        .and_then(|kb| kb.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        .map_or(0, |kb| kb * 1024)
+}
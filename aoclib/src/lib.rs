@@ -1,5 +1,89 @@
+//! Shared helpers for Advent of Code solutions.
+//!
+//! Everything a day crate should use is re-exported from the crate root, either as an
+//! item or as a module. The `lib` module holds the implementation and is hidden from the
+//! docs; its paths are not part of the stable API and may move between releases. The
+//! public surface is pinned by the snapshot in `tests/api_surface.txt`.
+
+#[doc(hidden)]
 pub mod lib {
+    pub mod bench;
+    pub mod circuit;
+    pub mod circular;
+    pub mod collections;
+    pub mod cycle;
+    pub mod dp;
+    pub mod error;
+    pub mod fmt;
+    pub mod geometry;
+    pub mod graph;
+    pub mod grid;
+    pub mod iter;
+    pub mod match_;
+    pub mod math;
+    #[cfg(all(feature = "mmap", unix, target_pointer_width = "64"))]
+    pub mod mmap;
+    pub mod numeral;
     pub mod parser;
+    pub mod point;
+    pub mod postprocess;
+    pub mod progress;
+    #[cfg(feature = "regex")]
+    pub mod regex;
+    pub mod render;
+    pub mod rules;
+    pub mod scan;
+    pub mod search;
+    pub mod sim;
+    pub mod solver;
+    pub mod subsequence;
+    pub mod tiles;
+    pub mod vm;
+    pub mod walk;
 }
 
-pub use lib::parser::*;
+pub use lib::bench;
+pub use lib::circuit;
+pub use lib::circular;
+pub use lib::collections;
+pub use lib::cycle;
+pub use lib::dp;
+pub use lib::error::AocError;
+pub use lib::fmt;
+pub use lib::geometry;
+pub use lib::graph;
+pub use lib::grid::{
+    flip_h, flip_v, mismatch_count, rotate_ccw, rotate_cw, transpose, BitGrid, Grid, GridDiff,
+    GridDisplay, InfiniteGrid, PackedGrid, Trails, WrappingGrid,
+};
+pub use lib::iter;
+pub use lib::iter::AocIterExt;
+pub use lib::match_;
+pub use lib::math;
+#[cfg(all(feature = "mmap", unix, target_pointer_width = "64"))]
+pub use lib::mmap::{read_input_mmap, MappedInput};
+pub use lib::numeral;
+pub use lib::parser::{
+    parse_columns, parse_columns_str, parse_columns_transposed, parse_columns_transposed_str,
+    parse_delimited, parse_delimited_lines, parse_delimited_lines_str, parse_delimited_str,
+    parse_lines, parse_lines_iter, parse_lines_str, parse_lines_with, parse_lines_with_iter,
+    parse_lines_with_str, parse_record, parse_records, parse_records_str, parse_sections,
+    parse_sections_str, parse_with, parse_with_str, read_input, ParseOptions, STDIN,
+};
+pub use lib::point::{Direction, Point2};
+pub use lib::postprocess;
+pub use lib::progress;
+#[cfg(feature = "regex")]
+pub use lib::regex;
+#[cfg(feature = "regex")]
+pub use lib::regex::{parse_lines_regex, parse_lines_regex_str};
+pub use lib::render;
+pub use lib::rules;
+pub use lib::scan;
+pub use lib::search;
+pub use lib::sim;
+pub use lib::solver::{example_file, format_answers, input_file, load_input, run, Answer, Solver};
+pub use lib::subsequence;
+pub use lib::tiles;
+pub use lib::vm;
+pub use lib::walk;
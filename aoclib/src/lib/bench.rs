@@ -0,0 +1,183 @@
+use crate::Solver;
+use std::error::Error;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Summary statistics over repeated timings of one phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// The fastest run.
+    pub min: Duration,
+    /// The median run (the mean of the two middle runs for an even count).
+    pub median: Duration,
+    /// The arithmetic mean of all runs.
+    pub mean: Duration,
+}
+
+impl Stats {
+    /// Computes statistics from a non-empty set of samples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::bench::Stats;
+    /// use std::time::Duration;
+    ///
+    /// let ms = Duration::from_millis;
+    /// let stats = Stats::from_samples(&[ms(4), ms(1), ms(3), ms(2)]);
+    /// assert_eq!(stats.min, ms(1));
+    /// assert_eq!(stats.median, Duration::from_micros(2500));
+    /// assert_eq!(stats.mean, Duration::from_micros(2500));
+    /// ```
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "cannot compute stats of zero samples");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        };
+        let total: Duration = sorted.iter().sum();
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: total / n as u32,
+        }
+    }
+}
+
+/// Times `f` once, returning its result and the elapsed time.
+pub fn time<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Benchmarks a solver's `parse`, `part1` and `part2` separately.
+///
+/// Each phase is run `runs` times. The parts are timed against a single parsed input,
+/// so their timings exclude parsing.
+///
+/// # Returns
+///
+/// The statistics of each phase, labelled `parse`, `part1` and `part2`.
+///
+/// # Errors
+///
+/// Returns an error if `runs` is zero or the input fails to parse.
+pub fn bench_solver<S: Solver>(
+    solver: &S,
+    content: &str,
+    runs: usize,
+) -> Result<Vec<(&'static str, Stats)>, Box<dyn Error>> {
+    if runs == 0 {
+        return Err("Number of benchmark runs must be at least 1".into());
+    }
+
+    let mut parse_samples = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let (parsed, elapsed) = time(|| solver.parse(black_box(content)));
+        black_box(parsed?);
+        parse_samples.push(elapsed);
+    }
+
+    let input = solver.parse(content)?;
+    let part1_samples: Vec<Duration> = (0..runs)
+        .map(|_| time(|| black_box(solver.part1(black_box(&input)))).1)
+        .collect();
+    let part2_samples: Vec<Duration> = (0..runs)
+        .map(|_| time(|| black_box(solver.part2(black_box(&input)))).1)
+        .collect();
+
+    Ok(vec![
+        ("parse", Stats::from_samples(&parse_samples)),
+        ("part1", Stats::from_samples(&part1_samples)),
+        ("part2", Stats::from_samples(&part2_samples)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Answer;
+
+    struct Lines;
+
+    impl Solver for Lines {
+        type Input = Vec<String>;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            if input.is_empty() {
+                return Err("empty input".into());
+            }
+            Ok(input.lines().map(str::to_string).collect())
+        }
+
+        fn part1(&self, input: &Self::Input) -> Answer {
+            input.len().into()
+        }
+
+        fn part2(&self, input: &Self::Input) -> Answer {
+            input.iter().map(String::len).sum::<usize>().into()
+        }
+    }
+
+    #[test]
+    fn test_stats_odd_count() {
+        let ns = Duration::from_nanos;
+        let stats = Stats::from_samples(&[ns(30), ns(10), ns(20)]);
+        assert_eq!(stats.min, ns(10));
+        assert_eq!(stats.median, ns(20));
+        assert_eq!(stats.mean, ns(20));
+    }
+
+    #[test]
+    fn test_stats_single_sample() {
+        let d = Duration::from_millis(5);
+        let stats = Stats::from_samples(&[d]);
+        assert_eq!(
+            stats,
+            Stats {
+                min: d,
+                median: d,
+                mean: d
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stats_empty() {
+        Stats::from_samples(&[]);
+    }
+
+    #[test]
+    fn test_time_returns_result() {
+        let (value, _) = time(|| 6 * 7);
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn test_bench_solver_phases() {
+        let results = bench_solver(&Lines, "a\nbb\nccc", 3).unwrap();
+        let phases: Vec<&str> = results.iter().map(|(phase, _)| *phase).collect();
+        assert_eq!(phases, vec!["parse", "part1", "part2"]);
+        for (_, stats) in results {
+            assert!(stats.min <= stats.median);
+        }
+    }
+
+    #[test]
+    fn test_bench_solver_errors() {
+        assert!(bench_solver(&Lines, "a", 0).is_err());
+        assert!(bench_solver(&Lines, "", 3).is_err());
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A two-input logic gate operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// Logical AND.
+    And,
+    /// Logical OR.
+    Or,
+    /// Exclusive OR.
+    Xor,
+}
+
+impl Op {
+    /// Applies the operation.
+    pub fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Op::And => a && b,
+            Op::Or => a || b,
+            Op::Xor => a ^ b,
+        }
+    }
+}
+
+impl FromStr for Op {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AND" => Ok(Op::And),
+            "OR" => Ok(Op::Or),
+            "XOR" => Ok(Op::Xor),
+            _ => Err(format!("Unknown gate '{}', expected AND, OR or XOR", s)),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Op::And => "AND",
+            Op::Or => "OR",
+            Op::Xor => "XOR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A gate reading two wires and driving a third, written `a OP b -> out`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gate {
+    /// First input wire.
+    pub a: String,
+    /// The operation.
+    pub op: Op,
+    /// Second input wire.
+    pub b: String,
+    /// Output wire.
+    pub out: String,
+}
+
+impl Gate {
+    /// Returns `true` if the gate reads `wire`.
+    pub fn reads(&self, wire: &str) -> bool {
+        self.a == wire || self.b == wire
+    }
+}
+
+/// A network of logic gates with named input wires, as in "crossed wires" puzzles.
+///
+/// By convention the bits of the numbers being combined are on wires `x00`, `x01`, ...
+/// and `y00`, `y01`, ..., and the result is read from `z00`, `z01`, ..., least
+/// significant bit first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Circuit {
+    /// Initial values of the input wires.
+    pub inputs: BTreeMap<String, bool>,
+    /// Every gate, in input order.
+    pub gates: Vec<Gate>,
+}
+
+impl Circuit {
+    /// Parses optional `wire: 0|1` lines followed by `a OP b -> out` gate lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first malformed line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::circuit::Circuit;
+    ///
+    /// let circuit = Circuit::parse("x00: 1\ny00: 1\n\nx00 AND y00 -> z00\n").unwrap();
+    /// let wires = circuit.evaluate().unwrap();
+    /// assert_eq!(Circuit::number(&wires, 'z'), 1);
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let mut inputs = BTreeMap::new();
+        let mut gates = Vec::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |msg: String| format!("line {}: {}", i + 1, msg);
+
+            if let Some((wire, value)) = line.split_once(':') {
+                let value = match value.trim() {
+                    "0" => false,
+                    "1" => true,
+                    other => return Err(error(format!("expected 0 or 1, got '{}'", other)).into()),
+                };
+                inputs.insert(wire.trim().to_string(), value);
+                continue;
+            }
+
+            match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [a, op, b, "->", out] => gates.push(Gate {
+                    a: a.to_string(),
+                    op: op.parse().map_err(error)?,
+                    b: b.to_string(),
+                    out: out.to_string(),
+                }),
+                _ => {
+                    let msg = format!("expected 'a OP b -> out', got '{}'", line);
+                    return Err(error(msg).into());
+                }
+            }
+        }
+
+        Ok(Circuit { inputs, gates })
+    }
+
+    /// Evaluates every wire from the circuit's own inputs.
+    ///
+    /// # Errors
+    ///
+    /// See [`Circuit::evaluate_with`].
+    pub fn evaluate(&self) -> Result<HashMap<String, bool>, String> {
+        self.evaluate_with(&self.inputs)
+    }
+
+    /// Evaluates every wire in dependency order, starting from `inputs`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a gate reads a wire that is neither an input nor driven by
+    /// a gate, or if gates form a feedback loop (which a bad set of swaps can cause).
+    pub fn evaluate_with(
+        &self,
+        inputs: &BTreeMap<String, bool>,
+    ) -> Result<HashMap<String, bool>, String> {
+        let drivers: HashMap<&str, &Gate> =
+            self.gates.iter().map(|g| (g.out.as_str(), g)).collect();
+        let mut values: HashMap<String, bool> = inputs.clone().into_iter().collect();
+
+        for gate in &self.gates {
+            // Iterative depth-first evaluation. A wire is "in progress" from when its
+            // inputs are queued until its value is known; meeting it again means a loop.
+            let mut in_progress = HashSet::new();
+            let mut stack = vec![(gate.out.as_str(), false)];
+            while let Some((wire, inputs_ready)) = stack.pop() {
+                if values.contains_key(wire) {
+                    continue;
+                }
+                let g = drivers
+                    .get(wire)
+                    .ok_or_else(|| format!("Wire '{}' has no value and no driving gate", wire))?;
+                if inputs_ready {
+                    let value = g.op.apply(values[&g.a], values[&g.b]);
+                    values.insert(wire.to_string(), value);
+                    in_progress.remove(wire);
+                    continue;
+                }
+                if !in_progress.insert(wire) {
+                    return Err(format!("Gates form a loop through wire '{}'", wire));
+                }
+                stack.push((wire, true));
+                stack.push((g.a.as_str(), false));
+                stack.push((g.b.as_str(), false));
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Reads the number on the wires starting with `prefix` (`x`, `y` or `z`), where
+    /// the digits after the prefix give the bit position.
+    pub fn number(values: &HashMap<String, bool>, prefix: char) -> u64 {
+        values
+            .iter()
+            .filter(|(_, &on)| on)
+            .filter_map(|(wire, _)| wire.strip_prefix(prefix)?.parse::<u32>().ok())
+            .fold(0, |n, bit| n | 1u64.checked_shl(bit).unwrap_or(0))
+    }
+
+    /// Builds the input wires for adding `x` and `y` as `bits`-bit numbers.
+    pub fn adder_inputs(x: u64, y: u64, bits: u32) -> BTreeMap<String, bool> {
+        (0..bits)
+            .flat_map(|i| {
+                [
+                    (format!("x{:02}", i), x >> i & 1 == 1),
+                    (format!("y{:02}", i), y >> i & 1 == 1),
+                ]
+            })
+            .collect()
+    }
+
+    /// Returns the number of bits of the `x` input.
+    pub fn input_bits(&self) -> u32 {
+        let wires = self
+            .gates
+            .iter()
+            .flat_map(|g| [&g.a, &g.b])
+            .chain(self.inputs.keys());
+        wires
+            .filter_map(|w| w.strip_prefix('x')?.parse::<u32>().ok())
+            .map(|bit| bit + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Swaps the output wires of the gates driving `a` and `b`.
+    ///
+    /// Returns `false` and leaves the circuit unchanged if either wire is not driven by
+    /// a gate.
+    pub fn swap_outputs(&mut self, a: &str, b: &str) -> bool {
+        let ia = self.gates.iter().position(|g| g.out == a);
+        let ib = self.gates.iter().position(|g| g.out == b);
+        match (ia, ib) {
+            (Some(ia), Some(ib)) => {
+                self.gates[ia].out = b.to_string();
+                self.gates[ib].out = a.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks the circuit by adding single-bit and carry-producing test values, and
+    /// returns the bit positions whose sum comes out wrong.
+    ///
+    /// For each bit `i` it tries `2^i + 0`, `0 + 2^i` and `2^i + 2^i`, which exercises
+    /// the sum and carry logic of that bit in isolation. Evaluation errors mark the bit
+    /// as wrong too.
+    pub fn adder_errors(&self) -> Vec<u32> {
+        let bits = self.input_bits();
+        (0..bits)
+            .filter(|&i| {
+                let bit = 1u64 << i;
+                [(bit, 0), (0, bit), (bit, bit)].iter().any(|&(x, y)| {
+                    let sum = self
+                        .evaluate_with(&Circuit::adder_inputs(x, y, bits))
+                        .map(|values| Circuit::number(&values, 'z'));
+                    sum != Ok(x + y)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the output wires of gates that break the structure of a ripple-carry
+    /// adder, sorted by name.
+    ///
+    /// In a correctly wired adder:
+    ///
+    /// * every `z` wire is driven by an XOR, except the final carry-out, which is an OR;
+    /// * an XOR of anything other than `x`/`y` inputs drives a `z` wire;
+    /// * an XOR of `x`/`y` inputs (other than bit 0) feeds another XOR;
+    /// * an AND (other than of `x00` and `y00`) feeds an OR.
+    ///
+    /// In "find the swapped wires" puzzles the flagged wires are the swapped ones.
+    pub fn miswired_adder_outputs(&self) -> Vec<String> {
+        let is_input = |w: &str| w.starts_with('x') || w.starts_with('y');
+        let is_first_bit = |g: &Gate| g.reads("x00") && g.reads("y00");
+        let last_z = format!("z{:02}", self.input_bits());
+        let feeds = |wire: &str, op: Op| self.gates.iter().any(|g| g.op == op && g.reads(wire));
+
+        let mut suspicious: Vec<String> = self
+            .gates
+            .iter()
+            .filter(|g| {
+                let xy_inputs = is_input(&g.a) && is_input(&g.b);
+                if g.out.starts_with('z') {
+                    return if g.out == last_z {
+                        g.op != Op::Or
+                    } else {
+                        g.op != Op::Xor
+                    };
+                }
+                match g.op {
+                    Op::Xor if !xy_inputs => true,
+                    Op::Xor => !is_first_bit(g) && !feeds(&g.out, Op::Xor),
+                    Op::And => !is_first_bit(g) && !feeds(&g.out, Op::Or),
+                    Op::Or => false,
+                }
+            })
+            .map(|g| g.out.clone())
+            .collect();
+        suspicious.sort();
+        suspicious.dedup();
+        suspicious
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "\
+x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+";
+
+    /// Builds a correct ripple-carry adder for `bits`-bit inputs.
+    fn ripple_carry_adder(bits: u32) -> Circuit {
+        let mut lines = vec![
+            "x00 XOR y00 -> z00".to_string(),
+            "x00 AND y00 -> c00".to_string(),
+        ];
+        for i in 1..bits {
+            let carry_out = if i == bits - 1 {
+                format!("z{:02}", bits)
+            } else {
+                format!("c{:02}", i)
+            };
+            lines.extend([
+                format!("x{0:02} XOR y{0:02} -> s{0:02}", i),
+                format!("y{0:02} AND x{0:02} -> a{0:02}", i),
+                format!("c{1:02} XOR s{0:02} -> z{0:02}", i, i - 1),
+                format!("s{0:02} AND c{1:02} -> b{0:02}", i, i - 1),
+                format!("a{0:02} OR b{0:02} -> {1}", i, carry_out),
+            ]);
+        }
+        Circuit::parse(&lines.join("\n")).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_evaluate_small() {
+        let circuit = Circuit::parse(SMALL).unwrap();
+        assert_eq!(circuit.inputs.len(), 6);
+        assert_eq!(circuit.gates.len(), 3);
+        assert_eq!(circuit.gates[1].op, Op::Xor);
+        let values = circuit.evaluate().unwrap();
+        assert_eq!(Circuit::number(&values, 'z'), 4);
+        assert_eq!(Circuit::number(&values, 'x'), 7);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Circuit::parse("x00: 2").is_err());
+        assert!(Circuit::parse("x00 NAND y00 -> z00").is_err());
+        let err = Circuit::parse("x00: 1\nx00 AND y00 z00").unwrap_err();
+        assert!(err.to_string().starts_with("line 2"));
+    }
+
+    #[test]
+    fn test_evaluate_out_of_order_gates() {
+        let circuit =
+            Circuit::parse("a OR b -> z00\nx00 XOR y00 -> a\nx00 AND y00 -> b\nx00: 1\ny00: 1")
+                .unwrap();
+        assert!(circuit.evaluate().unwrap()["z00"]);
+    }
+
+    #[test]
+    fn test_evaluate_shared_inputs() {
+        // `a` feeds both `b` and `z00`, and `c` reads the same wire twice
+        let circuit = Circuit::parse(
+            "a OR b -> z00\na AND x00 -> b\nx00 XOR y00 -> a\na XOR a -> c\nx00: 1\ny00: 0",
+        )
+        .unwrap();
+        let values = circuit.evaluate().unwrap();
+        assert!(values["z00"]);
+        assert!(values["b"]);
+        assert!(!values["c"]);
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        let missing = Circuit::parse("x00 AND q -> z00\nx00: 1").unwrap();
+        assert!(missing.evaluate().unwrap_err().contains("'q'"));
+        let looped = Circuit::parse("x00 AND b -> a\nx00 OR a -> b\nx00: 1").unwrap();
+        assert!(looped.evaluate().unwrap_err().contains("loop"));
+    }
+
+    #[test]
+    fn test_ripple_carry_adder_adds() {
+        let adder = ripple_carry_adder(8);
+        assert_eq!(adder.input_bits(), 8);
+        for (x, y) in [(0, 0), (1, 1), (200, 100), (255, 255), (37, 219)] {
+            let values = adder
+                .evaluate_with(&Circuit::adder_inputs(x, y, 8))
+                .unwrap();
+            assert_eq!(Circuit::number(&values, 'z'), x + y);
+        }
+        assert!(adder.adder_errors().is_empty());
+        assert!(adder.miswired_adder_outputs().is_empty());
+    }
+
+    #[test]
+    fn test_detects_swapped_outputs() {
+        let mut adder = ripple_carry_adder(8);
+        assert!(adder.swap_outputs("s03", "a03"));
+        assert!(adder.swap_outputs("z05", "b05"));
+        assert_eq!(
+            adder.miswired_adder_outputs(),
+            vec!["a03", "b05", "s03", "z05"]
+        );
+        let errors = adder.adder_errors();
+        assert!(errors.contains(&3) && errors.contains(&5), "{:?}", errors);
+
+        // Undoing the swaps repairs the adder
+        adder.swap_outputs("s03", "a03");
+        adder.swap_outputs("z05", "b05");
+        assert!(adder.adder_errors().is_empty());
+    }
+
+    #[test]
+    fn test_swap_outputs_unknown_wire() {
+        let mut circuit = Circuit::parse(SMALL).unwrap();
+        let before = circuit.clone();
+        assert!(!circuit.swap_outputs("z00", "nope"));
+        assert_eq!(circuit, before);
+    }
+}
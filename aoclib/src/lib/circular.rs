@@ -0,0 +1,149 @@
+/// Mixes a circular list: each value, in its original order, moves forward (or backward,
+/// if negative) by its own value, and this is repeated for `rounds` rounds. Every value
+/// is first multiplied by `key`.
+///
+/// Values are tracked by their original position, so duplicates are handled correctly.
+/// Moving one value past all `n - 1` others puts it back where it started, so moves are
+/// reduced modulo `n - 1` and large keys cost nothing extra.
+///
+/// Returns the mixed (and multiplied) values, rotated to start with the value that was
+/// first in the input.
+///
+/// # Panics
+///
+/// Panics if multiplying a value by `key` overflows `i64`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::circular::{grove_sum, mix};
+///
+/// let mixed = mix(&[1, 2, -3, 3, -2, 0, 4], 1, 1);
+/// assert_eq!(mixed, vec![1, 2, -3, 4, 0, 3, -2]);
+/// assert_eq!(grove_sum(&mixed), Some(3));
+/// ```
+pub fn mix(values: &[i64], rounds: usize, key: i64) -> Vec<i64> {
+    let values: Vec<i64> = values
+        .iter()
+        .map(|&v| {
+            v.checked_mul(key)
+                .unwrap_or_else(|| panic!("{} * {} overflowed i64", v, key))
+        })
+        .collect();
+    let n = values.len();
+    if n < 2 {
+        return values;
+    }
+
+    // order[i] is the original index of the value at position i
+    let mut order: Vec<usize> = (0..n).collect();
+    let cycle = n as i64 - 1;
+    for _ in 0..rounds {
+        for (original, &value) in values.iter().enumerate() {
+            let from = order
+                .iter()
+                .position(|&o| o == original)
+                .expect("every value is in the list");
+            order.remove(from);
+            let to = (from as i64 + value.rem_euclid(cycle)).rem_euclid(cycle) as usize;
+            order.insert(to, original);
+        }
+    }
+
+    let start = order.iter().position(|&o| o == 0).unwrap_or(0);
+    order.rotate_left(start);
+    order.into_iter().map(|o| values[o]).collect()
+}
+
+/// Returns the sum of the values 1000, 2000 and 3000 places after the first zero,
+/// wrapping around the list, or `None` if there is no zero.
+pub fn grove_sum(mixed: &[i64]) -> Option<i64> {
+    let zero = mixed.iter().position(|&v| v == 0)?;
+    Some(
+        [1000, 2000, 3000]
+            .iter()
+            .map(|offset| mixed[(zero + offset) % mixed.len()])
+            .sum(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [i64; 7] = [1, 2, -3, 3, -2, 0, 4];
+
+    /// Rotates a circular list to start at its first zero, for comparing mixes.
+    fn from_zero(list: &[i64]) -> Vec<i64> {
+        let mut list = list.to_vec();
+        let zero = list.iter().position(|&v| v == 0).unwrap();
+        list.rotate_left(zero);
+        list
+    }
+
+    #[test]
+    fn test_decryption_key() {
+        let key = 811589153;
+        let once = mix(&EXAMPLE, 1, key);
+        assert_eq!(
+            from_zero(&once),
+            vec![
+                0,
+                -2434767459,
+                3246356612,
+                -1623178306,
+                2434767459,
+                1623178306,
+                811589153
+            ]
+        );
+        let mixed = mix(&EXAMPLE, 10, key);
+        assert_eq!(
+            from_zero(&mixed),
+            vec![
+                0,
+                -2434767459,
+                1623178306,
+                3246356612,
+                -1623178306,
+                2434767459,
+                811589153
+            ]
+        );
+        assert_eq!(grove_sum(&mixed), Some(1623178306));
+    }
+
+    #[test]
+    fn test_duplicates_and_wraparound() {
+        // Moving by a multiple of n - 1 is a full lap and changes nothing
+        assert_eq!(mix(&[0, 3, 5], 1, 1), vec![0, 3, 5]);
+        // Equal values still move one at a time, in their original order
+        assert_eq!(mix(&[1, 1, 0], 1, 1), vec![1, 1, 0]);
+        assert_eq!(mix(&[2, 0, 2, 0], 1, 1), vec![2, 0, 2, 0]);
+        assert_eq!(mix(&[], 3, 5), Vec::<i64>::new());
+        assert_eq!(mix(&[7], 3, 5), vec![35]);
+        assert_eq!(grove_sum(&[1, 2]), None);
+    }
+
+    #[test]
+    fn test_matches_step_by_step_moves() {
+        // Moving one place at a time with swaps must agree with the modular shortcut
+        let values = [4, -7, 0, 11, -1, 3, 3, -9];
+        let n = values.len();
+        let mut list: Vec<(usize, i64)> = values.iter().copied().enumerate().collect();
+        for (original, &value) in values.iter().enumerate() {
+            let mut i = list.iter().position(|&(o, _)| o == original).unwrap();
+            for _ in 0..value.unsigned_abs() {
+                let j = if value > 0 {
+                    (i + 1) % n
+                } else {
+                    (i + n - 1) % n
+                };
+                list.swap(i, j);
+                i = j;
+            }
+        }
+        let brute: Vec<i64> = list.iter().map(|&(_, v)| v).collect();
+        assert_eq!(from_zero(&mix(&values, 1, 1)), from_zero(&brute));
+    }
+}
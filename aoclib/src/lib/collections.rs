@@ -0,0 +1,826 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, Range, Sub};
+
+/// A union-find structure over the elements `0..n`, with path compression and union
+/// by rank.
+///
+/// Map puzzle entities (grid cells, junction boxes, programs) to indices to track which
+/// of them are connected as edges are added.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::collections::DisjointSet;
+///
+/// let mut sets = DisjointSet::new(5);
+/// sets.union(0, 1);
+/// sets.union(3, 4);
+/// sets.union(1, 2);
+/// assert!(sets.connected(0, 2));
+/// assert_eq!(sets.size_of(2), 3);
+/// assert_eq!(sets.components(), vec![vec![0, 1, 2], vec![3, 4]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    /// Creates `n` singleton sets.
+    pub fn new(n: usize) -> Self {
+        DisjointSet {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the number of disjoint sets.
+    pub fn component_count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the representative of the set containing `x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is out of range.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Point everything on the path straight at the root
+        let mut current = x;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`.
+    ///
+    /// Returns `false` if they were already in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.count -= 1;
+        true
+    }
+
+    /// Returns `true` if `a` and `b` are in the same set.
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the number of elements in the set containing `x`.
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Returns every set as a sorted list of its elements, ordered by smallest element.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut index: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let i = *index.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[i].push(x);
+        }
+        components
+    }
+}
+
+/// A set of integers stored as sorted, disjoint, half-open ranges.
+///
+/// Overlapping and touching ranges are merged as they are inserted, so `ranges()` is
+/// always the shortest description of the set. Set operations work range by range,
+/// which keeps them fast for the huge but simply shaped sets in seed-mapping and
+/// ID-range puzzles.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::collections::RangeSet;
+///
+/// let mut ids: RangeSet = [3..6, 10..15, 5..8].into_iter().collect();
+/// assert_eq!(ids.ranges(), &[3..8, 10..15]);
+/// assert_eq!(ids.len(), 10);
+///
+/// ids.remove(4..11);
+/// assert_eq!(ids.ranges(), &[3..4, 11..15]);
+/// assert!(ids.contains(12) && !ids.contains(8));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet {
+    ranges: Vec<Range<i64>>,
+}
+
+impl RangeSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        RangeSet::default()
+    }
+
+    /// Returns the disjoint ranges making up the set, in increasing order.
+    pub fn ranges(&self) -> &[Range<i64>] {
+        &self.ranges
+    }
+
+    /// Returns the number of integers in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|r| r.end.abs_diff(r.start)).sum()
+    }
+
+    /// Returns `true` if the set contains no integers.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns whether `value` is in the set.
+    pub fn contains(&self, value: i64) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Returns whether every integer in `range` is in the set. Empty ranges are always
+    /// contained.
+    pub fn contains_range(&self, range: Range<i64>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        self.ranges
+            .get(i)
+            .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    /// Adds every integer in `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        // Ranges ending before `range` starts, and ranges starting after it ends, are
+        // untouched; everything between merges into one
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = match self.ranges.get(first..last) {
+            Some(overlapping) if !overlapping.is_empty() => {
+                range.start.min(overlapping[0].start)
+                    ..range.end.max(overlapping[overlapping.len() - 1].end)
+            }
+            _ => range,
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Removes every integer in `range`.
+    pub fn remove(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first >= last {
+            return;
+        }
+        let (head, tail) = (self.ranges[first].start, self.ranges[last - 1].end);
+        let remaining = [head..range.start, range.end..tail]
+            .into_iter()
+            .filter(|r| !r.is_empty());
+        self.ranges.splice(first..last, remaining);
+    }
+
+    /// Returns the integers in either set.
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.insert(range.clone());
+        }
+        result
+    }
+
+    /// Returns the integers in both sets.
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// Returns the integers in this set but not in `other`.
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.remove(range.clone());
+        }
+        result
+    }
+
+    /// Iterates over every integer in the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = i64> + '_ {
+        self.ranges.iter().flat_map(Clone::clone)
+    }
+}
+
+impl FromIterator<Range<i64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/// A fixed-size array of counts indexed by age, where advancing a day shifts every
+/// cohort down one age in O(1).
+///
+/// Built for age-cohort population puzzles: rather than tracking individuals, count
+/// how many are at each age. Counts are `u128` so exponential populations stay exact
+/// for far longer than with `u64`, and every addition is overflow-checked.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::collections::RotatingCounts;
+///
+/// // Lanternfish: timers count down to 0, then reset to 6 and spawn a fish at 8
+/// let mut fish: RotatingCounts<9> = [3, 4, 3, 1, 2].into_iter().collect();
+/// for _ in 0..80 {
+///     let spawning = fish.advance(); // now counted at age 8, as newborns
+///     fish.add(6, spawning);
+/// }
+/// assert_eq!(fish.total(), 5934);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RotatingCounts<const N: usize> {
+    counts: [u128; N],
+    /// Physical index of age 0.
+    head: usize,
+}
+
+impl<const N: usize> RotatingCounts<N> {
+    /// Creates an array with every count zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new() -> Self {
+        assert!(N > 0, "RotatingCounts needs at least one age");
+        RotatingCounts {
+            counts: [0; N],
+            head: 0,
+        }
+    }
+
+    /// Returns the count at `age`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `age >= N`.
+    pub fn get(&self, age: usize) -> u128 {
+        self.counts[self.slot(age)]
+    }
+
+    /// Adds `n` to the count at `age`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `age >= N` or the count would overflow a `u128`.
+    pub fn add(&mut self, age: usize, n: u128) {
+        let slot = self.slot(age);
+        self.counts[slot] = self.counts[slot]
+            .checked_add(n)
+            .unwrap_or_else(|| panic!("count at age {} overflowed u128", age));
+    }
+
+    /// Advances one day: every cohort moves down one age, and the cohort at age 0 wraps
+    /// around to age `N - 1`. Returns the size of that wrapped cohort.
+    pub fn advance(&mut self) -> u128 {
+        let expired = self.counts[self.head];
+        self.head = (self.head + 1) % N;
+        expired
+    }
+
+    /// Returns the counts in age order.
+    pub fn to_array(&self) -> [u128; N] {
+        std::array::from_fn(|age| self.get(age))
+    }
+
+    /// Returns the sum of all counts, or `None` if it overflows a `u128`.
+    pub fn checked_total(&self) -> Option<u128> {
+        self.counts
+            .iter()
+            .try_fold(0u128, |acc, &n| acc.checked_add(n))
+    }
+
+    /// Returns the sum of all counts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum overflows a `u128`; see [`RotatingCounts::checked_total`].
+    pub fn total(&self) -> u128 {
+        self.checked_total().expect("total overflowed u128")
+    }
+
+    fn slot(&self, age: usize) -> usize {
+        assert!(age < N, "age {} is out of range for {} ages", age, N);
+        (self.head + age) % N
+    }
+}
+
+impl<const N: usize> Default for RotatingCounts<N> {
+    fn default() -> Self {
+        RotatingCounts::new()
+    }
+}
+
+impl<const N: usize> FromIterator<usize> for RotatingCounts<N> {
+    /// Counts one individual per age in the iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an age is `N` or more.
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut counts = RotatingCounts::new();
+        for age in iter {
+            counts.add(age, 1);
+        }
+        counts
+    }
+}
+
+/// A multiset that counts how many times each item occurs.
+///
+/// Items that are not present have a count of zero, and counts never go negative:
+/// subtracting removes items whose count drops to zero.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::collections::Counter;
+///
+/// let letters: Counter<char> = "abracadabra".chars().collect();
+/// assert_eq!(letters.count(&'a'), 5);
+/// assert_eq!(letters.count(&'z'), 0);
+/// assert_eq!(letters.most_common()[..2], [(&'a', 5), (&'b', 2)]);
+///
+/// let fewer = letters - "aab".chars().collect();
+/// assert_eq!(fewer.count(&'a'), 3);
+/// assert_eq!(fewer.total(), 8);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, u64>,
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    /// Creates an empty counter.
+    pub fn new() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Counts one more occurrence of `item`.
+    pub fn insert(&mut self, item: T) {
+        self.insert_n(item, 1);
+    }
+
+    /// Counts `n` more occurrences of `item`.
+    pub fn insert_n(&mut self, item: T, n: u64) {
+        if n > 0 {
+            *self.counts.entry(item).or_insert(0) += n;
+        }
+    }
+
+    /// Removes up to `n` occurrences of `item`, returning how many were removed.
+    pub fn remove_n(&mut self, item: &T, n: u64) -> u64 {
+        let Some(count) = self.counts.get_mut(item) else {
+            return 0;
+        };
+        let removed = n.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+        removed
+    }
+
+    /// Returns how many times `item` was counted.
+    pub fn count(&self, item: &T) -> u64 {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns `true` if nothing has been counted.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Returns the sum of all counts.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Iterates over the distinct items and their counts, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, u64)> {
+        self.counts.iter().map(|(item, &n)| (item, n))
+    }
+
+    /// Returns every item with its count, most common first. Ties are broken by item
+    /// order so the result is deterministic.
+    pub fn most_common(&self) -> Vec<(&T, u64)>
+    where
+        T: Ord,
+    {
+        let mut items: Vec<(&T, u64)> = self.iter().collect();
+        items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        items
+    }
+
+    /// Consumes the counter, returning the underlying map.
+    pub fn into_map(self) -> HashMap<T, u64> {
+        self.counts
+    }
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+impl<T: Eq + Hash> Add for Counter<T> {
+    type Output = Counter<T>;
+
+    /// Adds the counts of both counters item by item.
+    fn add(mut self, other: Counter<T>) -> Counter<T> {
+        for (item, n) in other.counts {
+            self.insert_n(item, n);
+        }
+        self
+    }
+}
+
+impl<T: Eq + Hash> Sub for Counter<T> {
+    type Output = Counter<T>;
+
+    /// Subtracts the counts of `other` item by item, dropping items that reach zero.
+    fn sub(mut self, other: Counter<T>) -> Counter<T> {
+        for (item, n) in &other.counts {
+            self.remove_n(item, *n);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_sets_are_singletons() {
+        let mut sets = DisjointSet::new(3);
+        assert_eq!(sets.len(), 3);
+        assert_eq!(sets.component_count(), 3);
+        assert!(!sets.connected(0, 1));
+        assert_eq!(sets.size_of(2), 1);
+        assert!(DisjointSet::new(0).is_empty());
+    }
+
+    #[test]
+    fn test_union_reports_merges() {
+        let mut sets = DisjointSet::new(4);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(!sets.union(1, 0));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.component_count(), 1);
+        assert_eq!(sets.size_of(0), 4);
+    }
+
+    #[test]
+    fn test_components() {
+        let mut sets = DisjointSet::new(6);
+        sets.union(5, 1);
+        sets.union(2, 4);
+        sets.union(4, 5);
+        assert_eq!(sets.components(), vec![vec![0], vec![1, 2, 4, 5], vec![3]]);
+    }
+
+    #[test]
+    fn test_long_chain_is_compressed() {
+        let n = 100_000;
+        let mut sets = DisjointSet::new(n);
+        for i in 1..n {
+            sets.union(i - 1, i);
+        }
+        assert_eq!(sets.size_of(0), n);
+        // Union by rank keeps trees shallow, and finds flatten them completely
+        assert!(sets.rank.iter().all(|&r| r <= 17));
+        let root = sets.find(n - 1);
+        for i in 0..n {
+            sets.find(i);
+        }
+        assert!(sets.parent.iter().all(|&p| p == root));
+    }
+
+    #[test]
+    fn test_grid_regions() {
+        // Count connected regions of '#' in a grid, indexing cells as y * width + x
+        let rows = ["##..#", "#...#", "..#..", "....."];
+        let width = rows[0].len();
+        let filled = |x: usize, y: usize| rows[y].as_bytes()[x] == b'#';
+        let mut sets = DisjointSet::new(width * rows.len());
+        for y in 0..rows.len() {
+            for x in 0..width {
+                if !filled(x, y) {
+                    continue;
+                }
+                if x + 1 < width && filled(x + 1, y) {
+                    sets.union(y * width + x, y * width + x + 1);
+                }
+                if y + 1 < rows.len() && filled(x, y + 1) {
+                    sets.union(y * width + x, (y + 1) * width + x);
+                }
+            }
+        }
+        let regions: Vec<usize> = sets
+            .components()
+            .into_iter()
+            .filter(|c| filled(c[0] % width, c[0] / width))
+            .map(|c| c.len())
+            .collect();
+        assert_eq!(regions, vec![3, 2, 1]);
+    }
+
+    /// Reference model: the set as a bitmap over 0..64.
+    fn bits(set: &RangeSet) -> u64 {
+        set.iter().fold(0, |acc, v| acc | 1 << v)
+    }
+
+    fn random_set(seed: &mut u64) -> (RangeSet, u64) {
+        let mut set = RangeSet::new();
+        let mut model = 0u64;
+        for _ in 0..4 {
+            *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let start = (*seed >> 58) as i64;
+            let len = (*seed >> 40 & 15) as i64;
+            let end = (start + len).min(64);
+            set.insert(start..end);
+            for v in start..end {
+                model |= 1 << v;
+            }
+        }
+        (set, model)
+    }
+
+    #[test]
+    fn test_range_set_insert_merges() {
+        let mut set = RangeSet::new();
+        set.insert(10..20);
+        set.insert(30..40);
+        set.insert(20..25); // touching merges
+        assert_eq!(set.ranges(), &[10..25, 30..40]);
+        set.insert(5..6);
+        set.insert(24..31); // bridges two ranges
+        assert_eq!(set.ranges(), &[5..6, 10..40]);
+        set.insert(7..7); // empty is ignored
+        set.insert(0..100);
+        assert_eq!(set.ranges().len(), 1);
+        assert_eq!(set.len(), 100);
+    }
+
+    #[test]
+    fn test_range_set_remove_boundaries() {
+        let mut set: RangeSet = [0..10, 20..30].into_iter().collect();
+        set.remove(10..20); // touches both ranges but removes nothing
+        assert_eq!(set.ranges(), &[0..10, 20..30]);
+        set.remove(5..25);
+        assert_eq!(set.ranges(), &[0..5, 25..30]);
+        set.remove(2..3); // splits a range
+        assert_eq!(set.ranges(), &[0..2, 3..5, 25..30]);
+        set.remove(i64::MIN..i64::MAX);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_range_set_contains() {
+        let set: RangeSet = [-5..0, 3..4].into_iter().collect();
+        assert!(set.contains(-5) && set.contains(-1) && set.contains(3));
+        assert!(!set.contains(0) && !set.contains(4) && !set.contains(-6));
+        assert!(set.contains_range(-4..-1));
+        assert!(!set.contains_range(-1..4));
+        assert!(set.contains_range(100..100));
+    }
+
+    #[test]
+    fn test_range_set_operations_match_bitmaps() {
+        let mut seed = 99;
+        for _ in 0..500 {
+            let (a, bits_a) = random_set(&mut seed);
+            let (b, bits_b) = random_set(&mut seed);
+            assert_eq!(bits(&a), bits_a);
+            assert_eq!(bits(&a.union(&b)), bits_a | bits_b);
+            assert_eq!(bits(&a.intersection(&b)), bits_a & bits_b);
+            assert_eq!(bits(&a.difference(&b)), bits_a & !bits_b);
+            assert_eq!(a.len(), bits_a.count_ones() as u64);
+            // Results stay normalised: sorted, non-empty, not touching
+            for set in [a.union(&b), a.intersection(&b), a.difference(&b)] {
+                assert!(set.ranges().iter().all(|r| !r.is_empty()));
+                assert!(set.ranges().windows(2).all(|w| w[0].end < w[1].start));
+            }
+        }
+    }
+
+    fn lanternfish(days: usize) -> u128 {
+        let mut fish: RotatingCounts<9> = [3, 4, 3, 1, 2].into_iter().collect();
+        for _ in 0..days {
+            let spawning = fish.advance();
+            fish.add(6, spawning);
+        }
+        fish.total()
+    }
+
+    #[test]
+    fn test_rotating_counts_lanternfish() {
+        assert_eq!(lanternfish(18), 26);
+        assert_eq!(lanternfish(256), 26_984_457_539);
+    }
+
+    #[test]
+    fn test_rotating_counts_advance() {
+        let mut counts: RotatingCounts<3> = RotatingCounts::new();
+        counts.add(0, 5);
+        counts.add(2, 7);
+        assert_eq!(counts.to_array(), [5, 0, 7]);
+        assert_eq!(counts.advance(), 5);
+        assert_eq!(counts.to_array(), [0, 7, 5]);
+        assert_eq!(counts.advance(), 0);
+        assert_eq!(counts.advance(), 7);
+        assert_eq!(counts.to_array(), [5, 0, 7]);
+        assert_eq!(counts.get(2), 7);
+    }
+
+    #[test]
+    fn test_rotating_counts_beyond_u64() {
+        // Grow the population past what a u64 total could hold
+        let mut fish: RotatingCounts<9> = [3].into_iter().collect();
+        let mut day = 0;
+        while fish.total() <= u64::MAX as u128 {
+            let spawning = fish.advance();
+            fish.add(6, spawning);
+            day += 1;
+        }
+        assert!(day < 2000);
+        assert!(fish.checked_total().is_some());
+    }
+
+    #[test]
+    fn test_rotating_counts_checked_total_overflow() {
+        let mut counts: RotatingCounts<2> = RotatingCounts::new();
+        counts.add(0, u128::MAX);
+        counts.add(1, 1);
+        assert_eq!(counts.checked_total(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed u128")]
+    fn test_rotating_counts_add_overflow() {
+        let mut counts: RotatingCounts<2> = RotatingCounts::new();
+        counts.add(1, u128::MAX);
+        counts.add(1, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_rotating_counts_age_out_of_range() {
+        let _: RotatingCounts<9> = [9].into_iter().collect();
+    }
+
+    #[test]
+    fn test_counter_insert_and_remove() {
+        let mut counter = Counter::new();
+        counter.insert("a");
+        counter.insert_n("b", 3);
+        counter.insert_n("c", 0);
+        assert_eq!((counter.len(), counter.total()), (2, 4));
+        assert_eq!(counter.remove_n(&"b", 2), 2);
+        assert_eq!(counter.remove_n(&"b", 5), 1);
+        assert_eq!(counter.remove_n(&"z", 1), 0);
+        assert_eq!(counter.len(), 1);
+        assert!(!counter.is_empty());
+        assert_eq!(counter.into_map(), HashMap::from([("a", 1)]));
+    }
+
+    #[test]
+    fn test_counter_most_common_ties() {
+        let counter: Counter<u8> = [3, 1, 2, 2, 1, 5].into_iter().collect();
+        assert_eq!(
+            counter.most_common(),
+            vec![(&1, 2), (&2, 2), (&3, 1), (&5, 1)]
+        );
+        assert!(Counter::<u8>::new().most_common().is_empty());
+    }
+
+    #[test]
+    fn test_counter_arithmetic() {
+        let a: Counter<char> = "aabbbc".chars().collect();
+        let b: Counter<char> = "abd".chars().collect();
+        let sum = a.clone() + b.clone();
+        assert_eq!(
+            (sum.count(&'a'), sum.count(&'b'), sum.count(&'d')),
+            (3, 4, 1)
+        );
+        let difference = a - b;
+        assert_eq!(difference.count(&'a'), 1);
+        assert_eq!(difference.count(&'d'), 0);
+        // Subtraction never leaves zero or negative entries behind
+        let empty = difference.clone() - difference;
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_counter_population_step() {
+        // Lanternfish with a Counter: each generation is a fresh counter built from
+        // the previous one
+        let mut fish: Counter<u8> = [3, 4, 3, 1, 2].into_iter().collect();
+        for _ in 0..80 {
+            let mut next = Counter::new();
+            for (&timer, n) in fish.iter() {
+                if timer == 0 {
+                    next.insert_n(6, n);
+                    next.insert_n(8, n);
+                } else {
+                    next.insert_n(timer - 1, n);
+                }
+            }
+            fish = next;
+        }
+        assert_eq!(fish.total(), 5934);
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where a sequence of states produced by repeatedly applying a step function starts
+/// repeating.
+///
+/// State `i` is the state after `i` steps, with state 0 being the initial state.
+/// From `start` onwards the sequence repeats with period `length`, so state
+/// `start + length` equals state `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// The index of the first state that is part of the cycle.
+    pub start: usize,
+    /// The number of states in the cycle.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Maps step `n` to the smallest step that reaches the same state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::cycle::Cycle;
+    ///
+    /// let cycle = Cycle { start: 3, length: 4 };
+    /// assert_eq!(cycle.equivalent(2), 2);
+    /// assert_eq!(cycle.equivalent(7), 3);
+    /// assert_eq!(cycle.equivalent(1_000_000_000), 4);
+    /// ```
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Finds the cycle in the sequence `initial, step(initial), step(step(initial)), ...`
+/// by remembering every state seen.
+///
+/// The sequence must eventually repeat, or this never returns. Use [`brent`] instead
+/// when states are large or cannot be hashed.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::cycle::{find_cycle, Cycle};
+///
+/// // 1, 2, 4, 8, 16, 32 % 20 = 12, 24 % 20 = 4, ...
+/// let cycle = find_cycle(1, |&n| n * 2 % 20);
+/// assert_eq!(cycle, Cycle { start: 2, length: 4 });
+/// ```
+pub fn find_cycle<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut state = initial;
+    for i in 0.. {
+        if let Some(&start) = seen.get(&state) {
+            return Cycle {
+                start,
+                length: i - start,
+            };
+        }
+        let next = step(&state);
+        seen.insert(state, i);
+        state = next;
+    }
+    unreachable!("the sequence never repeated")
+}
+
+/// Finds the cycle in the sequence `initial, step(initial), ...` using Brent's
+/// algorithm, keeping only two states in memory at a time.
+///
+/// This calls `step` more often than [`find_cycle`] but needs neither hashing nor
+/// storage, so it suits huge states or sequences with very long tails.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::cycle::{brent, Cycle};
+///
+/// let cycle = brent(1, |&n| n * 2 % 20);
+/// assert_eq!(cycle, Cycle { start: 2, length: 4 });
+/// ```
+pub fn brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    // Find the period: move the hare in growing powers of two until it meets the tortoise
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Find the start: walk two pointers `length` apart until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Returns the state after applying `step` to `initial` `n` times, skipping over
+/// whole cycles once the sequence repeats.
+///
+/// States are simulated until either `n` steps have been taken or a state repeats;
+/// in the latter case the answer is looked up among the states already seen. This is
+/// the usual way to answer "what does it look like after 1,000,000,000 spin cycles".
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::cycle::state_after;
+///
+/// let rotate = |s: &String| format!("{}{}", &s[1..], &s[..1]);
+/// assert_eq!(state_after("abcde".to_string(), rotate, 1_000_000_002), "cdeab");
+/// ```
+pub fn state_after<S, F>(initial: S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history: Vec<S> = Vec::new();
+    let mut state = initial;
+    for i in 0..n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: i - start,
+            };
+            return history.swap_remove(cycle.equivalent(n));
+        }
+        let next = step(&state);
+        seen.insert(state.clone(), i);
+        history.push(state);
+        state = next;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A sequence with a tail of `tail` states followed by a cycle of `length` states
+    fn rho(tail: usize, length: usize) -> impl Fn(&usize) -> usize {
+        move |&i| {
+            if i + 1 < tail + length {
+                i + 1
+            } else {
+                tail
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_cycle_and_brent_agree() {
+        for tail in 0..6 {
+            for length in 1..7 {
+                let expected = Cycle {
+                    start: tail,
+                    length,
+                };
+                assert_eq!(find_cycle(0, rho(tail, length)), expected);
+                assert_eq!(brent(0, rho(tail, length)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        let expected = Cycle {
+            start: 0,
+            length: 1,
+        };
+        assert_eq!(find_cycle(7, |&n| n), expected);
+        assert_eq!(brent(7, |&n| n), expected);
+    }
+
+    #[test]
+    fn test_state_after_matches_simulation() {
+        let step = rho(3, 5);
+        for n in 0..30 {
+            let mut expected = 0;
+            for _ in 0..n {
+                expected = step(&expected);
+            }
+            assert_eq!(state_after(0, &step, n), expected, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_state_after_huge_n() {
+        // 3 + (10^12 - 3) % 5 = 3 + 2
+        assert_eq!(state_after(0, rho(3, 5), 1_000_000_000_000), 5);
+    }
+
+    #[test]
+    fn test_state_after_stops_early_without_cycle() {
+        let mut calls = 0;
+        let result = state_after(
+            0u64,
+            |&n| {
+                calls += 1;
+                n + 1
+            },
+            10,
+        );
+        assert_eq!(result, 10);
+        assert_eq!(calls, 10);
+    }
+
+    #[test]
+    fn test_state_after_on_grid_states() {
+        // A light moving around a ring of 4 cells
+        let spin = |cells: &Vec<bool>| {
+            let mut next = cells.clone();
+            next.rotate_right(1);
+            next
+        };
+        let after = state_after(vec![true, false, false, false], spin, 1_000_000_001);
+        assert_eq!(after, vec![false, true, false, false]);
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Builds a count map from a list of items, counting duplicates.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::dp::counts_of;
+///
+/// let counts = counts_of([3, 4, 3, 1, 2]);
+/// assert_eq!(counts[&3], 2);
+/// assert_eq!(counts.len(), 4);
+/// ```
+pub fn counts_of<T, I>(items: I) -> HashMap<T, u64>
+where
+    T: Eq + Hash,
+    I: IntoIterator<Item = T>,
+{
+    let mut counts = HashMap::new();
+    for item in items {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Evolves a multiset for `n` generations, where every item independently turns into
+/// the items returned by `step`.
+///
+/// Rather than tracking each item, the multiset is kept as a map from item to count,
+/// so `step` runs once per distinct item per generation. This keeps exponentially
+/// growing populations (lanternfish timers, splitting stones) tractable as long as
+/// the number of distinct items stays small.
+///
+/// # Arguments
+///
+/// * `initial` - The starting multiset as a map from item to count.
+/// * `step` - Returns what a single item becomes after one generation; an item may
+///   produce zero, one or several items, including duplicates.
+/// * `n` - The number of generations.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::dp::{counts_of, evolve_counts};
+///
+/// // Lanternfish: a timer of 0 resets to 6 and spawns a new fish at 8
+/// let fish = counts_of([3u8, 4, 3, 1, 2]);
+/// let step = |&t: &u8| if t == 0 { vec![6, 8] } else { vec![t - 1] };
+/// let after = evolve_counts(fish, step, 80);
+/// assert_eq!(after.values().sum::<u64>(), 5934);
+/// ```
+pub fn evolve_counts<T, F, I>(initial: HashMap<T, u64>, mut step: F, n: usize) -> HashMap<T, u64>
+where
+    T: Eq + Hash,
+    F: FnMut(&T) -> I,
+    I: IntoIterator<Item = T>,
+{
+    let mut counts = initial;
+    for _ in 0..n {
+        let mut next = HashMap::with_capacity(counts.len());
+        for (item, count) in &counts {
+            for child in step(item) {
+                *next.entry(child).or_insert(0) += count;
+            }
+        }
+        counts = next;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plutonian pebbles: 0 becomes 1, even-length numbers split in half, anything else
+    /// is multiplied by 2024.
+    fn blink(&stone: &u64) -> Vec<u64> {
+        if stone == 0 {
+            return vec![1];
+        }
+        let digits = stone.ilog10() + 1;
+        if digits % 2 == 0 {
+            let half = 10u64.pow(digits / 2);
+            vec![stone / half, stone % half]
+        } else {
+            vec![stone * 2024]
+        }
+    }
+
+    #[test]
+    fn test_counts_of() {
+        let counts = counts_of("abca".chars());
+        assert_eq!(counts, HashMap::from([('a', 2), ('b', 1), ('c', 1)]));
+    }
+
+    #[test]
+    fn test_evolve_counts_pebbles() {
+        let stones = counts_of([125, 17]);
+        let total = |n| {
+            evolve_counts(stones.clone(), blink, n)
+                .values()
+                .sum::<u64>()
+        };
+        assert_eq!(total(6), 22);
+        assert_eq!(total(25), 55312);
+    }
+
+    #[test]
+    fn test_evolve_counts_lanternfish_256() {
+        let fish = counts_of([3u8, 4, 3, 1, 2]);
+        let step = |&t: &u8| if t == 0 { vec![6, 8] } else { vec![t - 1] };
+        let total: u64 = evolve_counts(fish, step, 256).values().sum();
+        assert_eq!(total, 26_984_457_539);
+    }
+
+    #[test]
+    fn test_evolve_counts_zero_generations() {
+        let initial = counts_of([1, 1, 2]);
+        assert_eq!(evolve_counts(initial.clone(), |_| Vec::new(), 0), initial);
+    }
+
+    #[test]
+    fn test_evolve_counts_items_can_vanish() {
+        let initial = counts_of([1, 2, 3]);
+        let after = evolve_counts(initial, |&x| (x > 1).then_some(x - 1), 1);
+        assert_eq!(after, HashMap::from([(1, 1), (2, 1)]));
+    }
+
+    #[test]
+    fn test_evolve_counts_merges_duplicates() {
+        // Everything collapses into a single item whose count doubles each generation
+        let after = evolve_counts(counts_of([0]), |_| [0, 0], 10);
+        assert_eq!(after, HashMap::from([(0, 1024)]));
+    }
+}
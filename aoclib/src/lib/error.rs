@@ -0,0 +1,267 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// The ways reading and parsing puzzle input can fail.
+///
+/// The [`parser`](crate::parse_lines) functions return this rather than a boxed error,
+/// so callers can tell a missing file from a malformed line. It still converts into
+/// `Box<dyn Error>` with `?`, so solutions that do not care keep working unchanged.
+/// It is `Send` and `Sync`, so it can also be returned from a spawned thread.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{parse_lines, AocError};
+///
+/// match parse_lines::<u32, _>("no_such_file.txt") {
+///     Err(AocError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+///     other => panic!("expected an I/O error, got {:?}", other),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum AocError {
+    /// The input could not be read.
+    Io(io::Error),
+    /// A line of the input could not be parsed.
+    Parse {
+        /// 1-based line number, or 0 while it is unknown, as for a line parser called
+        /// on its own. The line is left out of the message when it is 0.
+        line: usize,
+        /// 1-based column, when the parser reported one.
+        column: Option<usize>,
+        /// The start of the offending line, shortened to [`SNIPPET_LEN`] characters.
+        snippet: String,
+        message: String,
+    },
+    /// A custom parser failed on the input as a whole.
+    Custom(Box<dyn Error + Send + Sync>),
+}
+
+/// The most characters of an offending line that a parse error quotes.
+pub const SNIPPET_LEN: usize = 40;
+
+impl AocError {
+    /// Creates a parse error for line number `line`, whose text is `content`, with no
+    /// column.
+    pub fn parse(line: usize, content: &str, message: impl fmt::Display) -> Self {
+        AocError::Parse {
+            line,
+            column: None,
+            snippet: snippet(content),
+            message: message.to_string(),
+        }
+    }
+
+    /// Sets the 1-based column of a parse error; other errors are returned unchanged.
+    ///
+    /// A custom line parser can return this to have its column reported along with
+    /// the line number, which is filled in by the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::{parse_lines_with_str, AocError};
+    ///
+    /// let err = parse_lines_with_str("ab\nax", |line| match line.find('x') {
+    ///     Some(i) => Err(AocError::parse(0, line, "unexpected 'x'").with_column(i + 1).into()),
+    ///     None => Ok(line.len()),
+    /// });
+    /// assert_eq!(err.unwrap_err().to_string(), "line 2, column 2 (\"ax\"): unexpected 'x'");
+    /// ```
+    pub fn with_column(self, column: usize) -> Self {
+        match self {
+            AocError::Parse {
+                line,
+                snippet,
+                message,
+                ..
+            } => AocError::Parse {
+                line,
+                column: Some(column),
+                snippet,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// Wraps an error from parsing line number `line`, keeping the column if `error`
+    /// is itself an [`AocError::Parse`].
+    pub(crate) fn at_line(line: usize, content: &str, error: Box<dyn Error>) -> Self {
+        match error.downcast::<AocError>() {
+            Ok(error) => match *error {
+                AocError::Parse {
+                    column, message, ..
+                } => AocError::Parse {
+                    line,
+                    column,
+                    snippet: snippet(content),
+                    message,
+                },
+                other => other,
+            },
+            Err(error) => AocError::parse(line, content, error),
+        }
+    }
+}
+
+/// Shortens `line` to at most [`SNIPPET_LEN`] characters, marking a cut with `...`.
+fn snippet(line: &str) -> String {
+    match line.char_indices().nth(SNIPPET_LEN) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::Io(e) => write!(f, "{}", e),
+            AocError::Parse {
+                line,
+                column,
+                snippet,
+                message,
+            } => {
+                let line = (*line > 0).then(|| format!("line {}", line));
+                let column = column.map(|column| format!("column {}", column));
+                let location: Vec<String> = line.into_iter().chain(column).collect();
+                if location.is_empty() {
+                    write!(f, "{:?}: {}", snippet, message)
+                } else {
+                    write!(f, "{} ({:?}): {}", location.join(", "), snippet, message)
+                }
+            }
+            AocError::Custom(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io(e) => Some(e),
+            AocError::Parse { .. } => None,
+            AocError::Custom(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(error: io::Error) -> Self {
+        AocError::Io(error)
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync>> for AocError {
+    /// Keeps an `AocError` that was boxed on the way, and wraps anything else as
+    /// [`AocError::Custom`].
+    fn from(error: Box<dyn Error + Send + Sync>) -> Self {
+        match error.downcast::<AocError>() {
+            Ok(error) => *error,
+            Err(error) => AocError::Custom(error),
+        }
+    }
+}
+
+impl From<Box<dyn Error>> for AocError {
+    /// Keeps an `AocError` that was boxed on the way, and wraps anything else as
+    /// [`AocError::Custom`]. Such an error may be neither `Send` nor `Sync`, so only
+    /// its message is kept.
+    fn from(error: Box<dyn Error>) -> Self {
+        match error.downcast::<AocError>() {
+            Ok(error) => *error,
+            Err(error) => AocError::Custom(error.to_string().into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            AocError::parse(3, "12x", "bad digit").to_string(),
+            "line 3 (\"12x\"): bad digit"
+        );
+        let located = AocError::Parse {
+            line: 3,
+            column: Some(3),
+            snippet: "12x".into(),
+            message: "bad digit".into(),
+        };
+        assert_eq!(located.to_string(), "line 3, column 3 (\"12x\"): bad digit");
+
+        // A line parser called on its own does not know its line number
+        let unknown = AocError::parse(0, "12x", "bad digit");
+        assert_eq!(unknown.to_string(), "\"12x\": bad digit");
+        assert_eq!(
+            unknown.with_column(3).to_string(),
+            "column 3 (\"12x\"): bad digit"
+        );
+    }
+
+    #[test]
+    fn test_at_line() {
+        let plain: Box<dyn Error> = "oops".into();
+        assert_eq!(
+            AocError::at_line(4, "abc", plain).to_string(),
+            "line 4 (\"abc\"): oops"
+        );
+
+        // A column reported by the parser survives, and the line is filled in
+        let located: Box<dyn Error> = Box::new(AocError::Parse {
+            line: 0,
+            column: Some(2),
+            snippet: String::new(),
+            message: "oops".into(),
+        });
+        assert_eq!(
+            AocError::at_line(4, "abc", located).to_string(),
+            "line 4, column 2 (\"abc\"): oops"
+        );
+    }
+
+    #[test]
+    fn test_from_box_unwraps_aoc_errors() {
+        let boxed: Box<dyn Error> = Box::new(AocError::parse(1, "", "x"));
+        assert!(matches!(
+            AocError::from(boxed),
+            AocError::Parse { line: 1, .. }
+        ));
+        let boxed: Box<dyn Error> = "x".into();
+        assert!(matches!(AocError::from(boxed), AocError::Custom(_)));
+
+        let boxed: Box<dyn Error + Send + Sync> = Box::new(AocError::parse(2, "", "x"));
+        assert!(matches!(
+            AocError::from(boxed),
+            AocError::Parse { line: 2, .. }
+        ));
+        let boxed: Box<dyn Error> = Box::new(io::Error::other("disk"));
+        let custom = AocError::from(boxed);
+        assert_eq!(custom.to_string(), "disk");
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<AocError>();
+
+        let error = std::thread::spawn(|| AocError::parse(1, "x", "bad"))
+            .join()
+            .unwrap();
+        let boxed: Box<dyn Error + Send + Sync> = error.into();
+        assert_eq!(boxed.to_string(), "line 1 (\"x\"): bad");
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("short"), "short");
+        let long = "0123456789".repeat(5);
+        assert_eq!(snippet(&long), format!("{}...", &long[..SNIPPET_LEN]));
+        assert_eq!(snippet(&"é".repeat(50)).chars().count(), SNIPPET_LEN + 3);
+    }
+}
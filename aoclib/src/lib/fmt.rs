@@ -0,0 +1,120 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+/// Formats a duration with three significant figures in the largest unit that keeps
+/// it at least 1, from nanoseconds up to seconds.
+///
+/// Unlike `{:?}` on a [`Duration`], the precision never varies with the value, so
+/// timings line up in tables and compare at a glance.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::fmt::duration;
+/// use std::time::Duration;
+///
+/// assert_eq!(duration(Duration::from_nanos(1_243_500)), "1.24 ms");
+/// assert_eq!(duration(Duration::from_nanos(87_654)), "87.7 µs");
+/// assert_eq!(duration(Duration::from_nanos(999)), "999 ns");
+/// assert_eq!(duration(Duration::from_secs(125)), "125 s");
+/// ```
+pub fn duration(d: Duration) -> String {
+    const UNITS: [(f64, &str); 3] = [(1e3, "µs"), (1e6, "ms"), (1e9, "s")];
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        return format!("{} ns", nanos);
+    }
+    let mut unit = match nanos {
+        n if n < 1_000_000 => 0,
+        n if n < 1_000_000_000 => 1,
+        _ => 2,
+    };
+    // 999.9 µs would round to 1000 µs, so show it as 1.00 ms instead
+    if (nanos as f64 / UNITS[unit].0).round() >= 1000.0 && unit < 2 {
+        unit += 1;
+    }
+    let (scale, name) = UNITS[unit];
+    let value = nanos as f64 / scale;
+    // Rounding can also carry into another digit, as 9.996 does to 10.00
+    let rounded = |places: i32| (value * 10f64.powi(places)).round() / 10f64.powi(places);
+    if rounded(2) < 10.0 {
+        format!("{:.2} {}", value, name)
+    } else if rounded(1) < 100.0 {
+        format!("{:.1} {}", value, name)
+    } else {
+        format!("{:.0} {}", value, name)
+    }
+}
+
+/// Formats a number with a comma between every group of three digits, whatever the
+/// system locale, so large answers are easy to read back.
+///
+/// Anything that displays as an optionally signed run of digits works, including
+/// every integer type; a fractional part is left as it is.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::fmt::thousands;
+///
+/// assert_eq!(thousands(3121910778619u64), "3,121,910,778,619");
+/// assert_eq!(thousands(-1234567i64), "-1,234,567");
+/// assert_eq!(thousands(999), "999");
+/// ```
+pub fn thousands<T: Display>(n: T) -> String {
+    let text = n.to_string();
+    let (sign, rest) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text.as_str()),
+    };
+    let digits_end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (digits, tail) = rest.split_at(digits_end);
+
+    let mut grouped = String::with_capacity(text.len() + digits.len() / 3);
+    grouped.push_str(sign);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped.push_str(tail);
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_units_and_precision() {
+        let ns = Duration::from_nanos;
+        assert_eq!(duration(ns(0)), "0 ns");
+        assert_eq!(duration(ns(1_000)), "1.00 µs");
+        assert_eq!(duration(ns(12_345)), "12.3 µs");
+        assert_eq!(duration(ns(123_456)), "123 µs");
+        assert_eq!(duration(ns(999_999)), "1.00 ms");
+        assert_eq!(duration(ns(999_400)), "999 µs");
+        assert_eq!(duration(ns(9_996_000)), "10.0 ms");
+        assert_eq!(duration(ns(99_960_000)), "100 ms");
+        assert_eq!(duration(Duration::from_millis(1_500)), "1.50 s");
+        assert_eq!(duration(Duration::from_secs(3_600)), "3600 s");
+    }
+
+    #[test]
+    fn test_thousands() {
+        assert_eq!(thousands(0), "0");
+        assert_eq!(thousands(1000), "1,000");
+        assert_eq!(thousands(100_000), "100,000");
+        assert_eq!(thousands(-100), "-100");
+        assert_eq!(
+            thousands(u128::MAX),
+            "340,282,366,920,938,463,463,374,607,431,768,211,455"
+        );
+        assert_eq!(thousands(i64::MIN), "-9,223,372,036,854,775,808");
+        assert_eq!(thousands(12345.5), "12,345.5");
+        assert_eq!(thousands("n/a"), "n/a");
+    }
+}
@@ -0,0 +1,926 @@
+use crate::lib::grid::Grid;
+use crate::lib::math::{gcd, Integer, Signed};
+use crate::lib::point::{Direction, Point2};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// An axis-aligned box of integer cells in 3D.
+///
+/// Like [`RangeSet`](crate::collections::RangeSet), bounds are half-open: the cuboid
+/// covers `min[i] <= c < max[i]` on each axis. Parsing converts the inclusive ranges
+/// of puzzle input (`x=10..12` is three cells).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    /// The smallest coordinate on each axis.
+    pub min: [i64; 3],
+    /// One past the largest coordinate on each axis.
+    pub max: [i64; 3],
+}
+
+impl Cuboid {
+    /// Creates a cuboid from half-open bounds. Bounds with `max <= min` on any axis
+    /// give an empty cuboid.
+    pub const fn new(min: [i64; 3], max: [i64; 3]) -> Self {
+        Cuboid { min, max }
+    }
+
+    /// Returns whether the cuboid has no cells.
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.max[i] <= self.min[i])
+    }
+
+    /// Returns the number of cells in the cuboid.
+    ///
+    /// Computed in `i128`, which holds any cuboid with sides up to 2^42 cells.
+    pub fn volume(&self) -> i128 {
+        if self.is_empty() {
+            return 0;
+        }
+        (0..3)
+            .map(|i| self.max[i] as i128 - self.min[i] as i128)
+            .product()
+    }
+
+    /// Returns whether the cell at `p` lies inside the cuboid.
+    pub fn contains(&self, p: [i64; 3]) -> bool {
+        (0..3).all(|i| self.min[i] <= p[i] && p[i] < self.max[i])
+    }
+
+    /// Returns the cells shared with `other`, or `None` if there are none.
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let overlap = Cuboid {
+            min: [0, 1, 2].map(|i| self.min[i].max(other.min[i])),
+            max: [0, 1, 2].map(|i| self.max[i].min(other.max[i])),
+        };
+        (!overlap.is_empty()).then_some(overlap)
+    }
+
+    /// Returns disjoint cuboids covering the cells of `self` that are not in `other`:
+    /// at most six slabs, cut off one axis at a time.
+    pub fn difference(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let Some(overlap) = self.intersection(other) else {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        };
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for axis in 0..3 {
+            if rest.min[axis] < overlap.min[axis] {
+                let mut below = rest;
+                below.max[axis] = overlap.min[axis];
+                pieces.push(below);
+                rest.min[axis] = overlap.min[axis];
+            }
+            if overlap.max[axis] < rest.max[axis] {
+                let mut above = rest;
+                above.min[axis] = overlap.max[axis];
+                pieces.push(above);
+                rest.max[axis] = overlap.max[axis];
+            }
+        }
+        pieces
+    }
+}
+
+impl FromStr for Cuboid {
+    type Err = Box<dyn Error>;
+
+    /// Parses inclusive ranges such as `x=-20..26,y=-36..17,z=-47..7`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cuboid = Cuboid::new([0; 3], [0; 3]);
+        let mut seen = [false; 3];
+        for part in s.trim().split(',') {
+            let (axis, range) = part
+                .trim()
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid range '{}'", part))?;
+            let i = match axis {
+                "x" => 0,
+                "y" => 1,
+                "z" => 2,
+                _ => return Err(format!("Unknown axis '{}'", axis).into()),
+            };
+            let (lo, hi) = range
+                .split_once("..")
+                .ok_or_else(|| format!("Invalid range '{}'", part))?;
+            let (lo, hi): (i64, i64) = (lo.parse()?, hi.parse()?);
+            cuboid.min[i] = lo.min(hi);
+            cuboid.max[i] = lo.max(hi) + 1;
+            seen[i] = true;
+        }
+        if seen != [true; 3] {
+            return Err(format!("Cuboid '{}' needs x, y and z ranges", s.trim()).into());
+        }
+        Ok(cuboid)
+    }
+}
+
+/// Parses a reboot step such as `on x=10..12,y=10..12,z=10..12`, returning whether it
+/// turns cells on and the cuboid it covers.
+///
+/// # Errors
+///
+/// Returns an error if the line does not start with `on` or `off`, or the cuboid is
+/// invalid.
+pub fn parse_step(line: &str) -> Result<(bool, Cuboid), Box<dyn Error>> {
+    let (state, cuboid) = line
+        .trim()
+        .split_once(' ')
+        .ok_or_else(|| format!("Invalid step '{}'", line))?;
+    let on = match state {
+        "on" => true,
+        "off" => false,
+        _ => return Err(format!("Unknown state '{}'", state).into()),
+    };
+    Ok((on, cuboid.parse()?))
+}
+
+/// A set of 3D cells built by switching cuboids on and off.
+///
+/// The set is stored as disjoint cuboids: switching a cuboid on or off first cuts it
+/// out of every stored cuboid it overlaps, so volumes are exact at any coordinate range
+/// without visiting individual cells.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::geometry::{parse_step, CuboidSet};
+///
+/// let steps = "\
+/// on x=10..12,y=10..12,z=10..12
+/// on x=11..13,y=11..13,z=11..13
+/// off x=9..11,y=9..11,z=9..11
+/// on x=10..10,y=10..10,z=10..10";
+/// let mut reactor = CuboidSet::new();
+/// for line in steps.lines() {
+///     let (on, cuboid) = parse_step(line).unwrap();
+///     reactor.apply(cuboid, on);
+/// }
+/// assert_eq!(reactor.volume(), 39);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CuboidSet {
+    cuboids: Vec<Cuboid>,
+}
+
+impl CuboidSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the disjoint cuboids making up the set, in no particular order.
+    pub fn cuboids(&self) -> &[Cuboid] {
+        &self.cuboids
+    }
+
+    /// Returns whether the set has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cuboids.is_empty()
+    }
+
+    /// Switches every cell of `cuboid` on or off.
+    pub fn apply(&mut self, cuboid: Cuboid, on: bool) {
+        self.remove(cuboid);
+        if on && !cuboid.is_empty() {
+            self.cuboids.push(cuboid);
+        }
+    }
+
+    /// Switches every cell of `cuboid` on.
+    pub fn insert(&mut self, cuboid: Cuboid) {
+        self.apply(cuboid, true);
+    }
+
+    /// Switches every cell of `cuboid` off.
+    pub fn remove(&mut self, cuboid: Cuboid) {
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|c| c.difference(&cuboid))
+            .collect();
+    }
+
+    /// Returns whether the cell at `p` is on.
+    pub fn contains(&self, p: [i64; 3]) -> bool {
+        self.cuboids.iter().any(|c| c.contains(p))
+    }
+
+    /// Returns the number of cells that are on.
+    pub fn volume(&self) -> i128 {
+        self.cuboids.iter().map(Cuboid::volume).sum()
+    }
+
+    /// Returns the number of cells that are on inside `region`, such as the
+    /// initialization area of the first part.
+    pub fn volume_within(&self, region: &Cuboid) -> i128 {
+        self.cuboids
+            .iter()
+            .filter_map(|c| c.intersection(region))
+            .map(|c| c.volume())
+            .sum()
+    }
+}
+
+/// Returns the area enclosed by a polygon with the given vertices, using the shoelace
+/// formula. Half cells are rounded down, which is exactly what [`interior_points`]
+/// expects.
+///
+/// The vertices go around the polygon in either direction; the edge from the last back
+/// to the first is implied, and repeating the first vertex at the end is harmless.
+/// Accumulates in `isize`; see [`polygon_area_i128`] for coordinates whose products
+/// could overflow.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::geometry::{boundary_points, interior_points, polygon_area};
+/// use aoclib::Point2;
+///
+/// // A trench dug around a 7x10 rectangle
+/// let corners = [(0, 0), (6, 0), (6, 9), (0, 9)].map(Point2::from);
+/// let area = polygon_area(&corners);
+/// let boundary = boundary_points(&corners) as isize;
+/// assert_eq!(area, 54);
+/// assert_eq!(interior_points(area, boundary) + boundary, 70);
+/// ```
+pub fn polygon_area(points: &[Point2]) -> isize {
+    twice_area(points, |v| v) / 2
+}
+
+/// Like [`polygon_area`], but accumulates in `i128` so that coordinates up to the full
+/// `isize` range cannot overflow.
+pub fn polygon_area_i128(points: &[Point2]) -> i128 {
+    twice_area(points, |v| v as i128) / 2
+}
+
+/// Returns the number of lattice points on the boundary of a polygon, counting each
+/// vertex once. Edges need not be axis-aligned.
+pub fn boundary_points(points: &[Point2]) -> u64 {
+    let edges = points.iter().zip(points.iter().cycle().skip(1));
+    edges
+        .map(|(a, b)| gcd(a.x.abs_diff(b.x), a.y.abs_diff(b.y)) as u64)
+        .sum()
+}
+
+/// Returns the number of lattice points strictly inside a lattice polygon, given its
+/// area (rounded down) and the number of lattice points on its boundary, by Pick's
+/// theorem.
+///
+/// Works with any integer type, so it pairs with either [`polygon_area`] or
+/// [`polygon_area_i128`].
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::geometry::interior_points;
+///
+/// // The triangle (0, 0), (3, 0), (0, 3) has area 4.5 and 9 boundary points; only
+/// // (1, 1) is inside
+/// assert_eq!(interior_points(4i128, 9), 1);
+/// ```
+pub fn interior_points<T: Integer>(area: T, boundary: T) -> T {
+    let two = T::ONE + T::ONE;
+    // i = A - b/2 + 1. With the area rounded down, an odd boundary is rounded down too
+    // and the halves cancel.
+    area + T::ONE - boundary / two
+}
+
+/// Returns twice the signed shoelace sum, made non-negative.
+fn twice_area<T, F>(points: &[Point2], convert: F) -> T
+where
+    T: Signed + std::iter::Sum,
+    F: Fn(isize) -> T,
+{
+    let edges = points.iter().zip(points.iter().cycle().skip(1));
+    let sum: T = edges
+        .map(|(a, b)| convert(a.x) * convert(b.y) - convert(b.x) * convert(a.y))
+        .sum();
+    sum.abs_value()
+}
+
+/// A unit vector along one of the cube's axes.
+type Axis = [i64; 3];
+
+/// How one face of a [`CubeNet`] sits on the folded cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Face {
+    /// The top-left cell of the face in the net.
+    origin: Point2,
+    /// The outward normal of the face.
+    normal: Axis,
+    /// The direction the net's `Right` points on the folded face.
+    right: Axis,
+    /// The direction the net's `Down` points on the folded face.
+    down: Axis,
+}
+
+impl Face {
+    fn axis(&self, dir: Direction) -> Axis {
+        match dir {
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => neg(self.right),
+            Direction::Up => neg(self.down),
+        }
+    }
+}
+
+/// A cube net: six square faces laid out flat, as in the monkey-map puzzle, folded up
+/// so that walking off the edge of one face continues on the face it touches on the
+/// cube.
+///
+/// Face adjacency and edge orientation are deduced from the layout by folding: each
+/// face learns where the net's right and down directions point in 3D from the face it
+/// was reached from. Any of the eleven nets works, in any rotation or reflection.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::geometry::CubeNet;
+/// use aoclib::{Direction, Grid, Point2};
+///
+/// // A cross-shaped net with 1x1 faces
+/// let net = Grid::parse(".#.\n###\n.#.\n.#.", |c| c == '#').unwrap();
+/// let cube = CubeNet::from_grid(&net, |&on| on).unwrap();
+/// assert_eq!(cube.size(), 1);
+/// // Walking right off the top face lands on the right face, heading down
+/// assert_eq!(
+///     cube.wrap(Point2::new(1, 0), Direction::Right),
+///     (Point2::new(2, 1), Direction::Down)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubeNet {
+    size: usize,
+    faces: Vec<Face>,
+}
+
+impl CubeNet {
+    /// Folds the cells of `grid` for which `on_surface` holds into a cube. The face size
+    /// is deduced from the number of surface cells.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the surface cells do not form six connected square faces
+    /// that fold into a cube.
+    pub fn from_grid<T, F>(grid: &Grid<T>, on_surface: F) -> Result<CubeNet, String>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let cells = grid.iter().filter(|(_, c)| on_surface(c)).count();
+        let size = (1..=cells)
+            .find(|n| 6 * n * n >= cells)
+            .filter(|n| 6 * n * n == cells)
+            .ok_or_else(|| format!("{} cells cannot cover the six faces of a cube", cells))?;
+
+        let on_face = |origin: Point2| {
+            (0..size as isize).all(|dy| {
+                (0..size as isize).all(|dx| {
+                    grid.get(origin + Point2::new(dx, dy))
+                        .is_some_and(&on_surface)
+                })
+            })
+        };
+        let origins: Vec<Point2> = grid
+            .positions()
+            .filter(|p| p.x % size as isize == 0 && p.y % size as isize == 0)
+            .filter(|&p| grid.get(p).is_some_and(&on_surface))
+            .collect();
+        if origins.len() != 6 || !origins.iter().all(|&o| on_face(o)) {
+            return Err(format!("Surface cells do not form six {0}x{0} faces", size));
+        }
+
+        // Fold outwards from the first face, tracking each face's frame in 3D
+        let mut faces = vec![Face {
+            origin: origins[0],
+            normal: [0, 0, 1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        }];
+        let mut i = 0;
+        while i < faces.len() {
+            let face = faces[i];
+            for dir in Direction::ALL {
+                let origin = face.origin + dir.delta() * size as isize;
+                if !origins.contains(&origin) || faces.iter().any(|f| f.origin == origin) {
+                    continue;
+                }
+                // The face beyond the edge in `dir` turns to face that way, and walking
+                // on in `dir` now heads into the cube
+                let axis = face.axis(dir);
+                let (right, down) = match dir {
+                    Direction::Right => (neg(face.normal), face.down),
+                    Direction::Left => (face.normal, face.down),
+                    Direction::Down => (face.right, neg(face.normal)),
+                    Direction::Up => (face.right, face.normal),
+                };
+                faces.push(Face {
+                    origin,
+                    normal: axis,
+                    right,
+                    down,
+                });
+            }
+            i += 1;
+        }
+        if faces.len() != 6 {
+            return Err("Faces of the net are not all connected".to_string());
+        }
+        for (i, face) in faces.iter().enumerate() {
+            if faces[..i].iter().any(|f| f.normal == face.normal) {
+                return Err(format!("Faces overlap when folded at {:?}", face.origin));
+            }
+        }
+        Ok(CubeNet { size, faces })
+    }
+
+    /// Returns the side length of each face.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the top-left cell of each face in the net, in the order faces were
+    /// reached while folding.
+    pub fn faces(&self) -> Vec<Point2> {
+        self.faces.iter().map(|f| f.origin).collect()
+    }
+
+    /// Returns the index (into [`CubeNet::faces`]) of the face containing `p`.
+    pub fn face_of(&self, p: Point2) -> Option<usize> {
+        let n = self.size as isize;
+        self.faces.iter().position(|f| {
+            (f.origin.x..f.origin.x + n).contains(&p.x)
+                && (f.origin.y..f.origin.y + n).contains(&p.y)
+        })
+    }
+
+    /// Returns the face across the edge of `face` in direction `dir`, and the heading
+    /// on that face of a walker who crossed the edge.
+    pub fn neighbour(&self, face: usize, dir: Direction) -> (usize, Direction) {
+        let from = &self.faces[face];
+        let axis = from.axis(dir);
+        let to = self
+            .faces
+            .iter()
+            .position(|f| f.normal == axis)
+            .expect("every direction leads to a face");
+        (to, self.heading_on(to, neg(from.normal)))
+    }
+
+    /// Returns the cell and heading after one step from `p` in `heading`, carrying on
+    /// around the cube when the step leaves a face.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not on the surface.
+    pub fn wrap(&self, p: Point2, heading: Direction) -> (Point2, Direction) {
+        let face = self
+            .face_of(p)
+            .unwrap_or_else(|| panic!("{:?} is not on the cube", p));
+        let next = p.step(heading);
+        if self.face_of(next) == Some(face) {
+            return (next, heading);
+        }
+
+        // Work in doubled coordinates, so cell centres of a cube spanning -n..n on each
+        // axis are integers
+        let from = &self.faces[face];
+        let n = self.size as i64;
+        let local = p - from.origin;
+        let mut q = scale(from.normal, n);
+        q = add(q, scale(from.right, 2 * local.x as i64 - (n - 1)));
+        q = add(q, scale(from.down, 2 * local.y as i64 - (n - 1)));
+        // Over the edge: one cell (two units) on, half a cell out and half a cell in
+        q = add(add(q, from.axis(heading)), neg(from.normal));
+
+        let (to, heading) = self.neighbour(face, heading);
+        let to = &self.faces[to];
+        let x = (dot(q, to.right) + n - 1) / 2;
+        let y = (dot(q, to.down) + n - 1) / 2;
+        (to.origin + Point2::new(x as isize, y as isize), heading)
+    }
+
+    /// Returns the net direction on `face` that points along `axis`.
+    fn heading_on(&self, face: usize, axis: Axis) -> Direction {
+        let face = &self.faces[face];
+        Direction::ALL
+            .into_iter()
+            .find(|&d| face.axis(d) == axis)
+            .expect("axis lies in the face")
+    }
+}
+
+fn neg(a: Axis) -> Axis {
+    a.map(|v| -v)
+}
+
+fn scale(a: Axis, k: i64) -> Axis {
+    a.map(|v| v * k)
+}
+
+fn add(a: Axis, b: Axis) -> Axis {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn dot(a: Axis, b: Axis) -> i64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Returns every antinode of same-label antennas inside a `width` x `height` area with
+/// its top-left corner at the origin.
+///
+/// Without `harmonics`, each ordered pair of antennas `a`, `b` sharing a label makes
+/// one antinode beyond `b`, as far from `b` as `a` is: the reflection `2b - a`. With
+/// `harmonics`, every lattice point on the line through any two same-label antennas
+/// is an antinode, including the antennas themselves. Steps along the line are
+/// reduced by the gcd of the offset, so no collinear point is skipped.
+///
+/// Antinodes of different labels are merged, and points outside the area dropped.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::geometry::antinodes;
+/// use aoclib::Point2;
+/// use std::collections::HashMap;
+///
+/// let antennas = HashMap::from([('a', vec![Point2::new(4, 3), Point2::new(5, 5)])]);
+/// let nodes = antinodes(&antennas, (10, 10), false);
+/// assert_eq!(nodes.len(), 2);
+/// assert!(nodes.contains(&Point2::new(3, 1)) && nodes.contains(&Point2::new(6, 7)));
+/// ```
+pub fn antinodes<K>(
+    points_by_label: &HashMap<K, Vec<Point2>>,
+    (width, height): (usize, usize),
+    harmonics: bool,
+) -> HashSet<Point2> {
+    let in_bounds =
+        |p: Point2| p.x >= 0 && p.y >= 0 && (p.x as usize) < width && (p.y as usize) < height;
+    let mut nodes = HashSet::new();
+    for points in points_by_label.values() {
+        for (i, &a) in points.iter().enumerate() {
+            for &b in &points[i + 1..] {
+                if a == b {
+                    continue;
+                }
+                let delta = b - a;
+                if !harmonics {
+                    nodes.extend([b + delta, a - delta].into_iter().filter(|&p| in_bounds(p)));
+                    continue;
+                }
+                let g = gcd(delta.x, delta.y);
+                let step = Point2::new(delta.x / g, delta.y / g);
+                for dir in [step, -step] {
+                    let mut p = a;
+                    while in_bounds(p) {
+                        nodes.insert(p);
+                        p += dir;
+                    }
+                }
+            }
+        }
+    }
+    nodes
+}
+
+/// Groups the positions of a grid's cells by their label, skipping cells for which
+/// `is_empty` holds. This is the usual first step before [`antinodes`].
+pub fn group_by_label<T, F>(grid: &Grid<T>, mut is_empty: F) -> HashMap<T, Vec<Point2>>
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> bool,
+{
+    let mut groups: HashMap<T, Vec<Point2>> = HashMap::new();
+    for (p, cell) in grid.iter() {
+        if !is_empty(cell) {
+            groups.entry(cell.clone()).or_default().push(p);
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cuboid(s: &str) -> Cuboid {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let c = cuboid("x=-20..26,y=-36..17,z=-47..7");
+        assert_eq!(c, Cuboid::new([-20, -36, -47], [27, 18, 8]));
+        assert_eq!(c.volume(), 47 * 54 * 55);
+        assert_eq!(cuboid("z=1..1,x=3..2,y=0..0").min, [2, 0, 1]);
+        assert!(parse_step("on x=1..2,y=1..2").is_err());
+        assert!(parse_step("toggle x=1..2,y=1..2,z=1..2").is_err());
+        assert!(parse_step("off x=1..2,y=1..b,z=1..2").is_err());
+        assert!(!parse_step("off x=1..2,y=1..2,z=1..2").unwrap().0);
+    }
+
+    #[test]
+    fn test_difference_is_disjoint_and_exact() {
+        let a = cuboid("x=0..9,y=0..9,z=0..9");
+        let b = cuboid("x=3..5,y=-4..4,z=8..20");
+        let pieces = a.difference(&b);
+        assert!(pieces.len() <= 6);
+        let overlap = a.intersection(&b).unwrap();
+        let total: i128 = pieces.iter().map(Cuboid::volume).sum();
+        assert_eq!(total, a.volume() - overlap.volume());
+        for (i, p) in pieces.iter().enumerate() {
+            assert!(p.intersection(&b).is_none());
+            assert!(pieces[i + 1..].iter().all(|q| p.intersection(q).is_none()));
+        }
+        assert_eq!(a.difference(&a), vec![]);
+        let far = cuboid("x=50..60,y=0..0,z=0..0");
+        assert_eq!(a.difference(&far), vec![a]);
+    }
+
+    #[test]
+    fn test_matches_cell_by_cell() {
+        let mut state: u64 = 22;
+        let mut next = |range: i64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as i64 % range
+        };
+        let mut set = CuboidSet::new();
+        let mut cells = std::collections::HashSet::new();
+        for _ in 0..30 {
+            let min = [next(10), next(10), next(10)];
+            let max = min.map(|m| m + 1 + next(6));
+            let c = Cuboid::new(min, max);
+            let on = next(3) != 0;
+            set.apply(c, on);
+            for x in min[0]..max[0] {
+                for y in min[1]..max[1] {
+                    for z in min[2]..max[2] {
+                        if on {
+                            cells.insert([x, y, z]);
+                        } else {
+                            cells.remove(&[x, y, z]);
+                        }
+                    }
+                }
+            }
+            assert_eq!(set.volume(), cells.len() as i128);
+        }
+        let region = Cuboid::new([2, 2, 2], [7, 7, 7]);
+        let inside = cells.iter().filter(|&&p| region.contains(p)).count();
+        assert_eq!(set.volume_within(&region), inside as i128);
+        assert!(cells.iter().all(|&p| set.contains(p)));
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        let (lo, hi) = (-(1 << 40), 1 << 40);
+        let mut set = CuboidSet::new();
+        set.insert(Cuboid::new([lo; 3], [hi; 3]));
+        let side = 1i128 << 41;
+        assert_eq!(set.volume(), side.pow(3));
+        set.remove(Cuboid::new([0; 3], [hi; 3]));
+        assert_eq!(set.volume(), side.pow(3) - (side / 2).pow(3));
+        assert_eq!(set.cuboids().len(), 3);
+        set.apply(Cuboid::new([i64::MIN; 3], [i64::MAX; 3]), false);
+        assert!(set.is_empty());
+    }
+
+    /// Corners of the dig plan from the lavaduct-lagoon example.
+    fn lagoon() -> Vec<Point2> {
+        let plan = "R 6,D 5,L 2,D 2,R 2,D 2,L 5,U 2,L 1,U 2,R 2,U 3,L 2,U 2";
+        let mut p = Point2::ORIGIN;
+        plan.split(',')
+            .map(|step| {
+                let (dir, n) = step.split_once(' ').unwrap();
+                let n: isize = n.parse().unwrap();
+                p += match dir {
+                    "R" => Point2::new(n, 0),
+                    "L" => Point2::new(-n, 0),
+                    "D" => Point2::new(0, n),
+                    _ => Point2::new(0, -n),
+                };
+                p
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lagoon_volume() {
+        let corners = lagoon();
+        let boundary = boundary_points(&corners);
+        assert_eq!(boundary, 38);
+        let area = polygon_area(&corners);
+        assert_eq!(
+            interior_points(area, boundary as isize) + boundary as isize,
+            62
+        );
+        let area = polygon_area_i128(&corners);
+        assert_eq!(interior_points(area, boundary as i128), 24);
+        // Going around the other way, or closing the loop explicitly, changes nothing
+        let mut reversed = corners.clone();
+        reversed.reverse();
+        assert_eq!(polygon_area(&reversed), polygon_area(&corners));
+        reversed.push(reversed[0]);
+        assert_eq!(boundary_points(&reversed), 38);
+        assert_eq!(polygon_area(&reversed), polygon_area(&corners));
+    }
+
+    #[test]
+    fn test_polygon_area_edge_cases() {
+        assert_eq!(polygon_area(&[]), 0);
+        assert_eq!(boundary_points(&[]), 0);
+        assert_eq!(polygon_area(&[Point2::new(3, 4)]), 0);
+        let triangle = [(0, 0), (3, 0), (0, 3)].map(Point2::from);
+        assert_eq!(polygon_area(&triangle), 4);
+        assert_eq!(boundary_points(&triangle), 9);
+        assert_eq!(interior_points(4, 9), 1);
+    }
+
+    #[test]
+    fn test_polygon_area_i128_does_not_overflow() {
+        let big = isize::MAX / 2;
+        let square = [(0, 0), (big, 0), (big, big), (0, big)].map(Point2::from);
+        assert_eq!(polygon_area_i128(&square), (big as i128) * (big as i128));
+    }
+
+    const MONKEY_MAP: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+";
+
+    /// Pads the ragged monkey map to a rectangle so it fits in a grid.
+    fn monkey_map() -> Grid<char> {
+        let width = MONKEY_MAP.lines().map(str::len).max().unwrap();
+        let padded: Vec<String> = MONKEY_MAP
+            .lines()
+            .map(|line| format!("{:width$}", line, width = width))
+            .collect();
+        Grid::parse(&padded.join("\n"), |c| c).unwrap()
+    }
+
+    #[test]
+    fn test_cube_net_example_edges() {
+        let map = monkey_map();
+        let cube = CubeNet::from_grid(&map, |&c| c != ' ').unwrap();
+        assert_eq!(cube.size(), 4);
+        // A to B and C to D from the puzzle description
+        assert_eq!(
+            cube.wrap(Point2::new(11, 5), Direction::Right),
+            (Point2::new(14, 8), Direction::Down)
+        );
+        assert_eq!(
+            cube.wrap(Point2::new(10, 11), Direction::Down),
+            (Point2::new(1, 7), Direction::Up)
+        );
+        assert_eq!(
+            cube.wrap(Point2::new(5, 5), Direction::Right),
+            (Point2::new(6, 5), Direction::Right)
+        );
+    }
+
+    #[test]
+    fn test_cube_net_password() {
+        let map = monkey_map();
+        let cube = CubeNet::from_grid(&map, |&c| c != ' ').unwrap();
+        let path = "10R5L5R10L4R5L5";
+        let mut p = map.find(|&c| c == '.').unwrap();
+        let mut heading = Direction::Right;
+        let mut number = String::new();
+        let walk = |p: &mut Point2, heading: &mut Direction, steps: usize| {
+            for _ in 0..steps {
+                let (next, next_heading) = cube.wrap(*p, *heading);
+                if map[next] == '#' {
+                    break;
+                }
+                (*p, *heading) = (next, next_heading);
+            }
+        };
+        for c in path.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            walk(&mut p, &mut heading, number.parse().unwrap());
+            number.clear();
+            heading = if c == 'R' {
+                heading.turn_right()
+            } else {
+                heading.turn_left()
+            };
+        }
+        walk(&mut p, &mut heading, number.parse().unwrap());
+        let facing = (heading.index() + 3) % 4;
+        assert_eq!(1000 * (p.y + 1) + 4 * (p.x + 1) + facing as isize, 5031);
+    }
+
+    #[test]
+    fn test_cube_net_walks_are_reversible_and_close() {
+        let nets = [
+            // The puzzle input shape
+            "
+.##
+.#.
+##.
+#..",
+            ".#..\n####\n.#..",
+            "##..\n.###\n..#.",
+            "#...\n###.\n..##",
+            "##..\n.##.\n..##",
+            "###..\n..###",
+        ];
+        for net in nets {
+            let grid = Grid::parse(net.trim_start(), |c| c == '#').unwrap();
+            let big = Grid::from_rows(
+                (0..grid.height() * 3)
+                    .map(|y| {
+                        (0..grid.width() * 3)
+                            .map(|x| grid[Point2::new(x as isize / 3, y as isize / 3)])
+                            .collect()
+                    })
+                    .collect(),
+            )
+            .unwrap();
+            let cube = CubeNet::from_grid(&big, |&on| on).unwrap();
+            assert_eq!(cube.size(), 3);
+            for (p, _) in big.iter().filter(|(_, &on)| on) {
+                for heading in Direction::ALL {
+                    // Stepping back undoes a step, even across an edge
+                    let (q, h) = cube.wrap(p, heading);
+                    assert_eq!(cube.wrap(q, h.reverse()), (p, heading.reverse()), "{}", net);
+                    // Walking straight on goes around the cube and back
+                    let mut state = (p, heading);
+                    for _ in 0..12 {
+                        state = cube.wrap(state.0, state.1);
+                    }
+                    assert_eq!(state, (p, heading), "{}", net);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cube_net_errors() {
+        let folds = |s: &str| CubeNet::from_grid(&Grid::parse(s, |c| c).unwrap(), |&c| c == '#');
+        assert!(folds("#####").is_err());
+        // Six faces, but two of them land on top of each other
+        assert!(folds("######").unwrap_err().contains("overlap"));
+        assert!(folds("###..\n....#\n....#\n....#")
+            .unwrap_err()
+            .contains("connected"));
+        assert!(folds("##\n##\n##").is_err());
+    }
+
+    const ANTENNAS: &str = "\
+............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............
+";
+
+    #[test]
+    fn test_antinodes_example() {
+        let grid = Grid::parse(ANTENNAS, |c| c).unwrap();
+        let antennas = group_by_label(&grid, |&c| c == '.');
+        assert_eq!(antennas.len(), 2);
+        assert_eq!(antennas[&'A'].len(), 3);
+        let bounds = (grid.width(), grid.height());
+        assert_eq!(antinodes(&antennas, bounds, false).len(), 14);
+        assert_eq!(antinodes(&antennas, bounds, true).len(), 34);
+    }
+
+    #[test]
+    fn test_antinodes_harmonics_reduce_step() {
+        // Antennas 4 apart on a diagonal: the points between them are in line too
+        let antennas = HashMap::from([(0, vec![Point2::new(0, 0), Point2::new(4, 2)])]);
+        let nodes = antinodes(&antennas, (9, 9), true);
+        let mut nodes: Vec<Point2> = nodes.into_iter().collect();
+        nodes.sort();
+        let expected: Vec<Point2> = (0..5).map(|i| Point2::new(2 * i, i)).collect();
+        assert_eq!(nodes, expected);
+        // A lone antenna or duplicate positions make no antinodes
+        let lone = HashMap::from([(0, vec![Point2::new(1, 1), Point2::new(1, 1)])]);
+        assert!(antinodes(&lone, (9, 9), true).is_empty());
+        assert!(antinodes(&lone, (9, 9), false).is_empty());
+    }
+}
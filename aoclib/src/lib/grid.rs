@@ -0,0 +1,1602 @@
+use crate::lib::point::Point2;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Index, IndexMut};
+
+/// A dense, rectangular 2D grid addressed by [`Point2`].
+///
+/// Cells are stored row by row; `(0, 0)` is the top-left corner of the input.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{Grid, Point2};
+///
+/// let grid = Grid::parse("#.\n.#", |c| c == '#').unwrap();
+/// assert_eq!((grid.width(), grid.height()), (2, 2));
+/// assert_eq!(grid.get(Point2::new(1, 1)), Some(&true));
+/// assert_eq!(grid.get(Point2::new(2, 0)), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a `width` x `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rows are not all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, Box<dyn Error>> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(
+                    format!("Row {} has {} cells, expected {}", y + 1, row.len(), width).into(),
+                );
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a block of text into a grid, mapping every character with `cell`.
+    ///
+    /// Trailing blank lines are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lines are not all the same length.
+    pub fn parse<F>(input: &str, mut cell: F) -> Result<Self, Box<dyn Error>>
+    where
+        F: FnMut(char) -> T,
+    {
+        let rows: Vec<Vec<T>> = input
+            .trim_end_matches(['\n', '\r'])
+            .lines()
+            .map(|line| line.chars().map(&mut cell).collect())
+            .collect();
+        Grid::from_rows(rows)
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns whether `p` lies inside the grid.
+    pub fn in_bounds(&self, p: Point2) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    /// Returns the cell at `p`, or `None` if it is out of bounds.
+    pub fn get(&self, p: Point2) -> Option<&T> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    /// Returns a mutable reference to the cell at `p`, or `None` if it is out of bounds.
+    pub fn get_mut(&mut self, p: Point2) -> Option<&mut T> {
+        self.offset(p).map(move |i| &mut self.cells[i])
+    }
+
+    /// Iterates over every position in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Point2> {
+        let width = self.width;
+        (0..self.width * self.height)
+            .map(move |i| Point2::new((i % width) as isize, (i / width) as isize))
+    }
+
+    /// Iterates over every cell together with its position, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Iterates over the rows as slices.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `max(1)` keeps `chunks` happy for zero-width grids, which have no cells anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Iterates over the in-bounds orthogonal neighbours of `p`.
+    pub fn neighbors4(&self, p: Point2) -> impl Iterator<Item = Point2> + '_ {
+        p.neighbors4().filter(move |&q| self.in_bounds(q))
+    }
+
+    /// Iterates over the in-bounds neighbours of `p`, including diagonals.
+    pub fn neighbors8(&self, p: Point2) -> impl Iterator<Item = Point2> + '_ {
+        p.neighbors8().filter(move |&q| self.in_bounds(q))
+    }
+
+    /// Like [`Grid::neighbors4`], but yields each neighbour with its cell.
+    pub fn neighbor_cells4(&self, p: Point2) -> impl Iterator<Item = (Point2, &T)> {
+        p.neighbors4()
+            .filter_map(move |q| self.get(q).map(|cell| (q, cell)))
+    }
+
+    /// Like [`Grid::neighbors8`], but yields each neighbour with its cell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::{Grid, Point2};
+    ///
+    /// let grid = Grid::parse("@@.\n.@@\n...", |c| c == '@').unwrap();
+    /// let rolls = grid.neighbor_cells8(Point2::new(1, 1)).filter(|(_, &roll)| roll);
+    /// assert_eq!(rolls.count(), 3);
+    /// ```
+    pub fn neighbor_cells8(&self, p: Point2) -> impl Iterator<Item = (Point2, &T)> {
+        p.neighbors8()
+            .filter_map(move |q| self.get(q).map(|cell| (q, cell)))
+    }
+
+    /// Counts the trails leaving every start cell, where a trail moves between
+    /// orthogonal neighbours `from` and `to` whenever `step(from, to)` holds and ends
+    /// at any cell satisfying `is_end`.
+    ///
+    /// For each start (in row-major order) this gives both how many distinct end cells
+    /// are reachable and how many distinct trails lead to an end, which are the score
+    /// and rating of hiking-trail puzzles. Trail counts are memoized per cell, so this
+    /// is linear in the grid size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` allows a trail to return to a cell it has visited, as the count
+    /// would be infinite. Steps that strictly increase a height never do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::Grid;
+    ///
+    /// let map = Grid::parse("0123\n1234\n8765\n9876", |c| c.to_digit(10).unwrap()).unwrap();
+    /// let trails = map.count_paths_monotonic(|&h| h == 0, |&a, &b| b == a + 1, |&h| h == 9);
+    /// assert_eq!(trails.len(), 1);
+    /// assert_eq!((trails[0].endpoints, trails[0].paths), (1, 16));
+    /// ```
+    pub fn count_paths_monotonic<S, P, E>(
+        &self,
+        mut is_start: S,
+        mut step: P,
+        mut is_end: E,
+    ) -> Vec<Trails>
+    where
+        S: FnMut(&T) -> bool,
+        P: FnMut(&T, &T) -> bool,
+        E: FnMut(&T) -> bool,
+    {
+        // Successors of every cell, and whether it ends a trail
+        let next: Vec<Vec<usize>> = self
+            .iter()
+            .map(|(p, cell)| {
+                self.neighbor_cells4(p)
+                    .filter(|(_, to)| step(cell, to))
+                    .filter_map(|(q, _)| self.offset(q))
+                    .collect()
+            })
+            .collect();
+        let ends: Vec<bool> = self.cells.iter().map(&mut is_end).collect();
+
+        let mut paths: Vec<Option<u64>> = vec![None; self.cells.len()];
+        let mut in_progress = vec![false; self.cells.len()];
+        let starts: Vec<(Point2, usize)> = self
+            .iter()
+            .filter(|(_, cell)| is_start(cell))
+            .filter_map(|(p, _)| Some((p, self.offset(p)?)))
+            .collect();
+
+        starts
+            .into_iter()
+            .map(|(start, i)| {
+                count_trails(i, &next, &ends, &mut paths, &mut in_progress);
+                let mut seen = vec![false; self.cells.len()];
+                seen[i] = true;
+                let mut stack = vec![i];
+                let mut endpoints = 0;
+                while let Some(j) = stack.pop() {
+                    endpoints += ends[j] as usize;
+                    for &k in &next[j] {
+                        if !seen[k] {
+                            seen[k] = true;
+                            stack.push(k);
+                        }
+                    }
+                }
+                Trails {
+                    start,
+                    endpoints,
+                    paths: paths[i].unwrap_or(0),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the position of the first cell (in row-major order) matching `pred`.
+    pub fn find<F>(&self, mut pred: F) -> Option<Point2>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().find(|(_, cell)| pred(cell)).map(|(p, _)| p)
+    }
+
+    /// Returns a new grid of the same size with every cell mapped through `f`.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Returns a view of the grid that displays every cell through `cell`, for
+    /// printing intermediate states of a simulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::Grid;
+    ///
+    /// let grid = Grid::parse("#.\n.#", |c| c == '#').unwrap();
+    /// let shown = grid.display_with(|&on| if on { '█' } else { ' ' });
+    /// assert_eq!(shown.to_string(), "█ \n █");
+    /// ```
+    pub fn display_with<F, D>(&self, cell: F) -> GridDisplay<'_, T, F>
+    where
+        F: Fn(&T) -> D,
+        D: fmt::Display,
+    {
+        GridDisplay { grid: self, cell }
+    }
+
+    /// Compares the grid with a later state of it, for seeing what changed between
+    /// steps of a simulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::{Grid, Point2};
+    ///
+    /// let before = Grid::parse("@@.\n.@.", |c| c).unwrap();
+    /// let after = Grid::parse("x@.\n.@.", |c| c).unwrap();
+    /// let diff = before.diff(&after);
+    /// assert_eq!(diff.changed(), vec![Point2::new(0, 0)]);
+    /// assert_eq!(diff.markers("[", "]").to_string(), "[x]@.\n.@.");
+    /// ```
+    pub fn diff<'a>(&'a self, later: &'a Grid<T>) -> GridDiff<'a, T>
+    where
+        T: PartialEq,
+    {
+        GridDiff {
+            before: self,
+            after: later,
+            markers: ("\x1b[7m", "\x1b[0m"),
+        }
+    }
+
+    /// Returns the grid mirrored along its main diagonal: row `i` becomes column `i`.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// Returns the grid rotated a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    /// Returns the grid rotated a quarter turn counter-clockwise.
+    pub fn rotate_ccw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remap(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+
+    /// Returns the grid mirrored left to right.
+    pub fn flip_h(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remap(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    /// Returns the grid mirrored top to bottom.
+    pub fn flip_v(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remap(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+
+    /// Builds a `width` x `height` grid whose cell `(x, y)` is copied from the cell of
+    /// `self` at `source(x, y)`.
+    fn remap<F>(&self, width: usize, height: usize, source: F) -> Grid<T>
+    where
+        T: Clone,
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let cells = (0..width * height)
+            .map(|i| {
+                let (x, y) = source(i % width, i / width);
+                self.cells[y * self.width + x].clone()
+            })
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Returns a 64-bit hash of the grid's size and cells that is the same on every
+    /// run, unlike the randomly seeded hashes of `HashMap`, so it can be logged or
+    /// compared between runs.
+    ///
+    /// Keeping hashes instead of whole grids is the cheap way to spot a repeated state
+    /// in a long simulation. Hashes can collide, so confirm a repeat before relying
+    /// on it, or use [`Grid::pack`] for an exact key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::Grid;
+    /// use std::collections::HashMap;
+    ///
+    /// // Mirroring a row gets back to the start every two steps
+    /// let mut grid = Grid::parse("ab.", |c| c).unwrap();
+    /// let mut seen = HashMap::new();
+    /// let mut step = 0;
+    /// let repeat = loop {
+    ///     if let Some(first) = seen.insert(grid.state_hash(), step) {
+    ///         break (first, step - first);
+    ///     }
+    ///     grid = grid.flip_h();
+    ///     step += 1;
+    /// };
+    /// assert_eq!(repeat, (0, 2));
+    /// ```
+    pub fn state_hash(&self) -> u64
+    where
+        T: Hash,
+    {
+        let mut hasher = Fnv1a::default();
+        self.width.hash(&mut hasher);
+        self.height.hash(&mut hasher);
+        self.cells.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Packs every cell into `bits` bits, giving an exact key for the grid's state
+    /// that is far smaller than the grid itself. `cell` maps each cell to a code below
+    /// `2^bits`; a grid of rocks, walls and space needs only two bits a cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not between 1 and 64, or a code does not fit in `bits`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::Grid;
+    ///
+    /// let grid = Grid::parse("O.#\n.O.", |c| c).unwrap();
+    /// let code = |c: &char| match c { '.' => 0, 'O' => 1, _ => 2 };
+    /// let packed = grid.pack(2, code);
+    /// assert_eq!(packed.unpack(|v| ['.', 'O', '#'][v as usize]), grid);
+    /// ```
+    pub fn pack<F>(&self, bits: u32, mut cell: F) -> PackedGrid
+    where
+        F: FnMut(&T) -> u64,
+    {
+        assert!((1..=64).contains(&bits), "cells take 1 to 64 bits");
+        let mut words = vec![0u64; (self.cells.len() * bits as usize).div_ceil(64)];
+        for (i, c) in self.cells.iter().enumerate() {
+            let code = cell(c);
+            assert!(
+                bits == 64 || code >> bits == 0,
+                "code {} does not fit in {} bits",
+                code,
+                bits
+            );
+            let at = i * bits as usize;
+            let (word, shift) = (at / 64, at % 64);
+            words[word] |= code << shift;
+            if shift + bits as usize > 64 {
+                words[word + 1] |= code >> (64 - shift);
+            }
+        }
+        PackedGrid {
+            width: self.width,
+            height: self.height,
+            bits,
+            words,
+        }
+    }
+
+    /// Compares `other` with the same-sized block of this grid whose top-left corner
+    /// is at `at`, returning the number of cells that differ. Stops as soon as more
+    /// than `limit` cells differ, returning `None`, as it does if the block does not
+    /// fit inside this grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::{Grid, Point2};
+    ///
+    /// let grid = Grid::parse("abcd\nefgh", |c| c).unwrap();
+    /// let block = Grid::parse("bc\nfx", |c| c).unwrap();
+    /// assert_eq!(grid.mismatches_at(&block, Point2::new(1, 0), 1), Some(1));
+    /// assert_eq!(grid.mismatches_at(&block, Point2::new(0, 0), 1), None);
+    /// assert_eq!(grid.mismatches_at(&block, Point2::new(3, 0), 9), None);
+    /// ```
+    pub fn mismatches_at(&self, other: &Grid<T>, at: Point2, limit: usize) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        let fits = at.x >= 0
+            && at.y >= 0
+            && at.x as usize + other.width <= self.width
+            && at.y as usize + other.height <= self.height;
+        if !fits {
+            return None;
+        }
+        let (x, y) = (at.x as usize, at.y as usize);
+        let mut count = 0;
+        for (dy, row) in other.rows().enumerate() {
+            let start = (y + dy) * self.width + x;
+            count += slice_mismatches(&self.cells[start..start + other.width], row);
+            if count > limit {
+                return None;
+            }
+        }
+        Some(count)
+    }
+
+    /// Returns every `r` such that folding the grid along the horizontal line above
+    /// row `r` makes exactly `smudges` pairs of overlapping cells differ. Rows that
+    /// fold past the edge are ignored, and each candidate stops being compared as soon
+    /// as it has too many differences.
+    ///
+    /// With `smudges` of 0 these are the lines of perfect reflection; with 1, the
+    /// lines that one changed cell would make perfect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::Grid;
+    ///
+    /// let grid = Grid::parse("#.\n..\n..\n#.", |c| c == '#').unwrap();
+    /// assert_eq!(grid.reflection_rows(0), vec![2]);
+    /// assert_eq!(grid.reflection_columns(0), vec![]);
+    /// assert_eq!(grid.reflection_columns(2), vec![1]);
+    /// ```
+    pub fn reflection_rows(&self, smudges: usize) -> Vec<usize>
+    where
+        T: PartialEq,
+    {
+        let width = self.width;
+        reflections(self.height, smudges, |a, b| {
+            slice_mismatches(
+                &self.cells[a * width..(a + 1) * width],
+                &self.cells[b * width..(b + 1) * width],
+            )
+        })
+    }
+
+    /// Returns every `c` such that folding the grid along the vertical line left of
+    /// column `c` makes exactly `smudges` pairs of cells differ, as for
+    /// [`Grid::reflection_rows`].
+    pub fn reflection_columns(&self, smudges: usize) -> Vec<usize>
+    where
+        T: PartialEq,
+    {
+        reflections(self.width, smudges, |a, b| {
+            self.rows().filter(|row| row[a] != row[b]).count()
+        })
+    }
+
+    /// Returns the index of `p` in `cells`, if it is in bounds.
+    fn offset(&self, p: Point2) -> Option<usize> {
+        self.in_bounds(p)
+            .then(|| p.y as usize * self.width + p.x as usize)
+    }
+}
+
+/// Returns rows turned into columns: `result[x][y] == rows[y][x]`.
+///
+/// # Panics
+///
+/// Panics if the rows are not all the same length.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::transpose;
+///
+/// let rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
+/// assert_eq!(transpose(&rows), vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+/// ```
+pub fn transpose<T: Clone>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    to_rows(&from_rows_checked(rows).transpose())
+}
+
+/// Returns the rows rotated a quarter turn clockwise.
+///
+/// # Panics
+///
+/// Panics if the rows are not all the same length.
+pub fn rotate_cw<T: Clone>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    to_rows(&from_rows_checked(rows).rotate_cw())
+}
+
+/// Returns the rows rotated a quarter turn counter-clockwise.
+///
+/// # Panics
+///
+/// Panics if the rows are not all the same length.
+pub fn rotate_ccw<T: Clone>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    to_rows(&from_rows_checked(rows).rotate_ccw())
+}
+
+/// Returns the rows with each row reversed, mirroring left to right.
+pub fn flip_h<T: Clone>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    rows.iter()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+/// Returns the rows in reverse order, mirroring top to bottom.
+pub fn flip_v<T: Clone>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    rows.iter().rev().cloned().collect()
+}
+
+fn from_rows_checked<T: Clone>(rows: &[Vec<T>]) -> Grid<T> {
+    Grid::from_rows(rows.to_vec()).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn to_rows<T: Clone>(grid: &Grid<T>) -> Vec<Vec<T>> {
+    (0..grid.height)
+        .map(|y| grid.cells[y * grid.width..(y + 1) * grid.width].to_vec())
+        .collect()
+}
+
+/// The trails leaving one start cell, as counted by [`Grid::count_paths_monotonic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Trails {
+    /// The start cell.
+    pub start: Point2,
+    /// How many distinct end cells some trail reaches.
+    pub endpoints: usize,
+    /// How many distinct trails lead to an end cell.
+    pub paths: u64,
+}
+
+/// Memoized count of the trails from cell `i` to an end.
+fn count_trails(
+    i: usize,
+    next: &[Vec<usize>],
+    ends: &[bool],
+    paths: &mut [Option<u64>],
+    in_progress: &mut [bool],
+) -> u64 {
+    if let Some(count) = paths[i] {
+        return count;
+    }
+    assert!(!in_progress[i], "trails must not revisit a cell");
+    in_progress[i] = true;
+    let mut count = ends[i] as u64;
+    for &j in &next[i] {
+        count += count_trails(j, next, ends, paths, in_progress);
+    }
+    in_progress[i] = false;
+    paths[i] = Some(count);
+    count
+}
+
+/// Returns the number of cells that differ between two grids of the same size.
+///
+/// # Panics
+///
+/// Panics if the grids are different sizes.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{mismatch_count, Grid};
+///
+/// let a = Grid::parse("#.#\n...", |c| c).unwrap();
+/// let b = Grid::parse("#..\n..#", |c| c).unwrap();
+/// assert_eq!(mismatch_count(&a, &b), 2);
+/// ```
+pub fn mismatch_count<T: PartialEq>(a: &Grid<T>, b: &Grid<T>) -> usize {
+    assert!(
+        (a.width, a.height) == (b.width, b.height),
+        "cannot compare a {}x{} grid with a {}x{} grid",
+        a.width,
+        a.height,
+        b.width,
+        b.height
+    );
+    slice_mismatches(&a.cells, &b.cells)
+}
+
+fn slice_mismatches<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().zip(b).filter(|(x, y)| x != y).count()
+}
+
+/// Finds the fold lines of `len` rows or columns that have exactly `smudges`
+/// differences, where `mismatches(a, b)` counts the differences between lines `a` and
+/// `b`. Each candidate is abandoned once it has more than `smudges`.
+fn reflections<F>(len: usize, smudges: usize, mut mismatches: F) -> Vec<usize>
+where
+    F: FnMut(usize, usize) -> usize,
+{
+    (1..len)
+        .filter(|&fold| {
+            let mut total = 0;
+            for (a, b) in (0..fold).rev().zip(fold..len) {
+                total += mismatches(a, b);
+                if total > smudges {
+                    return false;
+                }
+            }
+            total == smudges
+        })
+        .collect()
+}
+
+/// A grid of booleans stored one bit per cell, each row padded to whole 64-bit words.
+///
+/// Comparing rows XORs and counts bits a word at a time, which the compiler turns into
+/// wide vector instructions, so reflection and near-match searches over large on/off
+/// grids are much faster than on a [`Grid<bool>`].
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{BitGrid, Grid};
+///
+/// let grid = Grid::parse("#.##..##.\n..#.##.#.\n##......#", |c| c == '#').unwrap();
+/// let bits = BitGrid::from_grid(&grid, |&on| on);
+/// assert_eq!(bits.count_ones(), 12);
+/// assert_eq!(bits.reflection_columns(0), grid.reflection_columns(0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Creates a `width` x `height` grid with every cell off.
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        BitGrid {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    /// Creates a bit grid with the cells of `grid` for which `on` returns `true` set.
+    pub fn from_grid<T, F>(grid: &Grid<T>, mut on: F) -> Self
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut bits = BitGrid::new(grid.width, grid.height);
+        for (p, cell) in grid.iter() {
+            if on(cell) {
+                bits.set(p, true);
+            }
+        }
+        bits
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns whether the cell at `p` is on; cells out of bounds are off.
+    pub fn get(&self, p: Point2) -> bool {
+        self.bit(p)
+            .is_some_and(|(word, bit)| self.words[word] >> bit & 1 == 1)
+    }
+
+    /// Turns the cell at `p` on or off.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is out of bounds.
+    pub fn set(&mut self, p: Point2, on: bool) {
+        let (word, bit) = self.bit(p).unwrap_or_else(|| {
+            panic!("{:?} is outside the {}x{} grid", p, self.width, self.height)
+        });
+        if on {
+            self.words[word] |= 1 << bit;
+        } else {
+            self.words[word] &= !(1 << bit);
+        }
+    }
+
+    /// Returns the number of cells that are on.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns the words of row `y`; bits past the width are always zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &[u64] {
+        assert!(y < self.height, "row {} is outside the grid", y);
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    /// Returns the number of cells that differ between row `y` of this grid and row
+    /// `other_y` of `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grids have different widths or a row is out of bounds.
+    pub fn row_mismatches(&self, y: usize, other: &BitGrid, other_y: usize) -> usize {
+        assert_eq!(self.width, other.width, "rows must be the same width");
+        word_mismatches(self.row(y), other.row(other_y))
+    }
+
+    /// Returns the number of cells that differ between two grids of the same size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grids are different sizes.
+    pub fn mismatch_count(&self, other: &BitGrid) -> usize {
+        assert!(
+            (self.width, self.height) == (other.width, other.height),
+            "cannot compare a {}x{} grid with a {}x{} grid",
+            self.width,
+            self.height,
+            other.width,
+            other.height
+        );
+        word_mismatches(&self.words, &other.words)
+    }
+
+    /// Returns the grid mirrored along its main diagonal.
+    pub fn transpose(&self) -> BitGrid {
+        let mut result = BitGrid::new(self.height, self.width);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let p = Point2::new(x as isize, y as isize);
+                if self.get(p) {
+                    result.set(Point2::new(p.y, p.x), true);
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the horizontal fold lines with exactly `smudges` differences, as for
+    /// [`Grid::reflection_rows`].
+    pub fn reflection_rows(&self, smudges: usize) -> Vec<usize> {
+        reflections(self.height, smudges, |a, b| {
+            word_mismatches(self.row(a), self.row(b))
+        })
+    }
+
+    /// Returns the vertical fold lines with exactly `smudges` differences, as for
+    /// [`Grid::reflection_columns`]. Columns are compared as rows of the transpose.
+    pub fn reflection_columns(&self, smudges: usize) -> Vec<usize> {
+        self.transpose().reflection_rows(smudges)
+    }
+
+    /// Returns the word and bit holding `p`, if it is in bounds.
+    fn bit(&self, p: Point2) -> Option<(usize, usize)> {
+        if p.x < 0 || p.y < 0 || p.x as usize >= self.width || p.y as usize >= self.height {
+            return None;
+        }
+        let (x, y) = (p.x as usize, p.y as usize);
+        Some((y * self.stride + x / 64, x % 64))
+    }
+}
+
+fn word_mismatches(a: &[u64], b: &[u64]) -> usize {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x ^ y).count_ones() as usize)
+        .sum()
+}
+
+/// A grid's cells packed into a few bits each, as returned by [`Grid::pack`]. Hashable
+/// and comparable, so it works as a `HashSet` or `HashMap` key, for example as the
+/// state in [`find_cycle`](crate::cycle::find_cycle).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PackedGrid {
+    width: usize,
+    height: usize,
+    bits: u32,
+    words: Vec<u64>,
+}
+
+impl PackedGrid {
+    /// Returns the code of the cell at `p`, or `None` if it is out of bounds.
+    pub fn get(&self, p: Point2) -> Option<u64> {
+        if p.x < 0 || p.y < 0 || p.x as usize >= self.width || p.y as usize >= self.height {
+            return None;
+        }
+        let bits = self.bits as usize;
+        let at = (p.y as usize * self.width + p.x as usize) * bits;
+        let (word, shift) = (at / 64, at % 64);
+        let mut code = self.words[word] >> shift;
+        if shift + bits > 64 {
+            code |= self.words[word + 1] << (64 - shift);
+        }
+        Some(if bits == 64 {
+            code
+        } else {
+            code & ((1 << bits) - 1)
+        })
+    }
+
+    /// Rebuilds a grid, mapping each code back to a cell with `cell`.
+    pub fn unpack<T, F>(&self, mut cell: F) -> Grid<T>
+    where
+        F: FnMut(u64) -> T,
+    {
+        let width = self.width;
+        let cells = (0..width * self.height)
+            .map(|i| {
+                let p = Point2::new((i % width) as isize, (i / width) as isize);
+                cell(self.get(p).expect("position is in bounds"))
+            })
+            .collect();
+        Grid {
+            width,
+            height: self.height,
+            cells,
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash, used by [`Grid::state_hash`] because it is fixed rather
+/// than randomly seeded.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<T> Index<Point2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2) -> &T {
+        let (width, height) = (self.width, self.height);
+        self.get(p)
+            .unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", p, width, height))
+    }
+}
+
+impl<T> IndexMut<Point2> for Grid<T> {
+    fn index_mut(&mut self, p: Point2) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", p, width, height))
+    }
+}
+
+/// A read-only view of a grid that repeats it infinitely in every direction, as in
+/// puzzles where "the map repeats".
+///
+/// Out-of-bounds positions are wrapped with modular arithmetic, so no cells are copied.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{Grid, Point2, WrappingGrid};
+///
+/// let grid = Grid::parse("ab\ncd", |c| c).unwrap();
+/// let tiled = WrappingGrid::new(&grid);
+/// assert_eq!(tiled[Point2::new(2, 0)], 'a');
+/// assert_eq!(tiled[Point2::new(-1, -1)], 'd');
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrappingGrid<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<'a, T> WrappingGrid<'a, T> {
+    /// Wraps `grid`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid has no cells, since there is nothing to repeat.
+    pub fn new(grid: &'a Grid<T>) -> Self {
+        assert!(
+            grid.width > 0 && grid.height > 0,
+            "cannot repeat an empty grid"
+        );
+        WrappingGrid { grid }
+    }
+
+    /// Returns the underlying grid.
+    pub fn grid(&self) -> &'a Grid<T> {
+        self.grid
+    }
+
+    /// Returns the position in the underlying grid that `p` maps to.
+    pub fn wrap(&self, p: Point2) -> Point2 {
+        Point2::new(
+            p.x.rem_euclid(self.grid.width as isize),
+            p.y.rem_euclid(self.grid.height as isize),
+        )
+    }
+
+    /// Returns the cell that `p` maps to.
+    pub fn get(&self, p: Point2) -> &'a T {
+        &self.grid[self.wrap(p)]
+    }
+}
+
+impl<T> Index<Point2> for WrappingGrid<'_, T> {
+    type Output = T;
+
+    fn index(&self, p: Point2) -> &T {
+        self.get(p)
+    }
+}
+
+/// An unbounded grid backed by a hash map, where every cell that was never set holds a
+/// default value.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{InfiniteGrid, Point2};
+///
+/// let mut grid = InfiniteGrid::new('.');
+/// grid.set(Point2::new(-5, 100), '#');
+/// assert_eq!(grid[Point2::new(-5, 100)], '#');
+/// assert_eq!(grid[Point2::new(1_000_000, 0)], '.');
+/// assert_eq!(grid.bounds(), Some((Point2::new(-5, 100), Point2::new(-5, 100))));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfiniteGrid<T> {
+    cells: HashMap<Point2, T>,
+    default: T,
+}
+
+impl<T> InfiniteGrid<T> {
+    /// Creates a grid where every cell holds `default`.
+    pub fn new(default: T) -> Self {
+        InfiniteGrid {
+            cells: HashMap::new(),
+            default,
+        }
+    }
+
+    /// Creates an infinite grid holding the cells of `grid` at their positions and
+    /// `default` everywhere else.
+    pub fn from_grid(grid: &Grid<T>, default: T) -> Self
+    where
+        T: Clone,
+    {
+        InfiniteGrid {
+            cells: grid.iter().map(|(p, cell)| (p, cell.clone())).collect(),
+            default,
+        }
+    }
+
+    /// Returns the value of cells that were never set.
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// Returns the cell at `p`.
+    pub fn get(&self, p: Point2) -> &T {
+        self.cells.get(&p).unwrap_or(&self.default)
+    }
+
+    /// Returns a mutable reference to the cell at `p`, storing a copy of the default
+    /// there first if it was never set.
+    pub fn get_mut(&mut self, p: Point2) -> &mut T
+    where
+        T: Clone,
+    {
+        self.cells.entry(p).or_insert_with(|| self.default.clone())
+    }
+
+    /// Sets the cell at `p`, returning its previous value if it had been set.
+    pub fn set(&mut self, p: Point2, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    /// Resets the cell at `p` to the default, returning its value if it had been set.
+    pub fn remove(&mut self, p: Point2) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    /// Returns the number of cells that have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns whether no cell has been set.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Iterates over the cells that have been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.cells.iter().map(|(&p, cell)| (p, cell))
+    }
+
+    /// Returns the top-left and bottom-right corners of the smallest rectangle holding
+    /// every cell that has been set, or `None` if there are none.
+    pub fn bounds(&self) -> Option<(Point2, Point2)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point2::new(min.x.min(p.x), min.y.min(p.y)),
+                Point2::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+}
+
+impl<T> Index<Point2> for InfiniteGrid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2) -> &T {
+        self.get(p)
+    }
+}
+
+impl<T: Clone> IndexMut<Point2> for InfiniteGrid<T> {
+    fn index_mut(&mut self, p: Point2) -> &mut T {
+        self.get_mut(p)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    /// Writes the grid row by row, each cell with its own `Display`, without a trailing
+    /// newline.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_rows(self, f, |f, cell| write!(f, "{}", cell))
+    }
+}
+
+/// A grid rendered with a custom cell formatter, as returned by
+/// [`Grid::display_with`].
+pub struct GridDisplay<'a, T, F> {
+    grid: &'a Grid<T>,
+    cell: F,
+}
+
+impl<T, F, D> fmt::Display for GridDisplay<'_, T, F>
+where
+    F: Fn(&T) -> D,
+    D: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_rows(self.grid, f, |f, cell| write!(f, "{}", (self.cell)(cell)))
+    }
+}
+
+/// Writes the cells of a grid row by row, separating rows with newlines.
+fn write_rows<T, W>(grid: &Grid<T>, f: &mut fmt::Formatter, mut write_cell: W) -> fmt::Result
+where
+    W: FnMut(&mut fmt::Formatter, &T) -> fmt::Result,
+{
+    for (y, row) in grid.rows().enumerate() {
+        if y > 0 {
+            writeln!(f)?;
+        }
+        for cell in row {
+            write_cell(f, cell)?;
+        }
+    }
+    Ok(())
+}
+
+/// The changes between two grids, as returned by [`Grid::diff`].
+///
+/// Displays the newer grid with every changed cell wrapped in markers, by default ANSI
+/// reverse video so changes stand out in a terminal.
+pub struct GridDiff<'a, T> {
+    before: &'a Grid<T>,
+    after: &'a Grid<T>,
+    markers: (&'a str, &'a str),
+}
+
+impl<'a, T: PartialEq> GridDiff<'a, T> {
+    /// Replaces the markers written before and after each changed cell.
+    pub fn markers(self, start: &'a str, end: &'a str) -> Self {
+        GridDiff {
+            markers: (start, end),
+            ..self
+        }
+    }
+
+    /// Returns the positions of the newer grid whose cells differ from the older grid
+    /// (or lie outside it), in row-major order.
+    pub fn changed(&self) -> Vec<Point2> {
+        self.after
+            .iter()
+            .filter(|&(p, cell)| self.before.get(p) != Some(cell))
+            .map(|(p, _)| p)
+            .collect()
+    }
+}
+
+impl<T: PartialEq + fmt::Display> fmt::Display for GridDiff<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (start, end) = self.markers;
+        for (p, cell) in self.after.iter() {
+            if p.x == 0 && p.y > 0 {
+                writeln!(f)?;
+            }
+            if self.before.get(p) == Some(cell) {
+                write!(f, "{}", cell)?;
+            } else {
+                write!(f, "{}{}{}", start, cell, end)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_index() {
+        let grid = Grid::parse("abc\ndef\n\n", |c| c).unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Point2::new(0, 0)], 'a');
+        assert_eq!(grid[Point2::new(2, 1)], 'f');
+        assert_eq!(grid.get(Point2::new(-1, 0)), None);
+        assert_eq!(grid.get(Point2::new(0, 2)), None);
+    }
+
+    #[test]
+    fn test_parse_ragged_fails() {
+        assert!(Grid::parse("abc\nde", |c| c).is_err());
+    }
+
+    #[test]
+    fn test_new_and_mutate() {
+        let mut grid = Grid::new(2, 3, 0);
+        grid[Point2::new(1, 2)] = 5;
+        *grid.get_mut(Point2::new(0, 0)).unwrap() += 1;
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[1, 0][..], &[0, 0], &[0, 5]]
+        );
+        assert!(grid.get_mut(Point2::new(2, 0)).is_none());
+    }
+
+    #[test]
+    fn test_positions_and_find() {
+        let grid = Grid::parse("..\n.^", |c| c).unwrap();
+        assert_eq!(
+            grid.positions().collect::<Vec<_>>(),
+            vec![
+                Point2::new(0, 0),
+                Point2::new(1, 0),
+                Point2::new(0, 1),
+                Point2::new(1, 1)
+            ]
+        );
+        assert_eq!(grid.find(|&c| c == '^'), Some(Point2::new(1, 1)));
+        assert_eq!(grid.find(|&c| c == '#'), None);
+    }
+
+    #[test]
+    fn test_map() {
+        let grid = Grid::parse("#.\n.#", |c| c).unwrap();
+        let walls = grid.map(|&c| c == '#');
+        assert_eq!(walls.iter().filter(|(_, &wall)| wall).count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside")]
+    fn test_index_out_of_bounds_panics() {
+        let grid = Grid::new(1, 1, 0);
+        let _ = grid[Point2::new(1, 0)];
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = Grid::parse("abc\ndef", |c| c).unwrap();
+        let text = |g: &Grid<char>| {
+            g.rows()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert_eq!(text(&grid.transpose()), "ad\nbe\ncf");
+        assert_eq!(text(&grid.rotate_cw()), "da\neb\nfc");
+        assert_eq!(text(&grid.rotate_ccw()), "cf\nbe\nad");
+        assert_eq!(text(&grid.flip_h()), "cba\nfed");
+        assert_eq!(text(&grid.flip_v()), "def\nabc");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw(), grid.flip_h().flip_v());
+        assert_eq!(grid.transpose(), grid.rotate_cw().flip_h());
+        let empty: Grid<char> = Grid::new(0, 0, ' ');
+        assert_eq!(empty.rotate_cw(), empty);
+    }
+
+    #[test]
+    fn test_row_transforms_match_grid() {
+        let rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let grid = Grid::from_rows(rows.clone()).unwrap();
+        assert_eq!(transpose(&rows), to_rows(&grid.transpose()));
+        assert_eq!(rotate_cw(&rows), to_rows(&grid.rotate_cw()));
+        assert_eq!(rotate_ccw(&rows), to_rows(&grid.rotate_ccw()));
+        assert_eq!(flip_h(&rows), to_rows(&grid.flip_h()));
+        assert_eq!(flip_v(&rows), vec![vec![4, 5, 6], vec![1, 2, 3]]);
+        assert_eq!(transpose::<u8>(&[]), Vec::<Vec<u8>>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "Row 2 has 1 cells")]
+    fn test_transpose_ragged_panics() {
+        transpose(&[vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::parse("abc\ndef", |c| c).unwrap();
+        assert_eq!(grid.neighbors4(Point2::ORIGIN).count(), 2);
+        assert_eq!(grid.neighbors8(Point2::ORIGIN).count(), 3);
+        assert_eq!(grid.neighbors8(Point2::new(1, 0)).count(), 5);
+        let cells: String = grid
+            .neighbor_cells4(Point2::new(1, 1))
+            .map(|(_, &c)| c)
+            .collect();
+        assert_eq!(cells, "bfd");
+        let cells: String = grid
+            .neighbor_cells8(Point2::new(2, 1))
+            .map(|(_, &c)| c)
+            .collect();
+        assert_eq!(cells, "bce");
+    }
+
+    #[test]
+    fn test_wrapping_grid() {
+        let grid = Grid::parse("abc\ndef", |c| c).unwrap();
+        let tiled = WrappingGrid::new(&grid);
+        assert_eq!(tiled.wrap(Point2::new(7, -3)), Point2::new(1, 1));
+        assert_eq!(tiled[Point2::new(7, -3)], 'e');
+        assert_eq!(tiled[Point2::new(-3, 4)], 'a');
+        assert_eq!(*tiled.get(Point2::new(2, 1)), 'f');
+        assert_eq!(tiled.grid(), &grid);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty grid")]
+    fn test_wrapping_empty_grid_panics() {
+        let grid: Grid<u8> = Grid::new(0, 3, 0);
+        WrappingGrid::new(&grid);
+    }
+
+    #[test]
+    fn test_infinite_grid() {
+        let grid = Grid::parse("#.\n.#", |c| c == '#').unwrap();
+        let mut infinite = InfiniteGrid::from_grid(&grid, false);
+        assert_eq!(infinite.len(), 4);
+        assert!(infinite[Point2::new(1, 1)]);
+        assert!(!infinite[Point2::new(-10, 10)]);
+
+        infinite[Point2::new(-3, 5)] = true;
+        *infinite.get_mut(Point2::new(4, -2)) |= false;
+        assert_eq!(infinite.len(), 6);
+        assert_eq!(
+            infinite.bounds(),
+            Some((Point2::new(-3, -2), Point2::new(4, 5)))
+        );
+        assert_eq!(infinite.set(Point2::new(-3, 5), false), Some(true));
+        assert_eq!(infinite.remove(Point2::new(-3, 5)), Some(false));
+        assert_eq!(infinite.remove(Point2::new(-3, 5)), None);
+        assert_eq!(infinite.iter().filter(|(_, &on)| on).count(), 2);
+
+        let empty: InfiniteGrid<u8> = InfiniteGrid::new(0);
+        assert!(empty.is_empty());
+        assert_eq!(empty.bounds(), None);
+        assert_eq!(*empty.default_value(), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = Grid::parse("12\n34", |c| c.to_digit(10).unwrap()).unwrap();
+        assert_eq!(grid.to_string(), "12\n34");
+        assert_eq!(
+            grid.display_with(|n| format!("{:>3}", n * 10)).to_string(),
+            " 10 20\n 30 40"
+        );
+        assert_eq!(Grid::<u8>::new(0, 0, 0).to_string(), "");
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = Grid::parse("ab\ncd", |c| c).unwrap();
+        let after = Grid::parse("ab\nxd", |c| c).unwrap();
+        let diff = before.diff(&after);
+        assert_eq!(diff.changed(), vec![Point2::new(0, 1)]);
+        assert_eq!(diff.to_string(), "ab\n\x1b[7mx\x1b[0md");
+        assert!(before.diff(&before).changed().is_empty());
+
+        // Cells outside the older grid count as changed
+        let wider = Grid::parse("abz\ncdz", |c| c).unwrap();
+        assert_eq!(
+            before.diff(&wider).markers("*", "").to_string(),
+            "ab*z\ncd*z"
+        );
+    }
+
+    const TRAILS: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+    #[test]
+    fn test_count_paths_monotonic() {
+        let map = Grid::parse(TRAILS, |c| c.to_digit(10).unwrap()).unwrap();
+        let trails = map.count_paths_monotonic(|&h| h == 0, |&a, &b| b == a + 1, |&h| h == 9);
+        assert_eq!(trails.len(), 9);
+        assert_eq!(trails.iter().map(|t| t.endpoints).sum::<usize>(), 36);
+        assert_eq!(trails.iter().map(|t| t.paths).sum::<u64>(), 81);
+        let scores: Vec<usize> = trails.iter().map(|t| t.endpoints).collect();
+        assert_eq!(scores, vec![5, 6, 5, 3, 1, 3, 5, 3, 5]);
+        assert_eq!(trails[0].start, Point2::new(2, 0));
+    }
+
+    #[test]
+    fn test_count_paths_monotonic_unreachable_and_impassable() {
+        // '.' cells are impassable
+        let map = Grid::parse("0.9\n1.8\n234", |c| c.to_digit(10)).unwrap();
+        let step =
+            |a: &Option<u32>, b: &Option<u32>| matches!((a, b), (Some(a), Some(b)) if *b == a + 1);
+        let trails = map.count_paths_monotonic(|&h| h == Some(0), step, |&h| h == Some(9));
+        assert_eq!(trails[0].endpoints, 0);
+        assert_eq!(trails[0].paths, 0);
+        let trails = map.count_paths_monotonic(|&h| h == Some(0), step, |&h| h == Some(4));
+        assert_eq!((trails[0].endpoints, trails[0].paths), (1, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "revisit")]
+    fn test_count_paths_monotonic_cycle_panics() {
+        let map = Grid::new(2, 2, 0);
+        map.count_paths_monotonic(|_| true, |_, _| true, |_| false);
+    }
+
+    #[test]
+    fn test_state_hash() {
+        let a = Grid::parse("#.\n.#", |c| c).unwrap();
+        let b = Grid::parse("#.\n.#", |c| c).unwrap();
+        assert_eq!(a.state_hash(), b.state_hash());
+        assert_ne!(a.state_hash(), a.flip_h().state_hash());
+        // The size is part of the state: same cells, different shape
+        let row = Grid::parse("#..#", |c| c).unwrap();
+        assert_ne!(a.state_hash(), row.state_hash());
+        // Fixed across runs
+        assert_eq!(
+            Grid::new(0, 0, 0u8).state_hash(),
+            Grid::new(0, 0, 0u8).state_hash()
+        );
+    }
+
+    #[test]
+    fn test_pack_round_trip() {
+        for bits in [1, 3, 7, 13, 64] {
+            let max = if bits == 64 {
+                u64::MAX
+            } else {
+                (1 << bits) - 1
+            };
+            let grid = Grid::from_rows(
+                (0..5u64)
+                    .map(|y| {
+                        (0..7u64)
+                            .map(|x| (x * 31 + y * 17) % (max.min(1000) + 1))
+                            .collect()
+                    })
+                    .collect(),
+            )
+            .unwrap();
+            let packed = grid.pack(bits, |&v| v);
+            assert_eq!(packed.unpack(|v| v), grid, "{} bits", bits);
+            assert_eq!(packed.get(Point2::new(7, 0)), None);
+        }
+        let grid = Grid::parse("#.\n.#", |c| c == '#').unwrap();
+        let packed = grid.pack(1, |&on| on as u64);
+        assert_eq!(packed, grid.pack(1, |&on| on as u64));
+        assert_ne!(packed, grid.flip_h().pack(1, |&on| on as u64));
+        let mut seen = std::collections::HashSet::new();
+        assert!(seen.insert(packed.clone()));
+        assert!(!seen.insert(packed));
+    }
+
+    #[test]
+    fn test_pack_with_find_cycle() {
+        // A single cell bouncing along a row of four, packed as the cycle state
+        let step = |(grid, dir): &(PackedGrid, isize)| {
+            let dir = *dir;
+            let grid = grid.unpack(|v| v == 1);
+            let at = grid.find(|&on| on).unwrap();
+            let dir = if grid.in_bounds(at + Point2::new(dir, 0)) {
+                dir
+            } else {
+                -dir
+            };
+            let mut next = Grid::new(4, 1, false);
+            next[at + Point2::new(dir, 0)] = true;
+            (next.pack(1, |&on| on as u64), dir)
+        };
+        let start = Grid::parse("#...", |c| c == '#').unwrap();
+        let cycle = crate::lib::cycle::find_cycle((start.pack(1, |&on| on as u64), 1), step);
+        assert_eq!(cycle.length, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_pack_code_too_large_panics() {
+        Grid::new(1, 1, 4u64).pack(2, |&v| v);
+    }
+
+    const MIRRORS: [&str; 2] = [
+        "#.##..##.\n..#.##.#.\n##......#\n##......#\n..#.##.#.\n..##..##.\n#.#.##.#.",
+        "#...##..#\n#....#..#\n..##..###\n#####.##.\n#####.##.\n..##..###\n#....#..#",
+    ];
+
+    #[test]
+    fn test_reflections() {
+        let summary = |smudges: usize| -> usize {
+            MIRRORS
+                .iter()
+                .map(|pattern| {
+                    let grid = Grid::parse(pattern, |c| c == '#').unwrap();
+                    let columns: usize = grid.reflection_columns(smudges).iter().sum();
+                    let rows: usize = grid.reflection_rows(smudges).iter().sum();
+                    columns + 100 * rows
+                })
+                .sum()
+        };
+        assert_eq!(summary(0), 405);
+        assert_eq!(summary(1), 400);
+        let single = Grid::new(1, 1, '#');
+        assert!(single.reflection_rows(0).is_empty());
+    }
+
+    #[test]
+    fn test_bit_grid_matches_grid() {
+        for pattern in MIRRORS {
+            let grid = Grid::parse(pattern, |c| c == '#').unwrap();
+            let bits = BitGrid::from_grid(&grid, |&on| on);
+            for smudges in 0..3 {
+                assert_eq!(bits.reflection_rows(smudges), grid.reflection_rows(smudges));
+                assert_eq!(
+                    bits.reflection_columns(smudges),
+                    grid.reflection_columns(smudges)
+                );
+            }
+            assert_eq!(bits.transpose().transpose(), bits);
+        }
+        let a = Grid::parse(MIRRORS[0], |c| c == '#').unwrap();
+        let b = Grid::parse(MIRRORS[1], |c| c == '#').unwrap();
+        let (bits_a, bits_b) = (
+            BitGrid::from_grid(&a, |&on| on),
+            BitGrid::from_grid(&b, |&on| on),
+        );
+        assert_eq!(bits_a.mismatch_count(&bits_b), mismatch_count(&a, &b));
+        assert_eq!(bits_a.row_mismatches(2, &bits_a, 3), 0);
+        assert_eq!(bits_a.row_mismatches(0, &bits_b, 0), 7);
+    }
+
+    #[test]
+    fn test_bit_grid_wide_rows() {
+        // Rows longer than one word, with cells on both sides of the boundary
+        let mut bits = BitGrid::new(130, 2);
+        for x in [0, 63, 64, 129] {
+            bits.set(Point2::new(x, 1), true);
+        }
+        assert_eq!(bits.row(1).len(), 3);
+        assert!(bits.get(Point2::new(64, 1)));
+        assert!(!bits.get(Point2::new(130, 1)));
+        assert_eq!(bits.count_ones(), 4);
+        assert_eq!(bits.row_mismatches(0, &bits, 1), 4);
+        bits.set(Point2::new(63, 1), false);
+        assert_eq!(bits.count_ones(), 3);
+        let transposed = bits.transpose();
+        assert_eq!((transposed.width(), transposed.height()), (2, 130));
+        assert!(transposed.get(Point2::new(1, 129)));
+    }
+
+    #[test]
+    fn test_mismatches_at() {
+        let grid = Grid::parse("abcd\nefgh\nijkl", |c| c).unwrap();
+        let block = Grid::parse("fg\njk", |c| c).unwrap();
+        assert_eq!(grid.mismatches_at(&block, Point2::new(1, 1), 0), Some(0));
+        assert_eq!(grid.mismatches_at(&block, Point2::new(2, 1), 4), Some(4));
+        assert_eq!(grid.mismatches_at(&block, Point2::new(2, 1), 3), None);
+        assert_eq!(grid.mismatches_at(&block, Point2::new(-1, 0), 9), None);
+        assert_eq!(mismatch_count(&grid, &grid), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot compare")]
+    fn test_mismatch_count_size_panics() {
+        mismatch_count(&Grid::new(2, 1, 0), &Grid::new(1, 2, 0));
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::VecDeque;
+use std::iter::Peekable;
+
+/// Small iterator adapters that come up in almost every puzzle.
+///
+/// Implemented for every iterator; bring it into scope with
+/// `use aoclib::AocIterExt;`.
+pub trait AocIterExt: Iterator + Sized {
+    /// Yields consecutive non-overlapping groups of `n` items as vectors, dropping any
+    /// leftover items at the end.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::AocIterExt;
+    ///
+    /// let groups: Vec<Vec<u8>> = (1..=7).chunks_exact_vec(3).collect();
+    /// assert_eq!(groups, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    /// ```
+    fn chunks_exact_vec(self, n: usize) -> ChunksExactVec<Self> {
+        assert!(n > 0, "chunk size must be positive");
+        ChunksExactVec { iter: self, n }
+    }
+
+    /// Yields every run of `n` consecutive items as a vector, each overlapping the
+    /// previous one by `n - 1` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::AocIterExt;
+    ///
+    /// // Sums of three-measurement sliding windows
+    /// let depths = [199, 200, 208, 210, 200];
+    /// let sums: Vec<u32> = depths
+    ///     .into_iter()
+    ///     .windows_vec(3)
+    ///     .map(|w| w.iter().sum())
+    ///     .collect();
+    /// assert_eq!(sums, vec![607, 618, 618]);
+    /// ```
+    fn windows_vec(self, n: usize) -> WindowsVec<Self>
+    where
+        Self::Item: Clone,
+    {
+        assert!(n > 0, "window size must be positive");
+        WindowsVec {
+            iter: self,
+            n,
+            window: VecDeque::with_capacity(n),
+        }
+    }
+
+    /// Like [`Iterator::take_while`], but also yields the first item that fails the
+    /// predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::AocIterExt;
+    ///
+    /// // Trees visible looking right from a height of 5, including the one that blocks
+    /// let visible = [3, 5, 4, 9].into_iter().take_while_inclusive(|&h| h < 5).count();
+    /// assert_eq!(visible, 2);
+    /// ```
+    fn take_while_inclusive<P>(self, predicate: P) -> TakeWhileInclusive<Self, P>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        TakeWhileInclusive {
+            iter: self,
+            predicate,
+            done: false,
+        }
+    }
+
+    /// Groups runs of consecutive items with the same key, yielding each key with the
+    /// items of its run. Unlike a hash-based group-by, a key that reappears later starts
+    /// a new group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::AocIterExt;
+    ///
+    /// // Run-length encoding
+    /// let runs: Vec<(char, usize)> = "aaabccaa"
+    ///     .chars()
+    ///     .group_consecutive_by(|&c| c)
+    ///     .map(|(c, run)| (c, run.len()))
+    ///     .collect();
+    /// assert_eq!(runs, vec![('a', 3), ('b', 1), ('c', 2), ('a', 2)]);
+    /// ```
+    fn group_consecutive_by<K, F>(self, key: F) -> GroupConsecutiveBy<Self, F>
+    where
+        K: PartialEq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        GroupConsecutiveBy {
+            iter: self.peekable(),
+            key,
+        }
+    }
+
+    /// Yields every pair of adjacent items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::AocIterExt;
+    ///
+    /// let increases = [199, 200, 208, 210, 200, 207]
+    ///     .into_iter()
+    ///     .pairwise()
+    ///     .filter(|(a, b)| b > a)
+    ///     .count();
+    /// assert_eq!(increases, 4);
+    /// ```
+    fn pairwise(self) -> Pairwise<Self>
+    where
+        Self::Item: Clone,
+    {
+        Pairwise {
+            iter: self,
+            previous: None,
+        }
+    }
+}
+
+impl<I: Iterator> AocIterExt for I {}
+
+/// Iterator returned by [`AocIterExt::chunks_exact_vec`].
+#[derive(Debug, Clone)]
+pub struct ChunksExactVec<I> {
+    iter: I,
+    n: usize,
+}
+
+impl<I: Iterator> Iterator for ChunksExactVec<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk: Vec<I::Item> = self.iter.by_ref().take(self.n).collect();
+        (chunk.len() == self.n).then_some(chunk)
+    }
+}
+
+/// Iterator returned by [`AocIterExt::windows_vec`].
+#[derive(Debug, Clone)]
+pub struct WindowsVec<I: Iterator> {
+    iter: I,
+    n: usize,
+    window: VecDeque<I::Item>,
+}
+
+impl<I> Iterator for WindowsVec<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == self.n {
+            self.window.pop_front();
+        }
+        while self.window.len() < self.n {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+}
+
+/// Iterator returned by [`AocIterExt::take_while_inclusive`].
+#[derive(Debug, Clone)]
+pub struct TakeWhileInclusive<I, P> {
+    iter: I,
+    predicate: P,
+    done: bool,
+}
+
+impl<I, P> Iterator for TakeWhileInclusive<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.iter.next()?;
+        self.done = !(self.predicate)(&item);
+        Some(item)
+    }
+}
+
+/// Iterator returned by [`AocIterExt::group_consecutive_by`].
+pub struct GroupConsecutiveBy<I: Iterator, F> {
+    iter: Peekable<I>,
+    key: F,
+}
+
+impl<I, K, F> Iterator for GroupConsecutiveBy<I, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = (K, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.iter.next()?;
+        let key = (self.key)(&first);
+        let mut group = vec![first];
+        while let Some(item) = self.iter.next_if(|item| (self.key)(item) == key) {
+            group.push(item);
+        }
+        Some((key, group))
+    }
+}
+
+/// Iterator returned by [`AocIterExt::pairwise`].
+#[derive(Debug, Clone)]
+pub struct Pairwise<I: Iterator> {
+    iter: I,
+    previous: Option<I::Item>,
+}
+
+impl<I> Iterator for Pairwise<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = (I::Item, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.previous.is_none() {
+            self.previous = Some(self.iter.next()?);
+        }
+        let next = self.iter.next()?;
+        let previous = self.previous.replace(next.clone())?;
+        Some((previous, next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_exact_vec() {
+        let lines = ["a", "b", "c", "d", "e", "f"];
+        let groups: Vec<Vec<&str>> = lines.into_iter().chunks_exact_vec(3).collect();
+        assert_eq!(groups, vec![vec!["a", "b", "c"], vec!["d", "e", "f"]]);
+        assert_eq!((0..2).chunks_exact_vec(3).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be positive")]
+    fn test_chunks_exact_vec_zero() {
+        (0..2).chunks_exact_vec(0);
+    }
+
+    #[test]
+    fn test_windows_vec() {
+        let windows: Vec<Vec<u8>> = (1..=4).windows_vec(2).collect();
+        assert_eq!(windows, vec![vec![1, 2], vec![2, 3], vec![3, 4]]);
+        assert_eq!((1..=4).windows_vec(5).count(), 0);
+        assert_eq!((1..=4).windows_vec(4).count(), 1);
+        // Marker detection: the end of the first window of four distinct characters
+        let start = "mjqjpqmgbljsphdztnvjfqwrcgsmlb"
+            .chars()
+            .windows_vec(4)
+            .position(|w| (1..4).all(|i| !w[..i].contains(&w[i])))
+            .map(|i| i + 4);
+        assert_eq!(start, Some(7));
+    }
+
+    #[test]
+    fn test_take_while_inclusive() {
+        let taken: Vec<u8> = [1, 2, 5, 1, 2]
+            .into_iter()
+            .take_while_inclusive(|&x| x < 3)
+            .collect();
+        assert_eq!(taken, vec![1, 2, 5]);
+        let all: Vec<u8> = [1, 2]
+            .into_iter()
+            .take_while_inclusive(|&x| x < 3)
+            .collect();
+        assert_eq!(all, vec![1, 2]);
+        assert_eq!(
+            std::iter::empty::<u8>()
+                .take_while_inclusive(|_| true)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_group_consecutive_by() {
+        // Blank lines separate groups of numbers
+        let input = "1000\n2000\n\n4000\n\n5000\n6000";
+        let totals: Vec<u32> = input
+            .lines()
+            .group_consecutive_by(|l| l.is_empty())
+            .filter(|(blank, _)| !blank)
+            .map(|(_, lines)| lines.iter().map(|l| l.parse::<u32>().unwrap()).sum())
+            .collect();
+        assert_eq!(totals, vec![3000, 4000, 11000]);
+        assert_eq!(
+            std::iter::empty::<u8>()
+                .group_consecutive_by(|&x| x)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_pairwise() {
+        let pairs: Vec<(u8, u8)> = (1..=4).pairwise().collect();
+        assert_eq!(pairs, vec![(1, 2), (2, 3), (3, 4)]);
+        assert_eq!((1..=1).pairwise().count(), 0);
+        assert_eq!((1..1).pairwise().count(), 0);
+    }
+}
@@ -0,0 +1,589 @@
+use crate::lib::graph::topo_sort;
+use std::collections::VecDeque;
+use std::error::Error;
+
+/// Largest lookup table [`count_fitting`] builds before falling back to comparing every
+/// pair.
+const MAX_TABLE_SIZE: usize = 1 << 22;
+
+/// Converts a schematic of `#` and `.` into its column heights: the number of `#` in
+/// each column, not counting the full row at the top of a lock or the bottom of a key.
+///
+/// # Errors
+///
+/// Returns an error if the schematic is empty or its rows have different widths.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::match_::profile;
+///
+/// let lock = "#####\n.####\n.####\n.####\n.#.#.\n.#...\n.....";
+/// assert_eq!(profile(lock).unwrap(), vec![0, 5, 3, 4, 3]);
+/// ```
+pub fn profile(schematic: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+    let rows: Vec<&[u8]> = schematic
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.is_empty())
+        .map(str::as_bytes)
+        .collect();
+    let width = rows.first().ok_or("Empty schematic")?.len();
+    let mut heights = vec![0; width];
+    for (y, row) in rows.iter().enumerate() {
+        if row.len() != width {
+            return Err(format!(
+                "Schematic row {} has {} columns, expected {}",
+                y + 1,
+                row.len(),
+                width
+            )
+            .into());
+        }
+        for (x, &c) in row.iter().enumerate() {
+            if c == b'#' {
+                heights[x] += 1;
+            }
+        }
+    }
+    Ok(heights
+        .into_iter()
+        .map(|h: usize| h.saturating_sub(1))
+        .collect())
+}
+
+/// Splits blank-line separated schematics into locks (top row filled) and keys (the
+/// rest).
+pub fn split_schematics(input: &str) -> (Vec<&str>, Vec<&str>) {
+    input
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .partition(|s| {
+            s.lines()
+                .next()
+                .is_some_and(|top| top.bytes().all(|c| c == b'#'))
+        })
+}
+
+/// Counts the lock/key pairs whose pins do not overlap, i.e. whose column heights sum
+/// to at most `height` in every column.
+///
+/// # Arguments
+///
+/// * `locks` - Lock schematics, as accepted by [`profile`].
+/// * `keys` - Key schematics, as accepted by [`profile`].
+/// * `height` - The free space in each column (5 for 7-row schematics).
+///
+/// # Errors
+///
+/// Returns an error if a schematic is malformed or the schematics have different
+/// widths.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::match_::fit_profiles;
+///
+/// let locks = ["###\n#.#\n...\n...", "###\n###\n###\n..."];
+/// let keys = ["...\n...\n.#.\n###", "...\n###\n###\n###"];
+/// assert_eq!(fit_profiles(&locks, &keys, 2).unwrap(), 1);
+/// ```
+pub fn fit_profiles<S: AsRef<str>>(
+    locks: &[S],
+    keys: &[S],
+    height: usize,
+) -> Result<usize, Box<dyn Error>> {
+    let parse = |schematics: &[S]| {
+        schematics
+            .iter()
+            .map(|s| profile(s.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let (locks, keys) = (parse(locks)?, parse(keys)?);
+    if let Some(width) = locks.first().or(keys.first()).map(Vec::len) {
+        if let Some(bad) = locks.iter().chain(&keys).find(|p| p.len() != width) {
+            return Err(format!("Schematic has {} columns, expected {}", bad.len(), width).into());
+        }
+    }
+    Ok(count_fitting(&locks, &keys, height))
+}
+
+/// Counts the pairs of profiles that fit together, i.e. whose heights sum to at most
+/// `height` in every column. All profiles must have the same number of columns.
+///
+/// Instead of comparing every lock with every key, this buckets the locks into a table
+/// indexed by their heights and turns it into a cumulative count over every column, so
+/// each key is answered by a single lookup of "locks no taller than the space this key
+/// leaves". The table has `(height + 1)^columns` entries; when that would be too large,
+/// it falls back to comparing every pair.
+pub fn count_fitting(locks: &[Vec<usize>], keys: &[Vec<usize>], height: usize) -> usize {
+    let width = match locks.first() {
+        Some(lock) => lock.len(),
+        None => return 0,
+    };
+    let base = height + 1;
+    let size = u32::try_from(width)
+        .ok()
+        .and_then(|w| base.checked_pow(w))
+        .filter(|&size| size <= MAX_TABLE_SIZE);
+    let Some(size) = size else {
+        return locks
+            .iter()
+            .map(|lock| keys.iter().filter(|key| fits(lock, key, height)).count())
+            .sum();
+    };
+
+    let index = |heights: &[usize], flip: bool| {
+        heights
+            .iter()
+            .fold(0, |acc, &h| acc * base + if flip { height - h } else { h })
+    };
+    let mut table = vec![0usize; size];
+    for lock in locks.iter().filter(|l| l.iter().all(|&h| h <= height)) {
+        table[index(lock, false)] += 1;
+    }
+
+    // Cumulative sums along each column turn "locks with exactly these heights" into
+    // "locks with every height at most these"
+    let mut stride = 1;
+    for _ in 0..width {
+        for i in 0..size {
+            if (i / stride) % base != 0 {
+                table[i] += table[i - stride];
+            }
+        }
+        stride *= base;
+    }
+
+    keys.iter()
+        .filter(|k| k.len() == width && k.iter().all(|&h| h <= height))
+        .map(|key| table[index(key, true)])
+        .sum()
+}
+
+/// Returns whether a lock and key profile fit together without overlapping.
+pub fn fits(lock: &[usize], key: &[usize], height: usize) -> bool {
+    lock.len() == key.len() && lock.iter().zip(key).all(|(l, k)| l + k <= height)
+}
+
+/// Finds the unique way to assign each field to a column, where a field can only take
+/// a column if `constraints[field]` accepts every value in it.
+///
+/// This generalises ticket-translation puzzles: every field has a validity rule, every
+/// column holds one value from each ticket, and exactly one assignment is consistent.
+/// Returns `assignment[field] = column`. See [`assign_matrix`] for how the assignment is
+/// found.
+///
+/// # Errors
+///
+/// Returns an error if there are more fields than columns, or no assignment or more
+/// than one assignment is possible.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::match_::assign_fields;
+///
+/// let in_ranges = |ranges: [(u32, u32); 2]| {
+///     move |v: &u32| ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(v))
+/// };
+/// let constraints = [
+///     in_ranges([(0, 1), (4, 19)]),  // class
+///     in_ranges([(0, 5), (8, 19)]),  // row
+///     in_ranges([(0, 13), (16, 19)]), // seat
+/// ];
+/// let columns = vec![vec![3, 15, 5], vec![9, 1, 14], vec![18, 5, 9]];
+/// assert_eq!(assign_fields(&constraints, &columns).unwrap(), vec![1, 0, 2]);
+/// ```
+pub fn assign_fields<T, P>(constraints: &[P], columns: &[Vec<T>]) -> Result<Vec<usize>, String>
+where
+    P: Fn(&T) -> bool,
+{
+    let possible: Vec<Vec<bool>> = constraints
+        .iter()
+        .map(|fits| columns.iter().map(|c| c.iter().all(fits)).collect())
+        .collect();
+    assign_matrix(&possible)
+}
+
+/// Finds the unique assignment of rows (fields) to distinct columns, where
+/// `possible[field][column]` says whether the pair is allowed.
+///
+/// Fields are fixed by elimination: repeatedly assign any field with only one possible
+/// column, or any column with only one possible field, and strike it from the others.
+/// Every uniquely solvable puzzle falls to this, since a unique matching always leaves
+/// some field with a single option. When elimination gets stuck, the remaining fields
+/// are matched with Hopcroft-Karp and the matching is checked to be the only one, which
+/// tells apart "no assignment" from "several assignments" in the error.
+///
+/// # Errors
+///
+/// See [`assign_fields`].
+pub fn assign_matrix(possible: &[Vec<bool>]) -> Result<Vec<usize>, String> {
+    let fields = possible.len();
+    let columns = possible.first().map_or(0, Vec::len);
+    if let Some(row) = possible.iter().position(|r| r.len() != columns) {
+        return Err(format!(
+            "Field {} has {} columns, expected {}",
+            row,
+            possible[row].len(),
+            columns
+        ));
+    }
+    if fields > columns {
+        return Err(format!(
+            "{} fields cannot fit in {} columns",
+            fields, columns
+        ));
+    }
+
+    let mut open: Vec<Vec<bool>> = possible.to_vec();
+    let mut assignment: Vec<Option<usize>> = vec![None; fields];
+    let mut column_taken = vec![false; columns];
+    loop {
+        let single_column = (0..fields).find_map(|f| {
+            let mut candidates = (0..columns).filter(|&c| open[f][c]);
+            match (assignment[f], candidates.next(), candidates.next()) {
+                (None, Some(c), None) => Some((f, c)),
+                _ => None,
+            }
+        });
+        // A column only one field can use must belong to it, but only when every
+        // column has to be used
+        let single_field = (fields == columns)
+            .then(|| {
+                (0..columns).find_map(|c| {
+                    let mut candidates = (0..fields).filter(|&f| open[f][c]);
+                    match (column_taken[c], candidates.next(), candidates.next()) {
+                        (false, Some(f), None) if assignment[f].is_none() => Some((f, c)),
+                        _ => None,
+                    }
+                })
+            })
+            .flatten();
+        let Some((field, column)) = single_column.or(single_field) else {
+            break;
+        };
+        assignment[field] = Some(column);
+        column_taken[column] = true;
+        for (f, row) in open.iter_mut().enumerate() {
+            if f != field {
+                row[column] = false;
+            }
+        }
+        for (c, allowed) in open[field].iter_mut().enumerate() {
+            *allowed = c == column;
+        }
+    }
+
+    if let Some(f) = (0..fields).find(|&f| !open[f].iter().any(|&a| a)) {
+        return Err(format!("Field {} fits no remaining column", f));
+    }
+    if assignment.iter().all(Option::is_some) {
+        return Ok(assignment.into_iter().flatten().collect());
+    }
+
+    let matching =
+        hopcroft_karp(&open, columns).ok_or("No assignment gives every field its own column")?;
+    check_unique(&open, &matching)?;
+    Ok(matching)
+}
+
+/// Returns a maximum matching of fields to columns if it covers every field.
+fn hopcroft_karp(possible: &[Vec<bool>], columns: usize) -> Option<Vec<usize>> {
+    const FREE: usize = usize::MAX;
+    let fields = possible.len();
+    let adjacency: Vec<Vec<usize>> = possible
+        .iter()
+        .map(|row| (0..columns).filter(|&c| row[c]).collect())
+        .collect();
+    let mut field_match = vec![FREE; fields];
+    let mut column_match = vec![FREE; columns];
+    let mut layer = vec![0usize; fields];
+
+    // Layers fields by alternating path length from the free fields, returning whether
+    // some free column is reachable
+    let bfs = |field_match: &[usize], column_match: &[usize], layer: &mut [usize]| {
+        let mut queue = VecDeque::new();
+        for f in 0..fields {
+            layer[f] = if field_match[f] == FREE {
+                queue.push_back(f);
+                0
+            } else {
+                FREE
+            };
+        }
+        let mut found = false;
+        while let Some(f) = queue.pop_front() {
+            for &c in &adjacency[f] {
+                match column_match[c] {
+                    FREE => found = true,
+                    next if layer[next] == FREE => {
+                        layer[next] = layer[f] + 1;
+                        queue.push_back(next);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        found
+    };
+
+    fn augment(
+        f: usize,
+        adjacency: &[Vec<usize>],
+        field_match: &mut [usize],
+        column_match: &mut [usize],
+        layer: &mut [usize],
+    ) -> bool {
+        for &c in &adjacency[f] {
+            let next = column_match[c];
+            let extends = next == FREE
+                || (layer[next] == layer[f] + 1
+                    && augment(next, adjacency, field_match, column_match, layer));
+            if extends {
+                field_match[f] = c;
+                column_match[c] = f;
+                return true;
+            }
+        }
+        // Dead end: keep later searches in this phase away from it
+        layer[f] = FREE;
+        false
+    }
+
+    while bfs(&field_match, &column_match, &mut layer) {
+        for f in 0..fields {
+            if field_match[f] == FREE {
+                augment(
+                    f,
+                    &adjacency,
+                    &mut field_match,
+                    &mut column_match,
+                    &mut layer,
+                );
+            }
+        }
+    }
+    field_match
+        .iter()
+        .all(|&c| c != FREE)
+        .then_some(field_match)
+}
+
+/// Returns an error if a perfect `matching` is not the only one allowed by `possible`.
+///
+/// Another matching exists exactly when some field could move to a free column, or
+/// fields could rotate columns along a cycle of "field `f` could take the column of
+/// field `g`" edges.
+fn check_unique(possible: &[Vec<bool>], matching: &[usize]) -> Result<(), String> {
+    let mut owner = vec![None; possible.first().map_or(0, Vec::len)];
+    for (f, &c) in matching.iter().enumerate() {
+        owner[c] = Some(f);
+    }
+    let mut edges = Vec::new();
+    for (f, row) in possible.iter().enumerate() {
+        for (c, _) in row.iter().enumerate().filter(|(_, &a)| a) {
+            match owner[c] {
+                None => return Err(format!("Field {} could also take column {}", f, c)),
+                Some(g) if g != f => edges.push((f, g)),
+                _ => {}
+            }
+        }
+    }
+    topo_sort(0..matching.len(), edges)
+        .map(|_| ())
+        .map_err(|e| format!("Fields {:?} can swap columns", e.cycle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####
+";
+
+    #[test]
+    fn test_profiles() {
+        let (locks, keys) = split_schematics(EXAMPLE);
+        let heights = |s: &[&str]| s.iter().map(|s| profile(s).unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            heights(&locks),
+            vec![vec![0, 5, 3, 4, 3], vec![1, 2, 0, 5, 3]]
+        );
+        assert_eq!(
+            heights(&keys),
+            vec![
+                vec![5, 0, 2, 1, 3],
+                vec![4, 3, 4, 0, 2],
+                vec![3, 0, 2, 0, 1]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fit_profiles_example() {
+        let (locks, keys) = split_schematics(EXAMPLE);
+        assert_eq!(fit_profiles(&locks, &keys, 5).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_fit_profiles_errors() {
+        assert!(fit_profiles(&["##\n#."], &["..\n###"], 1).is_err());
+        assert!(fit_profiles(&["##\n.."], &["...\n###"], 1).is_err());
+        assert!(fit_profiles(&[""], &["..\n##"], 1).is_err());
+    }
+
+    #[test]
+    fn test_bucketed_matches_brute_force() {
+        // Deterministic pseudo-random profiles, including some too tall to fit anything
+        let mut seed = 12345u64;
+        let mut next = |limit: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % limit
+        };
+        let height = 5;
+        let mut profiles = || -> Vec<Vec<usize>> {
+            (0..200)
+                .map(|_| (0..5).map(|_| next(height + 2)).collect())
+                .collect()
+        };
+        let (locks, keys) = (profiles(), profiles());
+        let brute: usize = locks
+            .iter()
+            .map(|l| keys.iter().filter(|k| fits(l, k, height)).count())
+            .sum();
+        assert!(brute > 0);
+        assert_eq!(count_fitting(&locks, &keys, height), brute);
+    }
+
+    #[test]
+    fn test_count_fitting_falls_back_for_wide_profiles() {
+        // 11^10 buckets is too many, so every pair is compared
+        let locks = vec![vec![3; 10], vec![6; 10]];
+        let keys = vec![vec![7; 10], vec![4; 10], vec![0; 10]];
+        assert_eq!(count_fitting(&locks, &keys, 10), 5);
+        assert_eq!(count_fitting(&[], &keys, 10), 0);
+    }
+
+    #[test]
+    fn test_assign_fields_ticket_example() {
+        // class: 0-1 or 4-19, row: 0-5 or 8-19, seat: 0-13 or 16-19
+        let rules: [[(u32, u32); 2]; 3] =
+            [[(0, 1), (4, 19)], [(0, 5), (8, 19)], [(0, 13), (16, 19)]];
+        let constraints: Vec<_> = rules
+            .iter()
+            .map(|r| move |v: &u32| r.iter().any(|&(lo, hi)| (lo..=hi).contains(v)))
+            .collect();
+        let columns = vec![vec![3, 15, 5], vec![9, 1, 14], vec![18, 5, 9]];
+        assert_eq!(
+            assign_fields(&constraints, &columns).unwrap(),
+            vec![1, 0, 2]
+        );
+    }
+
+    #[test]
+    fn test_assign_matrix_reports_ambiguity() {
+        let (t, f) = (true, false);
+        // Fields 0, 1 and 2 can rotate through columns 0-2 along a cycle
+        let possible = vec![
+            vec![t, t, f, f],
+            vec![f, t, t, f],
+            vec![t, f, t, f],
+            vec![f, f, f, t],
+        ];
+        let err = assign_matrix(&possible).unwrap_err();
+        assert!(err.contains("can swap columns"), "{}", err);
+
+        // Two fields, three columns, and a field that could use the spare column
+        let possible = vec![vec![t, t, f], vec![t, t, t]];
+        assert!(assign_matrix(&possible)
+            .unwrap_err()
+            .contains("could also take"));
+    }
+
+    #[test]
+    fn test_assign_matrix_reports_impossible() {
+        // Three fields share two columns; elimination stalls and matching fails
+        let possible = vec![vec![true, true, false]; 3];
+        assert_eq!(
+            assign_matrix(&possible).unwrap_err(),
+            "No assignment gives every field its own column"
+        );
+    }
+
+    #[test]
+    fn test_assign_matrix_elimination_chain() {
+        // A staircase: field i fits columns i..n, so fields are fixed from the end
+        let n = 20;
+        let possible: Vec<Vec<bool>> = (0..n).map(|f| (0..n).map(|c| c >= f).collect()).collect();
+        let expected: Vec<usize> = (0..n).collect();
+        assert_eq!(assign_matrix(&possible).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_hopcroft_karp() {
+        // Greedy matching in field order would give field 0 column 0 and strand field 2
+        let possible = vec![
+            vec![true, true, false],
+            vec![false, true, true],
+            vec![true, false, false],
+        ];
+        assert_eq!(hopcroft_karp(&possible, 3), Some(vec![1, 2, 0]));
+        assert_eq!(hopcroft_karp(&[vec![true], vec![true]], 1), None);
+    }
+
+    #[test]
+    fn test_assign_matrix_errors() {
+        assert!(assign_matrix(&[vec![true], vec![true]]).is_err());
+        assert!(assign_matrix(&[vec![false, true], vec![false, true]]).is_err());
+        assert!(assign_matrix(&[vec![true, true], vec![true]]).is_err());
+        // One field, two columns it fits
+        assert!(assign_matrix(&[vec![true, true]])
+            .unwrap_err()
+            .contains("could also take"));
+        assert_eq!(assign_matrix(&[]).unwrap(), Vec::<usize>::new());
+    }
+}
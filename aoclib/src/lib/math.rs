@@ -0,0 +1,816 @@
+use std::collections::HashMap;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// The integer operations the generic number theory helpers need, implemented for all
+/// primitive integer types.
+pub trait Integer:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Div<Output = Self>
+    + Mul<Output = Self>
+    + Rem<Output = Self>
+    + Sub<Output = Self>
+{
+    /// The additive identity.
+    const ZERO: Self;
+    /// The multiplicative identity.
+    const ONE: Self;
+
+    /// Returns the absolute value; the identity for unsigned types.
+    fn abs_value(self) -> Self;
+}
+
+/// An [`Integer`] that can be negative, as needed by [`extended_gcd`].
+pub trait Signed: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_integer {
+    (unsigned: $($t:ty),*; signed: $($s:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn abs_value(self) -> Self {
+                self
+            }
+        })*
+        $(impl Integer for $s {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn abs_value(self) -> Self {
+                self.abs()
+            }
+        }
+
+        impl Signed for $s {})*
+    };
+}
+
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize; signed: i8, i16, i32, i64, i128, isize);
+
+/// Returns the greatest common divisor of `a` and `b`, which is never negative.
+/// `gcd(0, 0)` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::gcd;
+///
+/// assert_eq!(gcd(12u32, 18), 6);
+/// assert_eq!(gcd(-12i64, 18), 6);
+/// assert_eq!(gcd(0, 7), 7);
+/// ```
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs_value(), b.abs_value());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Returns the least common multiple of `a` and `b`, which is never negative.
+/// The lcm with 0 is 0.
+///
+/// Divides before multiplying, so this only overflows when the result itself does not
+/// fit in `T`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::lcm;
+///
+/// assert_eq!(lcm(4u8, 6), 12);
+/// assert_eq!(lcm(-4i32, 6), 12);
+/// ```
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    (a / gcd(a, b) * b).abs_value()
+}
+
+/// Returns the least common multiple of every value, or 1 for an empty iterator.
+///
+/// This is the answer to "when do all the cycles line up again" puzzles.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::lcm_of;
+///
+/// assert_eq!(lcm_of([2u64, 3, 4, 5, 6]), 60);
+/// assert_eq!(lcm_of(Vec::<u64>::new()), 1);
+/// ```
+pub fn lcm_of<T, I>(values: I) -> T
+where
+    T: Integer,
+    I: IntoIterator<Item = T>,
+{
+    values.into_iter().fold(T::ONE, lcm)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g`.
+///
+/// `g` is never negative. The coefficients are the ones found by the extended
+/// Euclidean algorithm, which keeps them no larger than `|b|` and `|a|` respectively
+/// (when both are non-zero).
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::extended_gcd;
+///
+/// let (g, x, y) = extended_gcd(240i64, 46);
+/// assert_eq!(g, 2);
+/// assert_eq!(240 * x + 46 * y, 2);
+/// ```
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < T::ZERO {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Computes `base^exp mod modulus`, always returning a value in `0..modulus`.
+///
+/// Intermediate products are taken in `i128`, so any `i64` inputs are safe.
+///
+/// # Panics
+///
+/// Panics if `modulus` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::mod_pow;
+///
+/// assert_eq!(mod_pow(7, 20201227 - 2, 20201227) * 7 % 20201227, 1);
+/// assert_eq!(mod_pow(-2, 3, 5), 2);
+/// ```
+pub fn mod_pow(base: i64, exp: u64, modulus: i64) -> i64 {
+    assert!(modulus > 0, "modulus must be positive, got {}", modulus);
+    let m = modulus as i128;
+    let mut result = 1 % m;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+/// Returns the `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, or `None` if `a`
+/// and `modulus` are not coprime.
+///
+/// # Panics
+///
+/// Panics if `modulus` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::mod_inverse;
+///
+/// assert_eq!(mod_inverse(3, 11), Some(4));
+/// assert_eq!(mod_inverse(6, 9), None);
+/// ```
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    assert!(modulus > 0, "modulus must be positive, got {}", modulus);
+    let m = modulus as i128;
+    let (g, x, _) = extended_gcd((a as i128).rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m) as i64)
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)`, returning the smallest
+/// non-negative solution and the modulus of the combined congruence.
+///
+/// The moduli need not be coprime: congruences that share factors are merged as long
+/// as they agree, in which case the combined modulus is their lcm. An empty system
+/// gives `(0, 1)`.
+///
+/// # Errors
+///
+/// Returns an error if a modulus is not positive, the congruences contradict each
+/// other, or the combined modulus does not fit in an `i128`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::chinese_remainder;
+///
+/// // Bus-schedule style: bus 7 at offset 0, bus 13 at offset 1, bus 59 at offset 4
+/// let buses = [(0, 7), (-1, 13), (-4, 59)];
+/// assert_eq!(chinese_remainder(&buses).unwrap(), (350, 7 * 13 * 59));
+/// assert!(chinese_remainder(&[(1, 4), (2, 6)]).is_err());
+/// ```
+pub fn chinese_remainder(congruences: &[(i64, i64)]) -> Result<(i128, i128), String> {
+    let mut residue: i128 = 0;
+    let mut modulus: i128 = 1;
+    for &(r, m) in congruences {
+        if m <= 0 {
+            return Err(format!("Modulus must be positive, got {}", m));
+        }
+        let (r, m) = ((r as i128).rem_euclid(m as i128), m as i128);
+
+        // Solve residue + modulus * t ≡ r (mod m) for t
+        let (g, inv, _) = extended_gcd(modulus.rem_euclid(m), m);
+        let diff = (r - residue).rem_euclid(m);
+        if diff % g != 0 {
+            return Err(format!(
+                "x ≡ {} (mod {}) contradicts x ≡ {} (mod {})",
+                r, m, residue, modulus
+            ));
+        }
+        let step = m / g;
+        // Both factors are below m, which fits in an i64, so this cannot overflow
+        let t = (diff / g).rem_euclid(step) * inv.rem_euclid(step) % step;
+
+        let combined = modulus
+            .checked_mul(step)
+            .ok_or("Combined modulus overflows i128")?;
+        residue = modulus
+            .checked_mul(t)
+            .and_then(|x| x.checked_add(residue))
+            .ok_or("Solution overflows i128")?
+            .rem_euclid(combined);
+        modulus = combined;
+    }
+    Ok((residue, modulus))
+}
+
+/// Returns every prime below `limit`, using a sieve of Eratosthenes.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::primes_below;
+///
+/// assert_eq!(primes_below(20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+/// ```
+pub fn primes_below(limit: u64) -> Vec<u64> {
+    primes_between(0, limit)
+}
+
+/// Returns every prime in `lo..hi`, in order.
+///
+/// This is a segmented sieve: it sieves the base primes up to `sqrt(hi)` once, then
+/// crosses off their multiples in fixed-size windows, so memory stays small even for
+/// ranges far from zero such as `10^12..10^12 + 10^6`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::primes_between;
+///
+/// assert_eq!(primes_between(1_000_000_000_000, 1_000_000_000_100).len(), 4);
+/// ```
+pub fn primes_between(lo: u64, hi: u64) -> Vec<u64> {
+    const SEGMENT: u64 = 1 << 16;
+    if hi <= lo {
+        return Vec::new();
+    }
+
+    // Base primes up to sqrt(hi) with a plain sieve
+    let root = isqrt(hi - 1) as usize;
+    let mut composite = vec![false; root + 1];
+    let mut base = Vec::new();
+    for i in 2..=root {
+        if !composite[i] {
+            base.push(i as u64);
+            for j in (i * i..=root).step_by(i) {
+                composite[j] = true;
+            }
+        }
+    }
+
+    let mut primes = Vec::new();
+    let mut start = lo.max(2);
+    while start < hi {
+        let end = hi.min(start.saturating_add(SEGMENT));
+        let mut sieve = vec![true; (end - start) as usize];
+        for &p in &base {
+            if p * p >= end {
+                break;
+            }
+            let first = (p * p).max(start.div_ceil(p) * p);
+            for multiple in (first..end).step_by(p as usize) {
+                sieve[(multiple - start) as usize] = false;
+            }
+        }
+        primes.extend(
+            sieve
+                .iter()
+                .enumerate()
+                .filter(|(_, &prime)| prime)
+                .map(|(i, _)| start + i as u64),
+        );
+        start = end;
+    }
+    primes
+}
+
+/// Returns whether `n` is prime.
+///
+/// Uses a deterministic Miller-Rabin test, which is exact for every `u64` and takes
+/// microseconds even for the largest ones.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::is_prime;
+///
+/// assert!(is_prime(1_000_000_007));
+/// assert!(!is_prime(1_000_000_007 * 3));
+/// ```
+pub fn is_prime(n: u64) -> bool {
+    // These bases are enough to make the test exact below 3.3 * 10^24
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for &p in &BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let (mut d, mut s) = (n - 1, 0);
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+    BASES.iter().all(|&a| {
+        let mut x = pow_mod_u64(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod_u64(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Returns the prime factorisation of `n` as `(prime, exponent)` pairs in increasing
+/// order of prime. 0 and 1 have no factors.
+///
+/// Uses trial division by 2, 3 and numbers of the form `6k ± 1`, stopping once the
+/// remaining cofactor is prime. That takes at most about 330,000 divisions for `n`
+/// up to 10^12, and stays fast well beyond that unless `n` is a product of two
+/// large primes.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::factorize;
+///
+/// assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+/// assert_eq!(factorize(999_999_999_989), vec![(999_999_999_989, 1)]);
+/// ```
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    if n < 2 {
+        return factors;
+    }
+    let mut n = n;
+    let mut divide_out = |n: &mut u64, p: u64| {
+        let mut exponent = 0;
+        while n.is_multiple_of(p) {
+            *n /= p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((p, exponent));
+        }
+        exponent > 0
+    };
+
+    divide_out(&mut n, 2);
+    divide_out(&mut n, 3);
+    let mut p = 5;
+    // Only worth re-testing the cofactor for primality after it shrinks
+    let mut check = true;
+    while p * p <= n {
+        if check && is_prime(n) {
+            break;
+        }
+        check = divide_out(&mut n, p) | divide_out(&mut n, p + 2);
+        p += 6;
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// Returns every positive divisor of `n` in increasing order. 0 has none.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::divisors;
+///
+/// assert_eq!(divisors(28), vec![1, 2, 4, 7, 14, 28]);
+/// assert_eq!(divisors(28).iter().sum::<u64>(), 56);
+/// ```
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut divisors = vec![1];
+    for (p, exponent) in factorize(n) {
+        let current = divisors.len();
+        let mut power = 1;
+        for _ in 0..exponent {
+            power *= p;
+            for i in 0..current {
+                divisors.push(divisors[i] * power);
+            }
+        }
+    }
+    divisors.sort_unstable();
+    divisors
+}
+
+/// An iterator over the secret numbers of a prune-and-mix generator, as returned by
+/// [`prune_mix_prng`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneMixPrng {
+    secret: u64,
+}
+
+impl Iterator for PruneMixPrng {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        // Multiplying and dividing by powers of two are shifts, and pruning modulo
+        // 2^24 is a mask
+        const PRUNE: u64 = (1 << 24) - 1;
+        let mut s = self.secret;
+        s = (s ^ (s << 6)) & PRUNE;
+        s = (s ^ (s >> 5)) & PRUNE;
+        s = (s ^ (s << 11)) & PRUNE;
+        self.secret = s;
+        Some(s)
+    }
+}
+
+/// Returns the secret numbers that follow `seed`: each is the previous one mixed
+/// (XORed) with itself times 64, divided by 32 and times 2048 in turn, pruning to
+/// 24 bits after each step. The seed itself is not yielded, and the sequence never
+/// ends.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::prune_mix_prng;
+///
+/// let next: Vec<u64> = prune_mix_prng(123).take(3).collect();
+/// assert_eq!(next, vec![15887950, 16495136, 527345]);
+/// assert_eq!(prune_mix_prng(1).nth(1999), Some(8685429));
+/// ```
+pub fn prune_mix_prng(seed: u64) -> PruneMixPrng {
+    PruneMixPrng {
+        secret: seed & ((1 << 24) - 1),
+    }
+}
+
+/// The number of distinct windows of four price changes, each in `-9..=9`.
+const WINDOWS: usize = 19 * 19 * 19 * 19;
+
+/// Sums, across buyers, the price each would sell at for every window of four
+/// consecutive price changes.
+///
+/// A buyer's prices are the last digits of their seed and the next `steps` secret
+/// numbers from [`prune_mix_prng`]. Each buyer sells the first time a window
+/// appears in their changes, so later repeats of a window are ignored. Windows are
+/// packed into a base-19 index and totalled in a flat array, so only the returned map
+/// is hashed.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::sell_window_totals;
+///
+/// let totals = sell_window_totals(&[123], 9);
+/// assert_eq!(totals[&[-1, -1, 0, 2]], 6);
+/// ```
+pub fn sell_window_totals(seeds: &[u64], steps: usize) -> HashMap<[i8; 4], u64> {
+    let totals = window_totals(seeds, steps);
+    (0..WINDOWS)
+        .filter(|&w| totals[w] > 0)
+        .map(|w| (unpack_window(w), totals[w]))
+        .collect()
+}
+
+/// Returns the window of four price changes that earns the most across all buyers,
+/// with that total, as described for [`sell_window_totals`]. Ties go to the smallest
+/// window. Returns `None` if no buyer has four changes.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::math::best_sell_window;
+///
+/// assert_eq!(best_sell_window(&[1, 2, 3, 2024], 2000), Some(([-2, 1, -1, 3], 23)));
+/// ```
+pub fn best_sell_window(seeds: &[u64], steps: usize) -> Option<([i8; 4], u64)> {
+    if steps < 4 || seeds.is_empty() {
+        return None;
+    }
+    let totals = window_totals(seeds, steps);
+    // Windows are indexed in lexicographic order, and max_by_key keeps the last maximum
+    let best = (0..WINDOWS).rev().max_by_key(|&w| totals[w])?;
+    Some((unpack_window(best), totals[best]))
+}
+
+fn window_totals(seeds: &[u64], steps: usize) -> Vec<u64> {
+    let mut totals = vec![0u64; WINDOWS];
+    // The last buyer to have seen each window, offset by one so zero means none
+    let mut seen = vec![0usize; WINDOWS];
+    for (buyer, &seed) in seeds.iter().enumerate() {
+        let mut window = 0;
+        let mut price = (seed % 10) as usize;
+        for (i, secret) in prune_mix_prng(seed).take(steps).enumerate() {
+            let next = (secret % 10) as usize;
+            // Keep the last four changes as base-19 digits, shifted to 0..=18
+            window = (window * 19 + next + 9 - price) % WINDOWS;
+            price = next;
+            if i >= 3 && seen[window] != buyer + 1 {
+                seen[window] = buyer + 1;
+                totals[window] += price as u64;
+            }
+        }
+    }
+    totals
+}
+
+fn unpack_window(mut index: usize) -> [i8; 4] {
+    let mut window = [0; 4];
+    for change in window.iter_mut().rev() {
+        *change = (index % 19) as i8 - 9;
+        index /= 19;
+    }
+    window
+}
+
+/// Returns the largest `r` with `r * r <= n`.
+fn isqrt(n: u64) -> u64 {
+    let mut r = (n as f64).sqrt() as u64;
+    while r.checked_mul(r).is_none_or(|sq| sq > n) {
+        r -= 1;
+    }
+    while (r + 1).checked_mul(r + 1).is_some_and(|sq| sq <= n) {
+        r += 1;
+    }
+    r
+}
+
+fn mul_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+fn pow_mod_u64(base: u64, exp: u64, m: u64) -> u64 {
+    let (mut result, mut base, mut exp) = (1 % m, base % m, exp);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_u64(result, base, m);
+        }
+        base = mul_mod_u64(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(gcd(0u8, 0), 0);
+        assert_eq!(gcd(17i32, -5), 1);
+        assert_eq!(gcd(u128::MAX, 5), 5);
+        assert_eq!(gcd(i64::MIN + 1, 0), i64::MAX);
+        assert_eq!(lcm(0usize, 9), 0);
+        assert_eq!(lcm(-3isize, -4), 12);
+        // Dividing first keeps this within u64
+        assert_eq!(lcm(1u64 << 62, 1 << 61), 1 << 62);
+    }
+
+    #[test]
+    fn test_lcm_of_cycle_lengths() {
+        // Ghost cycle lengths sharing a common factor of 263
+        let cycles = [263u64 * 47, 263 * 59, 263 * 71];
+        assert_eq!(lcm_of(cycles), 263 * 47 * 59 * 71);
+        assert_eq!(lcm_of([7u8]), 7);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [
+            (240i32, 46),
+            (-240, 46),
+            (240, -46),
+            (0, 5),
+            (5, 0),
+            (7, 7),
+            (1, -1),
+        ] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b), "gcd({}, {})", a, b);
+            assert_eq!(a * x + b * y, g, "bezout({}, {})", a, b);
+        }
+        assert_eq!(extended_gcd(0i8, 0), (0, 1, 0));
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 7), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(-3, 3, 7), 1);
+        // Fermat's little theorem with a modulus near i64::MAX
+        let p = 9_223_372_036_854_775_783; // largest prime below 2^63
+        assert_eq!(mod_pow(123_456_789, p as u64 - 1, p), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be positive")]
+    fn test_mod_pow_zero_modulus() {
+        mod_pow(2, 3, 0);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        for m in [2, 7, 11, 97, 1_000_000_007] {
+            for a in 1..50.min(m) {
+                let inv = mod_inverse(a, m).unwrap();
+                assert_eq!(
+                    (a as i128 * inv as i128) % m as i128,
+                    1,
+                    "{}^-1 mod {}",
+                    a,
+                    m
+                );
+            }
+        }
+        assert_eq!(mod_inverse(-1, 7), Some(6));
+        assert_eq!(mod_inverse(4, 8), None);
+        assert_eq!(mod_inverse(0, 5), None);
+    }
+
+    #[test]
+    fn test_chinese_remainder_coprime() {
+        assert_eq!(
+            chinese_remainder(&[(2, 3), (3, 5), (2, 7)]).unwrap(),
+            (23, 105)
+        );
+        // The bus schedule example 7,13,x,x,59,x,31,19
+        let buses = [(0, 7), (-1, 13), (-4, 59), (-6, 31), (-7, 19)];
+        assert_eq!(chinese_remainder(&buses).unwrap().0, 1_068_781);
+    }
+
+    #[test]
+    fn test_chinese_remainder_shared_factors() {
+        assert_eq!(chinese_remainder(&[(2, 4), (4, 6)]).unwrap(), (10, 12));
+        assert_eq!(chinese_remainder(&[(3, 6), (3, 6)]).unwrap(), (3, 6));
+        assert!(chinese_remainder(&[(0, 4), (1, 6)]).is_err());
+    }
+
+    #[test]
+    fn test_chinese_remainder_edge_cases() {
+        assert_eq!(chinese_remainder(&[]).unwrap(), (0, 1));
+        assert_eq!(chinese_remainder(&[(-1, 5)]).unwrap(), (4, 5));
+        assert!(chinese_remainder(&[(1, 0)]).is_err());
+    }
+
+    #[test]
+    fn test_chinese_remainder_large_moduli() {
+        // Two moduli near 2^62 combine to a modulus near 2^124, which still fits
+        let (p, q) = (4_611_686_018_427_387_847, 4_611_686_018_427_387_817);
+        let (x, m) = chinese_remainder(&[(5, p), (7, q)]).unwrap();
+        assert_eq!(m, p as i128 * q as i128);
+        assert_eq!(x % p as i128, 5);
+        assert_eq!(x % q as i128, 7);
+        // A third one no longer fits
+        assert!(chinese_remainder(&[(5, p), (7, q), (1, 1_000_003)]).is_err());
+    }
+
+    #[test]
+    fn test_sieves() {
+        assert!(primes_below(2).is_empty());
+        assert_eq!(primes_below(3), vec![2]);
+        assert_eq!(primes_below(1_000_000).len(), 78_498);
+        assert_eq!(primes_between(10, 30), vec![11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_between(29, 29), Vec::<u64>::new());
+        // Crosses several segments
+        let segmented = primes_between(900_000, 1_100_000);
+        let plain: Vec<u64> = primes_below(1_100_000)
+            .into_iter()
+            .filter(|&p| p >= 900_000)
+            .collect();
+        assert_eq!(segmented, plain);
+    }
+
+    #[test]
+    fn test_is_prime_matches_sieve() {
+        let primes = primes_below(100_000);
+        let count = (0..100_000).filter(|&n| is_prime(n)).count();
+        assert_eq!(count, primes.len());
+        assert!(primes.iter().all(|&p| is_prime(p)));
+        // Strong pseudoprimes to several small bases
+        assert!(!is_prime(3_215_031_751));
+        assert!(!is_prime(3_825_123_056_546_413_051));
+        assert!(is_prime(18_446_744_073_709_551_557)); // largest prime below 2^64
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(0), vec![]);
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(97), vec![(97, 1)]);
+        assert_eq!(factorize(1 << 40), vec![(2, 40)]);
+        // Two primes near 10^6 multiply to just under 10^12
+        assert_eq!(
+            factorize(999_983 * 999_979),
+            vec![(999_979, 1), (999_983, 1)]
+        );
+        for n in 1..2_000u64 {
+            let product: u64 = factorize(n).iter().map(|&(p, e)| p.pow(e)).product();
+            assert_eq!(product, n);
+        }
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert!(divisors(0).is_empty());
+        assert_eq!(divisors(1), vec![1]);
+        assert_eq!(divisors(36), vec![1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert_eq!(divisors(735_134_400).len(), 1344);
+        for n in 1..500u64 {
+            let brute: Vec<u64> = (1..=n).filter(|d| n % d == 0).collect();
+            assert_eq!(divisors(n), brute);
+        }
+    }
+
+    #[test]
+    fn test_prune_mix_prng() {
+        let secrets: Vec<u64> = prune_mix_prng(123).take(10).collect();
+        assert_eq!(
+            secrets,
+            vec![
+                15887950, 16495136, 527345, 704524, 1553684, 12683156, 11100544, 12249484, 7753432,
+                5908254
+            ]
+        );
+        let sum: u64 = [1, 10, 100, 2024]
+            .iter()
+            .map(|&seed| prune_mix_prng(seed).nth(1999).unwrap())
+            .sum();
+        assert_eq!(sum, 37327623);
+    }
+
+    #[test]
+    fn test_sell_windows() {
+        // Prices of 123: 3 0 6 5 4 4 6 4 4 2
+        let totals = sell_window_totals(&[123], 9);
+        assert_eq!(totals.len(), 6);
+        assert_eq!(totals[&[-3, 6, -1, -1]], 4);
+        assert_eq!(totals[&[-1, -1, 0, 2]], 6);
+        assert_eq!(totals[&[2, -2, 0, -2]], 2);
+
+        // Only the first sale of a window counts for each buyer
+        let best: u64 = sell_window_totals(&[1, 2, 3, 2024], 2000)
+            .into_values()
+            .max()
+            .unwrap();
+        assert_eq!(best, 23);
+        assert_eq!(
+            sell_window_totals(&[1, 2, 3, 2024], 2000)[&[-2, 1, -1, 3]],
+            23
+        );
+        assert_eq!(best_sell_window(&[123], 3), None);
+        assert_eq!(best_sell_window(&[], 2000), None);
+        assert_eq!(sell_window_totals(&[123], 3), HashMap::new());
+    }
+}
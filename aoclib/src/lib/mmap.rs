@@ -0,0 +1,164 @@
+use crate::AocError;
+use std::ffi::c_void;
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::ptr::{self, NonNull};
+
+// The workspace has no external dependencies, so rather than pulling in `memmap2` this
+// calls the C library's `mmap`, which `std` already links on every Unix target.
+const PROT_READ: i32 = 1;
+const MAP_PRIVATE: i32 = 2;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: i32,
+        flags: i32,
+        fd: i32,
+        offset: i64,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+}
+
+/// A puzzle input mapped into memory by [`read_input_mmap`], read as a `&str`.
+///
+/// The file is unmapped when this is dropped.
+#[derive(Debug)]
+pub struct MappedInput {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+impl MappedInput {
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: `ptr` is either dangling with `len` 0, or the start of a live read-only
+        // mapping of `len` bytes that lasts until `self` is dropped
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Deref for MappedInput {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        // SAFETY: `read_input_mmap` only returns mappings that passed UTF-8 validation,
+        // and the caller promised the file does not change while mapped
+        unsafe { std::str::from_utf8_unchecked(self.bytes()) }
+    }
+}
+
+impl Drop for MappedInput {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: the mapping was created by `mmap` with this address and length, and
+            // no reference into it outlives `self`
+            unsafe { munmap(self.ptr.as_ptr().cast(), self.len) };
+        }
+    }
+}
+
+/// Maps a file into memory read-only and returns it as a string, for multi-gigabyte
+/// inputs that [`read_input`](crate::read_input) would have to copy onto the heap.
+///
+/// The mapped pages are backed by the file, so the kernel reads them in on demand and
+/// can drop them again under memory pressure rather than swapping. Parsing straight out
+/// of the mapping, for example with `lines()`, never holds a second copy of the input.
+/// For files that fit comfortably in memory, `read_input` is barely slower; the
+/// `read_large` example compares the two with streaming.
+///
+/// Only available on 64-bit Unix targets with the `mmap` feature enabled.
+///
+/// # Safety
+///
+/// The file must not be modified or truncated while the returned [`MappedInput`] is
+/// alive: the string would change under the program's feet, or reading it would crash.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::read_input_mmap;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // SAFETY: nothing writes to the input while it is mapped
+/// let input = unsafe { read_input_mmap("huge.txt")? };
+/// let total: u64 = input.lines().map(|line| line.len() as u64).sum();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] if the file cannot be opened or mapped, or does not
+/// contain valid UTF-8.
+pub unsafe fn read_input_mmap<P: AsRef<Path>>(path: P) -> Result<MappedInput, AocError> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len() as usize;
+    if len == 0 {
+        // Zero-length mappings are rejected by mmap, and there is nothing to map anyway
+        return Ok(MappedInput {
+            ptr: NonNull::dangling(),
+            len: 0,
+        });
+    }
+
+    // SAFETY: a fresh private read-only mapping of an open file; the descriptor may be
+    // closed once the call returns
+    let ptr = unsafe {
+        mmap(
+            ptr::null_mut(),
+            len,
+            PROT_READ,
+            MAP_PRIVATE,
+            file.as_raw_fd(),
+            0,
+        )
+    };
+    if ptr as isize == -1 {
+        return Err(io::Error::last_os_error().into());
+    }
+    let mapped = MappedInput {
+        ptr: NonNull::new(ptr.cast()).ok_or_else(io::Error::last_os_error)?,
+        len,
+    };
+    if let Err(e) = std::str::from_utf8(mapped.bytes()) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, e).into());
+    }
+    Ok(mapped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn read(name: &str, content: &[u8]) -> Result<String, AocError> {
+        let path = format!("test_mmap_{}.txt", name);
+        fs::write(&path, content).unwrap();
+        // SAFETY: only this test touches the file
+        let result = unsafe { read_input_mmap(&path) }.map(|input| input.to_string());
+        let _ = fs::remove_file(&path);
+        result
+    }
+
+    #[test]
+    fn test_matches_read_to_string() {
+        let content = "1\n22\n333\n".repeat(5000);
+        assert_eq!(read("lines", content.as_bytes()).unwrap(), content);
+        assert_eq!(read("empty", b"").unwrap(), "");
+    }
+
+    #[test]
+    fn test_errors() {
+        match read("invalid", b"ok\n\xff\n") {
+            Err(AocError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            other => panic!("expected invalid data, got {:?}", other),
+        }
+        // SAFETY: the file does not exist
+        let missing = unsafe { read_input_mmap("nonexistent_file.txt") };
+        assert!(matches!(missing, Err(AocError::Io(_))));
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt;
+
+/// A positional numeral system with a custom set of digit characters.
+///
+/// The base is the number of digits, and the digits stand for consecutive values
+/// starting at `lowest`. Ordinary decimal is the digits `0123456789` from 0; the
+/// balanced quinary of SNAFU numbers is `=-012` from -2, where `=` is -2 and `-` is -1.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::numeral::NumeralSystem;
+///
+/// let snafu = NumeralSystem::snafu();
+/// assert_eq!(snafu.parse("1=-0-2").unwrap(), 1747);
+/// assert_eq!(snafu.format(4890).unwrap(), "2=-1=0");
+/// assert_eq!(snafu.numeral(-3).unwrap().to_string(), "-2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumeralSystem {
+    digits: Vec<char>,
+    lowest: i128,
+}
+
+impl NumeralSystem {
+    /// Creates a numeral system whose digits, in order, stand for `lowest`,
+    /// `lowest + 1` and so on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are fewer than two digits, a digit repeats, or no
+    /// digit stands for zero (which makes some numbers unwritable).
+    pub fn new(digits: &str, lowest: i128) -> Result<Self, String> {
+        let digits: Vec<char> = digits.chars().collect();
+        if digits.len() < 2 {
+            return Err("A numeral system needs at least two digits".to_string());
+        }
+        if let Some((i, c)) = digits
+            .iter()
+            .enumerate()
+            .find(|(i, c)| digits[..*i].contains(c))
+        {
+            return Err(format!("Digit '{}' at position {} repeats", c, i + 1));
+        }
+        let highest = lowest + digits.len() as i128 - 1;
+        if lowest > 0 || highest < 0 {
+            return Err(format!(
+                "Digits {}..={} do not include zero",
+                lowest, highest
+            ));
+        }
+        Ok(NumeralSystem { digits, lowest })
+    }
+
+    /// Returns the SNAFU system: balanced base five with digits `=-012`.
+    pub fn snafu() -> Self {
+        NumeralSystem::new("=-012", -2).expect("SNAFU digits are valid")
+    }
+
+    /// Returns the number of digits.
+    pub fn base(&self) -> usize {
+        self.digits.len()
+    }
+
+    /// Returns the value of a single digit character.
+    pub fn digit_value(&self, c: char) -> Option<i128> {
+        let i = self.digits.iter().position(|&d| d == c)?;
+        Some(self.lowest + i as i128)
+    }
+
+    /// Parses a numeral, most significant digit first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the numeral is empty, contains a character that is not a
+    /// digit, or its value overflows `i128`.
+    pub fn parse(&self, s: &str) -> Result<i128, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Empty numeral".to_string());
+        }
+        s.chars().enumerate().try_fold(0i128, |acc, (i, c)| {
+            let digit = self
+                .digit_value(c)
+                .ok_or_else(|| format!("Unknown digit '{}' at position {} of '{}'", c, i + 1, s))?;
+            acc.checked_mul(self.base() as i128)
+                .and_then(|acc| acc.checked_add(digit))
+                .ok_or_else(|| format!("Numeral '{}' overflows i128", s))
+        })
+    }
+
+    /// Writes `value` in this system, most significant digit first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value has a sign no digit can produce, such as a
+    /// negative number when every digit is non-negative.
+    pub fn format(&self, value: i128) -> Result<String, String> {
+        Ok(self.numeral(value)?.to_string())
+    }
+
+    /// Returns `value` wrapped so that it displays in this system.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be written, as for
+    /// [`NumeralSystem::format`].
+    pub fn numeral(&self, value: i128) -> Result<Numeral<'_>, String> {
+        let highest = self.lowest + self.base() as i128 - 1;
+        if (value < 0 && self.lowest == 0) || (value > 0 && highest == 0) {
+            return Err(format!("{} cannot be written with these digits", value));
+        }
+        Ok(Numeral {
+            system: self,
+            value,
+        })
+    }
+
+    /// Checks that formatting and parsing every value gives the value back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first value that does not survive the round trip.
+    pub fn check_round_trip<I>(&self, values: I) -> Result<(), String>
+    where
+        I: IntoIterator<Item = i128>,
+    {
+        for value in values {
+            let written = self.format(value)?;
+            let read = self.parse(&written)?;
+            if read != value {
+                return Err(format!(
+                    "{} was written as '{}' but read as {}",
+                    value, written, read
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A number displayed in a [`NumeralSystem`], as returned by
+/// [`NumeralSystem::numeral`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Numeral<'a> {
+    system: &'a NumeralSystem,
+    value: i128,
+}
+
+impl Numeral<'_> {
+    /// Returns the number's value.
+    pub fn value(&self) -> i128 {
+        self.value
+    }
+}
+
+impl fmt::Display for Numeral<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let system = self.system;
+        let base = system.base() as i128;
+        let mut digits = Vec::new();
+        let mut n = self.value;
+        loop {
+            // Pick the digit congruent to n, then carry what is left
+            let offset = (n - system.lowest).rem_euclid(base);
+            digits.push(system.digits[offset as usize]);
+            n = (n - (system.lowest + offset)) / base;
+            if n == 0 {
+                break;
+            }
+        }
+        let written: String = digits.iter().rev().collect();
+        f.pad(&written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snafu_examples() {
+        let snafu = NumeralSystem::snafu();
+        let pairs = [
+            (1, "1"),
+            (3, "1="),
+            (8, "2="),
+            (10, "20"),
+            (15, "1=0"),
+            (20, "1-0"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+        ];
+        for (value, written) in pairs {
+            assert_eq!(snafu.format(value).unwrap(), written);
+            assert_eq!(snafu.parse(written).unwrap(), value);
+        }
+        let fuel = "1=-0-2 12111 2=0= 21 2=01 111 20012 112 1=-1= 1-12 12 1= 122";
+        let total: i128 = fuel.split(' ').map(|s| snafu.parse(s).unwrap()).sum();
+        assert_eq!(total, 4890);
+        assert_eq!(snafu.format(total).unwrap(), "2=-1=0");
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let systems = [
+            NumeralSystem::snafu(),
+            NumeralSystem::new("0123456789", 0).unwrap(),
+            NumeralSystem::new("01", 0).unwrap(),
+            NumeralSystem::new("T01", -1).unwrap(),
+            NumeralSystem::new("abcdefg", -5).unwrap(),
+            NumeralSystem::new("xyz", -2).unwrap(),
+        ];
+        for system in &systems {
+            let writable = |v: &i128| system.numeral(*v).is_ok();
+            let values = (-500..=500).chain([i128::MAX / 2, i128::MIN / 2]);
+            assert_eq!(
+                system.check_round_trip(values.filter(writable)),
+                Ok(()),
+                "{:?}",
+                system
+            );
+        }
+        let decimal = &systems[1];
+        assert_eq!(decimal.format(1234).unwrap(), "1234");
+        assert_eq!(decimal.format(0).unwrap(), "0");
+        assert_eq!(format!("{:>6}", decimal.numeral(42).unwrap()), "    42");
+        assert_eq!(systems[3].format(-4).unwrap(), "TT");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(NumeralSystem::new("0", 0).is_err());
+        assert!(NumeralSystem::new("0120", 0)
+            .unwrap_err()
+            .contains("repeats"));
+        assert!(NumeralSystem::new("12", 1).is_err());
+        let binary = NumeralSystem::new("01", 0).unwrap();
+        assert!(binary.format(-1).is_err());
+        assert!(binary.parse("").is_err());
+        assert!(binary.parse("012").unwrap_err().contains("position 3"));
+        assert!(binary
+            .parse(&"1".repeat(128))
+            .unwrap_err()
+            .contains("overflows"));
+        let negative = NumeralSystem::new("-0", -1).unwrap();
+        assert_eq!(negative.format(-3).unwrap(), "--");
+        assert!(negative.format(1).is_err());
+    }
+}
@@ -1,12 +1,22 @@
+use crate::AocError;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::error::Error;
-use std::fs;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
 use std::path::Path;
 use std::str::FromStr;
 
+/// The path that makes the parser functions read standard input instead of a file, so
+/// a solution can be fed with `cat input.txt | cargo run` or a here-doc.
+pub const STDIN: &str = "-";
+
 /// Parses a file where each line is automatically converted to type `T`.
 ///
 /// This function reads a file and parses each line using the type's `FromStr` implementation.
 /// It's useful when your input file has one value per line that can be parsed into a specific type.
+/// To parse a string already in memory, use [`parse_lines_str`].
 ///
 /// # Type Parameters
 ///
@@ -15,7 +25,7 @@ use std::str::FromStr;
 ///
 /// # Arguments
 ///
-/// * `path` - Path to the input file
+/// * `path` - Path to the input file, or [`STDIN`] (`-`) to read standard input
 ///
 /// # Returns
 ///
@@ -43,18 +53,45 @@ use std::str::FromStr;
 /// # Errors
 ///
 /// This function will return an error if:
-/// * The file cannot be read
-/// * Any line in the file cannot be parsed into type `T`
-pub fn parse_lines<T, P>(path: P) -> Result<Vec<T>, Box<dyn Error>>
+/// * The file cannot be read ([`AocError::Io`])
+/// * Any line in the file cannot be parsed into type `T` ([`AocError::Parse`], with the
+///   line number and the start of the line)
+pub fn parse_lines<T, P>(path: P) -> Result<Vec<T>, AocError>
 where
     T: FromStr,
     T::Err: std::error::Error + 'static,
     P: AsRef<Path>,
 {
-    let content = fs::read_to_string(path)?;
-    content
+    parse_lines_str(&read_input(path)?)
+}
+
+/// Parses each line of `input` into type `T`, like [`parse_lines`] does for a file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_lines_str;
+///
+/// let numbers: Vec<i32> = parse_lines_str("1\n-2\n3").unwrap();
+/// assert_eq!(numbers, [1, -2, 3]);
+/// assert!(parse_lines_str::<i32>("1\nx").is_err());
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] for the first line that cannot be parsed.
+pub fn parse_lines_str<T>(input: &str) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: std::error::Error + 'static,
+{
+    input
         .lines()
-        .map(|line| line.parse::<T>().map_err(|e| e.into()))
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse::<T>()
+                .map_err(|e| AocError::parse(i + 1, line, e))
+        })
         .collect()
 }
 
@@ -62,6 +99,7 @@ where
 ///
 /// This function provides maximum flexibility by allowing you to define exactly how each
 /// line should be parsed. Use this when lines contain structured data that needs custom parsing.
+/// To parse a string already in memory, use [`parse_lines_with_str`].
 ///
 /// # Type Parameters
 ///
@@ -71,7 +109,7 @@ where
 ///
 /// # Arguments
 ///
-/// * `path` - Path to the input file
+/// * `path` - Path to the input file, or [`STDIN`] (`-`) to read standard input
 /// * `parser` - Function that parses a single line into type `T`
 ///
 /// # Returns
@@ -108,15 +146,46 @@ where
 /// # Errors
 ///
 /// This function will return an error if:
-/// * The file cannot be read
-/// * The parser function returns an error for any line
-pub fn parse_lines_with<T, P, F>(path: P, parser: F) -> Result<Vec<T>, Box<dyn Error>>
+/// * The file cannot be read ([`AocError::Io`])
+/// * The parser function returns an error for any line ([`AocError::Parse`], with the
+///   line number and the start of the line; a column is kept if the parser returned an
+///   `AocError::Parse` itself)
+pub fn parse_lines_with<T, P, F>(path: P, parser: F) -> Result<Vec<T>, AocError>
 where
     P: AsRef<Path>,
     F: Fn(&str) -> Result<T, Box<dyn Error>>,
 {
-    let content = fs::read_to_string(path)?;
-    content.lines().map(parser).collect()
+    parse_lines_with_str(&read_input(path)?, parser)
+}
+
+/// Parses each line of `input` with `parser`, like [`parse_lines_with`] does for a
+/// file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_lines_with_str;
+///
+/// let pairs = parse_lines_with_str("a=1\nb=2", |line| {
+///     let (key, value) = line.split_once('=').ok_or("missing '='")?;
+///     Ok((key.to_string(), value.parse::<u32>()?))
+/// })
+/// .unwrap();
+/// assert_eq!(pairs, [("a".to_string(), 1), ("b".to_string(), 2)]);
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] for the first line `parser` fails on.
+pub fn parse_lines_with_str<T, F>(input: &str, parser: F) -> Result<Vec<T>, AocError>
+where
+    F: Fn(&str) -> Result<T, Box<dyn Error>>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parser(line).map_err(|e| AocError::at_line(i + 1, line, e)))
+        .collect()
 }
 
 /// Parses an entire file using a custom parser function.
@@ -124,6 +193,7 @@ where
 /// Unlike `parse_lines_with`, this function passes the entire file content as a single string
 /// to your parser. Use this when you need to handle the file as a whole, such as parsing
 /// sections separated by blank lines or processing multi-line records.
+/// To parse a string already in memory, use [`parse_with_str`].
 ///
 /// # Type Parameters
 ///
@@ -133,7 +203,7 @@ where
 ///
 /// # Arguments
 ///
-/// * `path` - Path to the input file
+/// * `path` - Path to the input file, or [`STDIN`] (`-`) to read standard input
 /// * `parser` - Function that parses the entire file content into type `T`
 ///
 /// # Returns
@@ -171,15 +241,639 @@ where
 /// # Errors
 ///
 /// This function will return an error if:
-/// * The file cannot be read
-/// * The parser function returns an error
-pub fn parse_with<T, P, F>(path: P, parser: F) -> Result<T, Box<dyn Error>>
+/// * The file cannot be read ([`AocError::Io`])
+/// * The parser function returns an error ([`AocError::Custom`], or the parser's own
+///   error if it returned an `AocError`)
+pub fn parse_with<T, P, F>(path: P, parser: F) -> Result<T, AocError>
+where
+    P: AsRef<Path>,
+    F: Fn(&str) -> Result<T, Box<dyn Error>>,
+{
+    parse_with_str(&read_input(path)?, parser)
+}
+
+/// Parses the whole of `input` with `parser`, like [`parse_with`] does for a file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_with_str;
+///
+/// let sections = parse_with_str("a\nb\n\nc", |input| {
+///     Ok(input.split("\n\n").count())
+/// })
+/// .unwrap();
+/// assert_eq!(sections, 2);
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Custom`] if `parser` fails, or the parser's own error if it
+/// returned an `AocError`.
+pub fn parse_with_str<T, F>(input: &str, parser: F) -> Result<T, AocError>
+where
+    F: Fn(&str) -> Result<T, Box<dyn Error>>,
+{
+    Ok(parser(input)?)
+}
+
+/// Parses a file of values separated by `delim` into a flat list of `T`.
+///
+/// Line breaks separate values too, so this reads a single comma-separated line and a
+/// grid of space-separated numbers alike. Each value is trimmed before parsing. With a
+/// whitespace delimiter such as `' '` or `'\t'`, runs of it count as one separator;
+/// with any other delimiter an empty value is parsed like any other, and usually fails.
+/// For one list per line, use [`parse_delimited_lines`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::parse_delimited;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // 3,4,3,1,2
+/// let fish: Vec<u8> = parse_delimited("input.txt", ',')?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] if the file cannot be read, or an [`AocError::Parse`]
+/// with the line and column of the first value that cannot be parsed into type `T`.
+pub fn parse_delimited<T, P>(path: P, delim: char) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    P: AsRef<Path>,
+{
+    parse_delimited_str(&read_input(path)?, delim)
+}
+
+/// Parses values separated by `delim` in `input`, like [`parse_delimited`] does for a
+/// file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_delimited_str;
+///
+/// let values: Vec<u32> = parse_delimited_str("3, 4,3\n1,2\n", ',').unwrap();
+/// assert_eq!(values, [3, 4, 3, 1, 2]);
+/// let grid: Vec<i32> = parse_delimited_str("1  2 -3\n 4 5 6", ' ').unwrap();
+/// assert_eq!(grid, [1, 2, -3, 4, 5, 6]);
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] with the line and column of the first value that
+/// cannot be parsed into type `T`.
+pub fn parse_delimited_str<T>(input: &str, delim: char) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut values = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        values.extend(parse_fields(i + 1, line, delim)?);
+    }
+    Ok(values)
+}
+
+/// Parses a file with one list of values separated by `delim` on every line.
+///
+/// Values are split and trimmed as for [`parse_delimited`]; a blank line gives an empty
+/// list.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::parse_delimited_lines;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // 7 6 4 2 1
+/// // 1 2 7 8 9
+/// let reports: Vec<Vec<i32>> = parse_delimited_lines("input.txt", ' ')?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] if the file cannot be read, or an [`AocError::Parse`]
+/// with the line and column of the first value that cannot be parsed into type `T`.
+pub fn parse_delimited_lines<T, P>(path: P, delim: char) -> Result<Vec<Vec<T>>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    P: AsRef<Path>,
+{
+    parse_delimited_lines_str(&read_input(path)?, delim)
+}
+
+/// Parses one list of values separated by `delim` from every line of `input`, like
+/// [`parse_delimited_lines`] does for a file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_delimited_lines_str;
+///
+/// let rows: Vec<Vec<u8>> = parse_delimited_lines_str("1\t2\n\n3", '\t').unwrap();
+/// assert_eq!(rows, [vec![1, 2], vec![], vec![3]]);
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] with the line and column of the first value that
+/// cannot be parsed into type `T`.
+pub fn parse_delimited_lines_str<T>(input: &str, delim: char) -> Result<Vec<Vec<T>>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_fields(i + 1, line, delim))
+        .collect()
+}
+
+/// Parses a file of sections separated by blank lines, passing each section to
+/// `parser`.
+///
+/// Blank lines are lines with nothing but whitespace, so `\r\n` line endings work too.
+/// Each section is passed without its trailing line break. If `parser` fails with an
+/// [`AocError::Parse`] whose line counts from the start of the section (as the other
+/// `_str` parsers' errors do), the line is shifted to count from the start of the file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::{parse_lines_str, parse_sections};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // One elf's snacks per section
+/// let elves: Vec<Vec<u32>> = parse_sections("input.txt", |section| {
+///     Ok(parse_lines_str(section)?)
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] if the file cannot be read, or the first error from
+/// `parser`, as an [`AocError::Parse`] located at or in the section.
+pub fn parse_sections<T, P, F>(path: P, parser: F) -> Result<Vec<T>, AocError>
 where
     P: AsRef<Path>,
     F: Fn(&str) -> Result<T, Box<dyn Error>>,
 {
-    let content = fs::read_to_string(path)?;
-    parser(&content)
+    parse_sections_str(&read_input(path)?, parser)
+}
+
+/// Parses sections of `input` separated by blank lines with `parser`, like
+/// [`parse_sections`] does for a file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{parse_lines_str, parse_sections_str};
+///
+/// let parse = |section: &str| Ok(parse_lines_str::<u32>(section)?);
+/// let elves = parse_sections_str("1\n2\n\n3\n", parse).unwrap();
+/// assert_eq!(elves, [vec![1, 2], vec![3]]);
+///
+/// // The error counts lines from the start of the input, not of the section
+/// let err = parse_sections_str("1\n2\n\n3\nx", parse).unwrap_err();
+/// assert!(err.to_string().starts_with("line 5 "));
+/// ```
+///
+/// # Errors
+///
+/// Returns the first error from `parser`, as an [`AocError::Parse`] located at or in
+/// the section.
+pub fn parse_sections_str<T, F>(input: &str, parser: F) -> Result<Vec<T>, AocError>
+where
+    F: Fn(&str) -> Result<T, Box<dyn Error>>,
+{
+    sections(input)
+        .into_iter()
+        .map(|(first, section)| {
+            parser(section).map_err(|e| match AocError::from(e) {
+                AocError::Parse {
+                    line,
+                    column,
+                    snippet,
+                    message,
+                } => AocError::Parse {
+                    line: first + line.max(1) - 1,
+                    column,
+                    snippet,
+                    message,
+                },
+                AocError::Custom(e) => {
+                    AocError::parse(first, section.lines().next().unwrap_or_default(), e)
+                }
+                other => other,
+            })
+        })
+        .collect()
+}
+
+/// Splits `input` at blank lines, returning every section with the 1-based number of
+/// its first line.
+fn sections(input: &str) -> Vec<(usize, &str)> {
+    let mut sections = Vec::new();
+    // The line number and byte offset where the current section starts
+    let mut current: Option<(usize, usize)> = None;
+    let mut offset = 0;
+    let mut end = 0;
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        if line.trim().is_empty() {
+            if let Some((first, start)) = current.take() {
+                sections.push((first, &input[start..end]));
+            }
+        } else {
+            current.get_or_insert((i + 1, offset));
+            end = offset + line.trim_end_matches(['\n', '\r']).len();
+        }
+        offset += line.len();
+    }
+    if let Some((first, start)) = current {
+        sections.push((first, &input[start..end]));
+    }
+    sections
+}
+
+/// Parses one record of `key: value` or `key=value` fields into a map.
+///
+/// A line whose first `:` or `=` is followed by a space, the end of the line, or no
+/// other separator holds a single field, with everything after the separator as its
+/// value; header-body lines such as `Card 1: 41 48 | 83 86` read this way. Any other
+/// line holds several whitespace-separated fields, as in passport-style records like
+/// `ecl:gry pid:860033327`. Keys and values are trimmed.
+///
+/// Use it on its own for a single record, or with [`parse_sections`] (or
+/// [`parse_records`]) for records separated by blank lines.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_record;
+///
+/// let passport = parse_record("ecl:gry pid:860033327\nhgt:183cm").unwrap();
+/// assert_eq!(passport["pid"], "860033327");
+/// assert_eq!(passport.len(), 3);
+///
+/// let card = parse_record("Card 1: 41 48 | 83 86\nname = Lucky Seven").unwrap();
+/// assert_eq!(card["Card 1"], "41 48 | 83 86");
+/// assert_eq!(card["name"], "Lucky Seven");
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`], with the line number within `record`, for a field
+/// without a separator or a key that appears twice.
+pub fn parse_record(record: &str) -> Result<HashMap<String, String>, AocError> {
+    let mut fields = HashMap::new();
+    for (i, line) in record.lines().enumerate() {
+        let error = |message: String| AocError::parse(i + 1, line, message);
+        let Some(at) = line.find([':', '=']) else {
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Err(error("expected 'key: value' or 'key=value'".to_string()));
+        };
+        let rest = &line[at + 1..];
+        let pairs: Vec<&str> = if rest.is_empty()
+            || rest.starts_with(char::is_whitespace)
+            || !rest.contains([':', '='])
+        {
+            vec![line]
+        } else {
+            line.split_whitespace().collect()
+        };
+        for pair in pairs {
+            let (key, value) = pair
+                .split_once([':', '='])
+                .ok_or_else(|| error(format!("expected a separator in '{}'", pair)))?;
+            let key = key.trim();
+            if fields
+                .insert(key.to_string(), value.trim().to_string())
+                .is_some()
+            {
+                return Err(error(format!("duplicate key '{}'", key)));
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/// Parses a file of records separated by blank lines, each made of `key: value` or
+/// `key=value` fields as read by [`parse_record`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::parse_records;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let passports = parse_records("input.txt")?;
+/// let complete = passports.iter().filter(|p| p.contains_key("byr")).count();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] if the file cannot be read, or an [`AocError::Parse`]
+/// for the first malformed field, located in the file.
+pub fn parse_records<P: AsRef<Path>>(path: P) -> Result<Vec<HashMap<String, String>>, AocError> {
+    parse_records_str(&read_input(path)?)
+}
+
+/// Parses records of `input` separated by blank lines, like [`parse_records`] does for
+/// a file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_records_str;
+///
+/// let records = parse_records_str("a=1 b=2\n\nc: three").unwrap();
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[1]["c"], "three");
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] for the first malformed field, located in `input`.
+pub fn parse_records_str(input: &str) -> Result<Vec<HashMap<String, String>>, AocError> {
+    parse_sections_str(input, |section| Ok(parse_record(section)?))
+}
+
+/// Parses a file of `N` whitespace-separated columns into one `[T; N]` per line.
+///
+/// This is the common "two columns of numbers" input shape. Columns may be separated
+/// by any run of spaces or tabs, and blank lines are skipped. To get each column as its
+/// own list instead, use [`parse_columns_transposed`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::parse_columns;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // 3   4
+/// // 4   3
+/// let pairs: Vec<[u32; 2]> = parse_columns("input.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] if the file cannot be read, or an [`AocError::Parse`]
+/// for the first line that does not have exactly `N` columns or has a value that cannot
+/// be parsed into type `T`.
+pub fn parse_columns<T, const N: usize, P>(path: P) -> Result<Vec<[T; N]>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    P: AsRef<Path>,
+{
+    parse_columns_str(&read_input(path)?)
+}
+
+/// Parses `N` whitespace-separated columns from every line of `input`, like
+/// [`parse_columns`] does for a file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_columns_str;
+///
+/// let rows: Vec<[i64; 3]> = parse_columns_str("1 2 3\n\n4\t5   -6\n").unwrap();
+/// assert_eq!(rows, [[1, 2, 3], [4, 5, -6]]);
+/// assert!(parse_columns_str::<i64, 3>("1 2").is_err());
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] for the first line that does not have exactly `N`
+/// columns or has a value that cannot be parsed into type `T`.
+pub fn parse_columns_str<T, const N: usize>(input: &str) -> Result<Vec<[T; N]>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let fields: Vec<T> = parse_split(i + 1, line, line.split_whitespace())?;
+            let found = fields.len();
+            fields.try_into().map_err(|_| {
+                AocError::parse(
+                    i + 1,
+                    line,
+                    format!("expected {} columns, found {}", N, found),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Parses a file of `N` whitespace-separated columns into `N` lists, one per column.
+///
+/// Lines are read as for [`parse_columns`], so the lists always have the same length.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::parse_columns_transposed;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let [mut left, mut right] = parse_columns_transposed::<u32, 2, _>("input.txt")?;
+/// left.sort_unstable();
+/// right.sort_unstable();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] if the file cannot be read, or an [`AocError::Parse`]
+/// for the first line that does not have exactly `N` columns or has a value that cannot
+/// be parsed into type `T`.
+pub fn parse_columns_transposed<T, const N: usize, P>(path: P) -> Result<[Vec<T>; N], AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    P: AsRef<Path>,
+{
+    parse_columns_transposed_str(&read_input(path)?)
+}
+
+/// Parses `N` whitespace-separated columns of `input` into `N` lists, one per column,
+/// like [`parse_columns_transposed`] does for a file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_columns_transposed_str;
+///
+/// let [left, right] = parse_columns_transposed_str::<u32, 2>("3   4\n4   3\n2   5").unwrap();
+/// assert_eq!(left, [3, 4, 2]);
+/// assert_eq!(right, [4, 3, 5]);
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] for the first line that does not have exactly `N`
+/// columns or has a value that cannot be parsed into type `T`.
+pub fn parse_columns_transposed_str<T, const N: usize>(input: &str) -> Result<[Vec<T>; N], AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut columns: [Vec<T>; N] = std::array::from_fn(|_| Vec::new());
+    for row in parse_columns_str::<T, N>(input)? {
+        for (column, value) in columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+    }
+    Ok(columns)
+}
+
+/// Splits line number `number` on `delim` and parses every trimmed field, reporting
+/// the 1-based column of a field that fails.
+fn parse_fields<T>(number: usize, line: &str, delim: char) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    if line.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let fields = line.split(delim).map(str::trim);
+    if delim.is_whitespace() {
+        parse_split(number, line, fields.filter(|field| !field.is_empty()))
+    } else {
+        parse_split(number, line, fields)
+    }
+}
+
+/// Parses `fields`, which are slices of line number `number`, reporting the 1-based
+/// column of a field that fails.
+fn parse_split<'a, T, I>(number: usize, line: &'a str, fields: I) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    I: Iterator<Item = &'a str>,
+{
+    fields
+        .map(|field| {
+            field.parse().map_err(|e| {
+                // Every field is a slice of `line`, so its address gives its offset
+                let offset = field.as_ptr() as usize - line.as_ptr() as usize;
+                AocError::parse(number, line, format!("cannot parse '{}': {}", field, e))
+                    .with_column(line[..offset].chars().count() + 1)
+            })
+        })
+        .collect()
+}
+
+/// Lazily parses a file line by line into type `T`, reading it through a buffer.
+///
+/// Unlike [`parse_lines`], neither the file nor the parsed values are ever held in
+/// memory as a whole, so inputs of any size can be folded over in constant memory.
+/// Each item is the parsed line or an error for that line; iteration can go on past
+/// an error, but `?` or `collect` stop at the first.
+///
+/// # Arguments
+///
+/// * `path` - Path to the input file, or [`STDIN`] (`-`) to read standard input
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::parse_lines_iter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut total = 0u64;
+/// for value in parse_lines_iter::<u64, _>("huge.txt")? {
+///     total += value?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] up front if the file cannot be opened. Each item is an
+/// [`AocError::Io`] if reading the line fails, or an [`AocError::Parse`] if it cannot be
+/// parsed into type `T`.
+pub fn parse_lines_iter<T, P>(
+    path: P,
+) -> Result<impl Iterator<Item = Result<T, AocError>>, AocError>
+where
+    T: FromStr,
+    T::Err: std::error::Error + 'static,
+    P: AsRef<Path>,
+{
+    parse_lines_with_iter(path, |line| Ok(line.parse::<T>()?))
+}
+
+/// Lazily parses a file line by line with `parser`, reading it through a buffer.
+///
+/// This is the custom-parser counterpart of [`parse_lines_iter`], with `parser` taking
+/// the same form as for [`parse_lines_with`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::parse_lines_with_iter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut largest = 0;
+/// for pair in parse_lines_with_iter("pairs.txt", |line| {
+///     let (a, b) = line.split_once(',').ok_or("missing comma")?;
+///     Ok((a.parse::<u32>()?, b.parse::<u32>()?))
+/// })? {
+///     let (a, b) = pair?;
+///     largest = largest.max(a).max(b);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Io`] up front if the file cannot be opened. Each item is an
+/// [`AocError::Io`] if reading the line fails, or an [`AocError::Parse`] if `parser`
+/// fails on it.
+pub fn parse_lines_with_iter<T, P, F>(
+    path: P,
+    parser: F,
+) -> Result<impl Iterator<Item = Result<T, AocError>>, AocError>
+where
+    P: AsRef<Path>,
+    F: Fn(&str) -> Result<T, Box<dyn Error>>,
+{
+    let path = path.as_ref();
+    let reader: Box<dyn BufRead> = if path == Path::new(STDIN) {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(path)?))
+    };
+    Ok(reader.lines().enumerate().map(move |(i, line)| {
+        let line = line?;
+        parser(&line).map_err(|e| AocError::at_line(i + 1, &line, e))
+    }))
 }
 
 /// Reads a file and returns its contents as a raw string.
@@ -189,7 +883,7 @@ where
 ///
 /// # Arguments
 ///
-/// * `path` - Path to the input file
+/// * `path` - Path to the input file, or [`STDIN`] (`-`) to read standard input
 ///
 /// # Returns
 ///
@@ -215,14 +909,177 @@ where
 ///
 /// # Errors
 ///
-/// This function will return an error if:
+/// This function will return an [`AocError::Io`] if:
 /// * The file does not exist
 /// * The file cannot be read (permissions, etc.)
 /// * The file contains invalid UTF-8
-pub fn read_input<P: AsRef<Path>>(path: P) -> Result<String, Box<dyn Error>> {
+pub fn read_input<P: AsRef<Path>>(path: P) -> Result<String, AocError> {
+    let path = path.as_ref();
+    if path == Path::new(STDIN) {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        return Ok(content);
+    }
     Ok(fs::read_to_string(path)?)
 }
 
+/// Normalizes input text before it is parsed: strips a UTF-8 byte order mark, turns
+/// Windows line endings into `\n`, and drops blank lines at the end.
+///
+/// Inputs saved or copy-pasted on Windows otherwise break parsing in quiet ways, such
+/// as `split("\n\n")` finding no sections because they are separated by `\r\n\r\n`.
+/// Each normalization can be switched off, and trailing whitespace on every line can be
+/// trimmed as well; that is off by default, since some puzzles pad their lines to a
+/// fixed width.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::ParseOptions;
+///
+/// let pasted = "\u{feff}1\r\n2\r\n\r\n3 \r\n\r\n\r\n";
+/// let text = ParseOptions::new().normalize(pasted);
+/// assert_eq!(text, "1\n2\n\n3 ");
+/// assert_eq!(text.split("\n\n").count(), 2);
+///
+/// let trimmed = ParseOptions::new().trim_line_ends(true).normalize(pasted);
+/// assert_eq!(trimmed, "1\n2\n\n3");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    strip_bom: bool,
+    strip_crlf: bool,
+    trim_line_ends: bool,
+    trim_trailing_blank_lines: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            strip_bom: true,
+            strip_crlf: true,
+            trim_line_ends: false,
+            trim_trailing_blank_lines: true,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Creates the default options: strip a BOM, CRLF line endings and trailing blank
+    /// lines, but keep whitespace at the ends of lines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether a UTF-8 byte order mark at the start is removed.
+    pub fn strip_bom(self, strip_bom: bool) -> Self {
+        ParseOptions { strip_bom, ..self }
+    }
+
+    /// Sets whether `\r\n` line endings are turned into `\n`.
+    pub fn strip_crlf(self, strip_crlf: bool) -> Self {
+        ParseOptions { strip_crlf, ..self }
+    }
+
+    /// Sets whether whitespace at the end of every line is removed.
+    pub fn trim_line_ends(self, trim_line_ends: bool) -> Self {
+        ParseOptions {
+            trim_line_ends,
+            ..self
+        }
+    }
+
+    /// Sets whether blank lines at the end, and the final line break, are removed.
+    pub fn trim_trailing_blank_lines(self, trim_trailing_blank_lines: bool) -> Self {
+        ParseOptions {
+            trim_trailing_blank_lines,
+            ..self
+        }
+    }
+
+    /// Applies the options to `input`, borrowing it when nothing needs to change.
+    pub fn normalize<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(input);
+        if self.strip_bom {
+            if let Some(rest) = input.strip_prefix('\u{feff}') {
+                text = Cow::Borrowed(rest);
+            }
+        }
+        if self.strip_crlf && text.contains("\r\n") {
+            text = Cow::Owned(text.replace("\r\n", "\n"));
+        }
+        if self.trim_line_ends {
+            let trimmed: Vec<&str> = text.split('\n').map(str::trim_end).collect();
+            text = Cow::Owned(trimmed.join("\n"));
+        }
+        if self.trim_trailing_blank_lines {
+            // Keep everything up to the end of the last line with something on it
+            let end = text.rfind(|c: char| !c.is_whitespace()).map_or(0, |last| {
+                last + text[last..].find('\n').unwrap_or(text.len() - last)
+            });
+            match text {
+                Cow::Borrowed(s) => text = Cow::Borrowed(&s[..end]),
+                Cow::Owned(ref mut s) => s.truncate(end),
+            }
+        }
+        text
+    }
+
+    /// Reads a file (or standard input, for [`STDIN`]) like [`read_input`] and
+    /// normalizes it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AocError::Io`] if the input cannot be read.
+    pub fn read_input<P: AsRef<Path>>(&self, path: P) -> Result<String, AocError> {
+        let content = read_input(path)?;
+        Ok(match self.normalize(&content) {
+            Cow::Borrowed(text) if text.len() == content.len() => content,
+            text => text.into_owned(),
+        })
+    }
+
+    /// Parses each line of the normalized input like [`parse_lines`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input cannot be read or any line cannot be parsed.
+    pub fn parse_lines<T, P>(&self, path: P) -> Result<Vec<T>, AocError>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + 'static,
+        P: AsRef<Path>,
+    {
+        parse_lines_str(&self.read_input(path)?)
+    }
+
+    /// Parses each line of the normalized input with `parser` like [`parse_lines_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input cannot be read or `parser` fails on any line.
+    pub fn parse_lines_with<T, P, F>(&self, path: P, parser: F) -> Result<Vec<T>, AocError>
+    where
+        P: AsRef<Path>,
+        F: Fn(&str) -> Result<T, Box<dyn Error>>,
+    {
+        parse_lines_with_str(&self.read_input(path)?, parser)
+    }
+
+    /// Parses the whole normalized input with `parser` like [`parse_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input cannot be read or `parser` fails.
+    pub fn parse_with<T, P, F>(&self, path: P, parser: F) -> Result<T, AocError>
+    where
+        P: AsRef<Path>,
+        F: Fn(&str) -> Result<T, Box<dyn Error>>,
+    {
+        parse_with_str(&self.read_input(path)?, parser)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +1120,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_parse_lines_floats() {
         let path = create_test_file("floats", "1.5\n2.7\n3.14");
 
@@ -345,12 +1203,346 @@ mod tests {
 
         let result: Result<Vec<i32>, _> = parse_lines(&path);
         assert!(result.is_err());
+        assert!(matches!(result, Err(AocError::Parse { line: 3, .. })));
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_lines_with_error_kinds() {
+        let path = create_test_file("error_kinds", "1,2\n3;4");
+
+        let result = parse_lines_with(&path, |line| {
+            let (a, b) = line.split_once(',').ok_or("missing comma")?;
+            Ok((a.parse::<u32>()?, b.parse::<u32>()?))
+        });
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "line 2 (\"3;4\"): missing comma"
+        );
+
+        let result = parse_with(&path, |_| Err::<(), _>("no sections".into()));
+        assert!(matches!(result, Err(AocError::Custom(_))));
+
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_lines_with_error_locates_line() {
+        let mut content: Vec<String> = (0..3000).map(|i| format!("{},{}", i, i)).collect();
+        content[742] = format!("742;{}", "9".repeat(60));
+        let path = create_test_file("locate", &content.join("\n"));
+
+        let result = parse_lines_with(&path, |line| {
+            let (a, b) = line.split_once(',').ok_or("missing comma")?;
+            Ok((a.parse::<u32>()?, b.parse::<u32>()?))
+        });
+        match result {
+            Err(AocError::Parse { line, snippet, .. }) => {
+                assert_eq!(line, 743);
+                assert_eq!(snippet, format!("742;{}...", "9".repeat(36)));
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_lines_str() {
+        assert_eq!(parse_lines_str::<u8>("1\n2\n3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_lines_str::<u8>("").unwrap(), Vec::<u8>::new());
+        assert!(matches!(
+            parse_lines_str::<u8>("1\n256"),
+            Err(AocError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_lines_with_str() {
+        let parser = |line: &str| -> Result<(char, u32), Box<dyn Error>> {
+            let (name, count) = line.split_once(',').ok_or("missing comma")?;
+            Ok((name.parse()?, count.parse()?))
+        };
+        assert_eq!(
+            parse_lines_with_str("a,5\nb,3", parser).unwrap(),
+            vec![('a', 5), ('b', 3)]
+        );
+        assert_eq!(
+            parse_lines_with_str("a,5\nb3", parser)
+                .unwrap_err()
+                .to_string(),
+            "line 2 (\"b3\"): missing comma"
+        );
+    }
+
+    #[test]
+    fn test_parse_with_str() {
+        let sections = parse_with_str("a\nb\n\nc", |content| {
+            Ok(content
+                .split("\n\n")
+                .map(str::to_string)
+                .collect::<Vec<_>>())
+        });
+        assert_eq!(sections.unwrap(), vec!["a\nb", "c"]);
+        assert!(matches!(
+            parse_with_str(".", |_| Err::<(), _>("bad".into())),
+            Err(AocError::Custom(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_lines_iter() {
+        let path = create_test_file("iter", "1\n2\nx\n4");
+
+        let values: Vec<_> = parse_lines_iter::<u32, _>(&path).unwrap().collect();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[1].as_ref().unwrap(), &2);
+        assert!(matches!(values[2], Err(AocError::Parse { line: 3, .. })));
+        assert_eq!(values[3].as_ref().unwrap(), &4);
+
+        // Collecting stops at the first error, as parse_lines does
+        let collected: Result<Vec<u32>, _> = parse_lines_iter(&path).unwrap().collect();
+        assert_eq!(
+            collected.unwrap_err().to_string(),
+            parse_lines::<u32, _>(&path).unwrap_err().to_string()
+        );
+
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_lines_with_iter() {
+        let path = create_test_file("with_iter", "a,5\r\nb,3\r\n");
+
+        let data: Vec<(String, u32)> = parse_lines_with_iter(&path, |line| {
+            let (name, count) = line.split_once(',').ok_or("missing comma")?;
+            Ok((name.to_string(), count.parse()?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+        assert_eq!(data, vec![("a".to_string(), 5), ("b".to_string(), 3)]);
+        assert!(matches!(
+            parse_lines_with_iter("nonexistent_file.txt", |line| Ok(line.len())),
+            Err(AocError::Io(_))
+        ));
+
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_options_normalize() {
+        let options = ParseOptions::new();
+        assert!(matches!(options.normalize("a\nb"), Cow::Borrowed("a\nb")));
+        assert!(matches!(
+            options.normalize("a\nb\n\n"),
+            Cow::Borrowed("a\nb")
+        ));
+        assert_eq!(options.normalize("\u{feff}a"), "a");
+        assert_eq!(options.normalize("a\r\n\r\nb\r\n"), "a\n\nb");
+        assert_eq!(options.normalize("a  \n \n\t\n"), "a  ");
+        assert_eq!(options.normalize("\n \n"), "");
+        // A lone \r is not a line ending, so it is left alone
+        assert_eq!(options.normalize("a\rb"), "a\rb");
+    }
+
+    #[test]
+    fn test_parse_options_switches() {
+        let input = "\u{feff}a \r\nb\r\n\r\n";
+        let none = ParseOptions::new()
+            .strip_bom(false)
+            .strip_crlf(false)
+            .trim_trailing_blank_lines(false);
+        assert_eq!(none.normalize(input), input);
+        assert_eq!(none.strip_crlf(true).normalize(input), "\u{feff}a \nb\n\n");
+        assert_eq!(ParseOptions::new().normalize(input), "a \nb");
+        assert_eq!(
+            ParseOptions::new().trim_line_ends(true).normalize(input),
+            "a\nb"
+        );
+    }
+
+    #[test]
+    fn test_parse_options_sections() {
+        let path = create_test_file("windows", "1\r\n2\r\n\r\n3\r\n\r\n");
+
+        let sections = ParseOptions::new().parse_with(&path, |content| {
+            Ok(content
+                .split("\n\n")
+                .map(|section| section.lines().count())
+                .collect::<Vec<_>>())
+        });
+        assert_eq!(sections.unwrap(), vec![2, 1]);
+        // Without normalizing, the \r\n\r\n separator is missed
+        let raw = parse_with(&path, |content| Ok(content.split("\n\n").count()));
+        assert_eq!(raw.unwrap(), 1);
+
+        let numbers: Vec<u32> = ParseOptions::new()
+            .parse_lines_with(&path, |line| Ok(line.parse().unwrap_or(0)))
+            .unwrap();
+        assert_eq!(numbers, vec![1, 2, 0, 3]);
+
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_delimited() {
+        let values: Vec<u32> = parse_delimited_str("1,2, 3\r\n4,5\n", ',').unwrap();
+        assert_eq!(values, vec![1, 2, 3, 4, 5]);
+        let values: Vec<u32> = parse_delimited_str("  1   2\n\n3\t", ' ').unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+        let values: Vec<String> = parse_delimited_str("a\tb c\td", '\t').unwrap();
+        assert_eq!(values, vec!["a", "b c", "d"]);
+        assert_eq!(
+            parse_delimited_str::<u32>("", ',').unwrap(),
+            Vec::<u32>::new()
+        );
+
+        let path = create_test_file("delimited", "3,4,3,1,2");
+        assert_eq!(
+            parse_delimited::<u8, _>(&path, ',').unwrap(),
+            vec![3, 4, 3, 1, 2]
+        );
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_delimited_lines() {
+        let rows: Vec<Vec<i32>> = parse_delimited_lines_str("7 6 -4\n\n1  2", ' ').unwrap();
+        assert_eq!(rows, vec![vec![7, 6, -4], vec![], vec![1, 2]]);
+
+        let path = create_test_file("delimited_lines", "1;2\n3;4");
+        assert_eq!(
+            parse_delimited_lines::<u8, _>(&path, ';').unwrap(),
+            vec![vec![1, 2], vec![3, 4]]
+        );
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_delimited_errors() {
+        // Empty fields between commas are errors, located by column
+        match parse_delimited_str::<u32>("1,2\n3,,4", ',') {
+            Err(AocError::Parse { line, column, .. }) => {
+                assert_eq!((line, column), (2, Some(3)));
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        let err = parse_delimited_lines_str::<u8>("1 2\n 42 300", ' ').unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 5 (\" 42 300\"): cannot parse '300': \
+             number too large to fit in target type"
+        );
+        // Columns count characters, not bytes
+        let err = parse_delimited_str::<u8>("é,x", ',').unwrap_err();
+        assert!(err.to_string().starts_with("line 1, column 1 "));
+        let err = parse_delimited_str::<char>("é,xy", ',').unwrap_err();
+        assert!(err.to_string().starts_with("line 1, column 3 "));
+    }
+
+    #[test]
+    fn test_parse_columns() {
+        let rows: Vec<[u32; 2]> = parse_columns_str("3   4\n4\t3\n\n 9 1 \n").unwrap();
+        assert_eq!(rows, vec![[3, 4], [4, 3], [9, 1]]);
+        let none: Vec<[u32; 2]> = parse_columns_str("").unwrap();
+        assert!(none.is_empty());
+
+        let path = create_test_file("columns", "1 a\n2 b");
+        let rows: Vec<[String; 2]> = parse_columns(&path).unwrap();
+        assert_eq!(rows[1], ["2".to_string(), "b".to_string()]);
+        let [numbers, letters] = parse_columns_transposed::<String, 2, _>(&path).unwrap();
+        assert_eq!(numbers, vec!["1", "2"]);
+        assert_eq!(letters, vec!["a", "b"]);
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_columns_errors() {
+        let err = parse_columns_str::<u32, 2>("1 2\n3 4 5").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2 (\"3 4 5\"): expected 2 columns, found 3"
+        );
+        let err = parse_columns_str::<u32, 2>("1 2\n3").unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+        let err = parse_columns_transposed_str::<u32, 2>("1 2\n3\tx").unwrap_err();
+        assert!(matches!(
+            err,
+            AocError::Parse {
+                line: 2,
+                column: Some(3),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_sections() {
+        let parse = |section: &str| -> Result<String, Box<dyn Error>> { Ok(section.into()) };
+        assert_eq!(
+            parse_sections_str("\na\nb\n  \n\nc\r\n\r\nd\n\n", parse).unwrap(),
+            vec!["a\nb", "c", "d"]
+        );
+        assert!(parse_sections_str("", parse).unwrap().is_empty());
+
+        // A plain error is placed at the section's first line
+        let err = parse_sections_str("a\n\nb\nc", |section| {
+            if section.starts_with('b') {
+                Err("bad section".into())
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(err.unwrap_err().to_string(), "line 3 (\"b\"): bad section");
+
+        let path = create_test_file("sections_fn", "1\n2\n\n3");
+        let sums = parse_sections(&path, |section| {
+            Ok(parse_lines_str::<u32>(section)?.iter().sum::<u32>())
+        });
+        assert_eq!(sums.unwrap(), vec![3, 3]);
+        clean_up_test_file(&path);
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let record = parse_record("ecl:gry pid:860033327 eyr:2020\nhcl:#fffffd").unwrap();
+        assert_eq!(record.len(), 4);
+        assert_eq!(record["hcl"], "#fffffd");
+
+        let record = parse_record("Time: 12:30\nkey=\nname = A B").unwrap();
+        assert_eq!(record["Time"], "12:30");
+        assert_eq!(record["key"], "");
+        assert_eq!(record["name"], "A B");
+
+        let err = parse_record("a:1\nno separator").unwrap_err();
+        assert!(matches!(err, AocError::Parse { line: 2, .. }));
+        let err = parse_record("a:1 b:2\nb=3").unwrap_err();
+        assert_eq!(err.to_string(), "line 2 (\"b=3\"): duplicate key 'b'");
+        let err = parse_record("a:1 b:2 stray").unwrap_err();
+        assert!(err.to_string().ends_with("expected a separator in 'stray'"));
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let input = "ecl:gry pid:1\nhgt:183cm\n\niyr:2013\n\nbad\n";
+        let err = parse_records_str(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 6 (\"bad\"): expected 'key: value' or 'key=value'"
+        );
+
+        let path = create_test_file("records", &input.replace("bad\n", "cid=7"));
+        let records = parse_records(&path).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["hgt"], "183cm");
+        assert_eq!(records[2]["cid"], "7");
         clean_up_test_file(&path);
     }
 
     #[test]
     fn test_nonexistent_file() {
         let result: Result<Vec<String>, _> = parse_lines("nonexistent_file.txt");
-        assert!(result.is_err());
+        assert!(matches!(result, Err(AocError::Io(_))));
     }
 }
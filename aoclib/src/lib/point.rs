@@ -0,0 +1,290 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A point (or offset) on a 2D integer grid.
+///
+/// `x` grows to the right and `y` grows downwards, matching the row/column layout of
+/// puzzle inputs: the character at column `c` of line `r` is at `Point2 { x: c, y: r }`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{Direction, Point2};
+///
+/// let p = Point2::new(3, 4);
+/// assert_eq!(p + Direction::Up.delta(), Point2::new(3, 3));
+/// assert_eq!(p.manhattan(Point2::ORIGIN), 7);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2 {
+    /// Column, growing to the right.
+    pub x: isize,
+    /// Row, growing downwards.
+    pub y: isize,
+}
+
+impl Point2 {
+    /// The point `(0, 0)`.
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    /// Creates a point from its column and row.
+    pub const fn new(x: isize, y: isize) -> Self {
+        Point2 { x, y }
+    }
+
+    /// Returns the neighbouring point one step in `dir`.
+    pub fn step(self, dir: Direction) -> Self {
+        self + dir.delta()
+    }
+
+    /// Returns the Manhattan (taxicab) distance to `other`.
+    pub fn manhattan(self, other: Point2) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Iterates over the four orthogonal neighbours, in the order of [`Direction::ALL`].
+    pub fn neighbors4(self) -> impl Iterator<Item = Point2> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+
+    /// Iterates over the eight neighbours including diagonals, row by row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::Point2;
+    ///
+    /// let p = Point2::new(5, 5);
+    /// assert_eq!(p.neighbors8().count(), 8);
+    /// assert_eq!(p.neighbors8().next(), Some(Point2::new(4, 4)));
+    /// ```
+    pub fn neighbors8(self) -> impl Iterator<Item = Point2> {
+        NEIGHBOR_OFFSETS.into_iter().map(move |d| self + d)
+    }
+
+    /// Iterates over the eight neighbours that lie inside a `width` x `height` area
+    /// with its top-left corner at the origin, such as a grid or puzzle input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::Point2;
+    ///
+    /// // A corner cell has three neighbours
+    /// assert_eq!(Point2::ORIGIN.neighbors_within(10, 10).count(), 3);
+    /// ```
+    pub fn neighbors_within(self, width: usize, height: usize) -> impl Iterator<Item = Point2> {
+        self.neighbors8().filter(move |p| {
+            p.x >= 0 && p.y >= 0 && (p.x as usize) < width && (p.y as usize) < height
+        })
+    }
+}
+
+/// Offsets of the eight neighbours of a point, row by row.
+const NEIGHBOR_OFFSETS: [Point2; 8] = [
+    Point2::new(-1, -1),
+    Point2::new(0, -1),
+    Point2::new(1, -1),
+    Point2::new(-1, 0),
+    Point2::new(1, 0),
+    Point2::new(-1, 1),
+    Point2::new(0, 1),
+    Point2::new(1, 1),
+];
+
+impl From<(isize, isize)> for Point2 {
+    fn from((x, y): (isize, isize)) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl Add for Point2 {
+    type Output = Point2;
+
+    fn add(self, other: Point2) -> Point2 {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point2 {
+    fn add_assign(&mut self, other: Point2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Point2;
+
+    fn sub(self, other: Point2) -> Point2 {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point2 {
+    fn sub_assign(&mut self, other: Point2) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<isize> for Point2 {
+    type Output = Point2;
+
+    fn mul(self, k: isize) -> Point2 {
+        Point2::new(self.x * k, self.y * k)
+    }
+}
+
+impl Neg for Point2 {
+    type Output = Point2;
+
+    fn neg(self) -> Point2 {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+/// One of the four orthogonal directions on a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    /// Towards smaller `y`.
+    Up,
+    /// Towards larger `x`.
+    Right,
+    /// Towards larger `y`.
+    Down,
+    /// Towards smaller `x`.
+    Left,
+}
+
+impl Direction {
+    /// All directions in clockwise order, starting with `Up`.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Returns the unit offset of one step in this direction.
+    pub fn delta(self) -> Point2 {
+        match self {
+            Direction::Up => Point2::new(0, -1),
+            Direction::Right => Point2::new(1, 0),
+            Direction::Down => Point2::new(0, 1),
+            Direction::Left => Point2::new(-1, 0),
+        }
+    }
+
+    /// Returns the direction after a 90° clockwise turn.
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    /// Returns the direction after a 90° counter-clockwise turn.
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self.index() + 3) % 4]
+    }
+
+    /// Returns the opposite direction.
+    pub fn reverse(self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+
+    /// Returns the position of this direction in [`Direction::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Parses a direction from `^>v<`, `URDL` or `NESW`.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            '^' | 'U' | 'N' => Some(Direction::Up),
+            '>' | 'R' | 'E' => Some(Direction::Right),
+            'v' | 'D' | 'S' => Some(Direction::Down),
+            '<' | 'L' | 'W' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point2::new(1, 2);
+        let b = Point2::new(3, -4);
+        assert_eq!(a + b, Point2::new(4, -2));
+        assert_eq!(a - b, Point2::new(-2, 6));
+        assert_eq!(b * 2, Point2::new(6, -8));
+        assert_eq!(-a, Point2::new(-1, -2));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn test_point_manhattan() {
+        assert_eq!(Point2::new(-3, 4).manhattan(Point2::new(2, -1)), 10);
+        assert_eq!(Point2::ORIGIN.manhattan(Point2::ORIGIN), 0);
+    }
+
+    #[test]
+    fn test_point_from_tuple() {
+        assert_eq!(Point2::from((5, 6)), Point2::new(5, 6));
+    }
+
+    #[test]
+    fn test_direction_turns() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Down.reverse(), Direction::Up);
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.delta() + dir.reverse().delta(), Point2::ORIGIN);
+        }
+    }
+
+    #[test]
+    fn test_direction_step() {
+        let p = Point2::new(0, 0);
+        assert_eq!(p.step(Direction::Down), Point2::new(0, 1));
+        assert_eq!(p.step(Direction::Left), Point2::new(-1, 0));
+    }
+
+    #[test]
+    fn test_direction_from_char() {
+        assert_eq!(Direction::from_char('^'), Some(Direction::Up));
+        assert_eq!(Direction::from_char('E'), Some(Direction::Right));
+        assert_eq!(Direction::from_char('v'), Some(Direction::Down));
+        assert_eq!(Direction::from_char('L'), Some(Direction::Left));
+        assert_eq!(Direction::from_char('x'), None);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Point2::new(1, 1);
+        let four: Vec<Point2> = p.neighbors4().collect();
+        assert_eq!(
+            four,
+            vec![
+                Point2::new(1, 0),
+                Point2::new(2, 1),
+                Point2::new(1, 2),
+                Point2::new(0, 1)
+            ]
+        );
+        let eight: Vec<Point2> = p.neighbors8().collect();
+        assert_eq!(eight.len(), 8);
+        assert!(four.iter().all(|q| eight.contains(q)));
+        assert!(!eight.contains(&p));
+        assert!(eight
+            .iter()
+            .all(|q| q.x.abs_diff(1) <= 1 && q.y.abs_diff(1) <= 1));
+        assert_eq!(p.neighbors_within(3, 3).count(), 8);
+        assert_eq!(p.neighbors_within(2, 3).count(), 5);
+        assert_eq!(Point2::new(-5, 0).neighbors_within(3, 3).count(), 0);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::Answer;
+use std::error::Error;
+
+/// A transformation applied to every answer after solving and before printing.
+///
+/// [`run`](crate::run) picks one from the command line with [`from_args`], so a
+/// solver never needs to know how its answers end up being shown.
+pub trait PostProcessor {
+    /// Returns the answer to print in place of `answer`.
+    fn process(&self, answer: Answer) -> Answer;
+}
+
+/// Prints answers unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Raw;
+
+impl PostProcessor for Raw {
+    fn process(&self, answer: Answer) -> Answer {
+        answer
+    }
+}
+
+/// Replaces each answer with the SHA-256 digest of a salt and the answer, in hex.
+///
+/// This is for sharing runs publicly without spoiling the answers: the same salt and
+/// answer always give the same digest, so two people can check they agree, while the
+/// digest gives nothing away. Most answers are small numbers that could be found by
+/// hashing candidates, so the salt only protects them while it stays private.
+///
+/// Unsolved parts stay unsolved, and grids are hashed as their rows joined by `\n`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::postprocess::{PostProcessor, SaltedHash};
+/// use aoclib::Answer;
+///
+/// let hash = SaltedHash::new("pepper");
+/// let digest = hash.process(Answer::Number(42));
+/// assert_eq!(digest, hash.process(Answer::Text("42".into())));
+/// assert_ne!(digest, SaltedHash::new("salt").process(Answer::Number(42)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SaltedHash {
+    salt: String,
+}
+
+impl SaltedHash {
+    /// Creates a post-processor hashing answers with `salt`.
+    pub fn new(salt: &str) -> Self {
+        SaltedHash {
+            salt: salt.to_string(),
+        }
+    }
+}
+
+impl PostProcessor for SaltedHash {
+    fn process(&self, answer: Answer) -> Answer {
+        let value = match answer {
+            Answer::Number(n) => n.to_string(),
+            Answer::Text(s) => s,
+            Answer::Grid(rows) => rows.join("\n"),
+            Answer::Unsolved => return Answer::Unsolved,
+        };
+        let digest = sha256(format!("{}:{}", self.salt, value).as_bytes());
+        Answer::Text(digest.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+}
+
+/// Chooses the post-processor requested on the command line: [`SaltedHash`] with
+/// `--salted-hash SALT`, otherwise [`Raw`].
+///
+/// # Errors
+///
+/// Returns an error if `--salted-hash` is not followed by a salt.
+pub fn from_args(args: &[String]) -> Result<Box<dyn PostProcessor>, Box<dyn Error>> {
+    let Some(i) = args.iter().position(|arg| arg == "--salted-hash") else {
+        return Ok(Box::new(Raw));
+    };
+    match args.get(i + 1).filter(|salt| !salt.starts_with("--")) {
+        Some(salt) => Ok(Box::new(SaltedHash::new(salt))),
+        None => Err("Option '--salted-hash' requires a salt".into()),
+    }
+}
+
+/// Round constants: the first 32 bits of the fractional parts of the cube roots of the
+/// first 64 primes.
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Computes the SHA-256 digest of `data` (FIPS 180-4).
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::postprocess::sha256;
+///
+/// assert_eq!(sha256(b"abc")[..4], [0xba, 0x78, 0x16, 0xbf]);
+/// ```
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    // Pad with a 1 bit, zeros up to 56 bytes mod 64, then the length in bits
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (h, g, f, e) = (g, f, e, d.wrapping_add(t1));
+            (d, c, b, a) = (c, b, a, t1.wrapping_add(t2));
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (chunk, s) in digest.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&s.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(digest: [u8; 32]) -> String {
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            hex(sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // 56 bytes, so the padding spills into a second block
+        assert_eq!(
+            hex(sha256(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn test_salted_hash() {
+        let hash = SaltedHash::new("s");
+        assert_eq!(
+            hash.process(Answer::Number(7)),
+            Answer::Text(hex(sha256(b"s:7")))
+        );
+        assert_eq!(
+            hash.process(Answer::Grid(vec!["#.".into(), ".#".into()])),
+            Answer::Text(hex(sha256(b"s:#.\n.#")))
+        );
+        assert_eq!(hash.process(Answer::Unsolved), Answer::Unsolved);
+        assert_eq!(Raw.process(Answer::Number(7)), Answer::Number(7));
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = |s: &str| -> Vec<String> { s.split_whitespace().map(str::to_string).collect() };
+        let answer = || Answer::Number(1);
+        assert_eq!(
+            from_args(&args("--json")).unwrap().process(answer()),
+            answer()
+        );
+        assert_eq!(
+            from_args(&args("--salted-hash s --json"))
+                .unwrap()
+                .process(answer()),
+            SaltedHash::new("s").process(answer())
+        );
+        assert!(from_args(&args("--salted-hash")).is_err());
+        assert!(from_args(&args("--salted-hash --json")).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::fmt::{duration, thousands};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Width of the bar itself, in characters.
+const BAR_WIDTH: usize = 30;
+
+/// A progress bar for long brute-force loops, redrawn in place on one terminal line.
+///
+/// The count is atomic, so threads splitting a loop can share one `&Progress` and
+/// each call [`Progress::inc`]. Redrawing is throttled to a few times a second and
+/// skipped while another thread is drawing, so incrementing stays cheap enough to
+/// call every few thousand iterations. A message after the bar can say which part of
+/// the work is underway, e.g. `range 3/12`.
+///
+/// The bar goes to stderr by default, so it never mixes with the answers on stdout.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::progress::Progress;
+///
+/// let progress = Progress::new("scan", 1_000_000);
+/// for chunk in 0..100 {
+///     // ... 10,000 iterations of work ...
+///     progress.inc(10_000);
+/// }
+/// progress.finish();
+/// assert_eq!(progress.done(), 1_000_000);
+/// ```
+pub struct Progress {
+    label: String,
+    total: u64,
+    done: AtomicU64,
+    started: Instant,
+    interval: Duration,
+    display: Mutex<Display>,
+}
+
+struct Display {
+    out: Box<dyn Write + Send>,
+    message: String,
+    last_draw: Option<Instant>,
+}
+
+impl Progress {
+    /// Starts a bar labelled `label` for `total` units of work, drawn on stderr.
+    pub fn new(label: &str, total: u64) -> Self {
+        Progress::with_writer(label, total, io::stderr())
+    }
+
+    /// Starts a bar that draws to `out` instead of stderr.
+    pub fn with_writer<W: Write + Send + 'static>(label: &str, total: u64, out: W) -> Self {
+        Progress {
+            label: label.to_string(),
+            total,
+            done: AtomicU64::new(0),
+            started: Instant::now(),
+            interval: Duration::from_millis(100),
+            display: Mutex::new(Display {
+                out: Box::new(out),
+                message: String::new(),
+                last_draw: None,
+            }),
+        }
+    }
+
+    /// Returns the units of work done so far.
+    pub fn done(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    /// Records `n` more units of work, redrawing if the bar is due.
+    pub fn inc(&self, n: u64) {
+        self.done.fetch_add(n, Ordering::Relaxed);
+        // Another thread drawing means the bar is fresh enough already
+        if let Ok(mut display) = self.display.try_lock() {
+            if display
+                .last_draw
+                .is_none_or(|last| last.elapsed() >= self.interval)
+            {
+                self.draw(&mut display);
+            }
+        }
+    }
+
+    /// Replaces the text shown after the bar from the next redraw on, so it can be
+    /// updated as often as [`Progress::inc`] is called.
+    pub fn set_message(&self, message: &str) {
+        let mut display = self.display.lock().unwrap_or_else(|e| e.into_inner());
+        display.message.clear();
+        display.message.push_str(message);
+    }
+
+    /// Draws the bar one last time and moves to the next line.
+    pub fn finish(&self) {
+        let mut display = self.display.lock().unwrap_or_else(|e| e.into_inner());
+        self.draw(&mut display);
+        let _ = writeln!(display.out);
+    }
+
+    /// Returns the current line: label, bar, percentage, counts, elapsed time and
+    /// message.
+    pub fn line(&self) -> String {
+        let display = self.display.lock().unwrap_or_else(|e| e.into_inner());
+        self.format(&display.message)
+    }
+
+    fn format(&self, message: &str) -> String {
+        let done = self.done().min(self.total);
+        let percent = if self.total == 0 {
+            100
+        } else {
+            done as u128 * 100 / self.total as u128
+        };
+        let mut line = format!(
+            "{} [{}] {:>3}% {}/{} {}",
+            self.label,
+            bar(done, self.total, BAR_WIDTH),
+            percent,
+            thousands(done),
+            thousands(self.total),
+            duration(self.started.elapsed())
+        );
+        if !message.is_empty() {
+            line.push_str("  ");
+            line.push_str(message);
+        }
+        line
+    }
+
+    fn draw(&self, display: &mut Display) {
+        let line = self.format(&display.message);
+        // Progress output is best effort; a closed stderr must not stop the work
+        let _ = write!(display.out, "\r{}\x1b[K", line);
+        let _ = display.out.flush();
+        display.last_draw = Some(Instant::now());
+    }
+}
+
+/// Draws a bar `width` characters wide, filled in proportion to `done` out of `total`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::progress::bar;
+///
+/// assert_eq!(bar(1, 4, 8), "##......");
+/// assert_eq!(bar(5, 4, 4), "####");
+/// ```
+pub fn bar(done: u64, total: u64, width: usize) -> String {
+    let filled = if total == 0 {
+        width
+    } else {
+        (done.min(total) as u128 * width as u128 / total as u128) as usize
+    };
+    format!("{}{}", "#".repeat(filled), ".".repeat(width - filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// A writer whose output the test can read back.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_bar() {
+        assert_eq!(bar(0, 10, 5), ".....");
+        assert_eq!(bar(10, 10, 5), "#####");
+        assert_eq!(bar(7, 10, 10), "#######...");
+        assert_eq!(bar(0, 0, 3), "###");
+        assert_eq!(bar(u64::MAX / 2, u64::MAX, 4), "#...");
+    }
+
+    #[test]
+    fn test_line() {
+        let progress = Progress::with_writer("scan", 2_000, io::sink());
+        progress.inc(500);
+        let line = progress.line();
+        assert!(line.starts_with(&format!(
+            "scan [{}{}]  25% 500/2,000 ",
+            "#".repeat(7),
+            ".".repeat(23)
+        )));
+        progress.set_message("range 2/3");
+        assert!(progress.line().ends_with("  range 2/3"));
+        progress.inc(5_000);
+        assert!(progress.line().contains(" 100% 2,000/2,000 "));
+    }
+
+    #[test]
+    fn test_draws_throttled_and_finishes() {
+        let out = Captured::default();
+        let progress = Progress::with_writer("work", 100, out.clone());
+        for _ in 0..100 {
+            progress.inc(1);
+        }
+        // The first increment draws; the rest come within the redraw interval
+        assert_eq!(out.text().matches('\r').count(), 1);
+        progress.finish();
+        let text = out.text();
+        assert_eq!(text.matches('\r').count(), 2);
+        assert!(text.ends_with("\x1b[K\n"));
+        assert!(text.contains(" 100% 100/100 "));
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let progress = Progress::with_writer("threads", 40_000, io::sink());
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..10_000 {
+                        progress.inc(1);
+                    }
+                });
+            }
+        });
+        assert_eq!(progress.done(), 40_000);
+    }
+}
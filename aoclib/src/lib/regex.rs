@@ -0,0 +1,772 @@
+use crate::{parse_lines_with_str, read_input, AocError};
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+// The workspace has no external dependencies, so rather than the `regex` crate this is
+// a small backtracking engine. It covers the syntax puzzle lines need, and since it
+// backtracks, it is meant for line-sized text rather than whole files.
+
+/// A compiled regular expression.
+///
+/// Supported syntax:
+///
+/// * literals, `.` (any character), `^` and `$` (start and end of the text)
+/// * classes such as `[a-z_]` and `[^,]`, and `\d`, `\w`, `\s` with their negations
+///   `\D`, `\W`, `\S`
+/// * groups: `(...)` captures, `(?:...)` does not; alternation with `|`
+/// * quantifiers `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}`, made lazy with a trailing `?`
+/// * `\` before any other character matches it literally
+///
+/// A repeated character or class, such as `\d+` or `.*`, is matched in a loop and can
+/// run over lines of any length. A repeated group recurses once per repetition, so
+/// keep those to the handful of repetitions a puzzle line holds.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::regex::Regex;
+///
+/// let re = Regex::new(r"^Game (\d+): (.*)$").unwrap();
+/// let caps = re.captures("Game 3: 4 red, 2 blue").unwrap();
+/// assert_eq!(caps.get(1), Some("3"));
+/// assert_eq!(caps.get(2), Some("4 red, 2 blue"));
+/// assert!(re.captures("Round 3: 4 red").is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Regex {
+    pattern: String,
+    node: Node,
+    groups: usize,
+}
+
+/// The capture groups of a match, returned by [`Regex::captures`].
+///
+/// Group 0 is the whole match and the groups of the pattern are numbered from 1 in the
+/// order of their opening parentheses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captures<'t> {
+    text: &'t str,
+    spans: Spans,
+}
+
+impl<'t> Captures<'t> {
+    /// Returns the text group `i` matched, or `None` if it did not take part in the
+    /// match or does not exist.
+    pub fn get(&self, i: usize) -> Option<&'t str> {
+        let (start, end) = (*self.spans.get(i)?)?;
+        Some(&self.text[start..end])
+    }
+
+    /// Parses the text group `i` matched into type `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the group did not match or its text cannot be parsed.
+    pub fn parse<T>(&self, i: usize) -> Result<T, Box<dyn Error>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let text = self
+            .get(i)
+            .ok_or_else(|| format!("group {} did not match", i))?;
+        text.parse()
+            .map_err(|e| format!("cannot parse group {} '{}': {}", i, text, e).into())
+    }
+
+    /// Returns the number of groups, including group 0.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns `true` if there are no groups, which never happens for a match.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+/// Types that can be built from the capture groups of a regex match, for
+/// [`parse_lines_regex`].
+///
+/// Tuples of up to four [`FromStr`] types are built from groups 1, 2, ... in order.
+pub trait FromCaptures: Sized {
+    /// Builds a value from the groups of one match.
+    fn from_captures(caps: &Captures) -> Result<Self, Box<dyn Error>>;
+}
+
+macro_rules! tuple_from_captures {
+    ($($t:ident $i:tt),+) => {
+        impl<$($t),+> FromCaptures for ($($t,)+)
+        where
+            $($t: FromStr, $t::Err: fmt::Display,)+
+        {
+            fn from_captures(caps: &Captures) -> Result<Self, Box<dyn Error>> {
+                Ok(($(caps.parse::<$t>($i + 1)?,)+))
+            }
+        }
+    };
+}
+
+tuple_from_captures!(A 0);
+tuple_from_captures!(A 0, B 1);
+tuple_from_captures!(A 0, B 1, C 2);
+tuple_from_captures!(A 0, B 1, C 2, D 3);
+
+/// Parses a file line by line by matching each line against `pattern` and building a
+/// `T` from the capture groups.
+///
+/// The pattern may match anywhere in a line; anchor it with `^...$` to match whole
+/// lines. See [`Regex`] for the supported syntax. Only available with the `regex`
+/// feature enabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoclib::parse_lines_regex;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // move 1 from 2 to 1
+/// let moves: Vec<(usize, usize, usize)> =
+///     parse_lines_regex("input.txt", r"^move (\d+) from (\d+) to (\d+)$")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Custom`] if the pattern is invalid, an [`AocError::Io`] if the
+/// file cannot be read, or an [`AocError::Parse`] for the first line that does not
+/// match or whose groups cannot be converted.
+pub fn parse_lines_regex<T, P>(path: P, pattern: &str) -> Result<Vec<T>, AocError>
+where
+    T: FromCaptures,
+    P: AsRef<Path>,
+{
+    parse_lines_regex_str(&read_input(path)?, pattern)
+}
+
+/// Parses each line of `input` by matching it against `pattern`, like
+/// [`parse_lines_regex`] does for a file.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::parse_lines_regex_str;
+///
+/// let input = "p=0,4 v=3,-3\np=6,3 v=-1,-3";
+/// let robots: Vec<(i32, i32, i32, i32)> =
+///     parse_lines_regex_str(input, r"p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)").unwrap();
+/// assert_eq!(robots[1], (6, 3, -1, -3));
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`AocError::Custom`] if the pattern is invalid, or an
+/// [`AocError::Parse`] for the first line that does not match or whose groups cannot
+/// be converted.
+pub fn parse_lines_regex_str<T: FromCaptures>(
+    input: &str,
+    pattern: &str,
+) -> Result<Vec<T>, AocError> {
+    let regex = Regex::new(pattern).map_err(|e| AocError::Custom(e.into()))?;
+    parse_lines_with_str(input, |line| {
+        let caps = regex
+            .captures(line)
+            .ok_or_else(|| format!("does not match '{}'", regex.pattern))?;
+        T::from_captures(&caps)
+    })
+}
+
+/// The byte range each group matched, indexed by group number.
+type Spans = Vec<Option<(usize, usize)>>;
+
+/// What to match after a node, given the position it ended at; `false` backtracks.
+type Next<'a> = dyn FnMut(usize, &mut Spans) -> bool + 'a;
+
+/// A node of a parsed pattern.
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    Group(Box<Node>, Option<usize>),
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+impl Node {
+    /// Whether a node that matches a single character accepts `c`; `false` for any
+    /// other node.
+    fn accepts(&self, c: char) -> bool {
+        match self {
+            Node::Char(expected) => c == *expected,
+            Node::Any => true,
+            Node::Class(class) => class.contains(c),
+            _ => false,
+        }
+    }
+}
+
+/// A set of characters, such as `[a-z]` or `\d`.
+#[derive(Debug, Clone)]
+struct Class {
+    ranges: Vec<(char, char)>,
+    negated: bool,
+}
+
+impl Class {
+    fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != self.negated
+    }
+
+    /// Returns the class for `\d`, `\w` or `\s` (or their uppercase negations).
+    fn escape(c: char) -> Option<Class> {
+        let ranges = match c.to_ascii_lowercase() {
+            'd' => vec![('0', '9')],
+            'w' => vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+            's' => vec![(' ', ' '), ('\t', '\r')],
+            _ => return None,
+        };
+        Some(Class {
+            ranges,
+            negated: c.is_ascii_uppercase(),
+        })
+    }
+}
+
+impl Regex {
+    /// Compiles `pattern`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the problem if the pattern is malformed, for example
+    /// with an unclosed group or a quantifier with nothing to repeat.
+    pub fn new(pattern: &str) -> Result<Regex, String> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            groups: 0,
+        };
+        let node = parser.alternation()?;
+        if parser.pos < parser.chars.len() {
+            return Err(format!("Unmatched ')' in pattern '{}'", pattern));
+        }
+        Ok(Regex {
+            pattern: pattern.to_string(),
+            node,
+            groups: parser.groups,
+        })
+    }
+
+    /// Returns whether the pattern matches anywhere in `text`.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.captures(text).is_some()
+    }
+
+    /// Finds the leftmost match in `text` and returns its capture groups.
+    pub fn captures<'t>(&self, text: &'t str) -> Option<Captures<'t>> {
+        let starts = text.char_indices().map(|(i, _)| i).chain([text.len()]);
+        for start in starts {
+            let mut spans = vec![None; self.groups + 1];
+            let mut end = None;
+            let matched = self.matches(&self.node, text, start, &mut spans, &mut |pos, _| {
+                end = Some(pos);
+                true
+            });
+            if matched {
+                spans[0] = Some((start, end?));
+                return Some(Captures { text, spans });
+            }
+        }
+        None
+    }
+
+    /// Matches `node` at byte `pos` of `text`, then calls `next` with the position
+    /// after it, backtracking into `node` for as long as `next` fails.
+    fn matches(
+        &self,
+        node: &Node,
+        text: &str,
+        pos: usize,
+        spans: &mut Spans,
+        next: &mut Next,
+    ) -> bool {
+        match node {
+            Node::Char(_) | Node::Any | Node::Class(_) => text[pos..]
+                .chars()
+                .next()
+                .filter(|&c| node.accepts(c))
+                .is_some_and(|c| next(pos + c.len_utf8(), spans)),
+            Node::Start => pos == 0 && next(pos, spans),
+            Node::End => pos == text.len() && next(pos, spans),
+            Node::Group(inner, None) => self.matches(inner, text, pos, spans, next),
+            Node::Group(inner, Some(i)) => {
+                let i = *i;
+                self.matches(inner, text, pos, spans, &mut |end, spans| {
+                    let saved = spans[i].replace((pos, end));
+                    next(end, spans) || {
+                        spans[i] = saved;
+                        false
+                    }
+                })
+            }
+            Node::Concat(nodes) => self.sequence(nodes, text, pos, spans, next),
+            Node::Alternation(options) => options
+                .iter()
+                .any(|option| self.matches(option, text, pos, spans, next)),
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+            } => self.repeat(node, (*min, *max, *greedy), 0, text, pos, spans, next),
+        }
+    }
+
+    fn sequence(
+        &self,
+        nodes: &[Node],
+        text: &str,
+        pos: usize,
+        spans: &mut Spans,
+        next: &mut Next,
+    ) -> bool {
+        match nodes.split_first() {
+            None => next(pos, spans),
+            Some((first, rest)) => self.matches(first, text, pos, spans, &mut |p, spans| {
+                self.sequence(rest, text, p, spans, next)
+            }),
+        }
+    }
+
+    /// Matches `node` repeatedly, having matched it `count` times so far.
+    #[allow(clippy::too_many_arguments)]
+    fn repeat(
+        &self,
+        node: &Node,
+        (min, max, greedy): (usize, Option<usize>, bool),
+        count: usize,
+        text: &str,
+        pos: usize,
+        spans: &mut Spans,
+        next: &mut Next,
+    ) -> bool {
+        if matches!(node, Node::Char(_) | Node::Any | Node::Class(_)) {
+            // Single characters are repeated in a loop, so long runs such as `\d*` over
+            // a whole line take no stack
+            let mut ends = vec![pos];
+            for c in text[pos..].chars() {
+                if max.is_some_and(|max| ends.len() > max) || !node.accepts(c) {
+                    break;
+                }
+                ends.push(ends[ends.len() - 1] + c.len_utf8());
+            }
+            let candidates = ends.get(min..).unwrap_or_default();
+            return if greedy {
+                candidates.iter().rev().any(|&end| next(end, spans))
+            } else {
+                candidates.iter().any(|&end| next(end, spans))
+            };
+        }
+        let more = |spans: &mut Spans, next: &mut Next| {
+            max.is_none_or(|max| count < max)
+                && self.matches(node, text, pos, spans, &mut |p, spans| {
+                    // An empty match could repeat forever, so once min is reached it ends
+                    // the repeat, as in Perl and Python
+                    if p == pos && count >= min {
+                        next(p, spans)
+                    } else {
+                        self.repeat(node, (min, max, greedy), count + 1, text, p, spans, next)
+                    }
+                })
+        };
+        if count < min {
+            return more(spans, next);
+        }
+        // Greedy repeats try one more first, lazy ones try stopping first
+        if greedy && more(spans, next) {
+            return true;
+        }
+        next(pos, spans) || (!greedy && more(spans, next))
+    }
+}
+
+impl fmt::Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+/// A recursive-descent parser for patterns.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn error(&self, message: &str) -> String {
+        let pattern: String = self.chars.iter().collect();
+        format!(
+            "{} at position {} of pattern '{}'",
+            message, self.pos, pattern
+        )
+    }
+
+    fn alternation(&mut self) -> Result<Node, String> {
+        let mut options = vec![self.concat()?];
+        while self.eat('|') {
+            options.push(self.concat()?);
+        }
+        Ok(if options.len() == 1 {
+            options.pop().unwrap_or(Node::Concat(Vec::new()))
+        } else {
+            Node::Alternation(options)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, String> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        let Some(c) = self.peek() else {
+            return Err(self.error("Unexpected end"));
+        };
+        self.pos += 1;
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '(' => {
+                let index = if self.eat('?') {
+                    if !self.eat(':') {
+                        return Err(self.error("Only (?:...) groups are supported"));
+                    }
+                    None
+                } else {
+                    self.groups += 1;
+                    Some(self.groups)
+                };
+                let inner = self.alternation()?;
+                if !self.eat(')') {
+                    return Err(self.error("Unclosed group"));
+                }
+                Node::Group(Box::new(inner), index)
+            }
+            '[' => Node::Class(self.class()?),
+            '\\' => {
+                let escaped = self.escaped()?;
+                match Class::escape(escaped) {
+                    Some(class) => Node::Class(class),
+                    None => Node::Char(escaped),
+                }
+            }
+            '*' | '+' | '?' | '{' => {
+                self.pos -= 1;
+                return Err(self.error("Nothing to repeat"));
+            }
+            c => Node::Char(c),
+        })
+    }
+
+    fn escaped(&mut self) -> Result<char, String> {
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error("Trailing backslash"))?;
+        self.pos += 1;
+        Ok(match c {
+            'n' => '\n',
+            't' => '\t',
+            c => c,
+        })
+    }
+
+    fn class(&mut self) -> Result<Class, String> {
+        let negated = self.eat('^');
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("Unclosed character class"));
+            };
+            self.pos += 1;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let lo = if c == '\\' {
+                let escaped = self.escaped()?;
+                if let Some(class) = Class::escape(escaped) {
+                    if class.negated {
+                        return Err(self.error("Negated classes are not supported inside []"));
+                    }
+                    ranges.extend(class.ranges);
+                    continue;
+                }
+                escaped
+            } else {
+                c
+            };
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&c| c != ']') {
+                self.pos += 1;
+                let hi = match self.peek() {
+                    Some('\\') => {
+                        self.pos += 1;
+                        self.escaped()?
+                    }
+                    Some(c) => {
+                        self.pos += 1;
+                        c
+                    }
+                    None => return Err(self.error("Unclosed character class")),
+                };
+                if hi < lo {
+                    return Err(self.error("Invalid range"));
+                }
+                ranges.push((lo, hi));
+            } else {
+                ranges.push((lo, lo));
+            }
+        }
+        Ok(Class { ranges, negated })
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+                return self.counted(atom);
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        let greedy = !self.eat('?');
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+        })
+    }
+
+    /// Parses the rest of a `{n}`, `{n,}` or `{n,m}` quantifier.
+    fn counted(&mut self, atom: Node) -> Result<Node, String> {
+        let number = |parser: &mut Parser| -> Option<usize> {
+            let start = parser.pos;
+            while parser.peek().is_some_and(|c| c.is_ascii_digit()) {
+                parser.pos += 1;
+            }
+            parser.chars[start..parser.pos]
+                .iter()
+                .collect::<String>()
+                .parse()
+                .ok()
+        };
+        let min = number(self).ok_or_else(|| self.error("Expected a count"))?;
+        let max = if self.eat(',') {
+            number(self)
+        } else {
+            Some(min)
+        };
+        if !self.eat('}') {
+            return Err(self.error("Unclosed {}"));
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err(self.error("Invalid count range"));
+        }
+        let greedy = !self.eat('?');
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'t>(pattern: &str, text: &'t str) -> Option<&'t str> {
+        Regex::new(pattern).unwrap().captures(text)?.get(0)
+    }
+
+    #[test]
+    fn test_literals_and_anchors() {
+        assert_eq!(find("bc", "abcd"), Some("bc"));
+        assert_eq!(find("^bc", "abcd"), None);
+        assert_eq!(find("^ab", "abcd"), Some("ab"));
+        assert_eq!(find("d$", "abcd"), Some("d"));
+        assert_eq!(find("a.c", "a-c"), Some("a-c"));
+        assert_eq!(find(r"a\.c", "abc a.c"), Some("a.c"));
+        assert_eq!(find("", "abc"), Some(""));
+        assert_eq!(find("é+", "caféé!"), Some("éé"));
+    }
+
+    #[test]
+    fn test_classes() {
+        assert_eq!(find(r"\d+", "abc 123 45"), Some("123"));
+        assert_eq!(find(r"\w+", "  foo_1 bar"), Some("foo_1"));
+        assert_eq!(find(r"\S+", "  foo bar"), Some("foo"));
+        assert_eq!(find("[a-c]+", "xxbcaz"), Some("bca"));
+        assert_eq!(find("[^, ]+", ", , abc,"), Some("abc"));
+        assert_eq!(find(r"[-+\d]+", "x -12+3"), Some("-12+3"));
+        assert_eq!(find("[]a]+", "x]a]"), Some("]a]"));
+        assert_eq!(find("[a-]+", "x-a-"), Some("-a-"));
+    }
+
+    #[test]
+    fn test_quantifiers() {
+        assert_eq!(find("ab*c", "ac abbc"), Some("ac"));
+        assert_eq!(find("ab+c", "ac abbc"), Some("abbc"));
+        assert_eq!(find("colou?r", "color"), Some("color"));
+        assert_eq!(find(r"\d{2}", "1 234"), Some("23"));
+        assert_eq!(find(r"\d{2,}", "1 2345"), Some("2345"));
+        assert_eq!(find(r"\d{1,2}", "12345"), Some("12"));
+        assert_eq!(find("<.+>", "<a><b>"), Some("<a><b>"));
+        assert_eq!(find("<.+?>", "<a><b>"), Some("<a>"));
+        assert_eq!(find("(a*)*b", "aaab"), Some("aaab"));
+        assert_eq!(find("(a|)*$", "aa"), Some("aa"));
+
+        // An empty iteration is taken once and ends the repeat
+        assert_eq!(find("^(?:([^a]*|.+?)){0,2}", "a1"), Some(""));
+        let caps = Regex::new("(a*)+b").unwrap().captures("aab").unwrap();
+        assert_eq!((caps.get(0), caps.get(1)), (Some("aab"), Some("")));
+        assert_eq!(find("(a*){2,}b", "b"), Some("b"));
+        assert_eq!(find("()*?x", "x"), Some("x"));
+    }
+
+    #[test]
+    fn test_groups_and_alternation() {
+        let re = Regex::new(r"(\d+) (red|green|blue)").unwrap();
+        let caps = re.captures("3 blue, 4 red").unwrap();
+        assert_eq!(caps.len(), 3);
+        assert_eq!(caps.get(1), Some("3"));
+        assert_eq!(caps.get(2), Some("blue"));
+        assert_eq!(caps.parse::<u32>(1).unwrap(), 3);
+        assert!(caps.parse::<u32>(2).is_err());
+        assert!(caps.parse::<u32>(5).is_err());
+
+        // Groups that do not take part in the match are empty
+        let re = Regex::new("(a)|(b)").unwrap();
+        let caps = re.captures("b").unwrap();
+        assert_eq!((caps.get(1), caps.get(2)), (None, Some("b")));
+
+        // Backtracking out of a group forgets what it captured
+        let re = Regex::new("(a+)ab").unwrap();
+        assert_eq!(re.captures("aaab").unwrap().get(1), Some("aa"));
+
+        let re = Regex::new("(?:ab)+(c)").unwrap();
+        assert_eq!(re.captures("ababc").unwrap().get(1), Some("c"));
+        assert!(re.is_match("abc"));
+        assert!(!re.is_match("ac"));
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        for pattern in [
+            "(a", "a)", "*a", "a{2", "[ab", r"a\", "a{3,1}", "(?=a)", "[z-a]",
+        ] {
+            assert!(Regex::new(pattern).is_err(), "{}", pattern);
+        }
+        assert_eq!(
+            Regex::new("a(b").unwrap_err(),
+            "Unclosed group at position 3 of pattern 'a(b'"
+        );
+    }
+
+    #[test]
+    fn test_parse_lines_regex() {
+        let input = "Game 1: 3 blue\nGame 22: 10 red";
+        let games: Vec<(u32, u32, String)> =
+            parse_lines_regex_str(input, r"^Game (\d+): (\d+) (\w+)$").unwrap();
+        assert_eq!(games, vec![(1, 3, "blue".into()), (22, 10, "red".into())]);
+
+        let err = parse_lines_regex_str::<(u32,)>("Game 1\nRound 2", r"^Game (\d+)$");
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "line 2 (\"Round 2\"): does not match '^Game (\\d+)$'"
+        );
+        let err = parse_lines_regex_str::<(u8,)>("n=300", r"n=(\d+)");
+        assert!(matches!(err, Err(AocError::Parse { line: 1, .. })));
+        let err = parse_lines_regex_str::<(u8,)>("n=3", r"n=(\d+");
+        assert!(matches!(err, Err(AocError::Custom(_))));
+    }
+
+    #[test]
+    fn test_long_lines() {
+        // Each repeated character used to take stack frames, overflowing on long lines
+        let line = "7".repeat(100_000);
+        let parsed: Vec<(u8,)> = parse_lines_regex_str(&line, r"^(\d)\d*$").unwrap();
+        assert_eq!(parsed, vec![(7,)]);
+        let line = format!("<{}>", "x".repeat(100_000));
+        assert_eq!(find("<.*?>", &line).map(str::len), Some(100_002));
+        assert_eq!(find("x{3,5}?", &line), Some("xxx"));
+        assert_eq!(find("x{3,5}>", &line), Some("xxxxx>"));
+        assert_eq!(find(r"^<x{99999}>", &line), None);
+    }
+
+    #[test]
+    fn test_from_captures_custom() {
+        #[derive(Debug, PartialEq)]
+        struct Move {
+            count: usize,
+            from: usize,
+            to: usize,
+        }
+        impl FromCaptures for Move {
+            fn from_captures(caps: &Captures) -> Result<Self, Box<dyn Error>> {
+                Ok(Move {
+                    count: caps.parse(1)?,
+                    from: caps.parse(2)?,
+                    to: caps.parse(3)?,
+                })
+            }
+        }
+        let moves: Vec<Move> =
+            parse_lines_regex_str("move 1 from 2 to 1", r"move (\d+) from (\d+) to (\d+)").unwrap();
+        assert_eq!(
+            moves,
+            vec![Move {
+                count: 1,
+                from: 2,
+                to: 1
+            }]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+/// Draws a box around a grid of text rows.
+///
+/// Rows shorter than the widest row are padded with spaces so the right border lines
+/// up. This gives every picture-style answer the same look in terminal output.
+///
+/// # Arguments
+///
+/// * `rows` - The rows of the picture, top to bottom
+///
+/// # Returns
+///
+/// The framed picture, one line per row plus the top and bottom borders, without a
+/// trailing newline.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::render::frame;
+///
+/// let framed = frame(&["#..#", "####"]);
+/// assert_eq!(framed, "┌────┐\n│#..#│\n│####│\n└────┘");
+/// ```
+pub fn frame<S: AsRef<str>>(rows: &[S]) -> String {
+    let width = rows
+        .iter()
+        .map(|row| row.as_ref().chars().count())
+        .max()
+        .unwrap_or(0);
+    let border = "─".repeat(width);
+
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+    lines.push(format!("┌{}┐", border));
+    for row in rows {
+        let row = row.as_ref();
+        let padding = " ".repeat(width - row.chars().count());
+        lines.push(format!("│{}{}│", row, padding));
+    }
+    lines.push(format!("└{}┘", border));
+    lines.join("\n")
+}
+
+/// Escapes a string as a JSON string literal, including the surrounding quotes.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::render::json_string;
+///
+/// assert_eq!(json_string("a\"b\nc"), "\"a\\\"b\\nc\"");
+/// ```
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_pads_short_rows() {
+        let framed = frame(&["##", "#"]);
+        assert_eq!(framed, "┌──┐\n│##│\n│# │\n└──┘");
+    }
+
+    #[test]
+    fn test_frame_empty() {
+        let rows: [&str; 0] = [];
+        assert_eq!(frame(&rows), "┌┐\n└┘");
+    }
+
+    #[test]
+    fn test_frame_owned_rows() {
+        let rows = vec![String::from("ab"), String::from("cd")];
+        assert_eq!(frame(&rows).lines().count(), 4);
+    }
+
+    #[test]
+    fn test_json_string_escapes() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("tab\there"), "\"tab\\there\"");
+        assert_eq!(json_string("back\\slash"), "\"back\\\\slash\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+        assert_eq!(json_string("█"), "\"█\"");
+    }
+}
@@ -0,0 +1,551 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A part's ratings in the four categories `x`, `m`, `a` and `s`.
+pub type Part = [u64; 4];
+
+/// The category letters, in the order used by [`Part`] and [`PartRange`].
+pub const CATEGORIES: [char; 4] = ['x', 'm', 'a', 's'];
+
+/// Where a rule sends a part.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// The part is accepted (`A`).
+    Accept,
+    /// The part is rejected (`R`).
+    Reject,
+    /// The part moves on to the named workflow.
+    Workflow(String),
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Target::Accept),
+            "R" => Ok(Target::Reject),
+            "" => Err("Empty rule target".to_string()),
+            name => Ok(Target::Workflow(name.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Accept => write!(f, "A"),
+            Target::Reject => write!(f, "R"),
+            Target::Workflow(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A comparison of one category against a constant, such as `a<2006`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Condition {
+    /// Index of the category in [`CATEGORIES`].
+    pub category: usize,
+    /// `true` for `>`, `false` for `<`.
+    pub greater: bool,
+    /// The value compared against.
+    pub value: u64,
+}
+
+impl Condition {
+    /// Returns whether `part` satisfies the condition.
+    pub fn matches(&self, part: &Part) -> bool {
+        let rating = part[self.category];
+        if self.greater {
+            rating > self.value
+        } else {
+            rating < self.value
+        }
+    }
+
+    /// Splits `range` into the parts that satisfy the condition and the parts that do
+    /// not. Either side is `None` when it would be empty.
+    pub fn split(&self, range: &PartRange) -> (Option<PartRange>, Option<PartRange>) {
+        let (lo, hi) = (range.min[self.category], range.max[self.category]);
+        // Inclusive bounds of the matching and non-matching sides, before clamping
+        let (pass, fail) = if self.greater {
+            (
+                self.value.checked_add(1).map(|v| (v, hi)),
+                Some((lo, self.value)),
+            )
+        } else {
+            (
+                self.value.checked_sub(1).map(|v| (lo, v)),
+                Some((self.value, hi)),
+            )
+        };
+        let side = |bounds: Option<(u64, u64)>| {
+            let (a, b) = bounds?;
+            let (a, b) = (a.max(lo), b.min(hi));
+            (a <= b).then(|| {
+                let mut r = *range;
+                r.min[self.category] = a;
+                r.max[self.category] = b;
+                r
+            })
+        };
+        (side(pass), side(fail))
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let category = chars
+            .next()
+            .and_then(|c| CATEGORIES.iter().position(|&k| k == c))
+            .ok_or_else(|| format!("Unknown category in '{}'", s))?;
+        let greater = match chars.next() {
+            Some('>') => true,
+            Some('<') => false,
+            _ => return Err(format!("Expected '<' or '>' in '{}'", s)),
+        };
+        let value = chars
+            .as_str()
+            .parse()
+            .map_err(|e| format!("Invalid value in '{}': {}", s, e))?;
+        Ok(Condition {
+            category,
+            greater,
+            value,
+        })
+    }
+}
+
+/// One rule of a workflow: a condition and where matching parts go. The last rule of a
+/// workflow usually has no condition and catches everything else.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// The condition, or `None` for an unconditional fallback.
+    pub condition: Option<Condition>,
+    /// Where matching parts are sent.
+    pub target: Target,
+}
+
+/// A named chain of rules, as in `px{a<2006:qkq,m>2090:A,rfg}`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::rules::{Target, Workflow};
+///
+/// let w: Workflow = "px{a<2006:qkq,m>2090:A,rfg}".parse().unwrap();
+/// assert_eq!(w.name, "px");
+/// assert_eq!(w.evaluate(&[787, 2655, 1222, 2876]), &Target::Workflow("qkq".into()));
+/// assert_eq!(w.evaluate(&[787, 2655, 3000, 2876]), &Target::Accept);
+/// assert_eq!(w.evaluate(&[787, 100, 3000, 2876]), &Target::Workflow("rfg".into()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workflow {
+    /// The workflow's name.
+    pub name: String,
+    /// The rules, tried in order.
+    pub rules: Vec<Rule>,
+}
+
+impl Workflow {
+    /// Parses a comma separated rule chain such as `x>10:A, m<20:R, qs` into an unnamed
+    /// workflow.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rule is malformed or the chain is empty.
+    pub fn parse_rules(rules: &str) -> Result<Self, String> {
+        let rules = rules
+            .split(',')
+            .map(str::trim)
+            .map(|rule| match rule.split_once(':') {
+                Some((condition, target)) => Ok(Rule {
+                    condition: Some(condition.trim().parse()?),
+                    target: target.trim().parse()?,
+                }),
+                None => Ok(Rule {
+                    condition: None,
+                    target: rule.parse()?,
+                }),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Workflow {
+            name: String::new(),
+            rules,
+        })
+    }
+
+    /// Returns where `part` goes: the target of the first rule it matches, or
+    /// [`Target::Reject`] if it matches none.
+    pub fn evaluate(&self, part: &Part) -> &Target {
+        self.rules
+            .iter()
+            .find(|r| r.condition.is_none_or(|c| c.matches(part)))
+            .map_or(&Target::Reject, |r| &r.target)
+    }
+
+    /// Splits `range` by the rules, returning each non-empty piece together with where
+    /// it goes. Parts matching no rule are rejected.
+    pub fn split(&self, range: PartRange) -> Vec<(PartRange, &Target)> {
+        let mut pieces = Vec::new();
+        let mut rest = Some(range);
+        for rule in &self.rules {
+            let Some(current) = rest else { break };
+            let (pass, fail) = match rule.condition {
+                Some(condition) => condition.split(&current),
+                None => (Some(current), None),
+            };
+            if let Some(pass) = pass {
+                pieces.push((pass, &rule.target));
+            }
+            rest = fail;
+        }
+        if let Some(rest) = rest {
+            pieces.push((rest, &Target::Reject));
+        }
+        pieces
+    }
+}
+
+impl FromStr for Workflow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, body) = s
+            .strip_suffix('}')
+            .and_then(|s| s.split_once('{'))
+            .ok_or_else(|| format!("Expected 'name{{rules}}', got '{}'", s))?;
+        let mut workflow = Workflow::parse_rules(body)?;
+        workflow.name = name.trim().to_string();
+        Ok(workflow)
+    }
+}
+
+/// An axis-aligned box of parts: every combination of ratings with
+/// `min[i] <= rating[i] <= max[i]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PartRange {
+    /// Inclusive lower bounds, per category.
+    pub min: [u64; 4],
+    /// Inclusive upper bounds, per category.
+    pub max: [u64; 4],
+}
+
+impl PartRange {
+    /// Creates the range with every category in `min..=max`.
+    pub fn uniform(min: u64, max: u64) -> Self {
+        PartRange {
+            min: [min; 4],
+            max: [max; 4],
+        }
+    }
+
+    /// Returns the number of distinct parts in the range.
+    pub fn count(&self) -> u64 {
+        (0..4)
+            .map(|i| (self.max[i] + 1).saturating_sub(self.min[i]))
+            .product()
+    }
+
+    /// Returns whether `part` lies in the range.
+    pub fn contains(&self, part: &Part) -> bool {
+        (0..4).all(|i| (self.min[i]..=self.max[i]).contains(&part[i]))
+    }
+}
+
+/// A set of named workflows that route parts to acceptance or rejection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workflows {
+    workflows: HashMap<String, Workflow>,
+}
+
+impl Workflows {
+    /// Parses one workflow per line, stopping at the first blank line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the line of any malformed or duplicate workflow.
+    pub fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let mut workflows = HashMap::new();
+        for (i, line) in input
+            .lines()
+            .map(str::trim)
+            .take_while(|l| !l.is_empty())
+            .enumerate()
+        {
+            let workflow: Workflow = line.parse().map_err(|e| format!("line {}: {}", i + 1, e))?;
+            if workflows.contains_key(&workflow.name) {
+                return Err(
+                    format!("line {}: duplicate workflow '{}'", i + 1, workflow.name).into(),
+                );
+            }
+            workflows.insert(workflow.name.clone(), workflow);
+        }
+        Ok(Workflows { workflows })
+    }
+
+    /// Returns the workflow called `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&Workflow> {
+        self.workflows.get(name)
+    }
+
+    /// Runs `part` through the workflows from `start` and returns whether it ends up
+    /// accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rule sends the part to an unknown workflow, or the part
+    /// visits the same workflow twice (which would loop forever).
+    pub fn accepts(&self, part: &Part, start: &str) -> Result<bool, String> {
+        let mut name = start;
+        let mut visited = 0;
+        loop {
+            let workflow = self.lookup(name)?;
+            visited += 1;
+            if visited > self.workflows.len() {
+                return Err(format!("Part {:?} loops through workflow '{}'", part, name));
+            }
+            match workflow.evaluate(part) {
+                Target::Accept => return Ok(true),
+                Target::Reject => return Ok(false),
+                Target::Workflow(next) => name = next,
+            }
+        }
+    }
+
+    /// Returns disjoint ranges covering exactly the parts within `range` that are
+    /// accepted starting from `start`.
+    ///
+    /// Rather than testing parts one by one, the range is pushed through the workflows
+    /// symbolically, splitting it at every condition, so the work depends on the number
+    /// of rules rather than the number of parts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rule refers to an unknown workflow or the workflows can
+    /// send a range back to a workflow it already passed through.
+    pub fn accepted_ranges(&self, start: &str, range: PartRange) -> Result<Vec<PartRange>, String> {
+        let mut accepted = Vec::new();
+        let mut stack = vec![(start.to_string(), range, 0)];
+        while let Some((name, range, depth)) = stack.pop() {
+            if depth > self.workflows.len() {
+                return Err(format!("Workflow '{}' is part of a cycle", name));
+            }
+            for (piece, target) in self.lookup(&name)?.split(range) {
+                match target {
+                    Target::Accept => accepted.push(piece),
+                    Target::Reject => {}
+                    Target::Workflow(next) => stack.push((next.clone(), piece, depth + 1)),
+                }
+            }
+        }
+        Ok(accepted)
+    }
+
+    /// Counts the parts within `range` that are accepted starting from `start`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Workflows::accepted_ranges`].
+    pub fn count_accepted(&self, start: &str, range: PartRange) -> Result<u64, String> {
+        Ok(self
+            .accepted_ranges(start, range)?
+            .iter()
+            .map(PartRange::count)
+            .sum())
+    }
+
+    fn lookup(&self, name: &str) -> Result<&Workflow, String> {
+        self.workflows
+            .get(name)
+            .ok_or_else(|| format!("Unknown workflow '{}'", name))
+    }
+}
+
+/// Parses a part such as `{x=787,m=2655,a=1222,s=2876}`.
+///
+/// # Errors
+///
+/// Returns an error if a category is unknown, missing or repeated, or a rating is not
+/// a number.
+pub fn parse_part(s: &str) -> Result<Part, String> {
+    let body = s
+        .trim()
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .ok_or_else(|| format!("Expected '{{x=..,m=..,a=..,s=..}}', got '{}'", s))?;
+    let mut part = [None; 4];
+    for field in body.split(',') {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("Expected 'key=value', got '{}'", field))?;
+        let i = CATEGORIES
+            .iter()
+            .position(|c| key.trim().len() == 1 && key.trim().starts_with(*c))
+            .ok_or_else(|| format!("Unknown category '{}'", key))?;
+        if part[i].is_some() {
+            return Err(format!("Category '{}' appears twice", key));
+        }
+        part[i] = Some(
+            value
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid rating '{}': {}", value, e))?,
+        );
+    }
+    let mut ratings = [0; 4];
+    for (i, rating) in part.into_iter().enumerate() {
+        ratings[i] = rating.ok_or_else(|| format!("Missing category '{}'", CATEGORIES[i]))?;
+    }
+    Ok(ratings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}
+";
+
+    fn parts() -> Vec<Part> {
+        let (_, parts) = EXAMPLE.split_once("\n\n").unwrap();
+        parts.lines().map(|l| parse_part(l).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_rules_chain() {
+        let w = Workflow::parse_rules("x>10:A, m<20:R, qs").unwrap();
+        assert_eq!(w.rules.len(), 3);
+        assert_eq!(
+            w.rules[0].condition,
+            Some(Condition {
+                category: 0,
+                greater: true,
+                value: 10
+            })
+        );
+        assert_eq!(w.rules[1].target, Target::Reject);
+        assert_eq!(w.rules[2].condition, None);
+        assert_eq!(w.evaluate(&[5, 30, 0, 0]), &Target::Workflow("qs".into()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Workflow::parse_rules("q>10:A").is_err());
+        assert!(Workflow::parse_rules("x=10:A").is_err());
+        assert!(Workflow::parse_rules("x>ten:A").is_err());
+        assert!(Workflow::parse_rules("x>10:").is_err());
+        assert!("px{a<1:A".parse::<Workflow>().is_err());
+        let err = Workflows::parse("a{A}\na{R}").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+        assert!(parse_part("{x=1,m=2,a=3}").is_err());
+        assert!(parse_part("{x=1,m=2,a=3,s=4,x=5}").is_err());
+    }
+
+    #[test]
+    fn test_accepts_example() {
+        let workflows = Workflows::parse(EXAMPLE).unwrap();
+        let total: u64 = parts()
+            .iter()
+            .filter(|p| workflows.accepts(p, "in").unwrap())
+            .map(|p| p.iter().sum::<u64>())
+            .sum();
+        assert_eq!(total, 19114);
+    }
+
+    #[test]
+    fn test_count_accepted_example() {
+        let workflows = Workflows::parse(EXAMPLE).unwrap();
+        let count = workflows
+            .count_accepted("in", PartRange::uniform(1, 4000))
+            .unwrap();
+        assert_eq!(count, 167409079868000);
+    }
+
+    #[test]
+    fn test_accepted_ranges_match_brute_force() {
+        let workflows =
+            Workflows::parse("in{x<3:a,m>2:A,s<2:R,b}\na{a>3:R,s>1:A,b}\nb{x>4:A,a<2:A,R}")
+                .unwrap();
+        let range = PartRange::uniform(1, 5);
+        let ranges = workflows.accepted_ranges("in", range).unwrap();
+        let mut brute = 0;
+        for x in 1..=5 {
+            for m in 1..=5 {
+                for a in 1..=5 {
+                    for s in 1..=5 {
+                        let part = [x, m, a, s];
+                        let accepted = workflows.accepts(&part, "in").unwrap();
+                        let covering = ranges.iter().filter(|r| r.contains(&part)).count();
+                        assert_eq!(covering, accepted as usize, "{:?}", part);
+                        brute += accepted as u64;
+                    }
+                }
+            }
+        }
+        assert_eq!(workflows.count_accepted("in", range).unwrap(), brute);
+    }
+
+    #[test]
+    fn test_condition_split_edges() {
+        let range = PartRange::uniform(1, 10);
+        let below = |v| Condition {
+            category: 0,
+            greater: false,
+            value: v,
+        };
+        let above = |v| Condition {
+            category: 0,
+            greater: true,
+            value: v,
+        };
+        let count = |r: Option<PartRange>| r.map_or(0, |r| r.count() / 1000);
+        let (pass, fail) = below(1).split(&range);
+        assert_eq!((count(pass), count(fail)), (0, 10));
+        let (pass, fail) = below(0).split(&range);
+        assert_eq!((count(pass), count(fail)), (0, 10));
+        let (pass, fail) = above(10).split(&range);
+        assert_eq!((count(pass), count(fail)), (0, 10));
+        let (pass, fail) = above(0).split(&range);
+        assert_eq!((count(pass), count(fail)), (10, 0));
+        let (pass, fail) = below(4).split(&range);
+        assert_eq!((count(pass), count(fail)), (3, 7));
+    }
+
+    #[test]
+    fn test_routing_errors() {
+        let workflows = Workflows::parse("in{x>5:nowhere,A}\nl{l2}\nl2{l}").unwrap();
+        assert!(workflows.accepts(&[1, 1, 1, 1], "in").unwrap());
+        assert!(workflows.accepts(&[9, 1, 1, 1], "in").is_err());
+        assert!(workflows.accepts(&[1, 1, 1, 1], "l").is_err());
+        assert!(workflows
+            .accepted_ranges("in", PartRange::uniform(1, 10))
+            .is_err());
+        assert!(workflows
+            .accepted_ranges("l", PartRange::uniform(1, 10))
+            .is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::AocError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Builds a line parser that extracts typed fields from a template, for
+/// [`parse_lines_with`](crate::parse_lines_with) and friends.
+///
+/// Each `{}` in the template is a field, parsed with [`FromStr`] into the matching type;
+/// all other text must appear in the line exactly. A field runs to the end of the line
+/// if it ends the template, and otherwise takes at least one character and stops at the
+/// next occurrence of the text after it, so `"{}-{}"` reads `-3-5` as `-3` and `5`. The
+/// macro expands to a closure returning a tuple of the fields, with errors that give
+/// the column of the offending text.
+///
+/// The number of fields must match the number of types, and two fields may not be
+/// adjacent, since nothing would separate them; both are checked at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{aoc_parse, parse_lines_with_str};
+///
+/// let input = "move 1 from 2 to 1\nmove 3 from 1 to 3";
+/// let parse = aoc_parse!("move {} from {} to {}", usize, usize, usize);
+/// let moves = parse_lines_with_str(input, parse);
+/// assert_eq!(moves.unwrap(), vec![(1, 2, 1), (3, 1, 3)]);
+///
+/// // A single line can be parsed by calling the closure directly. Its errors then
+/// // give no line number, since only the column is known
+/// let parse = aoc_parse!("{} ({})", String, u32);
+/// let (name, weight) = parse("fwft (72)").unwrap();
+/// assert_eq!((name.as_str(), weight), ("fwft", 72));
+/// let err = parse("fwft (x)").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "column 7 (\"fwft (x)\"): cannot parse field 2 'x' as u32: invalid digit found in string"
+/// );
+///
+/// let parse = aoc_parse!("move {} from {} to {}", u8, u8, u8);
+/// let err = parse_lines_with_str("move 1 from x to 1", parse);
+/// assert_eq!(
+///     err.unwrap_err().to_string(),
+///     "line 1, column 13 (\"move 1 from x to 1\"): cannot parse field 2 'x' as u8: \
+///      invalid digit found in string"
+/// );
+/// ```
+///
+/// A template that does not fit the types is rejected when compiling:
+///
+/// ```compile_fail
+/// let parse = aoclib::aoc_parse!("{} -> {}", u32);
+/// ```
+#[macro_export]
+macro_rules! aoc_parse {
+    ($template:literal, $($t:ty),+ $(,)?) => {
+        |line: &str| -> ::std::result::Result<
+            ($($t,)+),
+            ::std::boxed::Box<dyn ::std::error::Error>,
+        > {
+            const {
+                assert!(
+                    $crate::scan::count_fields($template) == [$(stringify!($t)),+].len(),
+                    "aoc_parse!: the template's {{}} fields do not match the number of types"
+                )
+            };
+            let fields = $crate::scan::scan($template, line)?;
+            let mut index = 0;
+            Ok(($({
+                index += 1;
+                $crate::scan::parse_field::<$t>(line, fields[index - 1], index, stringify!($t))?
+            },)+))
+        }
+    };
+}
+
+/// Counts the `{}` fields in a template.
+///
+/// # Panics
+///
+/// Panics if two fields are adjacent. In a const context, such as the check
+/// [`aoc_parse!`](crate::aoc_parse) makes, this is a compile error.
+pub const fn count_fields(template: &str) -> usize {
+    let bytes = template.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    let mut previous_end = usize::MAX;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            assert!(
+                previous_end != i,
+                "adjacent {{}} fields cannot be told apart"
+            );
+            count += 1;
+            i += 2;
+            previous_end = i;
+        } else {
+            i += 1;
+        }
+    }
+    count
+}
+
+/// Splits `line` into the text of each `{}` field of `template`.
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] with the column where the line stops matching the
+/// template's literal text. Its line number is 0, meaning unknown, for
+/// [`parse_lines_with`](crate::parse_lines_with) and friends to fill in.
+pub fn scan<'a>(template: &str, line: &'a str) -> Result<Vec<&'a str>, AocError> {
+    let mut literals = template.split("{}");
+    let first = literals.next().unwrap_or_default();
+    let mut rest = line
+        .strip_prefix(first)
+        .ok_or_else(|| mismatch(line, line, first))?;
+    let literals: Vec<&str> = literals.collect();
+    let mut fields = Vec::with_capacity(literals.len());
+    for (i, literal) in literals.iter().enumerate() {
+        let (field, after) = if i + 1 == literals.len() {
+            // The last field runs to whatever text ends the template
+            let field = rest
+                .strip_suffix(literal)
+                .ok_or_else(|| mismatch(line, &rest[rest.len()..], literal))?;
+            (field, "")
+        } else {
+            // A field is never empty, so a leading '-' is a sign rather than a separator
+            let skip = rest.chars().next().map_or(0, char::len_utf8);
+            let end = rest[skip..]
+                .find(literal)
+                .ok_or_else(|| mismatch(line, rest, literal))?
+                + skip;
+            (&rest[..end], &rest[end + literal.len()..])
+        };
+        fields.push(field);
+        rest = after;
+    }
+    if !rest.is_empty() {
+        return Err(
+            AocError::parse(0, line, "unexpected text after the template")
+                .with_column(column(line, rest)),
+        );
+    }
+    Ok(fields)
+}
+
+/// Parses `field`, the text of field number `index` (from 1) of `line`, into a `T`
+/// called `type_name` in errors.
+///
+/// # Errors
+///
+/// Returns an [`AocError::Parse`] with the field's column and line number 0, meaning
+/// unknown, for [`parse_lines_with`](crate::parse_lines_with) and friends to fill in.
+pub fn parse_field<T>(line: &str, field: &str, index: usize, type_name: &str) -> Result<T, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    field.parse().map_err(|e| {
+        let message = format!(
+            "cannot parse field {} '{}' as {}: {}",
+            index, field, type_name, e
+        );
+        AocError::parse(0, line, message).with_column(column(line, field))
+    })
+}
+
+/// The error for `line` not containing `expected` at `at`, a slice of it.
+fn mismatch(line: &str, at: &str, expected: &str) -> AocError {
+    AocError::parse(0, line, format!("expected '{}'", expected)).with_column(column(line, at))
+}
+
+/// The 1-based column at which `part`, a slice of `line`, starts.
+fn column(line: &str, part: &str) -> usize {
+    let offset = part.as_ptr() as usize - line.as_ptr() as usize;
+    line[..offset].chars().count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_lines_with_str;
+
+    fn located(err: AocError) -> (Option<usize>, String) {
+        match err {
+            AocError::Parse {
+                column, message, ..
+            } => (column, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_scan() {
+        assert_eq!(
+            scan("move {} from {} to {}", "move 12 from 3 to 4").unwrap(),
+            ["12", "3", "4"]
+        );
+        assert_eq!(scan("{},{}", "a,b,c").unwrap(), ["a", "b,c"]);
+        assert_eq!(scan("{}-{}", "-3-5").unwrap(), ["-3", "5"]);
+        assert_eq!(scan("<{}>", "<a>b>").unwrap(), ["a>b"]);
+        assert_eq!(scan("{}", "").unwrap(), [""]);
+        assert_eq!(scan("Sue {}:", "Sue 1:").unwrap(), ["1"]);
+        assert!(scan("no fields", "no fields").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scan_errors() {
+        let err = |template, line| located(scan(template, line).unwrap_err());
+        assert_eq!(
+            err("move {} from {}", "mv 1 from 2"),
+            (Some(1), "expected 'move '".into())
+        );
+        assert_eq!(
+            err("move {} from {}", "move 1 to 2"),
+            (Some(6), "expected ' from '".into())
+        );
+        assert_eq!(err("({})", "(1"), (Some(3), "expected ')'".into()));
+        assert_eq!(err("{} x", "1 xé!"), (Some(6), "expected ' x'".into()));
+        assert_eq!(
+            err("a", "ab"),
+            (Some(2), "unexpected text after the template".into())
+        );
+    }
+
+    #[test]
+    fn test_count_fields() {
+        assert_eq!(count_fields(""), 0);
+        assert_eq!(count_fields("{} {}"), 2);
+        assert_eq!(count_fields("{ } {}}"), 1);
+        assert!(std::panic::catch_unwind(|| count_fields("{}{}")).is_err());
+    }
+
+    #[test]
+    fn test_aoc_parse() {
+        let parse = crate::aoc_parse!("{}: {}-{} {}", char, i32, i32, String);
+        assert_eq!(
+            parse("x: -3-5 word").unwrap(),
+            ('x', -3, 5, "word".to_string())
+        );
+        let single = crate::aoc_parse!("#{}", u8,);
+        assert_eq!(single("#7").unwrap(), (7,));
+
+        let input = "p=1,2\np=3,x";
+        let err = parse_lines_with_str(input, crate::aoc_parse!("p={},{}", i64, i64));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "line 2, column 5 (\"p=3,x\"): cannot parse field 2 'x' as i64: \
+             invalid digit found in string"
+        );
+        let err = parse_lines_with_str("q=1,2", crate::aoc_parse!("p={},{}", i64, i64));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "line 1, column 1 (\"q=1,2\"): expected 'p='"
+        );
+    }
+}
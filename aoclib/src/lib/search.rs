@@ -0,0 +1,826 @@
+use crate::lib::grid::Grid;
+use crate::lib::point::Point2;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// The outcome of a successful weighted search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<S> {
+    /// Total cost of the path.
+    pub cost: usize,
+    /// The states from the start to the goal, both included.
+    pub path: Vec<S>,
+}
+
+/// Shortest distances from a start state to every reachable state, as computed by
+/// [`dijkstra_all`].
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<S> {
+    /// Cost of the cheapest path to every reachable state.
+    pub distances: HashMap<S, usize>,
+    /// For every reachable state other than the start, all the states it can be
+    /// reached from on some cheapest path. Zero-cost edges that would close a cycle are
+    /// left out, so following predecessors always ends at the start.
+    pub predecessors: HashMap<S, Vec<S>>,
+}
+
+impl<S: Clone + Eq + Hash> ShortestPaths<S> {
+    /// Reconstructs one cheapest path to `target`, or `None` if it is unreachable.
+    pub fn path_to(&self, target: &S) -> Option<Vec<S>> {
+        self.distances.get(target)?;
+        let mut path = vec![target.clone()];
+        while let Some(previous) = self.predecessors.get(path.last()?).and_then(|p| p.first()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Returns every state that lies on at least one cheapest path to any of `targets`.
+    ///
+    /// This answers "how many tiles are part of any best route" style questions.
+    pub fn on_best_paths<'a, I>(&self, targets: I) -> HashSet<S>
+    where
+        I: IntoIterator<Item = &'a S>,
+        S: 'a,
+    {
+        let mut stack: Vec<S> = targets
+            .into_iter()
+            .filter(|t| self.distances.contains_key(t))
+            .cloned()
+            .collect();
+        let mut seen: HashSet<S> = stack.iter().cloned().collect();
+        while let Some(state) = stack.pop() {
+            for previous in self.predecessors.get(&state).into_iter().flatten() {
+                if seen.insert(previous.clone()) {
+                    stack.push(previous.clone());
+                }
+            }
+        }
+        seen
+    }
+}
+
+/// Finds a shortest path from `start` to any state satisfying `goal` with a
+/// breadth-first search over unweighted edges.
+///
+/// States can be anything hashable: grid positions, `(position, direction)` pairs or
+/// whole puzzle configurations.
+///
+/// # Arguments
+///
+/// * `start` - The initial state.
+/// * `neighbors` - Returns the states reachable in one step from a state.
+/// * `goal` - Returns `true` for a target state.
+///
+/// # Returns
+///
+/// The number of steps and the path from `start` to the first goal reached, both
+/// endpoints included, or `None` if no goal is reachable.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::search::bfs;
+///
+/// // Reach 10 from 1 using "+1" and "*2"
+/// let (cost, path) = bfs(1u32, |&n| [n + 1, n * 2], |&n| n == 10).unwrap();
+/// assert_eq!(cost, 4);
+/// assert_eq!(path, vec![1, 2, 4, 5, 10]);
+/// ```
+pub fn bfs<S, N, I, G>(start: S, mut neighbors: N, mut goal: G) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    // Every discovered state with the index of the state it was reached from
+    let mut nodes: Vec<(S, Option<usize>)> = vec![(start.clone(), None)];
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(index) = queue.pop_front() {
+        if goal(&nodes[index].0) {
+            let mut path = Vec::new();
+            let mut current = Some(index);
+            while let Some(i) = current {
+                path.push(nodes[i].0.clone());
+                current = nodes[i].1;
+            }
+            path.reverse();
+            return Some((path.len() - 1, path));
+        }
+        for next in neighbors(&nodes[index].0) {
+            if seen.insert(next.clone()) {
+                nodes.push((next, Some(index)));
+                queue.push_back(nodes.len() - 1);
+            }
+        }
+    }
+
+    None
+}
+
+/// Floods outwards from `start` and returns the number of steps to every reachable
+/// state, including `start` itself at distance 0.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::search::bfs_distances;
+///
+/// let distances = bfs_distances(0i32, |&n| [n - 1, n + 1].into_iter().filter(|n| n.abs() <= 2));
+/// assert_eq!(distances.len(), 5);
+/// assert_eq!(distances[&-2], 2);
+/// ```
+pub fn bfs_distances<S, N, I>(start: S, mut neighbors: N) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((state, distance)) = queue.pop_front() {
+        for next in neighbors(&state) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Finds a cheapest path from `start` to any state satisfying `goal` using Dijkstra's
+/// algorithm.
+///
+/// States only need to be hashable, so they can carry whatever the puzzle needs:
+/// position and facing, keys held, time step and so on.
+///
+/// # Arguments
+///
+/// * `start` - The initial state.
+/// * `successors` - Returns `(state, cost)` pairs for every move out of a state. Costs
+///   must be non-negative, which `usize` guarantees.
+/// * `goal` - Returns `true` for a target state.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::search::dijkstra;
+///
+/// // Walking costs 1 per step, teleporting from 2 to 9 costs 3
+/// let result = dijkstra(
+///     0u32,
+///     |&n| {
+///         let mut moves = vec![(n + 1, 1)];
+///         if n == 2 {
+///             moves.push((9, 3));
+///         }
+///         moves
+///     },
+///     |&n| n == 10,
+/// )
+/// .unwrap();
+/// assert_eq!(result.cost, 6);
+/// assert_eq!(result.path, vec![0, 1, 2, 9, 10]);
+/// ```
+pub fn dijkstra<S, N, I, G>(start: S, mut successors: N, mut goal: G) -> Option<SearchResult<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    G: FnMut(&S) -> bool,
+{
+    let mut nodes = Nodes::new(start);
+    let mut heap = BinaryHeap::from([Reverse((0, 0))]);
+
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        if cost > nodes.cost[index] {
+            continue;
+        }
+        if goal(&nodes.states[index]) {
+            return Some(SearchResult {
+                cost,
+                path: nodes.path(index),
+            });
+        }
+        for (next, step) in successors(&nodes.states[index]) {
+            let next_cost = cost + step;
+            let next_index = nodes.index_of(next);
+            if next_cost < nodes.cost[next_index] {
+                nodes.cost[next_index] = next_cost;
+                nodes.parent[next_index] = Some(index);
+                heap.push(Reverse((next_cost, next_index)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds a cheapest path from `start` to any state satisfying `goal` using A*, guided
+/// by `heuristic`.
+///
+/// The heuristic estimates the remaining cost from a state to the nearest goal. As long
+/// as it never overestimates (it is *admissible*), the result is as cheap as the one
+/// from [`dijkstra`], usually after exploring far fewer states. A heuristic that always
+/// returns 0 turns this into plain Dijkstra.
+///
+/// # Arguments
+///
+/// * `start` - The initial state.
+/// * `successors` - Returns `(state, cost)` pairs for every move out of a state.
+/// * `heuristic` - Returns a lower bound on the remaining cost from a state.
+/// * `goal` - Returns `true` for a target state.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::search::astar;
+/// use aoclib::{Direction, Point2};
+///
+/// // Open 100x100 room, guided by the Manhattan distance to the far corner
+/// let target = Point2::new(99, 99);
+/// let result = astar(
+///     Point2::ORIGIN,
+///     |&p| {
+///         Direction::ALL
+///             .into_iter()
+///             .map(move |d| (p.step(d), 1))
+///             .filter(|(n, _)| (0..100).contains(&n.x) && (0..100).contains(&n.y))
+///     },
+///     |&p| p.manhattan(target),
+///     |&p| p == target,
+/// )
+/// .unwrap();
+/// assert_eq!(result.cost, 198);
+/// ```
+pub fn astar<S, N, I, H, G>(
+    start: S,
+    mut successors: N,
+    mut heuristic: H,
+    mut goal: G,
+) -> Option<SearchResult<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    H: FnMut(&S) -> usize,
+    G: FnMut(&S) -> bool,
+{
+    let mut nodes = Nodes::new(start);
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&nodes.states[0]), 0, 0))]);
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        if cost > nodes.cost[index] {
+            continue;
+        }
+        if goal(&nodes.states[index]) {
+            return Some(SearchResult {
+                cost,
+                path: nodes.path(index),
+            });
+        }
+        for (next, step) in successors(&nodes.states[index]) {
+            let next_cost = cost + step;
+            let estimate = next_cost + heuristic(&next);
+            let next_index = nodes.index_of(next);
+            if next_cost < nodes.cost[next_index] {
+                nodes.cost[next_index] = next_cost;
+                nodes.parent[next_index] = Some(index);
+                heap.push(Reverse((estimate, next_cost, next_index)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Runs Dijkstra's algorithm from `start` over every reachable state, recording all
+/// cheapest predecessors so paths can be reconstructed afterwards.
+///
+/// See [`dijkstra`] for the meaning of `successors`.
+pub fn dijkstra_all<S, N, I>(start: S, mut successors: N) -> ShortestPaths<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut nodes = Nodes::new(start);
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut heap = BinaryHeap::from([Reverse((0, 0))]);
+
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        if cost > nodes.cost[index] {
+            continue;
+        }
+        for (next, step) in successors(&nodes.states[index]) {
+            let next_cost = cost + step;
+            let next_index = nodes.index_of(next);
+            predecessors.resize_with(nodes.states.len(), Vec::new);
+            if next_cost < nodes.cost[next_index] {
+                nodes.cost[next_index] = next_cost;
+                predecessors[next_index] = vec![index];
+                heap.push(Reverse((next_cost, next_index)));
+            } else if next_cost == nodes.cost[next_index]
+                && next_index != 0
+                && !predecessors[next_index].contains(&index)
+                // Only a zero-cost edge can lead back to an ancestor and close a cycle
+                && (step > 0 || !has_ancestor(&predecessors, index, next_index))
+            {
+                predecessors[next_index].push(index);
+            }
+        }
+    }
+
+    let states = &nodes.states;
+    ShortestPaths {
+        distances: states
+            .iter()
+            .cloned()
+            .zip(nodes.cost.iter().copied())
+            .collect(),
+        predecessors: predecessors
+            .iter()
+            .enumerate()
+            .filter(|(_, from)| !from.is_empty())
+            .map(|(i, from)| {
+                let from = from.iter().map(|&j| states[j].clone()).collect();
+                (states[i].clone(), from)
+            })
+            .collect(),
+    }
+}
+
+/// Returns whether `ancestor` is `index` or one of its recorded predecessors, directly
+/// or through others.
+fn has_ancestor(predecessors: &[Vec<usize>], index: usize, ancestor: usize) -> bool {
+    let mut stack = vec![index];
+    let mut seen = HashSet::from([index]);
+    while let Some(i) = stack.pop() {
+        if i == ancestor {
+            return true;
+        }
+        for &previous in &predecessors[i] {
+            if seen.insert(previous) {
+                stack.push(previous);
+            }
+        }
+    }
+    false
+}
+
+/// Interned search states with their best known cost and parent.
+struct Nodes<S> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    cost: Vec<usize>,
+    parent: Vec<Option<usize>>,
+}
+
+impl<S: Clone + Eq + Hash> Nodes<S> {
+    fn new(start: S) -> Self {
+        Nodes {
+            states: vec![start.clone()],
+            index: HashMap::from([(start, 0)]),
+            cost: vec![0],
+            parent: vec![None],
+        }
+    }
+
+    /// Returns the index of `state`, adding it with an infinite cost if it is new.
+    fn index_of(&mut self, state: S) -> usize {
+        if let Some(&i) = self.index.get(&state) {
+            return i;
+        }
+        self.states.push(state.clone());
+        self.cost.push(usize::MAX);
+        self.parent.push(None);
+        self.index.insert(state, self.states.len() - 1);
+        self.states.len() - 1
+    }
+
+    /// Follows parent links back from `index` to the start.
+    fn path(&self, index: usize) -> Vec<S> {
+        let mut path = Vec::new();
+        let mut current = Some(index);
+        while let Some(i) = current {
+            path.push(self.states[i].clone());
+            current = self.parent[i];
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Returns the region of orthogonally connected grid cells containing `start` whose
+/// cells are all `passable`. The region is empty if `start` itself is out of bounds or
+/// not passable.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::search::flood_fill;
+/// use aoclib::{Grid, Point2};
+///
+/// let grid = Grid::parse("..#\n.##\n#..", |c| c).unwrap();
+/// let region = flood_fill(&grid, Point2::ORIGIN, |&c| c == '.');
+/// assert_eq!(region.len(), 3);
+/// assert!(!region.contains(&Point2::new(2, 2)));
+/// ```
+pub fn flood_fill<T, P>(grid: &Grid<T>, start: Point2, mut passable: P) -> HashSet<Point2>
+where
+    P: FnMut(&T) -> bool,
+{
+    flood(start, |p| grid.get(p).is_some_and(&mut passable))
+}
+
+/// Returns the region of orthogonally connected points containing `start` that are all
+/// `passable`, for sparse maps stored as a set of points.
+///
+/// The predicate sees each point along with whether it is in `points`, so the same
+/// function fills a shape (`|_, inside| inside`) or the gaps around it. The plane is
+/// unbounded, so a predicate that accepts points outside `points` must bound the fill
+/// itself.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::search::flood_fill_set;
+/// use aoclib::Point2;
+/// use std::collections::HashSet;
+///
+/// // A ring of walls around a 2x2 room
+/// let walls: HashSet<Point2> = (0..4)
+///     .flat_map(|i| [(i, 0), (i, 3), (0, i), (3, i)])
+///     .map(Point2::from)
+///     .collect();
+/// let inside = Point2::new(1, 1);
+/// let room = flood_fill_set(&walls, inside, |p, wall| !wall && p.manhattan(inside) < 10);
+/// assert_eq!(room.len(), 4);
+/// ```
+pub fn flood_fill_set<P>(
+    points: &HashSet<Point2>,
+    start: Point2,
+    mut passable: P,
+) -> HashSet<Point2>
+where
+    P: FnMut(Point2, bool) -> bool,
+{
+    flood(start, |p| passable(p, points.contains(&p)))
+}
+
+/// Collects the orthogonally connected passable points around `start`.
+fn flood<P>(start: Point2, mut passable: P) -> HashSet<Point2>
+where
+    P: FnMut(Point2) -> bool,
+{
+    let mut region = HashSet::new();
+    if !passable(start) {
+        return region;
+    }
+    region.insert(start);
+    let mut stack = vec![start];
+    while let Some(p) = stack.pop() {
+        for next in p.neighbors4() {
+            if !region.contains(&next) && passable(next) {
+                region.insert(next);
+                stack.push(next);
+            }
+        }
+    }
+    region
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::point::Direction;
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#.E.
+.##...#.
+";
+
+    fn open_neighbors(grid: &Grid<char>) -> impl Fn(&Point2) -> Vec<Point2> + '_ {
+        move |&p| {
+            Direction::ALL
+                .iter()
+                .map(|&d| p.step(d))
+                .filter(|&n| grid.get(n).is_some_and(|&c| c != '#'))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_bfs_grid_shortest_path() {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let start = grid.find(|&c| c == 'S').unwrap();
+        let end = grid.find(|&c| c == 'E').unwrap();
+
+        let (cost, path) = bfs(start, open_neighbors(&grid), |&p| p == end).unwrap();
+        assert_eq!(cost, 10);
+        assert_eq!(path.len(), 11);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        for pair in path.windows(2) {
+            assert_eq!(pair[0].manhattan(pair[1]), 1);
+            assert_ne!(grid[pair[1]], '#');
+        }
+    }
+
+    #[test]
+    fn test_bfs_start_is_goal() {
+        assert_eq!(bfs(7, |&n| [n + 1], |&n| n == 7), Some((0, vec![7])));
+    }
+
+    #[test]
+    fn test_bfs_unreachable() {
+        let grid = Grid::parse("S#E\n.#.\n", |c| c).unwrap();
+        let end = grid.find(|&c| c == 'E').unwrap();
+        assert_eq!(
+            bfs(Point2::ORIGIN, open_neighbors(&grid), |&p| p == end),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bfs_composite_state() {
+        // Position plus a "key collected" flag: the key at 4 must be picked up before 0
+        let result = bfs(
+            (2i32, false),
+            |&(pos, key)| {
+                [pos - 1, pos + 1]
+                    .into_iter()
+                    .filter(|p| (0..=4).contains(p))
+                    .map(move |p| (p, key || p == 4))
+            },
+            |&(pos, key)| pos == 0 && key,
+        );
+        assert_eq!(result.map(|(cost, _)| cost), Some(6));
+    }
+
+    #[test]
+    fn test_bfs_distances_grid() {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let start = grid.find(|&c| c == 'S').unwrap();
+        let end = grid.find(|&c| c == 'E').unwrap();
+
+        let distances = bfs_distances(start, open_neighbors(&grid));
+        let open = grid.iter().filter(|(_, &c)| c != '#').count();
+        assert_eq!(distances.len(), open);
+        assert_eq!(distances[&start], 0);
+        assert_eq!(distances[&end], 10);
+    }
+
+    type Reindeer = (Point2, Direction);
+
+    /// Moves in a reindeer maze: stepping forward costs 1, turning costs 1000.
+    fn reindeer_moves(grid: &Grid<char>) -> impl Fn(&Reindeer) -> Vec<(Reindeer, usize)> + '_ {
+        move |&(p, d)| {
+            let mut moves = vec![((p, d.turn_left()), 1000), ((p, d.turn_right()), 1000)];
+            let ahead = p.step(d);
+            if grid.get(ahead).is_some_and(|&c| c != '#') {
+                moves.push(((ahead, d), 1));
+            }
+            moves
+        }
+    }
+
+    const REINDEER: &str = "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+";
+
+    #[test]
+    fn test_dijkstra_state_with_direction() {
+        let grid = Grid::parse(REINDEER, |c| c).unwrap();
+        let start = grid.find(|&c| c == 'S').unwrap();
+        let end = grid.find(|&c| c == 'E').unwrap();
+
+        let result = dijkstra(
+            (start, Direction::Right),
+            reindeer_moves(&grid),
+            |&(p, _)| p == end,
+        )
+        .unwrap();
+        assert_eq!(result.cost, 7036);
+        assert_eq!(result.path.first(), Some(&(start, Direction::Right)));
+        assert_eq!(result.path.last().map(|s| s.0), Some(end));
+    }
+
+    #[test]
+    fn test_dijkstra_matches_bfs_on_unit_costs() {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let start = grid.find(|&c| c == 'S').unwrap();
+        let end = grid.find(|&c| c == 'E').unwrap();
+        let neighbors = open_neighbors(&grid);
+
+        let result = dijkstra(
+            start,
+            |p| neighbors(p).into_iter().map(|n| (n, 1)),
+            |&p| p == end,
+        );
+        assert_eq!(result.map(|r| r.cost), Some(10));
+    }
+
+    #[test]
+    fn test_dijkstra_unreachable() {
+        assert_eq!(dijkstra(0u8, |_| Vec::new(), |&n| n == 1), None);
+    }
+
+    #[test]
+    fn test_dijkstra_all_paths_and_best_tiles() {
+        let grid = Grid::parse(REINDEER, |c| c).unwrap();
+        let start = grid.find(|&c| c == 'S').unwrap();
+        let end = grid.find(|&c| c == 'E').unwrap();
+
+        let paths = dijkstra_all((start, Direction::Right), reindeer_moves(&grid));
+        let best = Direction::ALL
+            .iter()
+            .filter_map(|&d| paths.distances.get(&(end, d)))
+            .min()
+            .copied()
+            .unwrap();
+        assert_eq!(best, 7036);
+
+        let ends: Vec<_> = Direction::ALL
+            .iter()
+            .map(|&d| (end, d))
+            .filter(|s| paths.distances.get(s) == Some(&best))
+            .collect();
+        let tiles: HashSet<Point2> = paths
+            .on_best_paths(&ends)
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(tiles.len(), 45);
+
+        let path = paths.path_to(&ends[0]).unwrap();
+        assert_eq!(path.first(), Some(&(start, Direction::Right)));
+        assert_eq!(path.last(), Some(&ends[0]));
+    }
+
+    #[test]
+    fn test_path_to_unreachable() {
+        let paths = dijkstra_all(0u8, |&n| if n < 3 { vec![(n + 1, 2)] } else { Vec::new() });
+        assert_eq!(paths.distances[&3], 6);
+        assert_eq!(paths.path_to(&3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(paths.path_to(&7), None);
+        assert_eq!(paths.path_to(&0), Some(vec![0]));
+    }
+
+    #[test]
+    fn test_dijkstra_all_zero_cost_cycles() {
+        // 0 and 1 are joined both ways for free, and 1 and 2 likewise, with 0 -> 2 at 0
+        let edges = |&n: &u8| match n {
+            0 => vec![(1, 0), (2, 0)],
+            1 => vec![(0, 0), (2, 0)],
+            2 => vec![(1, 0), (3, 1)],
+            _ => Vec::new(),
+        };
+        let paths = dijkstra_all(0u8, edges);
+        assert_eq!(paths.distances[&3], 1);
+        assert!(!paths.predecessors.contains_key(&0));
+        assert_eq!(paths.path_to(&0), Some(vec![0]));
+        assert_eq!(paths.path_to(&1).unwrap().first(), Some(&0));
+        assert_eq!(paths.path_to(&3).unwrap().first(), Some(&0));
+        assert_eq!(paths.on_best_paths(&[3]), HashSet::from([0, 1, 2, 3]));
+    }
+
+    /// Weighted grid where every digit is the cost of entering that cell.
+    const CHITONS: &str = "\
+1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581
+";
+
+    #[test]
+    fn test_astar_matches_dijkstra_on_weighted_grid() {
+        let grid = Grid::parse(CHITONS, |c| c.to_digit(10).unwrap() as usize).unwrap();
+        let end = Point2::new(grid.width() as isize - 1, grid.height() as isize - 1);
+        let successors = |&p: &Point2| {
+            Direction::ALL
+                .iter()
+                .filter_map(|&d| grid.get(p.step(d)).map(|&risk| (p.step(d), risk)))
+                .collect::<Vec<_>>()
+        };
+
+        let plain = dijkstra(Point2::ORIGIN, successors, |&p| p == end).unwrap();
+        let guided = astar(
+            Point2::ORIGIN,
+            successors,
+            |&p| p.manhattan(end),
+            |&p| p == end,
+        )
+        .unwrap();
+        assert_eq!(plain.cost, 40);
+        assert_eq!(guided.cost, 40);
+        assert_eq!(guided.path.first(), Some(&Point2::ORIGIN));
+        assert_eq!(guided.path.last(), Some(&end));
+        let path_cost: usize = guided.path[1..].iter().map(|&p| grid[p]).sum();
+        assert_eq!(path_cost, guided.cost);
+    }
+
+    #[test]
+    fn test_astar_explores_fewer_states() {
+        let target = Point2::new(30, 0);
+        let count = |use_heuristic: bool| {
+            let expanded = std::cell::Cell::new(0);
+            let result = astar(
+                Point2::ORIGIN,
+                |&p: &Point2| {
+                    expanded.set(expanded.get() + 1);
+                    Direction::ALL.map(|d| (p.step(d), 1))
+                },
+                |&p| {
+                    if use_heuristic {
+                        p.manhattan(target)
+                    } else {
+                        0
+                    }
+                },
+                |&p| p == target,
+            );
+            assert_eq!(result.map(|r| r.cost), Some(30));
+            expanded.get()
+        };
+        assert!(count(true) * 10 < count(false));
+    }
+
+    #[test]
+    fn test_astar_unreachable() {
+        let result = astar(
+            0u8,
+            |&n| if n < 5 { vec![(n + 1, 1)] } else { Vec::new() },
+            |_| 0,
+            |&n| n == 9,
+        );
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_flood_fill_grid() {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let start = grid.find(|&c| c == 'S').unwrap();
+        let region = flood_fill(&grid, start, |&c| c != '#');
+        assert_eq!(
+            region.len(),
+            bfs_distances(start, open_neighbors(&grid)).len()
+        );
+        assert!(flood_fill(&grid, Point2::new(2, 0), |&c| c != '#').is_empty());
+        assert!(flood_fill(&grid, Point2::new(-1, 0), |_| true).is_empty());
+        // Walls split into separate regions
+        let walls = flood_fill(&grid, Point2::new(2, 0), |&c| c == '#');
+        assert_eq!(walls.len(), 3);
+    }
+
+    #[test]
+    fn test_flood_fill_set_enclosed_area() {
+        // A loop of lava cubes in the plane: fill the outside within a margin, and
+        // whatever is neither outside nor lava is enclosed
+        let shape = "\
+.....
+.###.
+.#.#.
+.###.
+.....
+";
+        let grid = Grid::parse(shape, |c| c == '#').unwrap();
+        let lava: HashSet<Point2> = grid.iter().filter(|(_, &l)| l).map(|(p, _)| p).collect();
+        let in_box = |p: Point2| (-1..=5).contains(&p.x) && (-1..=5).contains(&p.y);
+        let outside = flood_fill_set(&lava, Point2::new(-1, -1), |p, l| !l && in_box(p));
+        assert_eq!(outside.len(), 49 - 8 - 1);
+        let shape_region = flood_fill_set(&lava, Point2::new(1, 1), |_, l| l);
+        assert_eq!(shape_region, lava);
+        assert!(flood_fill_set(&lava, Point2::new(2, 2), |_, l| l).is_empty());
+    }
+}
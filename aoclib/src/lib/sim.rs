@@ -0,0 +1,21 @@
+//! Simulations of mechanics that recur across puzzles.
+
+mod automaton;
+mod cascade;
+mod diffusion;
+mod disk;
+mod monkeys;
+mod patrol;
+mod recorder;
+mod robots;
+mod warehouse;
+
+pub use automaton::{parse_enhancement, Image};
+pub use cascade::{cascade_step, Cascade};
+pub use diffusion::{diffuse, diffuse_until_stable, empty_ground};
+pub use disk::{checksum, compact, parse_disk_map, Compaction};
+pub use monkeys::{Monkey, Monkeys, Operation, Relief};
+pub use patrol::{loop_obstacles, patrol, patrol_loops, Patrol};
+pub use recorder::Recorder;
+pub use robots::{Robot, Robots};
+pub use warehouse::{gps_sum, push, widen, Tile, Warehouse};
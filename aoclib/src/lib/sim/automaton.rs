@@ -0,0 +1,243 @@
+use crate::lib::grid::Grid;
+use crate::lib::point::Point2;
+use std::error::Error;
+
+/// An infinite two-state image: a finite window of cells plus the state of every cell
+/// outside it.
+///
+/// Each [`step`](Image::step) replaces every cell with a rule applied to its 3x3
+/// neighbourhood. The cells outside the window all see the same neighbourhood, so they
+/// stay uniform, but they are *not* necessarily off: a rule that turns an all-dark
+/// neighbourhood on lights the whole infinite background, and the next step may turn
+/// it off again. Tracking the background explicitly keeps counts right for such rules,
+/// which is the trench-map gotcha.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// The cells inside the window.
+    pub grid: Grid<bool>,
+    /// The state of every cell outside the window.
+    pub background: bool,
+}
+
+impl Image {
+    /// Parses a block of `#` (lit) and `.` (dark) cells on a dark background.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on any other character or if the rows differ in length.
+    pub fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let chars = Grid::parse(input, |c| c)?;
+        if let Some((p, c)) = chars.iter().find(|(_, &c)| c != '#' && c != '.') {
+            return Err(format!("Unknown pixel '{}' at {:?}", c, p).into());
+        }
+        Ok(Image {
+            grid: chars.map(|&c| c == '#'),
+            background: false,
+        })
+    }
+
+    /// Returns the state of the cell at `p`, which may lie outside the window.
+    pub fn get(&self, p: Point2) -> bool {
+        self.grid.get(p).copied().unwrap_or(self.background)
+    }
+
+    /// Returns the 9-bit index of the neighbourhood of `p`, reading the 3x3 block row by
+    /// row with the top-left cell as the most significant bit.
+    pub fn window(&self, p: Point2) -> usize {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| Point2::new(dx, dy)))
+            .fold(0, |acc, d| (acc << 1) | self.get(p + d) as usize)
+    }
+
+    /// Applies `rule` to every cell's neighbourhood index (see [`Image::window`]),
+    /// including the background.
+    ///
+    /// The window grows by one cell on every side, since cells next to it can change
+    /// even when the background does not.
+    pub fn step<F>(&self, mut rule: F) -> Image
+    where
+        F: FnMut(usize) -> bool,
+    {
+        let (width, height) = (self.grid.width() + 2, self.grid.height() + 2);
+        let mut grid = Grid::new(width, height, false);
+        for p in grid.positions().collect::<Vec<_>>() {
+            grid[p] = rule(self.window(p - Point2::new(1, 1)));
+        }
+        let background = rule(if self.background { 0b111_111_111 } else { 0 });
+        Image { grid, background }
+    }
+
+    /// Applies an image-enhancement algorithm: a 512-entry lookup table indexed by
+    /// [`Image::window`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::sim::{parse_enhancement, Image};
+    ///
+    /// // Lights a cell whenever its neighbourhood is entirely dark, so the background
+    /// // blinks on and off
+    /// let mut rule = parse_enhancement(&".".repeat(512)).unwrap();
+    /// rule[0] = true;
+    /// let image = Image::parse("#").unwrap().enhance(&rule);
+    /// assert!(image.background);
+    /// assert_eq!(image.lit_count(), None);
+    /// // The background goes dark again; only the middle of the dark 3x3 block lights up
+    /// assert_eq!(image.enhance(&rule).lit_count(), Some(1));
+    /// ```
+    pub fn enhance(&self, rule: &[bool; 512]) -> Image {
+        self.step(|i| rule[i])
+    }
+
+    /// Returns the number of lit cells, or `None` if the background is lit and the
+    /// count is infinite.
+    pub fn lit_count(&self) -> Option<usize> {
+        (!self.background).then(|| self.grid.iter().filter(|(_, &lit)| lit).count())
+    }
+}
+
+/// Parses a 512-character enhancement algorithm of `#` and `.`. Line breaks inside it
+/// are ignored.
+///
+/// # Errors
+///
+/// Returns an error on any other character or if there are not exactly 512 entries.
+pub fn parse_enhancement(input: &str) -> Result<[bool; 512], Box<dyn Error>> {
+    let bits: Vec<bool> = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(format!("Unknown enhancement character '{}'", c)),
+        })
+        .collect::<Result<_, _>>()?;
+    let len = bits.len();
+    bits.try_into()
+        .map_err(|_| format!("Enhancement has {} entries, expected 512", len).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A deterministic pseudo-random rule with the given background behaviour.
+    fn rule(seed: u64, dark_to_lit: bool, lit_to_lit: bool) -> [bool; 512] {
+        let mut state = seed;
+        let mut rule = [false; 512];
+        for entry in rule.iter_mut() {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            *entry = state >> 63 == 1;
+        }
+        rule[0] = dark_to_lit;
+        rule[511] = lit_to_lit;
+        rule
+    }
+
+    /// Simulates the same rule on a large, finite, explicitly padded area.
+    fn brute_force(image: &Image, rule: &[bool; 512], steps: usize) -> Vec<Vec<bool>> {
+        let pad = steps as isize * 2 + 2;
+        let (w, h) = (image.grid.width() as isize, image.grid.height() as isize);
+        let mut cells: Vec<Vec<bool>> = (-pad..h + pad)
+            .map(|y| {
+                (-pad..w + pad)
+                    .map(|x| image.get(Point2::new(x, y)))
+                    .collect()
+            })
+            .collect();
+        for _ in 0..steps {
+            let get = |x: isize, y: isize| {
+                let row = y.clamp(0, cells.len() as isize - 1) as usize;
+                let col = x.clamp(0, cells[0].len() as isize - 1) as usize;
+                cells[row][col]
+            };
+            cells = (0..cells.len() as isize)
+                .map(|y| {
+                    (0..cells[0].len() as isize)
+                        .map(|x| {
+                            let mut index = 0;
+                            for dy in -1..=1 {
+                                for dx in -1..=1 {
+                                    index = (index << 1) | get(x + dx, y + dy) as usize;
+                                }
+                            }
+                            rule[index]
+                        })
+                        .collect()
+                })
+                .collect();
+        }
+        // Crop to the area the incremental window covers
+        let keep = pad - steps as isize;
+        cells[keep as usize..cells.len() - keep as usize]
+            .iter()
+            .map(|row| row[keep as usize..row.len() - keep as usize].to_vec())
+            .collect()
+    }
+
+    const IMAGE: &str = "#..#.\n#....\n##..#\n..#..\n..###\n";
+
+    #[test]
+    fn test_parse() {
+        let image = Image::parse(IMAGE).unwrap();
+        assert_eq!(image.lit_count(), Some(10));
+        assert!(!image.background);
+        assert_eq!(image.window(Point2::new(2, 2)), 34);
+        assert!(Image::parse("#x").is_err());
+        assert!(parse_enhancement(&"#".repeat(511)).is_err());
+        assert!(parse_enhancement(&"#.\n".repeat(256)).is_ok());
+    }
+
+    #[test]
+    fn test_enhance_matches_brute_force() {
+        let image = Image::parse(IMAGE).unwrap();
+        for (dark_to_lit, lit_to_lit) in
+            [(false, false), (true, false), (false, true), (true, true)]
+        {
+            let rule = rule(7, dark_to_lit, lit_to_lit);
+            let mut current = image.clone();
+            for steps in 1..=4 {
+                current = current.enhance(&rule);
+                let expected = brute_force(&image, &rule, steps);
+                let actual: Vec<Vec<bool>> = current.grid.rows().map(<[bool]>::to_vec).collect();
+                assert_eq!(
+                    actual, expected,
+                    "rule ({}, {}), {} steps",
+                    dark_to_lit, lit_to_lit, steps
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_background_flips() {
+        // The trench-map shape: dark neighbourhoods light up, lit ones go dark
+        let rule = rule(3, true, false);
+        let image = Image::parse(IMAGE).unwrap();
+        let once = image.enhance(&rule);
+        let twice = once.enhance(&rule);
+        assert!(once.background);
+        assert_eq!(once.lit_count(), None);
+        assert!(!twice.background);
+        assert!(twice.lit_count().is_some());
+        assert_eq!((twice.grid.width(), twice.grid.height()), (9, 9));
+    }
+
+    #[test]
+    fn test_step_with_life_rule() {
+        // Conway's life as a window rule: a blinker oscillates with period 2
+        let life = |window: usize| {
+            let alive = window & 0b000_010_000 != 0;
+            let neighbours = (window & !0b000_010_000).count_ones();
+            neighbours == 3 || (alive && neighbours == 2)
+        };
+        let blinker = Image::parse(".....\n..#..\n..#..\n..#..\n.....").unwrap();
+        let next = blinker.step(life);
+        assert_eq!(next.lit_count(), Some(3));
+        // The window grew by one, so the original (1..=3, 2) is now (2..=4, 3)
+        assert!((2..=4).all(|x| next.get(Point2::new(x, 3))));
+        assert_eq!(next.step(life).lit_count(), Some(3));
+    }
+}
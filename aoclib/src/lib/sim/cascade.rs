@@ -0,0 +1,132 @@
+use crate::lib::grid::Grid;
+use crate::lib::point::Point2;
+
+/// What happened during one [`cascade_step`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cascade {
+    /// How many cells flashed.
+    pub flashes: usize,
+    /// Whether every cell flashed at once (never true for an empty grid).
+    pub all_flashed: bool,
+}
+
+/// Advances a chain-reaction energy grid by one step.
+///
+/// Every cell gains `increment` energy. Any cell whose energy then exceeds `threshold`
+/// flashes, giving `increment` energy to each of its eight neighbours, which may push
+/// them over the threshold in turn. A cell flashes at most once per step. Finally,
+/// every cell that flashed is set to `reset`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::sim::cascade_step;
+/// use aoclib::Grid;
+///
+/// let input = "11111\n19991\n19191\n19991\n11111";
+/// let mut grid = Grid::parse(input, |c| c.to_digit(10).unwrap()).unwrap();
+/// let step = cascade_step(&mut grid, 1, 9, 0);
+/// assert_eq!(step.flashes, 9);
+/// assert_eq!(grid.rows().next().unwrap(), &[3, 4, 5, 4, 3]);
+/// ```
+pub fn cascade_step(grid: &mut Grid<u32>, increment: u32, threshold: u32, reset: u32) -> Cascade {
+    let mut flashed = Grid::new(grid.width(), grid.height(), false);
+    let mut pending: Vec<Point2> = Vec::new();
+
+    let positions: Vec<Point2> = grid.positions().collect();
+    for &p in &positions {
+        grid[p] = grid[p].saturating_add(increment);
+        if grid[p] > threshold {
+            flashed[p] = true;
+            pending.push(p);
+        }
+    }
+
+    let mut flashes = 0;
+    while let Some(p) = pending.pop() {
+        flashes += 1;
+        for q in p.neighbors_within(grid.width(), grid.height()) {
+            grid[q] = grid[q].saturating_add(increment);
+            if grid[q] > threshold && !flashed[q] {
+                flashed[q] = true;
+                pending.push(q);
+            }
+        }
+    }
+
+    for &p in &positions {
+        if flashed[p] {
+            grid[p] = reset;
+        }
+    }
+    Cascade {
+        flashes,
+        all_flashed: !positions.is_empty() && flashes == positions.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526
+";
+
+    fn energy(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_flash_counts() {
+        let mut grid = energy(EXAMPLE);
+        let mut total = 0;
+        for step in 1..=100 {
+            total += cascade_step(&mut grid, 1, 9, 0).flashes;
+            if step == 10 {
+                assert_eq!(total, 204);
+            }
+        }
+        assert_eq!(total, 1656);
+    }
+
+    #[test]
+    fn test_first_synchronised_step() {
+        let mut grid = energy(EXAMPLE);
+        let step = (1..).find(|_| cascade_step(&mut grid, 1, 9, 0).all_flashed);
+        assert_eq!(step, Some(195));
+        assert!(grid.iter().all(|(_, &e)| e == 0));
+    }
+
+    #[test]
+    fn test_no_flash() {
+        let mut grid = energy("12\n34");
+        let step = cascade_step(&mut grid, 2, 9, 0);
+        assert_eq!(
+            step,
+            Cascade {
+                flashes: 0,
+                all_flashed: false
+            }
+        );
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&[3, 4][..], &[5, 6]]);
+    }
+
+    #[test]
+    fn test_custom_reset_and_empty_grid() {
+        let mut grid = energy("9");
+        assert!(cascade_step(&mut grid, 1, 9, 7).all_flashed);
+        assert_eq!(grid[Point2::ORIGIN], 7);
+        let mut empty: Grid<u32> = Grid::new(0, 0, 0);
+        assert!(!cascade_step(&mut empty, 1, 9, 0).all_flashed);
+    }
+}
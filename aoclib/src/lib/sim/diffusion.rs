@@ -0,0 +1,156 @@
+use crate::lib::point::{Direction, Point2};
+use std::collections::{HashMap, HashSet};
+
+/// Plays one round of propose-then-move diffusion, returning how many points moved.
+///
+/// Every point with a neighbour in any of its eight surrounding cells proposes to step
+/// in the first direction of `proposal_order` whose three cells on that side (straight
+/// ahead and both diagonals) are empty. Points with no neighbours stay put, as do points
+/// with no free side. Then every point whose target cell was proposed by no other point
+/// moves; colliding proposals are all cancelled. Finally the first direction of
+/// `proposal_order` moves to the back, ready for the next round.
+///
+/// A round where nothing moves is stable, and so is every round after it.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::sim::diffuse;
+/// use aoclib::{Direction, Point2};
+/// use std::collections::HashSet;
+///
+/// let mut elves: HashSet<Point2> = [(2, 1), (3, 1), (2, 2), (2, 4), (3, 4)]
+///     .into_iter()
+///     .map(Point2::from)
+///     .collect();
+/// let mut order = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+/// let rounds = (1..).find(|_| diffuse(&mut elves, &mut order) == 0);
+/// assert_eq!(rounds, Some(4));
+/// ```
+pub fn diffuse(points: &mut HashSet<Point2>, proposal_order: &mut [Direction]) -> usize {
+    let mut proposals: HashMap<Point2, Vec<Point2>> = HashMap::new();
+    for &p in points.iter() {
+        if !p.neighbors8().any(|q| points.contains(&q)) {
+            continue;
+        }
+        let occupied = |d: Point2| points.contains(&(p + d));
+        let free = proposal_order
+            .iter()
+            .find(|&&dir| side(dir).iter().all(|&d| !occupied(d)));
+        if let Some(&dir) = free {
+            proposals.entry(p.step(dir)).or_default().push(p);
+        }
+    }
+
+    let mut moved = 0;
+    for (target, from) in proposals {
+        if let [from] = from[..] {
+            points.remove(&from);
+            points.insert(target);
+            moved += 1;
+        }
+    }
+    if !proposal_order.is_empty() {
+        proposal_order.rotate_left(1);
+    }
+    moved
+}
+
+/// Runs [`diffuse`] until a round where nothing moves, returning the number of that
+/// round (counting from 1).
+pub fn diffuse_until_stable(
+    points: &mut HashSet<Point2>,
+    proposal_order: &mut [Direction],
+) -> usize {
+    (1..)
+        .find(|_| diffuse(points, proposal_order) == 0)
+        .expect("rounds are unbounded")
+}
+
+/// Returns the number of empty cells in the smallest rectangle containing every point.
+pub fn empty_ground(points: &HashSet<Point2>) -> usize {
+    let (Some(min_x), Some(max_x)) = (
+        points.iter().map(|p| p.x).min(),
+        points.iter().map(|p| p.x).max(),
+    ) else {
+        return 0;
+    };
+    let min_y = points.iter().map(|p| p.y).min().unwrap_or(0);
+    let max_y = points.iter().map(|p| p.y).max().unwrap_or(0);
+    let area = (max_x - min_x + 1) as usize * (max_y - min_y + 1) as usize;
+    area - points.len()
+}
+
+/// Returns the three offsets on the `dir` side of a cell.
+fn side(dir: Direction) -> [Point2; 3] {
+    let ahead = dir.delta();
+    let across = dir.turn_right().delta();
+    [ahead, ahead + across, ahead - across]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::grid::Grid;
+
+    const EXAMPLE: &str = "\
+....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..
+";
+
+    const ORDER: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    fn elves(input: &str) -> HashSet<Point2> {
+        let grid = Grid::parse(input, |c| c == '#').unwrap();
+        grid.iter()
+            .filter(|(_, &elf)| elf)
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    #[test]
+    fn test_small_example() {
+        let mut points = elves(".....\n..##.\n..#..\n.....\n..##.\n.....");
+        let mut order = ORDER;
+        assert_eq!(diffuse(&mut points, &mut order), 3);
+        assert_eq!(order[0], Direction::Down);
+        assert_eq!(points, elves("..##.\n.....\n..#..\n...#.\n..#..\n....."));
+        assert_eq!(diffuse_until_stable(&mut points, &mut order), 3);
+        assert_eq!(points, elves("..#..\n....#\n#....\n....#\n.....\n..#.."));
+    }
+
+    #[test]
+    fn test_larger_example() {
+        let mut points = elves(EXAMPLE);
+        let mut order = ORDER;
+        for _ in 0..10 {
+            diffuse(&mut points, &mut order);
+        }
+        assert_eq!(empty_ground(&points), 110);
+        assert_eq!(points.len(), 22);
+
+        let mut points = elves(EXAMPLE);
+        let mut order = ORDER;
+        assert_eq!(diffuse_until_stable(&mut points, &mut order), 20);
+    }
+
+    #[test]
+    fn test_isolated_and_empty() {
+        let mut points: HashSet<Point2> = [Point2::new(0, 0), Point2::new(5, 5)].into();
+        let mut order = ORDER;
+        assert_eq!(diffuse(&mut points, &mut order), 0);
+        assert_eq!(empty_ground(&points), 34);
+        assert_eq!(empty_ground(&HashSet::new()), 0);
+        assert_eq!(diffuse(&mut HashSet::new(), &mut []), 0);
+    }
+}
@@ -0,0 +1,217 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// How [`compact`] moves data to the front of the disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compaction {
+    /// Moves single blocks from the end of the disk into the leftmost free block,
+    /// fragmenting files.
+    Blocks,
+    /// Moves each whole file, highest id first and only once, into the leftmost free
+    /// span that fits it, if that span is to the left of the file.
+    WholeFiles,
+}
+
+/// Expands a dense disk map into blocks: digits alternate between the length of a file
+/// and the length of the free space after it, and files are numbered from 0 in order.
+/// Free blocks are `None`.
+///
+/// # Errors
+///
+/// Returns an error naming the first character that is not a digit.
+pub fn parse_disk_map(disk_map: &str) -> Result<Vec<Option<usize>>, String> {
+    let mut blocks = Vec::new();
+    for (i, c) in disk_map.trim().chars().enumerate() {
+        let len = c
+            .to_digit(10)
+            .ok_or_else(|| format!("Invalid disk map digit '{}' at position {}", c, i + 1))?;
+        let block = (i % 2 == 0).then_some(i / 2);
+        blocks.extend(std::iter::repeat_n(block, len as usize));
+    }
+    Ok(blocks)
+}
+
+/// Compacts the disk described by a dense disk map (see [`parse_disk_map`]) and
+/// returns the resulting blocks.
+///
+/// [`Compaction::WholeFiles`] keeps the free spans in one min-heap of start positions
+/// per span length, so finding the leftmost span that fits a file only peeks at the
+/// heaps for long enough spans instead of scanning the disk. Digits are at most 9, so
+/// there are rarely more than ten of them.
+///
+/// # Errors
+///
+/// Returns an error if the disk map is invalid.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::sim::{checksum, compact, Compaction};
+///
+/// let map = "2333133121414131402";
+/// assert_eq!(checksum(&compact(map, Compaction::Blocks).unwrap()), 1928);
+/// assert_eq!(checksum(&compact(map, Compaction::WholeFiles).unwrap()), 2858);
+/// ```
+pub fn compact(disk_map: &str, strategy: Compaction) -> Result<Vec<Option<usize>>, String> {
+    let mut blocks = parse_disk_map(disk_map)?;
+    match strategy {
+        Compaction::Blocks => compact_blocks(&mut blocks),
+        Compaction::WholeFiles => compact_files(&mut blocks),
+    }
+    Ok(blocks)
+}
+
+/// Returns the sum of each block's position times the id of the file in it.
+pub fn checksum(blocks: &[Option<usize>]) -> u64 {
+    blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, block)| block.map(|id| (i * id) as u64))
+        .sum()
+}
+
+fn compact_blocks(blocks: &mut [Option<usize>]) {
+    let (mut free, mut last) = (0, blocks.len());
+    loop {
+        while free < last && blocks[free].is_some() {
+            free += 1;
+        }
+        while last > free && blocks[last - 1].is_none() {
+            last -= 1;
+        }
+        if free + 1 >= last {
+            break;
+        }
+        blocks.swap(free, last - 1);
+    }
+}
+
+fn compact_files(blocks: &mut [Option<usize>]) {
+    // Each file as (id, start, length), and free spans bucketed by length
+    let mut files = Vec::new();
+    let mut free: Vec<BinaryHeap<Reverse<usize>>> = Vec::new();
+    let mut i = 0;
+    while i < blocks.len() {
+        let len = blocks[i..].iter().take_while(|&&b| b == blocks[i]).count();
+        match blocks[i] {
+            Some(id) => files.push((id, i, len)),
+            None => {
+                if free.len() <= len {
+                    free.resize(len + 1, BinaryHeap::new());
+                }
+                free[len].push(Reverse(i));
+            }
+        }
+        i += len;
+    }
+
+    for &(id, start, len) in files.iter().rev() {
+        // The leftmost free span long enough for the file
+        let best = (len..free.len())
+            .filter_map(|l| free[l].peek().map(|&Reverse(s)| (s, l)))
+            .min();
+        let Some((target, span)) = best.filter(|&(s, _)| s < start) else {
+            continue;
+        };
+        free[span].pop();
+        if span > len {
+            free[span - len].push(Reverse(target + len));
+        }
+        blocks[target..target + len].fill(Some(id));
+        blocks[start..start + len].fill(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(blocks: &[Option<usize>]) -> String {
+        blocks
+            .iter()
+            .map(|b| b.map_or('.', |id| char::from_digit(id as u32, 10).unwrap()))
+            .collect()
+    }
+
+    const EXAMPLE: &str = "2333133121414131402";
+
+    #[test]
+    fn test_parse_disk_map() {
+        assert_eq!(render(&parse_disk_map("12345").unwrap()), "0..111....22222");
+        assert_eq!(
+            render(&parse_disk_map(EXAMPLE).unwrap()),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+        assert!(parse_disk_map("12a").unwrap_err().contains("position 3"));
+    }
+
+    #[test]
+    fn test_compact_blocks() {
+        let blocks = compact("12345", Compaction::Blocks).unwrap();
+        assert_eq!(render(&blocks), "022111222......");
+        let blocks = compact(EXAMPLE, Compaction::Blocks).unwrap();
+        assert_eq!(
+            render(&blocks),
+            "0099811188827773336446555566.............."
+        );
+        assert_eq!(checksum(&blocks), 1928);
+    }
+
+    #[test]
+    fn test_compact_whole_files() {
+        let blocks = compact(EXAMPLE, Compaction::WholeFiles).unwrap();
+        assert_eq!(
+            render(&blocks),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+        assert_eq!(checksum(&blocks), 2858);
+        // Nothing fits to the left, so nothing moves
+        assert_eq!(
+            render(&compact("119", Compaction::WholeFiles).unwrap()),
+            "0.111111111"
+        );
+    }
+
+    #[test]
+    fn test_whole_files_matches_scan() {
+        // A straightforward quadratic scan as a reference
+        fn scan(map: &str) -> Vec<Option<usize>> {
+            let mut blocks = parse_disk_map(map).unwrap();
+            let max_id = blocks.iter().flatten().max().copied().unwrap_or(0);
+            for id in (0..=max_id).rev() {
+                let Some(start) = blocks.iter().position(|&b| b == Some(id)) else {
+                    continue;
+                };
+                let len = blocks[start..]
+                    .iter()
+                    .take_while(|&&b| b == Some(id))
+                    .count();
+                let target = (0..start).find(|&s| blocks[s..s + len].iter().all(|b| b.is_none()));
+                if let Some(target) = target {
+                    blocks[target..target + len].fill(Some(id));
+                    blocks[start..start + len].fill(None);
+                }
+            }
+            blocks
+        }
+        let mut state = 9u64;
+        for _ in 0..50 {
+            let map: String = (0..41)
+                .map(|i: usize| {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    // Zero-length files merge the free spans around them
+                    let digit = (state >> 33) as u32 % 10;
+                    char::from_digit(if i % 5 == 2 { 0 } else { digit }, 10).unwrap()
+                })
+                .collect();
+            assert_eq!(
+                compact(&map, Compaction::WholeFiles).unwrap(),
+                scan(&map),
+                "{}",
+                map
+            );
+        }
+    }
+}
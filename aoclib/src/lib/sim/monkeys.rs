@@ -0,0 +1,309 @@
+use std::error::Error;
+
+/// How a monkey changes an item's worry level when inspecting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// `new = old + n`
+    Add(u64),
+    /// `new = old * n`
+    Multiply(u64),
+    /// `new = old * old`
+    Square,
+}
+
+impl Operation {
+    /// Applies the operation to a worry level.
+    pub fn apply(self, old: u128) -> u128 {
+        match self {
+            Operation::Add(n) => old + n as u128,
+            Operation::Multiply(n) => old * n as u128,
+            Operation::Square => old * old,
+        }
+    }
+}
+
+/// One monkey: the items it holds and how it decides where to throw them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkey {
+    /// Worry levels of the held items, in the order they will be inspected.
+    pub items: Vec<u64>,
+    /// How inspecting an item changes its worry level.
+    pub operation: Operation,
+    /// The divisibility test applied after inspection.
+    pub divisor: u64,
+    /// The monkey that receives items passing the test.
+    pub if_true: usize,
+    /// The monkey that receives items failing the test.
+    pub if_false: usize,
+}
+
+/// How worry levels are kept in check after each inspection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relief {
+    /// Worry is divided by three (rounding down) after every inspection.
+    DivideByThree,
+    /// There is no relief, so worry levels are reduced modulo the least common multiple
+    /// of every monkey's divisor. This keeps them small without changing the outcome
+    /// of any divisibility test.
+    Modulus,
+}
+
+/// A troop of monkeys playing keep-away with items.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::sim::{Monkeys, Relief};
+///
+/// let input = "\
+/// Monkey 0:
+///   Starting items: 79, 98
+///   Operation: new = old * 19
+///   Test: divisible by 23
+///     If true: throw to monkey 2
+///     If false: throw to monkey 3
+///
+/// Monkey 1:
+///   Starting items: 54, 65, 75, 74
+///   Operation: new = old + 6
+///   Test: divisible by 19
+///     If true: throw to monkey 2
+///     If false: throw to monkey 0
+///
+/// Monkey 2:
+///   Starting items: 79, 60, 97
+///   Operation: new = old * old
+///   Test: divisible by 13
+///     If true: throw to monkey 1
+///     If false: throw to monkey 3
+///
+/// Monkey 3:
+///   Starting items: 74
+///   Operation: new = old + 3
+///   Test: divisible by 17
+///     If true: throw to monkey 0
+///     If false: throw to monkey 1
+/// ";
+/// let mut monkeys = Monkeys::parse(input).unwrap();
+/// monkeys.run(20, Relief::DivideByThree);
+/// assert_eq!(monkeys.monkey_business(), 10605);
+///
+/// let mut monkeys = Monkeys::parse(input).unwrap();
+/// monkeys.run(10_000, Relief::Modulus);
+/// assert_eq!(monkeys.monkey_business(), 2713310158);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkeys {
+    monkeys: Vec<Monkey>,
+    inspections: Vec<u64>,
+    modulus: u64,
+}
+
+impl Monkeys {
+    /// Creates a troop from its monkeys.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a divisor is zero or a monkey throws to a monkey that does
+    /// not exist.
+    pub fn new(monkeys: Vec<Monkey>) -> Result<Self, String> {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            if monkey.divisor == 0 {
+                return Err(format!("Monkey {} tests divisibility by zero", i));
+            }
+            if let Some(target) = [monkey.if_true, monkey.if_false]
+                .into_iter()
+                .find(|&t| t >= monkeys.len())
+            {
+                return Err(format!("Monkey {} throws to unknown monkey {}", i, target));
+            }
+        }
+        let modulus = monkeys
+            .iter()
+            .fold(1, |acc, m| crate::lib::math::lcm(acc, m.divisor));
+        Ok(Monkeys {
+            inspections: vec![0; monkeys.len()],
+            monkeys,
+            modulus,
+        })
+    }
+
+    /// Parses blank-line separated `Monkey N:` blocks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first line that does not match the format.
+    pub fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let lines: Vec<&str> = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let monkeys = lines
+            .chunks(6)
+            .map(parse_monkey)
+            .collect::<Result<_, _>>()?;
+        Ok(Monkeys::new(monkeys)?)
+    }
+
+    /// Returns the monkeys in their current state.
+    pub fn monkeys(&self) -> &[Monkey] {
+        &self.monkeys
+    }
+
+    /// Returns how many items each monkey has inspected so far.
+    pub fn inspections(&self) -> &[u64] {
+        &self.inspections
+    }
+
+    /// Plays one round: each monkey in turn inspects and throws every item it holds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a worry level overflows `u64` under [`Relief::DivideByThree`], which
+    /// does not bound them.
+    pub fn round(&mut self, relief: Relief) {
+        for i in 0..self.monkeys.len() {
+            let items = std::mem::take(&mut self.monkeys[i].items);
+            self.inspections[i] += items.len() as u64;
+            let monkey = self.monkeys[i].clone();
+            for item in items {
+                let worry = monkey.operation.apply(item as u128);
+                let worry = match relief {
+                    Relief::DivideByThree => u64::try_from(worry / 3)
+                        .unwrap_or_else(|_| panic!("worry level {} overflowed u64", worry)),
+                    Relief::Modulus => (worry % self.modulus as u128) as u64,
+                };
+                let target = if worry.is_multiple_of(monkey.divisor) {
+                    monkey.if_true
+                } else {
+                    monkey.if_false
+                };
+                self.monkeys[target].items.push(worry);
+            }
+        }
+    }
+
+    /// Plays `rounds` rounds.
+    pub fn run(&mut self, rounds: usize, relief: Relief) {
+        for _ in 0..rounds {
+            self.round(relief);
+        }
+    }
+
+    /// Returns the product of the two highest inspection counts (or the only one, for a
+    /// single monkey).
+    pub fn monkey_business(&self) -> u64 {
+        let mut counts = self.inspections.clone();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        counts.iter().take(2).product()
+    }
+}
+
+fn parse_monkey(lines: &[&str]) -> Result<Monkey, Box<dyn Error>> {
+    let field = |i: usize, prefix: &str| -> Result<&str, Box<dyn Error>> {
+        let line = lines.get(i).copied().unwrap_or("");
+        line.strip_prefix(prefix)
+            .map(str::trim)
+            .ok_or_else(|| format!("Expected '{}', got '{}'", prefix, line).into())
+    };
+    field(0, "Monkey ")?;
+    let items = field(1, "Starting items:")?
+        .split(',')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| item.trim().parse())
+        .collect::<Result<_, _>>()?;
+    let operation = match field(2, "Operation: new = old")?.split_once(' ') {
+        Some(("*", "old")) => Operation::Square,
+        Some(("+", "old")) => Operation::Multiply(2),
+        Some(("*", n)) => Operation::Multiply(n.parse()?),
+        Some(("+", n)) => Operation::Add(n.parse()?),
+        _ => return Err(format!("Unknown operation '{}'", lines[2]).into()),
+    };
+    Ok(Monkey {
+        items,
+        operation,
+        divisor: field(3, "Test: divisible by")?.parse()?,
+        if_true: field(4, "If true: throw to monkey")?.parse()?,
+        if_false: field(5, "If false: throw to monkey")?.parse()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+
+    #[test]
+    fn test_first_round() {
+        let mut monkeys = Monkeys::parse(EXAMPLE).unwrap();
+        monkeys.round(Relief::DivideByThree);
+        let items: Vec<&[u64]> = monkeys.monkeys().iter().map(|m| &m.items[..]).collect();
+        assert_eq!(
+            items,
+            vec![
+                &[20, 23, 27, 26][..],
+                &[2080, 25, 167, 207, 401, 1046],
+                &[],
+                &[]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inspection_counts() {
+        let mut monkeys = Monkeys::parse(EXAMPLE).unwrap();
+        monkeys.run(20, Relief::DivideByThree);
+        assert_eq!(monkeys.inspections(), &[101, 95, 7, 105]);
+
+        let mut monkeys = Monkeys::parse(EXAMPLE).unwrap();
+        monkeys.run(20, Relief::Modulus);
+        assert_eq!(monkeys.inspections(), &[99, 97, 8, 103]);
+        monkeys.run(980, Relief::Modulus);
+        assert_eq!(monkeys.inspections(), &[5204, 4792, 199, 5192]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Monkeys::parse("Monkey 0:\n  Starting items: 1").is_err());
+        let bad_target = EXAMPLE.replace("throw to monkey 3", "throw to monkey 9");
+        let err = Monkeys::parse(&bad_target).unwrap_err().to_string();
+        assert_eq!(err, "Monkey 0 throws to unknown monkey 9");
+        let bad_op = EXAMPLE.replace("old + 3", "old - 3");
+        assert!(Monkeys::parse(&bad_op).is_err());
+        let doubled = EXAMPLE.replace("old + 3", "old + old");
+        assert_eq!(
+            Monkeys::parse(&doubled).unwrap().monkeys()[3].operation,
+            Operation::Multiply(2)
+        );
+    }
+}
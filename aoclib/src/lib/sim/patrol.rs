@@ -0,0 +1,259 @@
+use crate::lib::grid::Grid;
+use crate::lib::point::{Direction, Point2};
+use std::num::NonZeroUsize;
+use std::thread;
+
+/// The outcome of walking a guard through a grid with [`patrol`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patrol {
+    /// Every `(position, facing)` state in the order the guard occupied it, starting
+    /// with the start state. Turning in place adds a state at the same position.
+    pub path: Vec<(Point2, Direction)>,
+    /// `true` if the guard came back to an earlier state instead of leaving the grid.
+    pub looped: bool,
+}
+
+impl Patrol {
+    /// Returns the distinct positions on the path, in the order they were first visited.
+    pub fn visited(&self) -> Vec<Point2> {
+        let mut seen = std::collections::HashSet::new();
+        self.path
+            .iter()
+            .map(|&(p, _)| p)
+            .filter(|&p| seen.insert(p))
+            .collect()
+    }
+}
+
+/// Walks a guard through `grid` until it leaves the grid or starts repeating itself.
+///
+/// Cells set to `true` are obstacles. Each tick the guard steps forward if the cell in
+/// front of it is free, leaves the grid if that cell is out of bounds, and otherwise
+/// faces `turn_rule(facing)` without moving. A start position outside the grid yields
+/// an empty path.
+///
+/// # Arguments
+///
+/// * `grid` - The obstacle map.
+/// * `start` - The guard's starting position and facing.
+/// * `turn_rule` - The new facing after bumping into an obstacle, e.g.
+///   [`Direction::turn_right`].
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{sim, Direction, Grid, Point2};
+///
+/// let grid = Grid::parse(".#.\n...\n", |c| c == '#').unwrap();
+/// let patrol = sim::patrol(&grid, (Point2::new(1, 1), Direction::Up), Direction::turn_right);
+/// assert!(!patrol.looped);
+/// assert_eq!(patrol.visited(), vec![Point2::new(1, 1), Point2::new(2, 1)]);
+/// ```
+pub fn patrol<F>(grid: &Grid<bool>, start: (Point2, Direction), turn_rule: F) -> Patrol
+where
+    F: Fn(Direction) -> Direction,
+{
+    let mut path = Vec::new();
+    let looped = walk(grid, start, None, &turn_rule, |p, d| path.push((p, d)));
+    Patrol { path, looped }
+}
+
+/// Returns whether the guard from [`patrol`] ends up in a loop, optionally with one
+/// extra obstacle placed at `extra_obstacle`.
+///
+/// This does not record the path, so it is the cheap check to run once per candidate
+/// when searching for obstacles that trap the guard.
+pub fn patrol_loops<F>(
+    grid: &Grid<bool>,
+    start: (Point2, Direction),
+    extra_obstacle: Option<Point2>,
+    turn_rule: F,
+) -> bool
+where
+    F: Fn(Direction) -> Direction,
+{
+    walk(grid, start, extra_obstacle, &turn_rule, |_, _| {})
+}
+
+/// Finds every free cell where a single added obstacle makes the guard loop forever.
+///
+/// Only cells on the guard's original path can change its route, so those are the
+/// candidates; the start position is never one. Candidates are checked in parallel
+/// across the available CPU cores and returned in the order the guard first reaches
+/// them.
+pub fn loop_obstacles<F>(grid: &Grid<bool>, start: (Point2, Direction), turn_rule: F) -> Vec<Point2>
+where
+    F: Fn(Direction) -> Direction + Sync,
+{
+    let candidates: Vec<Point2> = patrol(grid, start, &turn_rule)
+        .visited()
+        .into_iter()
+        .filter(|&p| p != start.0)
+        .collect();
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = candidates.len().div_ceil(threads).max(1);
+    let turn_rule = &turn_rule;
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = candidates
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .copied()
+                        .filter(|&p| patrol_loops(grid, start, Some(p), turn_rule))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("patrol worker panicked"))
+            .collect()
+    })
+}
+
+/// Runs the patrol, calling `visit` for every state, and returns whether it looped.
+fn walk<F, V>(
+    grid: &Grid<bool>,
+    (mut pos, mut facing): (Point2, Direction),
+    extra_obstacle: Option<Point2>,
+    turn_rule: &F,
+    mut visit: V,
+) -> bool
+where
+    F: Fn(Direction) -> Direction,
+    V: FnMut(Point2, Direction),
+{
+    if !grid.in_bounds(pos) {
+        return false;
+    }
+    // One bit per facing for every cell
+    let mut seen = vec![0u8; grid.width() * grid.height()];
+
+    loop {
+        let index = pos.y as usize * grid.width() + pos.x as usize;
+        let bit = 1 << facing.index();
+        if seen[index] & bit != 0 {
+            return true;
+        }
+        seen[index] |= bit;
+        visit(pos, facing);
+
+        let ahead = pos.step(facing);
+        match grid.get(ahead) {
+            None => return false,
+            Some(&blocked) if blocked || Some(ahead) == extra_obstacle => {
+                facing = turn_rule(facing)
+            }
+            Some(_) => pos = ahead,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    fn example() -> (Grid<bool>, (Point2, Direction)) {
+        let chars = Grid::parse(EXAMPLE, |c| c).unwrap();
+        let start = chars.find(|&c| c == '^').unwrap();
+        (chars.map(|&c| c == '#'), (start, Direction::Up))
+    }
+
+    #[test]
+    fn test_patrol_example_leaves_grid() {
+        let (grid, start) = example();
+        let patrol = patrol(&grid, start, Direction::turn_right);
+        assert!(!patrol.looped);
+        assert_eq!(patrol.visited().len(), 41);
+        assert_eq!(patrol.path[0], start);
+    }
+
+    #[test]
+    fn test_patrol_detects_loop() {
+        let grid = Grid::parse(".#..\n...#\n#...\n..#.\n", |c| c == '#').unwrap();
+        let patrol = patrol(
+            &grid,
+            (Point2::new(1, 1), Direction::Up),
+            Direction::turn_right,
+        );
+        assert!(patrol.looped);
+        assert_eq!(patrol.visited().len(), 4);
+    }
+
+    #[test]
+    fn test_patrol_boxed_in_counts_as_loop() {
+        let grid = Grid::parse(".#.\n#.#\n.#.\n", |c| c == '#').unwrap();
+        let start = (Point2::new(1, 1), Direction::Up);
+        let patrol = patrol(&grid, start, Direction::turn_right);
+        assert!(patrol.looped);
+        assert_eq!(patrol.path.len(), 4);
+    }
+
+    #[test]
+    fn test_patrol_start_out_of_bounds() {
+        let grid = Grid::new(2, 2, false);
+        let patrol = patrol(
+            &grid,
+            (Point2::new(5, 5), Direction::Up),
+            Direction::turn_right,
+        );
+        assert!(patrol.path.is_empty());
+        assert!(!patrol.looped);
+    }
+
+    #[test]
+    fn test_patrol_loops_with_extra_obstacle() {
+        let (grid, start) = example();
+        assert!(!patrol_loops(&grid, start, None, Direction::turn_right));
+        assert!(patrol_loops(
+            &grid,
+            start,
+            Some(Point2::new(3, 6)),
+            Direction::turn_right
+        ));
+    }
+
+    #[test]
+    fn test_loop_obstacles_example() {
+        let (grid, start) = example();
+        let mut found = loop_obstacles(&grid, start, Direction::turn_right);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                Point2::new(1, 8),
+                Point2::new(3, 6),
+                Point2::new(3, 8),
+                Point2::new(6, 7),
+                Point2::new(7, 7),
+                Point2::new(7, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_turn_rule_is_respected() {
+        let grid = Grid::parse(".#.\n...\n", |c| c == '#').unwrap();
+        let start = (Point2::new(1, 1), Direction::Up);
+        let right = patrol(&grid, start, Direction::turn_right);
+        let left = patrol(&grid, start, Direction::turn_left);
+        assert_eq!(right.visited(), vec![Point2::new(1, 1), Point2::new(2, 1)]);
+        assert_eq!(left.visited(), vec![Point2::new(1, 1), Point2::new(0, 1)]);
+    }
+}
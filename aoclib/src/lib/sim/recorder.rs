@@ -0,0 +1,291 @@
+use std::collections::VecDeque;
+
+/// Runs a simulation while keeping snapshots of its state, so it can be rewound to
+/// an earlier step or have a stretch of its history replayed without starting over.
+///
+/// A snapshot is taken every `interval` steps and only the latest `capacity` are
+/// kept, so memory stays bounded however long the simulation runs. Going back to a
+/// step restores the nearest snapshot at or before it and replays from there, which
+/// costs at most `interval - 1` calls to `step`. `step` must be deterministic for a
+/// replay to match the original run.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::sim::Recorder;
+///
+/// let mut sim = Recorder::new(1u64, |&n| n * 3 % 1000, 10, 2);
+/// sim.run(37);
+/// assert_eq!(sim.time(), 37);
+/// let later = *sim.state();
+///
+/// // Look back without losing our place
+/// assert_eq!(sim.replay(25, 27).unwrap(), vec![443, 329, 987]);
+/// assert_eq!(sim.state(), &later);
+///
+/// // Or go back and run again from there
+/// assert_eq!(sim.rewind(25), Ok(&443));
+/// sim.run(12);
+/// assert_eq!(sim.state(), &later);
+/// assert!(sim.rewind(5).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Recorder<S, F> {
+    state: S,
+    time: usize,
+    step: F,
+    interval: usize,
+    capacity: usize,
+    snapshots: VecDeque<(usize, S)>,
+}
+
+impl<S, F> Recorder<S, F>
+where
+    S: Clone,
+    F: FnMut(&S) -> S,
+{
+    /// Starts recording a simulation at `initial`, which counts as step 0 and is the
+    /// first snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` or `capacity` is zero.
+    pub fn new(initial: S, step: F, interval: usize, capacity: usize) -> Self {
+        assert!(interval > 0, "snapshot interval must be positive");
+        assert!(capacity > 0, "snapshot capacity must be positive");
+        let mut snapshots = VecDeque::with_capacity(capacity);
+        snapshots.push_back((0, initial.clone()));
+        Recorder {
+            state: initial,
+            time: 0,
+            step,
+            interval,
+            capacity,
+            snapshots,
+        }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns the number of steps taken to reach the current state.
+    pub fn time(&self) -> usize {
+        self.time
+    }
+
+    /// Returns the earliest step that can still be rewound to or replayed.
+    pub fn earliest(&self) -> usize {
+        self.snapshots.front().map_or(self.time, |&(t, _)| t)
+    }
+
+    /// Iterates over the kept snapshots, oldest first, with the step of each.
+    pub fn snapshots(&self) -> impl Iterator<Item = (usize, &S)> {
+        self.snapshots.iter().map(|(t, s)| (*t, s))
+    }
+
+    /// Advances one step, returning the new state.
+    pub fn step(&mut self) -> &S {
+        self.state = (self.step)(&self.state);
+        self.time += 1;
+        if self.time.is_multiple_of(self.interval) {
+            if self.snapshots.len() == self.capacity {
+                self.snapshots.pop_front();
+            }
+            self.snapshots.push_back((self.time, self.state.clone()));
+        }
+        &self.state
+    }
+
+    /// Advances `steps` steps, returning the new state.
+    pub fn run(&mut self, steps: usize) -> &S {
+        for _ in 0..steps {
+            self.step();
+        }
+        &self.state
+    }
+
+    /// Advances until `done` holds for the current state, giving up after `limit`
+    /// steps. Returns the step at which `done` first held, which is the current step
+    /// if it holds already.
+    pub fn run_until<P>(&mut self, mut done: P, limit: usize) -> Option<usize>
+    where
+        P: FnMut(&S) -> bool,
+    {
+        for _ in 0..limit {
+            if done(&self.state) {
+                return Some(self.time);
+            }
+            self.step();
+        }
+        done(&self.state).then_some(self.time)
+    }
+
+    /// Goes back to the state at step `time`, discarding the snapshots after it.
+    /// Running on from there repeats the original run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `time` is in the future or older than the earliest kept
+    /// snapshot.
+    pub fn rewind(&mut self, time: usize) -> Result<&S, String> {
+        let (at, state) = self.restore(time)?;
+        while self.snapshots.back().is_some_and(|&(t, _)| t > at) {
+            self.snapshots.pop_back();
+        }
+        self.state = state;
+        self.time = at;
+        while self.time < time {
+            self.step();
+        }
+        Ok(&self.state)
+    }
+
+    /// Returns the states at steps `from` to `to` inclusive, recomputed from the
+    /// snapshots, without changing the current state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `to` is before `from` or in the future, or `from` is older
+    /// than the earliest kept snapshot.
+    pub fn replay(&mut self, from: usize, to: usize) -> Result<Vec<S>, String> {
+        if to < from {
+            return Err(format!(
+                "Cannot replay backwards from step {} to {}",
+                from, to
+            ));
+        }
+        if to > self.time {
+            return Err(format!(
+                "Step {} has not happened yet; the simulation is at step {}",
+                to, self.time
+            ));
+        }
+        let (mut at, mut state) = self.restore(from)?;
+        let mut states = Vec::with_capacity(to - from + 1);
+        loop {
+            if at >= from {
+                states.push(state.clone());
+            }
+            if at == to {
+                return Ok(states);
+            }
+            state = (self.step)(&state);
+            at += 1;
+        }
+    }
+
+    /// Returns a copy of the latest snapshot at or before `time`.
+    fn restore(&self, time: usize) -> Result<(usize, S), String> {
+        if time > self.time {
+            return Err(format!(
+                "Step {} has not happened yet; the simulation is at step {}",
+                time, self.time
+            ));
+        }
+        self.snapshots
+            .iter()
+            .rev()
+            .find(|&&(t, _)| t <= time)
+            .map(|(t, s)| (*t, s.clone()))
+            .ok_or_else(|| {
+                format!(
+                    "Step {} is older than the earliest snapshot, at step {}",
+                    time,
+                    self.earliest()
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::vm::Computer;
+
+    fn lcg(n: &u64) -> u64 {
+        n.wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407)
+    }
+
+    fn states(steps: usize) -> Vec<u64> {
+        std::iter::successors(Some(7), |n| Some(lcg(n)))
+            .take(steps + 1)
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshots_ring() {
+        let mut sim = Recorder::new(7u64, lcg, 5, 3);
+        sim.run(23);
+        let kept: Vec<usize> = sim.snapshots().map(|(t, _)| t).collect();
+        assert_eq!(kept, vec![10, 15, 20]);
+        assert_eq!(sim.earliest(), 10);
+        let expected = states(23);
+        assert!(sim.snapshots().all(|(t, &s)| s == expected[t]));
+        assert_eq!(sim.state(), &expected[23]);
+    }
+
+    #[test]
+    fn test_replay_and_rewind() {
+        let expected = states(40);
+        let mut sim = Recorder::new(7u64, lcg, 4, 6);
+        sim.run(40);
+        assert_eq!(sim.replay(23, 40).unwrap(), expected[23..=40].to_vec());
+        assert_eq!(sim.replay(30, 30).unwrap(), vec![expected[30]]);
+        assert_eq!(sim.time(), 40);
+
+        assert_eq!(sim.rewind(26), Ok(&expected[26]));
+        assert_eq!(sim.time(), 26);
+        let kept: Vec<usize> = sim.snapshots().map(|(t, _)| t).collect();
+        assert_eq!(kept, vec![20, 24]);
+        sim.run(14);
+        assert_eq!(sim.state(), &expected[40]);
+        assert_eq!(sim.rewind(40), Ok(&expected[40]));
+
+        assert!(sim.rewind(41).unwrap_err().contains("not happened"));
+        assert!(sim
+            .replay(10, 12)
+            .unwrap_err()
+            .contains("earliest snapshot"));
+        assert!(sim.replay(30, 29).is_err());
+        assert!(sim.replay(30, 41).is_err());
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut sim = Recorder::new(0u32, |&n| n + 3, 2, 2);
+        assert_eq!(sim.run_until(|&n| n >= 10, 100), Some(4));
+        assert_eq!(sim.run_until(|&n| n >= 10, 100), Some(4));
+        assert_eq!(sim.run_until(|&n| n > 100, 5), None);
+        assert_eq!(sim.time(), 9);
+    }
+
+    #[test]
+    fn test_records_vm_run() {
+        // Step the VM one output at a time by running with the remaining bits of A
+        let program = "\
+Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+        let vm = Computer::parse(program).unwrap();
+        let full = vm.run().unwrap();
+        let mut sim = Recorder::new(
+            (729u64, Vec::new()),
+            |(a, out): &(u64, Vec<u8>)| {
+                let mut out = out.clone();
+                out.push((a / 2 % 8) as u8);
+                (a / 2, out)
+            },
+            3,
+            2,
+        );
+        sim.run_until(|(a, _)| *a == 0, 100);
+        assert_eq!(sim.state().1, full);
+        let back = sim.rewind(sim.time() - 2).unwrap().clone();
+        assert_eq!(back.1, full[..full.len() - 2]);
+    }
+}
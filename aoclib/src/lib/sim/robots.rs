@@ -0,0 +1,267 @@
+use crate::lib::point::Point2;
+use std::error::Error;
+
+/// A robot moving in a straight line at constant velocity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Robot {
+    /// Position at time 0.
+    pub position: Point2,
+    /// Displacement per second.
+    pub velocity: Point2,
+}
+
+/// A swarm of robots on a grid whose edges wrap around (a torus).
+///
+/// Since robots never interact, the position of every robot after `n` seconds can be
+/// computed directly instead of simulating each step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Robots {
+    width: isize,
+    height: isize,
+    robots: Vec<Robot>,
+}
+
+impl Robots {
+    /// Creates a swarm on a `width` x `height` torus.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either dimension is zero.
+    pub fn new(width: usize, height: usize, robots: Vec<Robot>) -> Self {
+        assert!(width > 0 && height > 0, "grid must not be empty");
+        Robots {
+            width: width as isize,
+            height: height as isize,
+            robots,
+        }
+    }
+
+    /// Parses one robot per line in the form `p=X,Y v=DX,DY`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first line that does not match the format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::sim::Robots;
+    /// use aoclib::Point2;
+    ///
+    /// let robots = Robots::parse("p=2,4 v=2,-3", 11, 7).unwrap();
+    /// assert_eq!(robots.positions_after(5), vec![Point2::new(1, 3)]);
+    /// ```
+    pub fn parse(input: &str, width: usize, height: usize) -> Result<Self, Box<dyn Error>> {
+        let robots = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_robot(line.trim()).ok_or_else(|| {
+                    format!("line {}: expected 'p=X,Y v=DX,DY', got '{}'", i + 1, line)
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Robots::new(width, height, robots))
+    }
+
+    /// Returns the robots in the swarm.
+    pub fn robots(&self) -> &[Robot] {
+        &self.robots
+    }
+
+    /// Returns the position of every robot after `n` seconds, in input order.
+    pub fn positions_after(&self, n: usize) -> Vec<Point2> {
+        // Reduce n first so the multiplication cannot overflow
+        let (nx, ny) = (
+            (n % self.width as usize) as isize,
+            (n % self.height as usize) as isize,
+        );
+        self.robots
+            .iter()
+            .map(|r| {
+                Point2::new(
+                    (r.position.x + r.velocity.x * nx).rem_euclid(self.width),
+                    (r.position.y + r.velocity.y * ny).rem_euclid(self.height),
+                )
+            })
+            .collect()
+    }
+
+    /// Counts the robots in each quadrant after `n` seconds.
+    ///
+    /// Quadrants are returned as `[top-left, top-right, bottom-left, bottom-right]`.
+    /// Robots exactly on the middle row or column of an odd-sized grid belong to no
+    /// quadrant.
+    pub fn quadrant_counts(&self, n: usize) -> [usize; 4] {
+        let (mid_x, mid_y) = (self.width / 2, self.height / 2);
+        let mut counts = [0; 4];
+        for p in self.positions_after(n) {
+            let left = p.x < mid_x;
+            let right = p.x >= self.width - mid_x;
+            let top = p.y < mid_y;
+            let bottom = p.y >= self.height - mid_y;
+            match (left, right, top, bottom) {
+                (true, _, true, _) => counts[0] += 1,
+                (_, true, true, _) => counts[1] += 1,
+                (true, _, _, true) => counts[2] += 1,
+                (_, true, _, true) => counts[3] += 1,
+                _ => {}
+            }
+        }
+        counts
+    }
+
+    /// Finds the first second at which the robots are most tightly clustered, such as
+    /// the moment they line up into a picture.
+    ///
+    /// The x coordinates repeat every `width` seconds and the y coordinates every
+    /// `height` seconds, so the time of minimum variance is found independently for
+    /// each axis and the two are combined with the Chinese remainder theorem. This
+    /// takes `width + height` projections instead of `width * height`.
+    ///
+    /// Returns `None` if the two per-axis times cannot coincide, which can only happen
+    /// when `width` and `height` share a factor.
+    pub fn calmest_frame(&self) -> Option<usize> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let best_x =
+            (0..width).min_by_key(|&t| spread(self.positions_after(t).iter().map(|p| p.x)))?;
+        let best_y =
+            (0..height).min_by_key(|&t| spread(self.positions_after(t).iter().map(|p| p.y)))?;
+
+        // Smallest t with t = best_x (mod width) and t = best_y (mod height)
+        (0..height)
+            .map(|k| best_x + k * width)
+            .find(|t| t % height == best_y)
+    }
+}
+
+/// Parses `p=X,Y v=DX,DY`.
+fn parse_robot(line: &str) -> Option<Robot> {
+    let (p, v) = line.split_once(' ')?;
+    let pair = |s: &str, prefix: &str| -> Option<Point2> {
+        let (x, y) = s.strip_prefix(prefix)?.split_once(',')?;
+        Some(Point2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+    };
+    Some(Robot {
+        position: pair(p, "p=")?,
+        velocity: pair(v.trim(), "v=")?,
+    })
+}
+
+/// Returns the variance of `values` scaled by `n²`, which avoids floating point and
+/// orders samples the same way as the true variance.
+fn spread<I: Iterator<Item = isize>>(values: I) -> i128 {
+    let (mut n, mut sum, mut sum_sq) = (0i128, 0i128, 0i128);
+    for v in values {
+        n += 1;
+        sum += v as i128;
+        sum_sq += (v as i128) * (v as i128);
+    }
+    n * sum_sq - sum * sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+";
+
+    #[test]
+    fn test_parse() {
+        let robots = Robots::parse(EXAMPLE, 11, 7).unwrap();
+        assert_eq!(robots.robots().len(), 12);
+        assert_eq!(
+            robots.robots()[0],
+            Robot {
+                position: Point2::new(0, 4),
+                velocity: Point2::new(3, -3),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Robots::parse("p=1,2", 11, 7).is_err());
+        assert!(Robots::parse("p=1,2 v=a,3", 11, 7).is_err());
+        let err = Robots::parse("p=1,2 v=1,1\nq=1,2 v=1,1", 11, 7).unwrap_err();
+        assert!(err.to_string().starts_with("line 2"));
+    }
+
+    #[test]
+    fn test_positions_after_wraps() {
+        let robots = Robots::parse("p=2,4 v=2,-3", 11, 7).unwrap();
+        let path: Vec<Point2> = (0..=5).map(|n| robots.positions_after(n)[0]).collect();
+        assert_eq!(
+            path,
+            vec![
+                Point2::new(2, 4),
+                Point2::new(4, 1),
+                Point2::new(6, 5),
+                Point2::new(8, 2),
+                Point2::new(10, 6),
+                Point2::new(1, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_positions_after_large_n() {
+        let robots = Robots::parse("p=2,4 v=2,-3", 11, 7).unwrap();
+        // Positions repeat every lcm(11, 7) = 77 seconds
+        assert_eq!(
+            robots.positions_after(5 + 77 * 1_000_000_000),
+            robots.positions_after(5)
+        );
+    }
+
+    #[test]
+    fn test_quadrant_counts_example() {
+        let robots = Robots::parse(EXAMPLE, 11, 7).unwrap();
+        let counts = robots.quadrant_counts(100);
+        assert_eq!(counts, [1, 3, 4, 1]);
+        assert_eq!(counts.iter().product::<usize>(), 12);
+    }
+
+    #[test]
+    fn test_calmest_frame_finds_cluster() {
+        // Robots that all pass through a 2x2 block at t = 40, plus a few stationary ones
+        let (width, height, target) = (11, 7, 40);
+        let mut seed = 12345u32;
+        let mut next = |m: isize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((seed >> 16) as isize) % m
+        };
+        let mut robots = Vec::new();
+        for i in 0..40 {
+            let velocity = Point2::new(next(21) - 10, next(13) - 6);
+            let at_target = Point2::new(4 + i % 2, 2 + (i / 2) % 2);
+            let position = Point2::new(
+                (at_target.x - velocity.x * target).rem_euclid(width),
+                (at_target.y - velocity.y * target).rem_euclid(height),
+            );
+            robots.push(Robot { position, velocity });
+        }
+        let robots = Robots::new(width as usize, height as usize, robots);
+        assert_eq!(robots.calmest_frame(), Some(target as usize));
+    }
+
+    #[test]
+    fn test_spread() {
+        assert_eq!(spread([3, 3, 3].into_iter()), 0);
+        assert!(spread([0, 10].into_iter()) > spread([4, 6].into_iter()));
+    }
+}
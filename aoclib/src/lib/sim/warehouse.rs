@@ -0,0 +1,340 @@
+use crate::lib::grid::Grid;
+use crate::lib::point::{Direction, Point2};
+use std::error::Error;
+
+/// A cell of a box-pushing warehouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tile {
+    /// Free space (`.`); the robot's own cell is also floor.
+    Floor,
+    /// An immovable wall (`#`).
+    Wall,
+    /// A single-width box (`O`).
+    Box,
+    /// The left half of a double-width box (`[`).
+    BoxLeft,
+    /// The right half of a double-width box (`]`).
+    BoxRight,
+}
+
+impl Tile {
+    /// Parses a warehouse character; the robot (`@`) stands on floor.
+    pub fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' | '@' => Some(Tile::Floor),
+            '#' => Some(Tile::Wall),
+            'O' => Some(Tile::Box),
+            '[' => Some(Tile::BoxLeft),
+            ']' => Some(Tile::BoxRight),
+            _ => None,
+        }
+    }
+
+    /// Returns the character used for this tile in puzzle inputs.
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Floor => '.',
+            Tile::Wall => '#',
+            Tile::Box => 'O',
+            Tile::BoxLeft => '[',
+            Tile::BoxRight => ']',
+        }
+    }
+}
+
+/// A parsed warehouse puzzle: the map, the robot's starting position and its moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warehouse {
+    /// The map, with the robot's cell as floor.
+    pub grid: Grid<Tile>,
+    /// The robot's starting position.
+    pub robot: Point2,
+    /// The moves to make, in order.
+    pub moves: Vec<Direction>,
+}
+
+impl Warehouse {
+    /// Parses a warehouse map, a blank line and a list of `^>v<` moves (which may span
+    /// several lines).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the map contains an unknown character, has no robot, or the
+    /// moves contain anything other than arrows.
+    pub fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let input = input.replace("\r\n", "\n");
+        let (map, moves) = input.split_once("\n\n").unwrap_or((&input, ""));
+
+        let chars = Grid::parse(map, |c| c)?;
+        let robot = chars
+            .find(|&c| c == '@')
+            .ok_or("Warehouse map has no robot '@'")?;
+        if let Some((p, c)) = chars.iter().find(|(_, &c)| Tile::from_char(c).is_none()) {
+            return Err(format!("Unknown tile '{}' at {:?}", c, p).into());
+        }
+        let grid = chars.map(|&c| Tile::from_char(c).expect("tiles were checked above"));
+
+        let moves = moves
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                Some(c)
+                    .filter(|c| "^>v<".contains(*c))
+                    .and_then(Direction::from_char)
+                    .ok_or_else(|| format!("Unknown move '{}'", c))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Warehouse { grid, robot, moves })
+    }
+}
+
+/// Moves the robot through the warehouse, pushing boxes along the way, and returns
+/// its final position.
+///
+/// A move pushes every box in a contiguous chain in front of the robot. If any box in
+/// the chain would hit a wall, nothing moves. Double-width boxes pushed vertically
+/// push everything in front of *both* halves, so a single move can shift a whole
+/// pyramid of boxes.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::sim::{push, Tile, Warehouse};
+/// use aoclib::Point2;
+///
+/// let mut w = Warehouse::parse("#@O.O.#\n\n>>>").unwrap();
+/// let robot = push(&mut w.grid, w.robot, &w.moves);
+/// assert_eq!(robot, Point2::new(3, 0));
+/// assert_eq!(w.grid[Point2::new(4, 0)], Tile::Box);
+/// assert_eq!(w.grid[Point2::new(5, 0)], Tile::Box);
+/// ```
+pub fn push(grid: &mut Grid<Tile>, mut robot: Point2, moves: &[Direction]) -> Point2 {
+    for &dir in moves {
+        let ahead = robot.step(dir);
+        if can_push(grid, ahead, dir) {
+            shift(grid, ahead, dir);
+            robot = ahead;
+        }
+    }
+    robot
+}
+
+/// Doubles the width of a warehouse for the wide variant of the puzzle: walls and floor
+/// are doubled, and every box becomes a double-width box. The robot's x coordinate
+/// doubles too.
+pub fn widen(grid: &Grid<Tile>) -> Grid<Tile> {
+    let rows = grid
+        .rows()
+        .map(|row| {
+            row.iter()
+                .flat_map(|&tile| match tile {
+                    Tile::Box | Tile::BoxLeft | Tile::BoxRight => [Tile::BoxLeft, Tile::BoxRight],
+                    other => [other, other],
+                })
+                .collect()
+        })
+        .collect();
+    Grid::from_rows(rows).expect("widened rows have equal lengths")
+}
+
+/// Sums the "GPS coordinate" `100 * y + x` of every box, measured at its left edge.
+pub fn gps_sum(grid: &Grid<Tile>) -> usize {
+    grid.iter()
+        .filter(|(_, &tile)| tile == Tile::Box || tile == Tile::BoxLeft)
+        .map(|(p, _)| 100 * p.y as usize + p.x as usize)
+        .sum()
+}
+
+/// Returns whether whatever occupies `pos` can move one step in `dir`.
+fn can_push(grid: &Grid<Tile>, pos: Point2, dir: Direction) -> bool {
+    let vertical = matches!(dir, Direction::Up | Direction::Down);
+    match grid.get(pos).copied().unwrap_or(Tile::Wall) {
+        Tile::Floor => true,
+        Tile::Wall => false,
+        Tile::BoxLeft if vertical => {
+            can_push(grid, pos.step(dir), dir)
+                && can_push(grid, pos.step(Direction::Right).step(dir), dir)
+        }
+        Tile::BoxRight if vertical => {
+            can_push(grid, pos.step(dir), dir)
+                && can_push(grid, pos.step(Direction::Left).step(dir), dir)
+        }
+        Tile::Box | Tile::BoxLeft | Tile::BoxRight => can_push(grid, pos.step(dir), dir),
+    }
+}
+
+/// Moves whatever occupies `pos` one step in `dir`, after making room in front of it.
+/// The move must have been checked with [`can_push`].
+fn shift(grid: &mut Grid<Tile>, pos: Point2, dir: Direction) {
+    let vertical = matches!(dir, Direction::Up | Direction::Down);
+    let tile = grid[pos];
+    let partner = match tile {
+        Tile::Floor | Tile::Wall => return,
+        Tile::BoxLeft if vertical => Some(pos.step(Direction::Right)),
+        Tile::BoxRight if vertical => Some(pos.step(Direction::Left)),
+        _ => None,
+    };
+
+    for cell in std::iter::once(pos).chain(partner) {
+        let moved = grid[cell];
+        shift(grid, cell.step(dir), dir);
+        grid[cell.step(dir)] = moved;
+        grid[cell] = Tile::Floor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<
+";
+
+    const WIDE_EXAMPLE: &str = "\
+#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^
+";
+
+    fn render(grid: &Grid<Tile>, robot: Point2) -> String {
+        grid.iter()
+            .map(|(p, &tile)| {
+                let c = if p == robot { '@' } else { tile.to_char() };
+                if p.x as usize == grid.width() - 1 {
+                    format!("{}\n", c)
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_warehouse() {
+        let w = Warehouse::parse(EXAMPLE).unwrap();
+        assert_eq!((w.grid.width(), w.grid.height()), (8, 8));
+        assert_eq!(w.robot, Point2::new(2, 2));
+        assert_eq!(w.grid[w.robot], Tile::Floor);
+        assert_eq!(w.moves.len(), 15);
+        assert_eq!(w.moves[0], Direction::Left);
+    }
+
+    #[test]
+    fn test_parse_warehouse_errors() {
+        assert!(Warehouse::parse("#.O#\n\n<").is_err());
+        assert!(Warehouse::parse("#@x#\n\n<").is_err());
+        assert!(Warehouse::parse("#@O#\n\n<N").is_err());
+    }
+
+    #[test]
+    fn test_push_single_width() {
+        let mut w = Warehouse::parse(EXAMPLE).unwrap();
+        let robot = push(&mut w.grid, w.robot, &w.moves);
+        assert_eq!(
+            render(&w.grid, robot),
+            "\
+########
+#....OO#
+##.....#
+#.....O#
+#.#O@..#
+#...O..#
+#...O..#
+########
+"
+        );
+        assert_eq!(gps_sum(&w.grid), 2028);
+    }
+
+    #[test]
+    fn test_push_blocked_chain_does_not_move() {
+        let mut w = Warehouse::parse("#.OO@#\n\n<<<").unwrap();
+        let robot = push(&mut w.grid, w.robot, &w.moves);
+        assert_eq!(render(&w.grid, robot), "#OO@.#\n");
+    }
+
+    #[test]
+    fn test_push_double_width() {
+        let w = Warehouse::parse(WIDE_EXAMPLE).unwrap();
+        let mut grid = widen(&w.grid);
+        let robot = push(&mut grid, Point2::new(w.robot.x * 2, w.robot.y), &w.moves);
+        assert_eq!(
+            render(&grid, robot),
+            "\
+##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############
+"
+        );
+        assert_eq!(gps_sum(&grid), 105 + 207 + 306);
+    }
+
+    #[test]
+    fn test_push_double_width_pyramid() {
+        let map = "\
+########
+#......#
+#.[][].#
+#..[]..#
+#...@..#
+########";
+        let Warehouse {
+            mut grid, robot, ..
+        } = Warehouse::parse(map).unwrap();
+        let robot = push(&mut grid, robot, &[Direction::Up]);
+        assert_eq!(
+            render(&grid, robot),
+            "\
+########
+#.[][].#
+#..[]..#
+#...@..#
+#......#
+########
+"
+        );
+        // The next push would hit the top wall, so nothing moves
+        let before = grid.clone();
+        assert_eq!(push(&mut grid, robot, &[Direction::Up]), robot);
+        assert_eq!(grid, before);
+    }
+
+    #[test]
+    fn test_push_double_width_partially_blocked() {
+        // Only the right half of the upper box is under a wall, which blocks everything
+        let map = "\
+######
+#..#.#
+#.[].#
+#.[].#
+#..@.#
+######";
+        let Warehouse {
+            mut grid, robot, ..
+        } = Warehouse::parse(map).unwrap();
+        let before = grid.clone();
+        assert_eq!(push(&mut grid, robot, &[Direction::Up]), robot);
+        assert_eq!(grid, before);
+    }
+}
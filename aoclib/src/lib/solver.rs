@@ -0,0 +1,622 @@
+use crate::{bench, postprocess, render};
+use std::env;
+use std::error::Error;
+use std::fmt;
+
+/// The answer produced by a single part of a puzzle.
+///
+/// Most puzzles have numeric answers, but some ask for a code or a word, and some part 2s
+/// produce a picture that has to be read by eye. Use `Answer::from` (or `.into()`) to
+/// convert the result of a part function.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::Answer;
+///
+/// assert_eq!(Answer::from(42usize), Answer::Number(42));
+/// assert_eq!(Answer::from("ABC").to_string(), "ABC");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    /// A numeric answer; wide enough for both signed and unsigned puzzle results.
+    Number(i128),
+    /// A textual answer.
+    Text(String),
+    /// A picture, one string per row. Displayed inside a frame.
+    Grid(Vec<String>),
+    /// The part has not been solved yet.
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Grid(rows) => write!(f, "\n{}", render::frame(rows)),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+impl Answer {
+    /// Renders the answer as a JSON object with a `type` and a `value`.
+    ///
+    /// Grids are emitted as text with rows separated by newlines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::Answer;
+    ///
+    /// assert_eq!(Answer::Number(7).to_json(), r#"{"type":"number","value":7}"#);
+    /// assert_eq!(
+    ///     Answer::Grid(vec!["#.".into(), ".#".into()]).to_json(),
+    ///     r##"{"type":"grid","value":"#.\n.#"}"##
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        match self {
+            // Numbers are quoted when they exceed what a JSON double represents exactly
+            Answer::Number(n) if n.unsigned_abs() > (1u128 << 53) => {
+                format!(r#"{{"type":"number","value":"{}"}}"#, n)
+            }
+            Answer::Number(n) => format!(r#"{{"type":"number","value":{}}}"#, n),
+            Answer::Text(s) => format!(r#"{{"type":"text","value":{}}}"#, render::json_string(s)),
+            Answer::Grid(rows) => format!(
+                r#"{{"type":"grid","value":{}}}"#,
+                render::json_string(&rows.join("\n"))
+            ),
+            Answer::Unsolved => r#"{"type":"unsolved","value":null}"#.to_string(),
+        }
+    }
+}
+
+macro_rules! impl_answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl From<Vec<Vec<char>>> for Answer {
+    fn from(cells: Vec<Vec<char>>) -> Self {
+        Answer::Grid(
+            cells
+                .into_iter()
+                .map(|row| row.into_iter().collect())
+                .collect(),
+        )
+    }
+}
+
+/// A solution to one day's puzzle.
+///
+/// The input is parsed once by `parse` and the result is shared by both parts, so
+/// each part only has to deal with the already structured data. Solvers whose part 2
+/// builds on part 1's intermediate state can additionally override `solve_both` to
+/// run that work once for both answers.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{Answer, Solver};
+/// use std::error::Error;
+///
+/// struct Sum;
+///
+/// impl Solver for Sum {
+///     type Input = Vec<i64>;
+///
+///     fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>> {
+///         Ok(input.lines().map(|l| l.parse()).collect::<Result<_, _>>()?)
+///     }
+///
+///     fn part1(&self, input: &Self::Input) -> Answer {
+///         input.iter().sum::<i64>().into()
+///     }
+///
+///     fn part2(&self, input: &Self::Input) -> Answer {
+///         input.iter().product::<i64>().into()
+///     }
+/// }
+///
+/// let input = Sum.parse("2\n3\n4").unwrap();
+/// assert_eq!(Sum.part1(&input), Answer::Number(9));
+/// assert_eq!(Sum.part2(&input), Answer::Number(24));
+/// ```
+pub trait Solver {
+    /// The parsed representation of the puzzle input.
+    type Input;
+
+    /// Parses the raw puzzle input.
+    fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>>;
+
+    /// Solves part 1 of the puzzle.
+    fn part1(&self, input: &Self::Input) -> Answer;
+
+    /// Solves part 2 of the puzzle.
+    fn part2(&self, input: &Self::Input) -> Answer;
+
+    /// Solves both parts, returning `(part1, part2)`.
+    ///
+    /// The default calls `part1` and `part2`. Override it when part 2 needs part 1's
+    /// intermediate results (a finished simulation, a distance map, ...) so the shared
+    /// work is done once. The runner always prefers this method, while `part1` and
+    /// `part2` remain available for benchmarking and tests of a single part.
+    fn solve_both(&self, input: &Self::Input) -> (Answer, Answer) {
+        (self.part1(input), self.part2(input))
+    }
+
+    /// Parses `input` and solves both parts.
+    ///
+    /// Handy for running a puzzle's example in tests:
+    ///
+    /// ```ignore
+    /// let (part1, part2) = Day05.solve(include_str!("../example.txt")).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input fails to parse.
+    fn solve(&self, input: &str) -> Result<(Answer, Answer), Box<dyn Error>> {
+        let input = self.parse(input)?;
+        Ok(self.solve_both(&input))
+    }
+
+    /// Returns the puzzle's example inputs compiled into the binary, example `N` at
+    /// index `N - 1`, so `--example` works away from the source tree.
+    ///
+    /// Implement it with [`examples!`](crate::examples):
+    ///
+    /// ```ignore
+    /// fn examples(&self) -> &'static [&'static str] {
+    ///     aoclib::examples!()
+    /// }
+    /// ```
+    ///
+    /// The default embeds nothing, so `--example` needs the files on disk.
+    fn examples(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Embeds example inputs from the calling crate's directory, for
+/// [`Solver::examples`].
+///
+/// With no arguments this embeds `example.txt`. Otherwise it embeds the given files in
+/// order, so a day with two examples uses `examples!("example.txt", "example2.txt")`.
+/// The files are read at compile time, relative to the crate's `Cargo.toml`.
+#[macro_export]
+macro_rules! examples {
+    () => {
+        $crate::examples!("example.txt")
+    };
+    ($($file:literal),+ $(,)?) => {
+        &[$(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $file))),+]
+    };
+}
+
+/// Returns the file name of a day's example input.
+///
+/// Example 1 is `example.txt`; further examples from the same puzzle statement are
+/// numbered `example2.txt`, `example3.txt` and so on.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::example_file;
+///
+/// assert_eq!(example_file(1), "example.txt");
+/// assert_eq!(example_file(2), "example2.txt");
+/// ```
+pub fn example_file(index: usize) -> String {
+    if index <= 1 {
+        "example.txt".to_string()
+    } else {
+        format!("example{}.txt", index)
+    }
+}
+
+/// Chooses the input file from the command-line arguments: `input.txt` by default, the
+/// path given with `--input PATH` (`-` for standard input), or an example file when
+/// `--example [N]` is given.
+///
+/// [`load_input`] reads the whole file; this is for solutions that read it some other
+/// way, such as streaming it with [`parse_lines_iter`](crate::parse_lines_iter).
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::input_file;
+///
+/// let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+/// assert_eq!(input_file(&args("--json")).unwrap(), "input.txt");
+/// assert_eq!(input_file(&args("--example 2")).unwrap(), "example2.txt");
+/// assert_eq!(input_file(&args("--input -")).unwrap(), "-");
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if `--example` or `--input` is given an invalid value, or both are
+/// given.
+pub fn input_file(args: &[String]) -> Result<String, Box<dyn Error>> {
+    if let Some(i) = args.iter().position(|arg| arg == "--input") {
+        if example_index(args)?.is_some() {
+            return Err("Options '--input' and '--example' cannot be combined".into());
+        }
+        return match args.get(i + 1).filter(|path| !path.starts_with("--")) {
+            Some(path) => Ok(path.clone()),
+            None => Err("Option '--input' requires a path, or '-' for standard input".into()),
+        };
+    }
+    Ok(example_index(args)?.map_or_else(|| "input.txt".to_string(), example_file))
+}
+
+/// Returns the example number requested with `--example [N]`, if any.
+fn example_index(args: &[String]) -> Result<Option<usize>, Box<dyn Error>> {
+    let Some(i) = args.iter().position(|arg| arg == "--example") else {
+        return Ok(None);
+    };
+    match args.get(i + 1).filter(|next| !next.starts_with("--")) {
+        Some(index) => {
+            Ok(Some(index.parse().map_err(|_| {
+                format!("Invalid example number '{}'", index)
+            })?))
+        }
+        None => Ok(Some(1)),
+    }
+}
+
+/// Reads the input chosen by the command-line arguments, as [`run`] does: `input.txt`,
+/// the file or standard input named with `--input PATH`, or with `--example [N]` the
+/// example file (see [`example_file`]).
+///
+/// An example file on disk wins, so edits to it take effect without a rebuild. When it
+/// is missing, the copy embedded through [`Solver::examples`] is used instead.
+///
+/// # Errors
+///
+/// Returns an error if the options are invalid, or the file cannot be read and no
+/// embedded example stands in for it.
+pub fn load_input<S: Solver>(solver: &S, args: &[String]) -> Result<String, Box<dyn Error>> {
+    let path = input_file(args)?;
+    crate::read_input(&path).or_else(|e| {
+        example_index(args)?
+            .and_then(|index| solver.examples().get(index.max(1) - 1))
+            .map(|example| example.to_string())
+            .ok_or_else(|| format!("Cannot read '{}': {}", path, e).into())
+    })
+}
+
+/// Runs a solver against `./input.txt` and prints both answers.
+///
+/// This is intended to be the entire body of a day crate's `main`. The following
+/// command-line options are understood:
+///
+/// * `--example [N]` - use the puzzle's example input (see [`example_file`]) instead of
+///   `input.txt`, falling back to the embedded copy (see [`load_input`])
+/// * `--input PATH` - read the input from `PATH` instead of `input.txt`; `-` reads
+///   standard input, so `cat input.txt | cargo run -- --input -` works
+/// * `--json` - print the answers as a single JSON object
+/// * `--salted-hash SALT` - print a salted hash of each answer instead of the answer
+///   itself (see [`postprocess::SaltedHash`])
+/// * `--bench N` - time `parse`, `part1` and `part2` over `N` runs each and print one
+///   tab-separated `phase min median mean` line per phase, in nanoseconds
+///
+/// # Examples
+///
+/// ```ignore
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     aoclib::run(&Day05)
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the input file cannot be read or fails to parse, or if the
+/// options are invalid.
+pub fn run<S: Solver>(solver: &S) -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let content = load_input(solver, &args)?;
+
+    if let Some(i) = args.iter().position(|arg| arg == "--bench") {
+        let runs: usize = args
+            .get(i + 1)
+            .ok_or("Option '--bench' requires a number of runs")?
+            .parse()?;
+        for (phase, stats) in bench::bench_solver(solver, &content, runs)? {
+            println!(
+                "{}\t{}\t{}\t{}",
+                phase,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos()
+            );
+        }
+        return Ok(());
+    }
+
+    let input = solver.parse(&content)?;
+    let (part1, part2) = solver.solve_both(&input);
+    print!("{}", format_answers(&args, part1, part2)?);
+    Ok(())
+}
+
+/// Formats both answers the way [`run`] prints them, honoring its `--json` and
+/// `--salted-hash SALT` options.
+///
+/// A `main` that solves the puzzle some other way, such as by streaming its input,
+/// can print this to keep the output the same as every other day's.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::{format_answers, Answer};
+///
+/// let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+/// let text = format_answers(&args(""), Answer::from(3), Answer::Unsolved).unwrap();
+/// assert_eq!(text, "Part 1: 3\nPart 2: unsolved\n");
+/// let json = format_answers(&args("--json"), Answer::from(3), Answer::from(4)).unwrap();
+/// assert!(json.starts_with(r#"{"part1":{"type":"number","value":3}"#));
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the options are invalid.
+pub fn format_answers(
+    args: &[String],
+    part1: Answer,
+    part2: Answer,
+) -> Result<String, Box<dyn Error>> {
+    let post = postprocess::from_args(args)?;
+    let (part1, part2) = (post.process(part1), post.process(part2));
+    Ok(if args.iter().any(|arg| arg == "--json") {
+        format!(
+            "{{\"part1\":{},\"part2\":{}}}\n",
+            part1.to_json(),
+            part2.to_json()
+        )
+    } else {
+        format!("Part 1: {}\nPart 2: {}\n", part1, part2)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct LineCount;
+
+    impl Solver for LineCount {
+        type Input = Vec<String>;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            Ok(input.lines().map(str::to_string).collect())
+        }
+
+        fn part1(&self, input: &Self::Input) -> Answer {
+            input.len().into()
+        }
+
+        fn part2(&self, _input: &Self::Input) -> Answer {
+            Answer::Unsolved
+        }
+    }
+
+    /// Counts how often its expensive simulation runs.
+    struct Simulation {
+        runs: Cell<usize>,
+    }
+
+    impl Simulation {
+        fn simulate(&self, input: &[u64]) -> Vec<u64> {
+            self.runs.set(self.runs.get() + 1);
+            input
+                .iter()
+                .scan(0, |acc, x| {
+                    *acc += x;
+                    Some(*acc)
+                })
+                .collect()
+        }
+    }
+
+    impl Solver for Simulation {
+        type Input = Vec<u64>;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            Ok(input.split(',').map(str::parse).collect::<Result<_, _>>()?)
+        }
+
+        fn part1(&self, input: &Self::Input) -> Answer {
+            self.simulate(input).last().copied().unwrap_or(0).into()
+        }
+
+        fn part2(&self, input: &Self::Input) -> Answer {
+            self.simulate(input)
+                .iter()
+                .max()
+                .copied()
+                .unwrap_or(0)
+                .into()
+        }
+
+        fn solve_both(&self, input: &Self::Input) -> (Answer, Answer) {
+            let states = self.simulate(input);
+            let last = states.last().copied().unwrap_or(0);
+            let max = states.iter().max().copied().unwrap_or(0);
+            (last.into(), max.into())
+        }
+    }
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_solve_parses_and_runs_both_parts() {
+        let (part1, part2) = LineCount.solve("a\nb").unwrap();
+        assert_eq!(part1, Answer::Number(2));
+        assert_eq!(part2, Answer::Unsolved);
+    }
+
+    #[test]
+    fn test_solve_both_default_matches_parts() {
+        let input = LineCount.parse("x\ny\nz").unwrap();
+        assert_eq!(
+            LineCount.solve_both(&input),
+            (LineCount.part1(&input), LineCount.part2(&input))
+        );
+    }
+
+    #[test]
+    fn test_solve_prefers_solve_both() {
+        let sim = Simulation { runs: Cell::new(0) };
+        let (part1, part2) = sim.solve("1,2,3").unwrap();
+        assert_eq!(part1, Answer::Number(6));
+        assert_eq!(part2, Answer::Number(6));
+        assert_eq!(sim.runs.get(), 1, "shared simulation should run once");
+    }
+
+    #[test]
+    fn test_input_file() {
+        assert_eq!(input_file(&args("")).unwrap(), "input.txt");
+        assert_eq!(input_file(&args("--json")).unwrap(), "input.txt");
+        assert_eq!(input_file(&args("--example")).unwrap(), "example.txt");
+        assert_eq!(
+            input_file(&args("--example --json")).unwrap(),
+            "example.txt"
+        );
+        assert_eq!(input_file(&args("--example 3")).unwrap(), "example3.txt");
+        assert!(input_file(&args("--example x")).is_err());
+        assert_eq!(input_file(&args("--input big.txt")).unwrap(), "big.txt");
+        assert_eq!(input_file(&args("--input - --json")).unwrap(), "-");
+        assert!(input_file(&args("--input")).is_err());
+        assert!(input_file(&args("--input --json")).is_err());
+        assert!(input_file(&args("--input a.txt --example")).is_err());
+    }
+
+    #[test]
+    fn test_load_input_falls_back_to_embedded_examples() {
+        struct Embedded;
+
+        impl Solver for Embedded {
+            type Input = ();
+
+            fn parse(&self, _input: &str) -> Result<Self::Input, Box<dyn Error>> {
+                Ok(())
+            }
+
+            fn part1(&self, _input: &Self::Input) -> Answer {
+                Answer::Unsolved
+            }
+
+            fn part2(&self, _input: &Self::Input) -> Answer {
+                Answer::Unsolved
+            }
+
+            fn examples(&self) -> &'static [&'static str] {
+                &["first\n", "second\n"]
+            }
+        }
+
+        // The aoclib crate directory has no example files, so these come from the binary
+        assert_eq!(
+            load_input(&Embedded, &args("--example")).unwrap(),
+            "first\n"
+        );
+        assert_eq!(
+            load_input(&Embedded, &args("--example 2")).unwrap(),
+            "second\n"
+        );
+        assert!(load_input(&Embedded, &args("--example 3"))
+            .unwrap_err()
+            .to_string()
+            .contains("example3.txt"));
+        assert!(load_input(&LineCount, &args("--example")).is_err());
+        assert!(load_input(&Embedded, &args("")).is_err());
+    }
+
+    #[test]
+    fn test_answer_from_integers() {
+        assert_eq!(Answer::from(5u8), Answer::Number(5));
+        assert_eq!(Answer::from(-7i32), Answer::Number(-7));
+        assert_eq!(Answer::from(u64::MAX), Answer::Number(u64::MAX as i128));
+    }
+
+    #[test]
+    fn test_answer_from_text() {
+        assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+        assert_eq!(
+            Answer::from("xyz".to_string()),
+            Answer::Text("xyz".to_string())
+        );
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::Number(3121910778619).to_string(), "3121910778619");
+        assert_eq!(Answer::Number(-12).to_string(), "-12");
+        assert_eq!(Answer::Text("HELLO".to_string()).to_string(), "HELLO");
+        assert_eq!(Answer::Unsolved.to_string(), "unsolved");
+    }
+
+    #[test]
+    fn test_answer_grid_display() {
+        let answer = Answer::from(vec![vec!['#', '.'], vec!['.', '#']]);
+        assert_eq!(
+            answer,
+            Answer::Grid(vec!["#.".to_string(), ".#".to_string()])
+        );
+        assert_eq!(answer.to_string(), "\n┌──┐\n│#.│\n│.#│\n└──┘");
+    }
+
+    #[test]
+    fn test_answer_to_json() {
+        assert_eq!(
+            Answer::Number(-3).to_json(),
+            r#"{"type":"number","value":-3}"#
+        );
+        assert_eq!(
+            Answer::Number(1 << 60).to_json(),
+            r#"{"type":"number","value":"1152921504606846976"}"#
+        );
+        assert_eq!(
+            Answer::Text("a\"b".to_string()).to_json(),
+            r#"{"type":"text","value":"a\"b"}"#
+        );
+        assert_eq!(
+            Answer::Unsolved.to_json(),
+            r#"{"type":"unsolved","value":null}"#
+        );
+    }
+
+    #[test]
+    fn test_solver_parts_share_parsed_input() {
+        let input = LineCount.parse("a\nb\nc").unwrap();
+        assert_eq!(LineCount.part1(&input), Answer::Number(3));
+        assert_eq!(LineCount.part2(&input), Answer::Unsolved);
+    }
+}
@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+
+/// Selects the `k` items, kept in order, that form the largest subsequence of `items`
+/// under `cmp`, and returns their indices in increasing order.
+///
+/// Subsequences are compared lexicographically, so the first selected item matters
+/// most: for digits this picks the largest `k`-digit number that can be read off in
+/// order. Among items that `cmp` considers equal the leftmost is kept, which leaves
+/// the most choice for later positions. To prefer some equal items over others, break
+/// the tie inside `cmp`; the selection is then the largest under that full order, so
+/// a better tie-break at one position outranks anything at later positions.
+///
+/// Uses a monotonic stack: with `n - k` items to drop, each item pops the smaller items
+/// before it while there are drops left, since a larger item earlier always beats
+/// anything after it. Each item is pushed and popped at most once, so this is O(n).
+///
+/// Returns `None` if `k` is larger than `items.len()`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::subsequence::select_max_subsequence;
+///
+/// let digits = [8, 1, 8, 1, 9, 1, 2];
+/// let picked = select_max_subsequence(&digits, 5, |a, b| a.cmp(b)).unwrap();
+/// assert_eq!(picked, [0, 2, 4, 5, 6]);
+/// let largest: Vec<_> = picked.iter().map(|&i| digits[i]).collect();
+/// assert_eq!(largest, [8, 8, 9, 1, 2]);
+///
+/// // Equal letters are told apart by their cost, preferring the cheaper one
+/// let items = [('b', 5), ('a', 1), ('b', 2), ('a', 0)];
+/// let cheapest = |x: &(char, u32), y: &(char, u32)| x.0.cmp(&y.0).then(y.1.cmp(&x.1));
+/// assert_eq!(select_max_subsequence(&items, 2, cheapest), Some(vec![2, 3]));
+/// ```
+pub fn select_max_subsequence<T, F>(items: &[T], k: usize, mut cmp: F) -> Option<Vec<usize>>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if k > items.len() {
+        return None;
+    }
+
+    let mut drops = items.len() - k;
+    let mut stack: Vec<usize> = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        while drops > 0
+            && stack
+                .last()
+                .is_some_and(|&top| cmp(&items[top], item) == Ordering::Less)
+        {
+            stack.pop();
+            drops -= 1;
+        }
+        stack.push(i);
+    }
+    // Drops left over come off the end, where the smallest items are
+    stack.truncate(k);
+    Some(stack)
+}
+
+/// Selects the `k` items, kept in order, that form the smallest subsequence of `items`
+/// under `cmp`, and returns their indices in increasing order.
+///
+/// This is [`select_max_subsequence`] with `cmp` reversed, so again the leftmost of
+/// equal items is kept.
+///
+/// Returns `None` if `k` is larger than `items.len()`.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::subsequence::select_min_subsequence;
+///
+/// let digits = [1, 4, 3, 2, 2, 1, 9];
+/// let picked = select_min_subsequence(&digits, 3, |a, b| a.cmp(b)).unwrap();
+/// assert_eq!(picked.iter().map(|&i| digits[i]).collect::<Vec<_>>(), [1, 1, 9]);
+/// ```
+pub fn select_min_subsequence<T, F>(items: &[T], k: usize, mut cmp: F) -> Option<Vec<usize>>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    select_max_subsequence(items, k, |a, b| cmp(b, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every increasing list of `k` indices below `n`.
+    fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+        if k == 0 {
+            return vec![Vec::new()];
+        }
+        (k - 1..n)
+            .flat_map(|last| {
+                combinations(last, k - 1).into_iter().map(move |mut c| {
+                    c.push(last);
+                    c
+                })
+            })
+            .collect()
+    }
+
+    fn values(items: &[u8], indices: &[usize]) -> Vec<u8> {
+        indices.iter().map(|&i| items[i]).collect()
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut state = 7u64;
+        for _ in 0..200 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let n = (state >> 60) as usize % 9;
+            let items: Vec<u8> = (0..n).map(|i| ((state >> (i * 3)) % 4) as u8).collect();
+            for k in 0..=n {
+                let all = combinations(n, k);
+                let best = all.iter().map(|c| values(&items, c)).max().unwrap();
+                let worst = all.iter().map(|c| values(&items, c)).min().unwrap();
+
+                let max = select_max_subsequence(&items, k, u8::cmp).unwrap();
+                let min = select_min_subsequence(&items, k, u8::cmp).unwrap();
+                assert_eq!(values(&items, &max), best, "{:?} k={}", items, k);
+                assert_eq!(values(&items, &min), worst, "{:?} k={}", items, k);
+                assert!(max.windows(2).all(|w| w[0] < w[1]));
+                assert!(min.windows(2).all(|w| w[0] < w[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_keeps_leftmost_of_equal_items() {
+        assert_eq!(
+            select_max_subsequence(&[5, 5, 5], 2, u8::cmp),
+            Some(vec![0, 1])
+        );
+        assert_eq!(
+            select_max_subsequence(&[5, 3, 5], 1, u8::cmp),
+            Some(vec![0])
+        );
+        assert_eq!(
+            select_min_subsequence(&[1, 3, 1], 1, u8::cmp),
+            Some(vec![0])
+        );
+    }
+
+    #[test]
+    fn test_custom_tie_breaking() {
+        // Compare by digit, breaking ties by letter so (5, 'b') beats (5, 'a')
+        let items = [(5, 'a'), (5, 'b'), (3, 'c')];
+        let later = |x: &(u8, char), y: &(u8, char)| x.0.cmp(&y.0).then(x.1.cmp(&y.1));
+        assert_eq!(select_max_subsequence(&items, 1, later), Some(vec![1]));
+        // The tie-break ranks before later items, so the 'b' beats keeping both 5s
+        assert_eq!(select_max_subsequence(&items, 2, later), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(select_max_subsequence(&[1, 2], 3, u8::cmp), None);
+        assert_eq!(select_max_subsequence(&[1, 2], 0, u8::cmp), Some(vec![]));
+        assert_eq!(
+            select_max_subsequence(&[1, 2], 2, u8::cmp),
+            Some(vec![0, 1])
+        );
+        assert_eq!(
+            select_min_subsequence::<u8, _>(&[], 0, u8::cmp),
+            Some(vec![])
+        );
+    }
+}
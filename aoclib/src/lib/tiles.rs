@@ -0,0 +1,405 @@
+use crate::lib::grid::Grid;
+use crate::lib::point::Point2;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+/// A square image tile with an id, as in `Tile 2311:` blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tile {
+    /// The tile's id.
+    pub id: u64,
+    /// The tile's pixels, `true` for `#`.
+    pub grid: Grid<bool>,
+}
+
+impl Tile {
+    /// Parses blank-line separated tiles, each a `Tile <id>:` header followed by rows
+    /// of `#` and `.`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the tile that has a bad header, an unknown pixel, or is
+    /// not square.
+    pub fn parse_all(input: &str) -> Result<Vec<Tile>, Box<dyn Error>> {
+        let input = input.replace("\r\n", "\n");
+        input
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|block| !block.is_empty())
+            .map(|block| {
+                let (header, pixels) = block.split_once('\n').unwrap_or((block, ""));
+                let id = header
+                    .strip_prefix("Tile ")
+                    .and_then(|h| h.strip_suffix(':'))
+                    .and_then(|id| id.trim().parse().ok())
+                    .ok_or_else(|| format!("Invalid tile header '{}'", header))?;
+                let chars = Grid::parse(pixels, |c| c)?;
+                if chars.iter().any(|(_, &c)| c != '#' && c != '.') {
+                    return Err(format!("Tile {} has an unknown pixel", id).into());
+                }
+                if chars.width() != chars.height() || chars.width() < 2 {
+                    return Err(format!("Tile {} is not square", id).into());
+                }
+                Ok(Tile {
+                    id,
+                    grid: chars.map(|&c| c == '#'),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The result of [`assemble`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assembly {
+    /// Tile ids in their assembled positions, row by row.
+    pub ids: Vec<Vec<u64>>,
+    /// The full image with every tile's border stripped.
+    pub image: Grid<bool>,
+}
+
+impl Assembly {
+    /// Returns the ids of the four corner tiles: top-left, top-right, bottom-left and
+    /// bottom-right.
+    pub fn corners(&self) -> [u64; 4] {
+        let last = self.ids.len() - 1;
+        [
+            self.ids[0][0],
+            self.ids[0][last],
+            self.ids[last][0],
+            self.ids[last][last],
+        ]
+    }
+}
+
+/// Returns the eight orientations of a grid: the four rotations (clockwise, a quarter
+/// turn at a time) of the grid, then the four rotations of its mirror image.
+pub fn orientations<T: Clone>(grid: &Grid<T>) -> Vec<Grid<T>> {
+    let mut result = Vec::with_capacity(8);
+    let mut current = grid.clone();
+    for i in 0..8 {
+        if i == 4 {
+            current = grid.flip_h();
+        }
+        let next = current.rotate_cw();
+        result.push(current);
+        current = next;
+    }
+    result
+}
+
+/// Arranges square tiles into a square image so that touching edges match, trying
+/// every tile in all eight orientations, and strips the borders.
+///
+/// Tiles are placed row by row. Edges are indexed up front, so each position only
+/// considers tiles whose edge matches its left (or upper) neighbour, and a depth-first
+/// search backtracks if a placement leads to a dead end. The assembled image can come
+/// out in any of its eight orientations.
+///
+/// # Errors
+///
+/// Returns an error if the tile count is not a square, tiles differ in size, or no
+/// arrangement fits.
+pub fn assemble(tiles: &[Tile]) -> Result<Assembly, String> {
+    let side = (0..=tiles.len())
+        .find(|n| n * n >= tiles.len())
+        .filter(|n| n * n == tiles.len() && *n > 0)
+        .ok_or_else(|| format!("{} tiles cannot form a square", tiles.len()))?;
+    let size = tiles[0].grid.width();
+    if let Some(t) = tiles.iter().find(|t| t.grid.width() != size) {
+        return Err(format!("Tile {} is not {}x{}", t.id, size, size));
+    }
+
+    // Every orientation of every tile, with its edges read left to right / top to
+    // bottom so that touching edges of neighbours compare equal
+    let variants: Vec<Vec<Grid<bool>>> = tiles.iter().map(|t| orientations(&t.grid)).collect();
+    let mut by_left: HashMap<Vec<bool>, Vec<(usize, usize)>> = HashMap::new();
+    let mut by_top: HashMap<Vec<bool>, Vec<(usize, usize)>> = HashMap::new();
+    for (t, grids) in variants.iter().enumerate() {
+        for (o, grid) in grids.iter().enumerate() {
+            by_left.entry(column(grid, 0)).or_default().push((t, o));
+            by_top.entry(row(grid, 0)).or_default().push((t, o));
+        }
+    }
+
+    let search = Search {
+        variants: &variants,
+        by_left: &by_left,
+        by_top: &by_top,
+        side,
+    };
+    let mut placed = Vec::with_capacity(tiles.len());
+    let mut used = vec![false; tiles.len()];
+    if !search.place(&mut placed, &mut used) {
+        return Err("No arrangement of the tiles fits together".to_string());
+    }
+
+    let inner = size - 2;
+    let mut image = Grid::new(side * inner, side * inner, false);
+    for (i, &(t, o)) in placed.iter().enumerate() {
+        let (tile_x, tile_y) = (i % side, i / side);
+        let grid = &variants[t][o];
+        for y in 0..inner {
+            for x in 0..inner {
+                let to = Point2::new((tile_x * inner + x) as isize, (tile_y * inner + y) as isize);
+                image[to] = grid[Point2::new(x as isize + 1, y as isize + 1)];
+            }
+        }
+    }
+    let ids = placed
+        .chunks(side)
+        .map(|row| row.iter().map(|&(t, _)| tiles[t].id).collect())
+        .collect();
+    Ok(Assembly { ids, image })
+}
+
+/// A placement in progress for [`assemble`].
+struct Search<'a> {
+    variants: &'a [Vec<Grid<bool>>],
+    by_left: &'a HashMap<Vec<bool>, Vec<(usize, usize)>>,
+    by_top: &'a HashMap<Vec<bool>, Vec<(usize, usize)>>,
+    side: usize,
+}
+
+impl Search<'_> {
+    /// Fills the next position, recursing until every tile is placed.
+    fn place(&self, placed: &mut Vec<(usize, usize)>, used: &mut [bool]) -> bool {
+        let i = placed.len();
+        if i == used.len() {
+            return true;
+        }
+        let grid = |(t, o): (usize, usize)| &self.variants[t][o];
+        let left = (!i.is_multiple_of(self.side)).then(|| grid(placed[i - 1]));
+        let above = (i >= self.side).then(|| grid(placed[i - self.side]));
+
+        let all: Vec<(usize, usize)>;
+        let candidates = match (left, above) {
+            (Some(left), _) => self.by_left.get(&column(left, left.width() - 1)),
+            (None, Some(above)) => self.by_top.get(&row(above, above.height() - 1)),
+            (None, None) => {
+                all = (0..used.len())
+                    .flat_map(|t| (0..8).map(move |o| (t, o)))
+                    .collect();
+                Some(&all)
+            }
+        };
+
+        for &(t, o) in candidates.into_iter().flatten() {
+            if used[t] {
+                continue;
+            }
+            if let Some(above) = above {
+                if row(grid((t, o)), 0) != row(above, above.height() - 1) {
+                    continue;
+                }
+            }
+            used[t] = true;
+            placed.push((t, o));
+            if self.place(placed, used) {
+                return true;
+            }
+            placed.pop();
+            used[t] = false;
+        }
+        false
+    }
+}
+
+/// Where [`count_pattern`] found the most copies of a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternCount {
+    /// Index into [`orientations`] of the image orientation with the most matches.
+    pub orientation: usize,
+    /// How many (possibly overlapping) copies of the pattern were found.
+    pub occurrences: usize,
+    /// Lit cells of the image that are not part of any copy.
+    pub uncovered: usize,
+}
+
+/// Counts copies of `pattern` in `image`, trying all eight orientations of the image
+/// and keeping the one with the most copies.
+///
+/// A copy matches where every `true` cell of the pattern is lit in the image; `false`
+/// cells of the pattern match anything. This is the sea monster search: `uncovered`
+/// is the water roughness.
+pub fn count_pattern(image: &Grid<bool>, pattern: &Grid<bool>) -> PatternCount {
+    let offsets: Vec<Point2> = pattern
+        .iter()
+        .filter(|(_, &lit)| lit)
+        .map(|(p, _)| p)
+        .collect();
+    let lit = image.iter().filter(|(_, &lit)| lit).count();
+
+    orientations(image)
+        .iter()
+        .enumerate()
+        .map(|(orientation, image)| {
+            let mut covered: HashSet<Point2> = HashSet::new();
+            let mut occurrences = 0;
+            for origin in image.positions() {
+                let matches = offsets
+                    .iter()
+                    .all(|&d| image.get(origin + d).copied().unwrap_or(false));
+                if matches {
+                    occurrences += 1;
+                    covered.extend(offsets.iter().map(|&d| origin + d));
+                }
+            }
+            PatternCount {
+                orientation,
+                occurrences,
+                uncovered: lit - covered.len(),
+            }
+        })
+        .max_by_key(|c| (c.occurrences, std::cmp::Reverse(c.orientation)))
+        .expect("there are always eight orientations")
+}
+
+fn row(grid: &Grid<bool>, y: usize) -> Vec<bool> {
+    grid.rows().nth(y).map(<[bool]>::to_vec).unwrap_or_default()
+}
+
+fn column(grid: &Grid<bool>, x: usize) -> Vec<bool> {
+    grid.rows().map(|row| row[x]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONSTER: &str = "\
+..................#.
+#....##....##....###
+.#..#..#..#..#..#...
+";
+
+    fn random_grid(width: usize, height: usize, seed: &mut u64) -> Grid<bool> {
+        let mut g = Grid::new(width, height, false);
+        for p in g.positions().collect::<Vec<_>>() {
+            *seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            g[p] = *seed >> 63 == 1;
+        }
+        g
+    }
+
+    fn crop(grid: &Grid<bool>, x: usize, y: usize, w: usize, h: usize) -> Grid<bool> {
+        let rows = (y..y + h)
+            .map(|y| {
+                (x..x + w)
+                    .map(|x| grid[Point2::new(x as isize, y as isize)])
+                    .collect()
+            })
+            .collect();
+        Grid::from_rows(rows).unwrap()
+    }
+
+    /// Cuts a random picture into `side` x `side` tiles of `size` x `size` whose
+    /// touching edges are equal, then scrambles their order and orientation.
+    fn scrambled_tiles(side: usize, size: usize, seed: &mut u64) -> (Vec<Tile>, Grid<bool>) {
+        let step = size - 1;
+        let picture = random_grid(side * step + 1, side * step + 1, seed);
+        let mut tiles = Vec::new();
+        let inner = size - 2;
+        let mut expected = Grid::new(side * inner, side * inner, false);
+        for ty in 0..side {
+            for tx in 0..side {
+                let tile = crop(&picture, tx * step, ty * step, size, size);
+                for y in 0..inner {
+                    for x in 0..inner {
+                        let to = Point2::new((tx * inner + x) as isize, (ty * inner + y) as isize);
+                        expected[to] = tile[Point2::new(x as isize + 1, y as isize + 1)];
+                    }
+                }
+                *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                let orientation = (*seed >> 61) as usize;
+                tiles.push(Tile {
+                    id: 1000 + (ty * side + tx) as u64,
+                    grid: orientations(&tile).swap_remove(orientation),
+                });
+            }
+        }
+        // Deterministic shuffle
+        for i in (1..tiles.len()).rev() {
+            *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            tiles.swap(i, (*seed >> 33) as usize % (i + 1));
+        }
+        (tiles, expected)
+    }
+
+    #[test]
+    fn test_orientations_are_distinct() {
+        let grid = Grid::parse("ab\ncd", |c| c).unwrap();
+        let all: Vec<String> = orientations(&grid)
+            .iter()
+            .map(|g| g.rows().flatten().collect())
+            .collect();
+        assert_eq!(
+            all,
+            vec!["abcd", "cadb", "dcba", "bdac", "badc", "dbca", "cdab", "acbd"]
+        );
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let tiles = Tile::parse_all("Tile 1:\n#.\n.#\n\nTile 22:\n..\n##\n").unwrap();
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(tiles[1].id, 22);
+        assert!(tiles[0].grid[Point2::new(1, 1)]);
+        assert!(Tile::parse_all("Tile x:\n#.\n.#").is_err());
+        assert!(Tile::parse_all("Tile 1:\n#.#\n.#.").is_err());
+        assert!(Tile::parse_all("Tile 1:\n#o\n.#").is_err());
+    }
+
+    #[test]
+    fn test_assemble_scrambled_picture() {
+        let mut seed = 2020;
+        let (tiles, expected) = scrambled_tiles(4, 10, &mut seed);
+        let assembly = assemble(&tiles).unwrap();
+        assert!(orientations(&expected).contains(&assembly.image));
+
+        // Whatever orientation it came out in, the corners are the original corners
+        let mut corners = assembly.corners().to_vec();
+        corners.sort();
+        assert_eq!(corners, vec![1000, 1003, 1012, 1015]);
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let mut seed = 7;
+        let (mut tiles, _) = scrambled_tiles(2, 6, &mut seed);
+        tiles.pop();
+        assert!(assemble(&tiles).unwrap_err().contains("square"));
+        assert!(assemble(&[]).is_err());
+
+        let (mut tiles, _) = scrambled_tiles(2, 6, &mut seed);
+        tiles[0].grid = Grid::new(6, 6, true);
+        tiles[1].grid = Grid::new(6, 6, false);
+        assert!(assemble(&tiles).unwrap_err().contains("No arrangement"));
+    }
+
+    #[test]
+    fn test_count_pattern_finds_monsters_in_any_orientation() {
+        let monster = Grid::parse(MONSTER, |c| c == '#').unwrap();
+        let mut image = Grid::new(24, 12, false);
+        // Two monsters and some unrelated noise
+        for origin in [Point2::new(1, 1), Point2::new(3, 6)] {
+            for (p, &lit) in monster.iter() {
+                if lit {
+                    image[origin + p] = true;
+                }
+            }
+        }
+        image[Point2::new(0, 11)] = true;
+        image[Point2::new(23, 0)] = true;
+        let lit = image.iter().filter(|(_, &l)| l).count();
+
+        // Hide the image under a rotation and a flip
+        let hidden = orientations(&image).swap_remove(6);
+        let count = count_pattern(&hidden, &monster);
+        assert_eq!(count.occurrences, 2);
+        assert_eq!(count.uncovered, lit - 2 * 15);
+        assert_eq!(count.uncovered, 2);
+    }
+}
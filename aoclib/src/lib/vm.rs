@@ -0,0 +1,462 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+
+/// Mnemonics of the eight opcodes, indexed by opcode.
+const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+/// A 3-bit computer with three registers, as in "chronospatial computer" puzzles.
+///
+/// Programs are lists of 3-bit numbers read as `opcode, operand` pairs:
+///
+/// | opcode | name  | effect                          |
+/// |--------|-------|---------------------------------|
+/// | 0      | `adv` | `A = A >> combo`                |
+/// | 1      | `bxl` | `B = B ^ literal`               |
+/// | 2      | `bst` | `B = combo % 8`                 |
+/// | 3      | `jnz` | jump to `literal` if `A != 0`   |
+/// | 4      | `bxc` | `B = B ^ C` (operand ignored)   |
+/// | 5      | `out` | output `combo % 8`              |
+/// | 6      | `bdv` | `B = A >> combo`                |
+/// | 7      | `cdv` | `C = A >> combo`                |
+///
+/// Combo operands 0-3 are literal, 4-6 read registers A-C and 7 is invalid. The
+/// program halts when the instruction pointer runs past its end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Computer {
+    /// Initial values of registers A, B and C.
+    pub registers: [u64; 3],
+    /// The program as 3-bit numbers.
+    pub program: Vec<u8>,
+}
+
+impl Computer {
+    /// Maximum number of instructions executed before a run is abandoned.
+    pub const MAX_STEPS: usize = 10_000_000;
+
+    /// Parses the `Register A: ...`, `Register B: ...`, `Register C: ...` and
+    /// `Program: ...` lines of a puzzle input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a register or the program is missing or malformed.
+    pub fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let mut registers = [None; 3];
+        let mut program = None;
+
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Expected 'key: value', got '{}'", line))?;
+            match key {
+                "Register A" => registers[0] = Some(value.trim().parse()?),
+                "Register B" => registers[1] = Some(value.trim().parse()?),
+                "Register C" => registers[2] = Some(value.trim().parse()?),
+                "Program" => {
+                    let values = value
+                        .split(',')
+                        .map(|v| match v.trim().parse::<u8>() {
+                            Ok(n) if n < 8 => Ok(n),
+                            _ => Err(format!("Invalid 3-bit value '{}'", v.trim())),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    program = Some(values);
+                }
+                other => return Err(format!("Unknown key '{}'", other).into()),
+            }
+        }
+
+        let register =
+            |i: usize| registers[i].ok_or(format!("Missing register {}", ["A", "B", "C"][i]));
+        Ok(Computer {
+            registers: [register(0)?, register(1)?, register(2)?],
+            program: program.ok_or("Missing program")?,
+        })
+    }
+
+    /// Runs the program from its initial registers and returns its output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on an invalid combo operand or if the program runs for more
+    /// than [`Computer::MAX_STEPS`] instructions.
+    pub fn run(&self) -> Result<Vec<u8>, String> {
+        self.run_with_a(self.registers[0])
+    }
+
+    /// Runs the program with register A replaced by `a`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Computer::run`].
+    pub fn run_with_a(&self, a: u64) -> Result<Vec<u8>, String> {
+        self.execute(a, |_, _| {})
+    }
+
+    /// Runs the program with register A replaced by `a`, recording how often each
+    /// instruction executes and which backward jumps are taken.
+    ///
+    /// # Errors
+    ///
+    /// See [`Computer::run`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoclib::vm::Computer;
+    ///
+    /// let c = Computer { registers: [729, 0, 0], program: vec![0, 1, 5, 4, 3, 0] };
+    /// let profile = c.profile_with_a(729).unwrap();
+    /// assert_eq!(profile.counts, vec![10, 10, 10]);
+    /// let hot = profile.hot_loops(5);
+    /// assert_eq!((hot[0].start, hot[0].end, hot[0].iterations), (0, 4, 9));
+    /// ```
+    pub fn profile_with_a(&self, a: u64) -> Result<Profile, String> {
+        let mut counts = vec![0; self.program.len() / 2];
+        let mut jumps: BTreeMap<(usize, usize), u64> = BTreeMap::new();
+        let output = self.execute(a, |ip, target| {
+            counts[ip / 2] += 1;
+            if let Some(target) = target.filter(|&t| t <= ip) {
+                *jumps.entry((target, ip)).or_insert(0) += 1;
+            }
+        })?;
+        Ok(Profile {
+            program: self.program.clone(),
+            output,
+            counts,
+            jumps,
+        })
+    }
+
+    /// Runs the program, calling `trace` before each instruction with its address and
+    /// the jump target if it is a taken jump.
+    fn execute<F>(&self, a: u64, mut trace: F) -> Result<Vec<u8>, String>
+    where
+        F: FnMut(usize, Option<usize>),
+    {
+        let mut reg = [a, self.registers[1], self.registers[2]];
+        let mut output = Vec::new();
+        let mut ip = 0;
+        let mut steps = 0;
+
+        while ip + 1 < self.program.len() {
+            steps += 1;
+            if steps > Self::MAX_STEPS {
+                return Err(format!(
+                    "Program did not halt within {} steps",
+                    Self::MAX_STEPS
+                ));
+            }
+            let (opcode, operand) = (self.program[ip], self.program[ip + 1]);
+            let literal = operand as u64;
+            let combo = || match operand {
+                0..=3 => Ok(literal),
+                4..=6 => Ok(reg[operand as usize - 4]),
+                _ => Err(format!("Invalid combo operand 7 at {}", ip)),
+            };
+            let shift = |value: u64, by: u64| {
+                value
+                    .checked_shr(by.min(u32::MAX as u64) as u32)
+                    .unwrap_or(0)
+            };
+
+            let jump = (opcode == 3 && reg[0] != 0).then_some(literal as usize);
+            trace(ip, jump);
+
+            match opcode {
+                0 => reg[0] = shift(reg[0], combo()?),
+                1 => reg[1] ^= literal,
+                2 => reg[1] = combo()? % 8,
+                3 if reg[0] != 0 => {
+                    ip = literal as usize;
+                    continue;
+                }
+                3 => {}
+                4 => reg[1] ^= reg[2],
+                5 => output.push((combo()? % 8) as u8),
+                6 => reg[1] = shift(reg[0], combo()?),
+                7 => reg[2] = shift(reg[0], combo()?),
+                _ => return Err(format!("Invalid opcode {} at {}", opcode, ip)),
+            }
+            ip += 2;
+        }
+
+        Ok(output)
+    }
+
+    /// Finds the smallest value of register A that makes the program output a copy of
+    /// itself.
+    ///
+    /// See [`search_input`] for the assumptions this relies on.
+    pub fn find_quine(&self) -> Option<u64> {
+        search_input(&self.program, 3, |a| self.run_with_a(a).ok())
+    }
+}
+
+/// Execution statistics from [`Computer::profile_with_a`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// The program that was run.
+    pub program: Vec<u8>,
+    /// What the program printed.
+    pub output: Vec<u8>,
+    /// How many times each instruction ran, indexed by instruction (address / 2).
+    pub counts: Vec<u64>,
+    /// How many times each backward jump was taken, keyed by `(target, address)`.
+    pub jumps: BTreeMap<(usize, usize), u64>,
+}
+
+/// A loop found by [`Profile::hot_loops`]: the instructions from `start` to `end`
+/// (addresses, inclusive), repeated through the jump at `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotLoop {
+    /// Address of the first instruction in the loop body.
+    pub start: usize,
+    /// Address of the jump that closes the loop.
+    pub end: usize,
+    /// How many times the jump was taken.
+    pub iterations: u64,
+    /// Instructions executed inside the loop, over all iterations.
+    pub executed: u64,
+}
+
+impl Profile {
+    /// Returns the total number of instructions executed.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Returns every loop whose closing jump was taken at least `min_iterations` times,
+    /// busiest first.
+    ///
+    /// These are the candidates for reading the program by hand: a loop that runs
+    /// millions of times is usually computing something (a multiplication, a division,
+    /// a digit of output) that can be replaced with a closed form.
+    pub fn hot_loops(&self, min_iterations: u64) -> Vec<HotLoop> {
+        let mut loops: Vec<HotLoop> = self
+            .jumps
+            .iter()
+            .filter(|(_, &n)| n >= min_iterations)
+            .map(|(&(start, end), &iterations)| HotLoop {
+                start,
+                end,
+                iterations,
+                executed: self.counts[start / 2..=end / 2].iter().sum(),
+            })
+            .collect();
+        loops.sort_by_key(|l| (std::cmp::Reverse(l.executed), l.start));
+        loops
+    }
+
+    /// Formats an annotated listing: each instruction with its execution count and
+    /// share of the total, followed by the loops taken at least `min_iterations` times.
+    pub fn report(&self, min_iterations: u64) -> String {
+        let total = self.total().max(1);
+        let mut out = String::new();
+        for (i, &count) in self.counts.iter().enumerate() {
+            let (opcode, operand) = (self.program[2 * i], self.program[2 * i + 1]);
+            let _ = writeln!(
+                out,
+                "{:>4}  {} {}  {:>12}  {:>5.1}%",
+                2 * i,
+                MNEMONICS.get(opcode as usize).unwrap_or(&"???"),
+                operand,
+                count,
+                100.0 * count as f64 / total as f64
+            );
+        }
+        for l in self.hot_loops(min_iterations) {
+            let _ = writeln!(
+                out,
+                "loop {}..={}: {} iterations, {} instructions",
+                l.start, l.end, l.iterations, l.executed
+            );
+        }
+        out
+    }
+}
+
+/// Finds the smallest input that makes `run` produce exactly `target`.
+///
+/// This is a constraint-guided depth-first search over output digits, built for
+/// programs that consume `bits_per_output` bits of their input per output value, with
+/// the last output depending only on the most significant bits (the usual shape of a
+/// loop that prints `f(A)` and then shifts `A` right). The input is built from its most
+/// significant end: each round appends `bits_per_output` bits and keeps only the
+/// candidates whose output matches the corresponding suffix of `target`, backtracking
+/// when none do.
+///
+/// # Arguments
+///
+/// * `target` - The output to reproduce.
+/// * `bits_per_output` - How many input bits each output value consumes.
+/// * `run` - Runs the program on an input, returning `None` if it fails.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::vm::search_input;
+///
+/// // Prints the input's octal digits from least to most significant
+/// let run = |mut a: u64| {
+///     let mut out = Vec::new();
+///     loop {
+///         out.push((a % 8) as u8);
+///         a /= 8;
+///         if a == 0 {
+///             return Some(out);
+///         }
+///     }
+/// };
+/// assert_eq!(search_input(&[5, 2, 7], 3, run), Some(0o725));
+/// ```
+pub fn search_input<F>(target: &[u8], bits_per_output: u32, mut run: F) -> Option<u64>
+where
+    F: FnMut(u64) -> Option<Vec<u8>>,
+{
+    fn dfs<F>(target: &[u8], bits: u32, prefix: u64, matched: usize, run: &mut F) -> Option<u64>
+    where
+        F: FnMut(u64) -> Option<Vec<u8>>,
+    {
+        if matched == target.len() {
+            return Some(prefix);
+        }
+        let suffix = &target[target.len() - matched - 1..];
+        for digit in 0..1u64 << bits {
+            let candidate = prefix.checked_shl(bits)? | digit;
+            if candidate == 0 {
+                continue;
+            }
+            if run(candidate).as_deref() == Some(suffix) {
+                if let Some(found) = dfs(target, bits, candidate, matched + 1, run) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    if target.is_empty() {
+        return None;
+    }
+    dfs(target, bits_per_output, 0, 0, &mut run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computer(registers: [u64; 3], program: &[u8]) -> Computer {
+        Computer {
+            registers,
+            program: program.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0\n";
+        assert_eq!(
+            Computer::parse(input).unwrap(),
+            computer([729, 0, 0], &[0, 1, 5, 4, 3, 0])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Computer::parse("Register A: 1\nProgram: 0,1").is_err());
+        assert!(
+            Computer::parse("Register A: 1\nRegister B: 0\nRegister C: 0\nProgram: 0,8").is_err()
+        );
+        assert!(
+            Computer::parse("Register A: x\nRegister B: 0\nRegister C: 0\nProgram: 0").is_err()
+        );
+    }
+
+    #[test]
+    fn test_run_example() {
+        let c = computer([729, 0, 0], &[0, 1, 5, 4, 3, 0]);
+        assert_eq!(c.run().unwrap(), vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_run_small_programs() {
+        assert_eq!(
+            computer([10, 0, 0], &[5, 0, 5, 1, 5, 4]).run().unwrap(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            computer([2024, 0, 0], &[0, 1, 5, 4, 3, 0]).run().unwrap(),
+            vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]
+        );
+        // bst 6 with C = 9 leaves B = 1, then output B
+        assert_eq!(computer([0, 0, 9], &[2, 6, 5, 5]).run().unwrap(), vec![1]);
+        // bxc with B = 2024, C = 43690 leaves B = 44354, and 44354 % 8 = 2
+        assert_eq!(
+            computer([0, 2024, 43690], &[4, 0, 5, 5]).run().unwrap(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_run_errors() {
+        assert!(computer([1, 0, 0], &[5, 7]).run().is_err());
+        // jnz 0 with A never reaching 0 loops forever
+        assert!(computer([1, 0, 0], &[3, 0]).run().is_err());
+    }
+
+    #[test]
+    fn test_find_quine_example() {
+        let c = computer([2024, 0, 0], &[0, 3, 5, 4, 3, 0]);
+        let a = c.find_quine().unwrap();
+        assert_eq!(a, 117440);
+        assert_eq!(c.run_with_a(a).unwrap(), c.program);
+    }
+
+    #[test]
+    fn test_profile_counts_and_loops() {
+        // bst A, cdv 1, adv 3, out B, jnz 0: one pass per octal digit of A
+        let c = computer([0, 0, 0], &[2, 4, 7, 1, 0, 3, 5, 5, 3, 0]);
+        let profile = c.profile_with_a(0o777).unwrap();
+        assert_eq!(profile.output, c.run_with_a(0o777).unwrap());
+        assert_eq!(profile.counts, vec![3, 3, 3, 3, 3]);
+        assert_eq!(profile.total(), 15);
+        assert_eq!(profile.jumps.len(), 1);
+
+        let hot = profile.hot_loops(1);
+        assert_eq!(
+            hot,
+            vec![HotLoop {
+                start: 0,
+                end: 8,
+                iterations: 2,
+                executed: 15
+            }]
+        );
+        assert!(profile.hot_loops(3).is_empty());
+    }
+
+    #[test]
+    fn test_profile_report() {
+        let c = computer([729, 0, 0], &[0, 1, 5, 4, 3, 0]);
+        let report = c.profile_with_a(729).unwrap().report(5);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("   0  adv 1"));
+        assert!(lines[2].contains("jnz 0"));
+        assert!(lines[2].ends_with("33.3%"));
+        assert_eq!(lines[3], "loop 0..=4: 9 iterations, 30 instructions");
+    }
+
+    #[test]
+    fn test_profile_propagates_errors() {
+        assert!(computer([1, 0, 0], &[5, 7]).profile_with_a(1).is_err());
+    }
+
+    #[test]
+    fn test_search_input_no_solution() {
+        // A program whose output never changes cannot produce [1]
+        assert_eq!(search_input(&[1], 3, |_| Some(vec![0])), None);
+        assert_eq!(search_input(&[], 3, |_| Some(Vec::new())), None);
+    }
+}
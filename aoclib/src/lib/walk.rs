@@ -0,0 +1,176 @@
+use crate::lib::point::{Direction, Point2};
+use std::collections::HashSet;
+use std::error::Error;
+
+/// Follows turn-and-walk instructions on an unbounded grid, remembering every point it
+/// passes through.
+///
+/// Points are recorded one step at a time, so crossing an earlier path mid-walk counts
+/// as a revisit even if the walker never stops there.
+///
+/// # Examples
+///
+/// ```
+/// use aoclib::walk::Walker;
+/// use aoclib::{Direction, Point2};
+///
+/// let mut walker = Walker::new(Point2::ORIGIN, Direction::Up);
+/// walker.follow("R8, R4, R4, R8").unwrap();
+/// assert_eq!(walker.first_revisit(), Some(Point2::new(4, 0)));
+/// assert_eq!(walker.position().manhattan(Point2::ORIGIN), 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Walker {
+    position: Point2,
+    heading: Direction,
+    visited: HashSet<Point2>,
+    first_revisit: Option<Point2>,
+    steps: usize,
+}
+
+impl Walker {
+    /// Creates a walker at `start` facing `heading`; the start counts as visited.
+    pub fn new(start: Point2, heading: Direction) -> Self {
+        Walker {
+            position: start,
+            heading,
+            visited: HashSet::from([start]),
+            first_revisit: None,
+            steps: 0,
+        }
+    }
+
+    /// Returns the current position.
+    pub fn position(&self) -> Point2 {
+        self.position
+    }
+
+    /// Returns the direction the walker is facing.
+    pub fn heading(&self) -> Direction {
+        self.heading
+    }
+
+    /// Returns every point visited so far, including the start.
+    pub fn visited(&self) -> &HashSet<Point2> {
+        &self.visited
+    }
+
+    /// Returns the first point the walker stepped on twice, if any.
+    pub fn first_revisit(&self) -> Option<Point2> {
+        self.first_revisit
+    }
+
+    /// Returns the total number of single steps taken.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Turns a quarter turn clockwise.
+    pub fn turn_right(&mut self) {
+        self.heading = self.heading.turn_right();
+    }
+
+    /// Turns a quarter turn anticlockwise.
+    pub fn turn_left(&mut self) {
+        self.heading = self.heading.turn_left();
+    }
+
+    /// Faces `heading` without moving.
+    pub fn face(&mut self, heading: Direction) {
+        self.heading = heading;
+    }
+
+    /// Walks `n` steps in the current heading.
+    pub fn forward(&mut self, n: usize) {
+        for _ in 0..n {
+            self.position = self.position.step(self.heading);
+            self.steps += 1;
+            if !self.visited.insert(self.position) && self.first_revisit.is_none() {
+                self.first_revisit = Some(self.position);
+            }
+        }
+    }
+
+    /// Follows comma or whitespace separated instructions such as `R2, L3`: `R` or
+    /// `L` turns right or left, and the number that follows is how far to walk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on an instruction that does not start with `R` or `L` or has no
+    /// valid distance. Instructions before it have already been applied.
+    pub fn follow(&mut self, instructions: &str) -> Result<(), Box<dyn Error>> {
+        for instruction in instructions
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+        {
+            let (turn, distance) = instruction.split_at(1.min(instruction.len()));
+            match turn {
+                "R" => self.turn_right(),
+                "L" => self.turn_left(),
+                _ => return Err(format!("Invalid instruction '{}'", instruction).into()),
+            }
+            let distance = distance
+                .parse()
+                .map_err(|e| format!("Invalid distance in '{}': {}", instruction, e))?;
+            self.forward(distance);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance_after(instructions: &str) -> usize {
+        let mut walker = Walker::new(Point2::ORIGIN, Direction::Up);
+        walker.follow(instructions).unwrap();
+        walker.position().manhattan(Point2::ORIGIN)
+    }
+
+    #[test]
+    fn test_follow_examples() {
+        assert_eq!(distance_after("R2, L3"), 5);
+        assert_eq!(distance_after("R2, R2, R2"), 2);
+        assert_eq!(distance_after("R5, L5, R5, R3"), 12);
+    }
+
+    #[test]
+    fn test_first_revisit_mid_walk() {
+        let mut walker = Walker::new(Point2::ORIGIN, Direction::Up);
+        walker.follow("R8, R4, R4, R8").unwrap();
+        assert_eq!(walker.first_revisit(), Some(Point2::new(4, 0)));
+        assert_eq!(walker.steps(), 24);
+        assert_eq!(walker.heading(), Direction::Up);
+    }
+
+    #[test]
+    fn test_no_revisit() {
+        let mut walker = Walker::new(Point2::new(3, 3), Direction::Right);
+        walker.forward(3);
+        walker.turn_left();
+        walker.forward(2);
+        assert_eq!(walker.position(), Point2::new(6, 1));
+        assert_eq!(walker.visited().len(), 6);
+        assert_eq!(walker.first_revisit(), None);
+    }
+
+    #[test]
+    fn test_returning_to_start_is_a_revisit() {
+        let mut walker = Walker::new(Point2::ORIGIN, Direction::Left);
+        walker.forward(1);
+        walker.face(Direction::Right);
+        walker.forward(2);
+        assert_eq!(walker.first_revisit(), Some(Point2::ORIGIN));
+    }
+
+    #[test]
+    fn test_follow_errors() {
+        let mut walker = Walker::new(Point2::ORIGIN, Direction::Up);
+        assert!(walker.follow("R2, X3").is_err());
+        // R2 was applied before the error
+        assert_eq!(walker.position(), Point2::new(2, 0));
+        assert!(walker.follow("R").is_err());
+        assert!(walker.follow("Lx").is_err());
+    }
+}
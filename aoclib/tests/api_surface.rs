@@ -0,0 +1,507 @@
+//! Snapshot of aoclib's public API.
+//!
+//! Every item re-exported from the crate root is listed in `tests/api_surface.txt`
+//! together with its signature, including any `where` clause, joined onto one line.
+//! Public fields, enum variants and their fields, and the rules of the exported macros
+//! are listed as well. Any addition, removal or signature change makes this test fail,
+//! so API changes are always a deliberate, reviewed edit.
+//!
+//! After an intentional change, regenerate the snapshot with:
+//!
+//! ```text
+//! UPDATE_API_SNAPSHOT=1 cargo test -p aoclib --test api_surface
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const SNAPSHOT: &str = "tests/api_surface.txt";
+
+/// A `pub use` statement, relative to the module it re-exports from.
+enum Export {
+    /// `pub use lib::module::{a, b};`
+    Items(String, Vec<String>),
+    /// `pub use lib::module;`
+    Module(String),
+}
+
+/// Collects the `pub use` statements starting with `prefix` (e.g. `pub use lib::` in
+/// the crate root), with the prefix removed.
+fn exports(source: &str, prefix: &str) -> Vec<Export> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+
+    for line in source.lines() {
+        let line = line.trim();
+        if current.is_empty() && !line.starts_with(prefix) {
+            continue;
+        }
+        current.push_str(line);
+        current.push(' ');
+        if line.ends_with(';') {
+            statements.push(std::mem::take(&mut current));
+        }
+    }
+
+    statements
+        .iter()
+        .map(|s| {
+            let path = s
+                .trim()
+                .trim_start_matches(prefix)
+                .trim_end_matches(';')
+                .trim();
+            match path.split_once("::") {
+                Some((module, items)) => {
+                    let items = items
+                        .trim_matches(|c| c == '{' || c == '}')
+                        .split(',')
+                        .map(|i| i.trim().to_string())
+                        .filter(|i| !i.is_empty())
+                        .collect();
+                    Export::Items(module.to_string(), items)
+                }
+                None => Export::Module(path.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Returns the name declared by a `pub` item line such as `pub fn name<T>(...)`.
+fn item_name(decl: &str) -> Option<String> {
+    const KINDS: [&str; 8] = [
+        "fn ", "struct ", "enum ", "trait ", "type ", "const ", "static ", "mod ",
+    ];
+    let rest = decl.strip_prefix("pub ")?;
+    let rest = rest.strip_prefix("const fn ").map_or(rest, |_| &rest[6..]);
+    let rest = rest.strip_prefix("unsafe fn ").map_or(rest, |_| &rest[7..]);
+    let rest = KINDS.iter().find_map(|k| rest.strip_prefix(k))?;
+    Some(
+        rest.chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect(),
+    )
+}
+
+/// Returns the self type of an `impl` header, e.g. `Grid` for `impl<T> Grid<T> {`.
+fn impl_target(header: &str) -> String {
+    let header = header.trim_end_matches('{').trim();
+    let target = match header.split_once(" for ") {
+        Some((_, target)) => target,
+        None => {
+            let rest = header.trim_start_matches("impl");
+            if rest.starts_with('<') {
+                let mut depth = 0;
+                let end = rest
+                    .char_indices()
+                    .find(|&(_, c)| {
+                        match c {
+                            '<' => depth += 1,
+                            '>' => depth -= 1,
+                            _ => {}
+                        }
+                        depth == 0
+                    })
+                    .map(|(i, _)| i + 1)
+                    .unwrap_or(0);
+                &rest[end..]
+            } else {
+                rest
+            }
+        }
+    };
+    target
+        .trim()
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect()
+}
+
+/// The kind of block a line of source is nested in.
+enum Block {
+    /// `impl Type { ... }`: only `pub` methods are part of the API.
+    Inherent,
+    /// `impl Trait for Type { ... }`: the header itself is the API entry.
+    TraitImpl,
+    /// `pub trait Trait { ... }`: every method and associated type is part of the API.
+    TraitDecl,
+    /// `pub enum Enum { ... }`: every variant and its fields are part of the API.
+    EnumDecl,
+    /// `pub struct Struct { ... }`: only `pub` fields are part of the API.
+    StructDecl,
+}
+
+/// Reads the signature starting on line `first` up to the `{` or `;` that ends it,
+/// taking further lines from `lines` as needed, and joins it into one line. A
+/// constant runs to its `;`, so its value is included.
+fn signature<'a>(first: &str, lines: &mut impl Iterator<Item = &'a str>) -> String {
+    let mut text = first.trim().to_string();
+    let constant = text.split_once(" = ").is_some_and(|(declaration, _)| {
+        declaration.contains("const ") && !declaration.contains("fn ")
+            || declaration.contains("static ")
+    });
+    let ends = |text: &str| match constant {
+        true => text.ends_with(';'),
+        false => text.ends_with(['{', ';', '}']),
+    };
+    while !ends(&text) {
+        match lines.next() {
+            Some(line) => {
+                text.push(' ');
+                text.push_str(line.trim());
+            }
+            None => break,
+        }
+    }
+    normalise(&text)
+}
+
+/// Collapses the whitespace of a signature and drops the line breaks and trailing
+/// commas rustfmt adds to long ones, so a signature reads the same however it is
+/// wrapped.
+fn normalise(signature: &str) -> String {
+    signature
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace("[ ", "[")
+        .replace("< ", "<")
+        .replace(", )", ")")
+        .replace(", ]", "]")
+        .replace(", >", ">")
+        .replace(" )", ")")
+        .replace(" ]", "]")
+        .trim_end_matches('{')
+        .trim_end()
+        .trim_end_matches(',')
+        .to_string()
+}
+
+/// Lists the public items of a module source file as `(owner, entry)` pairs, where
+/// `owner` is the top-level item the entry belongs to.
+///
+/// Items marked `#[doc(hidden)]` and everything after `#[cfg(test)]` are skipped.
+fn public_items(source: &str) -> Vec<(String, String)> {
+    let mut items = Vec::new();
+    let mut hidden = false;
+    let mut block: Option<(String, Block)> = None;
+    // The struct variant whose fields are being listed, inside an enum
+    let mut variant: Option<String> = None;
+    let mut lines = source.lines();
+
+    while let Some(line) = lines.next() {
+        if line == "#[cfg(test)]" {
+            break;
+        }
+        let trimmed = line.trim();
+        if trimmed == "#[doc(hidden)]" {
+            hidden = true;
+            continue;
+        }
+        if trimmed.starts_with("#[") || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if line == "}" {
+            block = None;
+        } else if line.starts_with("impl") {
+            let target = impl_target(line);
+            let kind = if line.contains(" for ") {
+                let header = signature(line, &mut lines);
+                if !hidden {
+                    items.push((target.clone(), header));
+                }
+                Block::TraitImpl
+            } else {
+                Block::Inherent
+            };
+            block = Some((target, kind));
+        } else if !hidden {
+            match &block {
+                None if !line.starts_with(' ') => {
+                    if let Some(name) = item_name(trimmed) {
+                        let signature = signature(line, &mut lines);
+                        let opens_block = !signature.ends_with(['}', ';']);
+                        items.push((name.clone(), format!("{}: {}", name, signature)));
+                        if opens_block {
+                            if trimmed.starts_with("pub trait") {
+                                block = Some((name, Block::TraitDecl));
+                            } else if trimmed.starts_with("pub enum") {
+                                block = Some((name, Block::EnumDecl));
+                            } else if trimmed.starts_with("pub struct") {
+                                block = Some((name, Block::StructDecl));
+                            }
+                        }
+                    }
+                }
+                Some((target, Block::Inherent)) if line.starts_with("    pub") => {
+                    if let Some(name) = item_name(trimmed) {
+                        let signature = signature(line, &mut lines);
+                        items.push((
+                            target.clone(),
+                            format!("{}::{}: {}", target, name, signature),
+                        ));
+                    }
+                }
+                Some((target, Block::TraitDecl))
+                    if line.starts_with("    fn ") || line.starts_with("    type ") =>
+                {
+                    if let Some(name) = item_name(&format!("pub {}", trimmed)) {
+                        let signature = signature(line, &mut lines);
+                        items.push((
+                            target.clone(),
+                            format!("{}::{}: {}", target, name, signature),
+                        ));
+                    }
+                }
+                Some((target, Block::EnumDecl))
+                    if line.starts_with("    ") && line[4..].starts_with(char::is_uppercase) =>
+                {
+                    let name = trimmed.trim_end_matches(['{', ',']).trim();
+                    items.push((target.clone(), format!("{}::{}", target, name)));
+                    if trimmed.ends_with('{') {
+                        variant = Some(name.to_string());
+                    }
+                }
+                Some((_, Block::EnumDecl)) if line.starts_with("    }") => variant = None,
+                Some((target, Block::EnumDecl)) if line.starts_with("        ") => {
+                    if let Some(variant) = &variant {
+                        let field = normalise(trimmed);
+                        items.push((
+                            target.clone(),
+                            format!("{}::{}::{}", target, variant, field),
+                        ));
+                    }
+                }
+                Some((target, Block::StructDecl)) if line.starts_with("    pub ") => {
+                    let field = normalise(trimmed);
+                    if let Some((name, _)) = field["pub ".len()..].split_once(':') {
+                        items.push((target.clone(), format!("{}::{}: {}", target, name, field)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        hidden = false;
+    }
+
+    items
+}
+
+/// Lists the rules of the `#[macro_export]` macros in a source file, as the macro's
+/// name followed by the rule's matcher.
+fn exported_macros(source: &str) -> Vec<String> {
+    let mut macros = Vec::new();
+    let mut lines = source.lines();
+
+    while let Some(line) = lines.next() {
+        if line != "#[macro_export]" {
+            continue;
+        }
+        let Some(name) = lines.next().and_then(|l| l.strip_prefix("macro_rules! ")) else {
+            continue;
+        };
+        let name = name.trim_end_matches('{').trim();
+        for rule in lines.by_ref().take_while(|l| *l != "}") {
+            if let Some((matcher, _)) = rule
+                .strip_prefix("    (")
+                .and_then(|rule| rule.rsplit_once(" => "))
+            {
+                macros.push(format!("macro {}!({}", name, normalise(matcher)));
+            }
+        }
+    }
+
+    macros
+}
+
+/// Lists the exported macros of every source file under `dir`; they belong to the
+/// crate root whichever module defines them.
+fn macros_under(dir: &Path) -> Vec<String> {
+    let mut macros = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            macros.extend(macros_under(&path));
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            macros.extend(exported_macros(&fs::read_to_string(&path).unwrap()));
+        }
+    }
+    macros
+}
+
+/// Lists the public items of `src/lib/<module>.rs`, including those it re-exports
+/// from its own submodules in `src/lib/<module>/` with `pub use sub::{...};`.
+fn module_items(root: &Path, module: &str) -> Vec<(String, String)> {
+    let source = fs::read_to_string(root.join(format!("src/lib/{}.rs", module)))
+        .unwrap_or_else(|e| panic!("cannot read module '{}': {}", module, e));
+    let mut items = public_items(&source);
+
+    for export in exports(&source, "pub use ") {
+        if let Export::Items(sub, names) = export {
+            let path = format!("{}/{}", module, sub);
+            if root.join(format!("src/lib/{}.rs", path)).is_file() {
+                let reexported = module_items(root, &path)
+                    .into_iter()
+                    .filter(|(owner, _)| names.contains(owner));
+                items.extend(reexported);
+            }
+        }
+    }
+
+    items
+}
+
+/// Renders the full public API surface of the crate.
+fn api_surface(root: &Path) -> Vec<String> {
+    let lib_rs = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+    let mut surface = Vec::new();
+
+    for export in exports(&lib_rs, "pub use lib::") {
+        let (module, filter, prefix) = match export {
+            Export::Items(module, items) => (module, Some(items), String::new()),
+            Export::Module(module) => {
+                let prefix = format!("{}::", module);
+                surface.push(format!("mod {}", module));
+                (module, None, prefix)
+            }
+        };
+        for (owner, entry) in module_items(root, &module) {
+            let exported = filter.as_ref().is_none_or(|items| items.contains(&owner));
+            if exported {
+                surface.push(format!("{}{}", prefix, entry));
+            }
+        }
+    }
+
+    surface.extend(macros_under(&root.join("src")));
+    surface.sort();
+    surface
+}
+
+#[test]
+fn test_public_api_matches_snapshot() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let actual = api_surface(root).join("\n") + "\n";
+    let snapshot_path = root.join(SNAPSHOT);
+
+    if env::var_os("UPDATE_API_SNAPSHOT").is_some() {
+        fs::write(&snapshot_path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&snapshot_path).unwrap_or_default();
+    if expected != actual {
+        let expected: Vec<&str> = expected.lines().collect();
+        let actual: Vec<&str> = actual.lines().collect();
+        let removed: Vec<_> = expected.iter().filter(|l| !actual.contains(l)).collect();
+        let added: Vec<_> = actual.iter().filter(|l| !expected.contains(l)).collect();
+        panic!(
+            "aoclib public API changed.\nremoved: {:#?}\nadded: {:#?}\n\
+             Re-run with UPDATE_API_SNAPSHOT=1 if the change is intentional.",
+            removed, added
+        );
+    }
+}
+
+#[test]
+fn test_item_name() {
+    assert_eq!(
+        item_name("pub fn parse_lines<T, P>(path: P)"),
+        Some("parse_lines".into())
+    );
+    assert_eq!(item_name("pub struct Grid<T> {"), Some("Grid".into()));
+    assert_eq!(
+        item_name("pub const fn new(x: isize) -> Self"),
+        Some("new".into())
+    );
+    assert_eq!(item_name("pub const ORIGIN: Point2"), Some("ORIGIN".into()));
+    assert_eq!(
+        item_name("pub unsafe fn read_input_mmap<P>(path: P)"),
+        Some("read_input_mmap".into())
+    );
+    assert_eq!(item_name("fn private()"), None);
+}
+
+#[test]
+fn test_public_items() {
+    let source = "\
+pub fn parse<T, P>(
+    path: P,
+    strict: bool,
+) -> Result<Vec<T>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+}
+
+pub struct Cycle {
+    pub start: usize,
+    hidden: usize,
+}
+
+pub enum AocError {
+    Io(io::Error),
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+pub const ALL: [u8; 2] = [
+    1, 2,
+];
+";
+    let entries: Vec<String> = public_items(source).into_iter().map(|(_, e)| e).collect();
+    assert_eq!(
+        entries,
+        [
+            "parse: pub fn parse<T, P>(path: P, strict: bool) -> Result<Vec<T>, Error> \
+             where T: FromStr, T::Err: Display",
+            "Cycle: pub struct Cycle",
+            "Cycle::start: pub start: usize",
+            "AocError: pub enum AocError",
+            "AocError::Io(io::Error)",
+            "AocError::Parse",
+            "AocError::Parse::line: usize",
+            "AocError::Parse::message: String",
+            "ALL: pub const ALL: [u8; 2] = [1, 2];",
+        ]
+    );
+}
+
+#[test]
+fn test_exported_macros() {
+    let source = "\
+#[macro_export]
+macro_rules! examples {
+    () => {
+        $crate::examples!(\"example.txt\")
+    };
+    ($($file:literal),+ $(,)?) => {
+        &[$(include_str!($file)),+]
+    };
+}
+
+macro_rules! private {
+    () => {};
+}
+";
+    assert_eq!(
+        exported_macros(source),
+        [
+            "macro examples!()",
+            "macro examples!($($file:literal),+ $(,)?)"
+        ]
+    );
+}
+
+#[test]
+fn test_impl_target() {
+    assert_eq!(impl_target("impl<T: Clone> Grid<T> {"), "Grid");
+    assert_eq!(impl_target("impl fmt::Display for Answer {"), "Answer");
+    assert_eq!(impl_target("impl Answer {"), "Answer");
+}
@@ -0,0 +1,875 @@
+use aoclib::render::frame;
+use aoclib::{Answer, Solver};
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// The total number of positions in the puzzle's circular track
+const TRACK_SIZE: isize = 100;
+
+/// The puzzle's starting position on the track
+const START_POSITION: isize = 50;
+
+/// The most columns a visualisation uses; on longer tracks each column covers
+/// several neighbouring positions
+const MAX_WIDTH: isize = 100;
+
+/// Heatmap shading, from no visits to the most visited column
+const SHADES: &[u8] = b" .:-=+*#%@";
+
+/// Solver for the circular track puzzle.
+///
+/// The puzzle's track has 100 positions and starts at 50 ([`Day01::default`]); both
+/// can be changed from the command line to solve similar circular-track problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day01 {
+    /// The number of positions on the track, numbered from 0.
+    pub track_size: isize,
+    /// The position the dial starts at.
+    pub start: isize,
+}
+
+impl Default for Day01 {
+    fn default() -> Self {
+        Day01 {
+            track_size: TRACK_SIZE,
+            start: START_POSITION,
+        }
+    }
+}
+
+impl Day01 {
+    /// Reads the track from command-line arguments, starting from the puzzle's:
+    ///
+    /// * `--track-size N` - the track has positions `0..N`
+    /// * `--start P` - the dial starts at position `P`, taken modulo the track size
+    ///
+    /// Other arguments are left for [`aoclib::run`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option is missing its value or the value is invalid.
+    pub fn from_args(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        let value = |name: &str| -> Result<Option<isize>, Box<dyn Error>> {
+            let Some(i) = args.iter().position(|arg| arg == name) else {
+                return Ok(None);
+            };
+            let value = args
+                .get(i + 1)
+                .ok_or_else(|| format!("Option '{}' requires a value", name))?;
+            Ok(Some(value.parse().map_err(|_| {
+                format!("Invalid value '{}' for '{}'", value, name)
+            })?))
+        };
+        let mut day = Day01::default();
+        if let Some(track_size) = value("--track-size")? {
+            if track_size < 1 {
+                return Err(format!("Track size must be at least 1, got {}", track_size).into());
+            }
+            day.track_size = track_size;
+        }
+        if let Some(start) = value("--start")? {
+            day.start = start;
+        }
+        // The default start is reduced too, so a short track alone stays consistent
+        day.start = day.start.rem_euclid(day.track_size);
+        Ok(day)
+    }
+}
+
+impl Solver for Day01 {
+    type Input = Vec<Instruction>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        input
+            .lines()
+            .map(|line| Instruction::from_str(line).map_err(|e| e.into()))
+            .collect()
+    }
+
+    fn part1(&self, instructions: &Self::Input) -> Answer {
+        part1(instructions, self.track_size, self.start).into()
+    }
+
+    fn part2(&self, instructions: &Self::Input) -> Answer {
+        part2(instructions, self.track_size, self.start).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        aoclib::examples!()
+    }
+}
+
+/// Parses the puzzle input and solves both parts.
+///
+/// # Errors
+///
+/// Returns an error if any line is not a valid instruction.
+pub fn solve(input: &str) -> Result<(Answer, Answer), Box<dyn Error>> {
+    Day01::default().solve(input)
+}
+
+/// Solves part 1: counts how many times position 0 is reached after each complete
+/// instruction.
+///
+/// Starting at position `start` on a track of `track_size` positions (50 and 100 in
+/// the puzzle), applies each instruction all at once and checks if the final position
+/// lands on 0.
+pub fn part1(instructions: &[Instruction], track_size: isize, start: isize) -> usize {
+    instructions
+        .iter()
+        .fold(Dial::new(start), |dial, instruction| {
+            dial.stop(instruction, track_size)
+        })
+        .zeros
+}
+
+/// Solves part 2: counts how many times position 0 is crossed during step-by-step movement.
+///
+/// Starting at position `start` on a track of `track_size` positions, moves one step
+/// at a time for each turn and counts every time position 0 is reached during the
+/// movement (not just at the end). Each turn's count is worked out arithmetically, so
+/// the run time does not depend on how large the rotations are. A jump moves straight
+/// to its target, so it only counts if it lands on 0.
+pub fn part2(instructions: &[Instruction], track_size: isize, start: isize) -> usize {
+    instructions
+        .iter()
+        .fold(Dial::new(start), |dial, instruction| {
+            dial.sweep(instruction, track_size)
+        })
+        .zeros
+}
+
+/// Solves both parts in a single pass over a stream of instructions, returning
+/// `(part1, part2)`.
+///
+/// Nothing but the two [`Dial`]s is kept between instructions, so an input of any
+/// size is solved in constant memory when the instructions are parsed lazily, e.g.
+/// line by line from a file.
+///
+/// # Errors
+///
+/// Stops at and returns the first error in the stream.
+pub fn solve_stream<I, E>(
+    instructions: I,
+    track_size: isize,
+    start: isize,
+) -> Result<(usize, usize), E>
+where
+    I: IntoIterator<Item = Result<Instruction, E>>,
+{
+    let (stops, sweeps) = instructions.into_iter().try_fold(
+        (Dial::new(start), Dial::new(start)),
+        |(stops, sweeps), instruction| {
+            let instruction = instruction?;
+            Ok((
+                stops.stop(&instruction, track_size),
+                sweeps.sweep(&instruction, track_size),
+            ))
+        },
+    )?;
+    Ok((stops.zeros, sweeps.zeros))
+}
+
+/// The state carried from one instruction to the next: where the dial points and how
+/// many times it has reached 0 so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    pub position: isize,
+    pub zeros: usize,
+}
+
+impl Dial {
+    /// A dial at `start` that has not reached 0 yet.
+    pub fn new(start: isize) -> Self {
+        Dial {
+            position: start,
+            zeros: 0,
+        }
+    }
+
+    /// Applies `instruction` all at once, counting it if it ends on 0 (part 1).
+    pub fn stop(self, instruction: &Instruction, track_size: isize) -> Self {
+        let position = instruction.apply(self.position, track_size);
+        Dial {
+            position,
+            zeros: self.zeros + usize::from(position == 0),
+        }
+    }
+
+    /// Applies `instruction` one step at a time, counting every step that lands on 0
+    /// (part 2). A negative rotation takes no steps.
+    pub fn sweep(self, instruction: &Instruction, track_size: isize) -> Self {
+        let (steps, direction, towards_zero) = match *instruction {
+            Instruction::Right(rotation) => (rotation.max(0), 1, track_size - self.position),
+            Instruction::Left(rotation) => (rotation.max(0), -1, self.position),
+            Instruction::Jump(_) => return self.stop(instruction, track_size),
+        };
+        Dial {
+            position: (self.position + direction * steps).rem_euclid(track_size),
+            zeros: self.zeros + zeros_reached(steps, towards_zero, track_size),
+        }
+    }
+}
+
+/// Draws one line per instruction showing the track during that move, as part 2
+/// sees it: `~` for the positions stepped over, `@` where the move stops and `*` at
+/// position 0 if the move reached it (`|` marks position 0 otherwise). The move, the
+/// positions it went between and the zeros it reached follow the strip.
+///
+/// Tracks longer than 100 positions are scaled down to 100 columns.
+pub fn strips(instructions: &[Instruction], track_size: isize, start: isize) -> String {
+    let width = track_size.min(MAX_WIDTH);
+    let column = |position| column(position, width, track_size);
+    let mut out = String::new();
+    let mut dial = Dial::new(start);
+
+    for instruction in instructions {
+        let after = dial.sweep(instruction, track_size);
+        let mut cells = vec!['.'; width as usize];
+        cells[0] = '|';
+        let (laps, runs) = steps_taken(dial.position, instruction, track_size);
+        if laps > 0 {
+            cells.fill('~');
+        }
+        for run in runs {
+            cells[column(*run.start())..=column(*run.end())].fill('~');
+        }
+        if after.zeros > dial.zeros {
+            cells[0] = '*';
+        }
+        cells[column(after.position)] = '@';
+        let zeros = match after.zeros - dial.zeros {
+            0 => String::new(),
+            1 => "  1 zero".to_string(),
+            n => format!("  {} zeros", n),
+        };
+        let line = format!(
+            "{} {:>6} {:>3} -> {:<3}{}",
+            cells.into_iter().collect::<String>(),
+            instruction.to_string(),
+            dial.position,
+            after.position,
+            zeros
+        );
+        out += line.trim_end();
+        out.push('\n');
+        dial = after;
+    }
+    out
+}
+
+/// Draws a heatmap of how often each position is stepped on over every move, with a
+/// ruler of positions underneath and a line saying how often 0 was reached.
+///
+/// The shading runs from the least to the most visited position, so the pattern
+/// shows even when whole laps have stepped on every position many times; positions
+/// never stepped on are blank. Tracks longer than 100 positions are scaled down to
+/// 100 columns, each showing the average of the positions it covers, rounded up so
+/// that a column with any visits is never blank.
+pub fn heatmap(instructions: &[Instruction], track_size: isize, start: isize) -> String {
+    let width = track_size.min(MAX_WIDTH);
+    // Visits after the last whole lap of each move, summed over each column's positions
+    let mut visits = vec![0u128; width as usize];
+    let mut laps = 0;
+    let mut dial = Dial::new(start);
+
+    for instruction in instructions {
+        let (whole, runs) = steps_taken(dial.position, instruction, track_size);
+        laps += whole;
+        for run in runs {
+            let (first, last) = (*run.start(), *run.end());
+            for col in column(first, width, track_size)..=column(last, width, track_size) {
+                let col = col as isize;
+                let from = first.max(column_start(col, width, track_size));
+                let to = last.min(column_start(col + 1, width, track_size) - 1);
+                visits[col as usize] += (to - from + 1) as u128;
+            }
+        }
+        dial = dial.sweep(instruction, track_size);
+    }
+
+    let columns: Vec<u64> = (0..width)
+        .map(|col| {
+            let positions =
+                column_start(col + 1, width, track_size) - column_start(col, width, track_size);
+            laps + visits[col as usize].div_ceil(positions as u128) as u64
+        })
+        .collect();
+    let visited = || columns.iter().copied().filter(|&v| v > 0);
+    let (least, most) = (visited().min().unwrap_or(0), visited().max().unwrap_or(0));
+    let levels = SHADES.len() as u64 - 2;
+    let heat: String = columns
+        .iter()
+        .map(|&v| match v {
+            0 => ' ',
+            v => SHADES[1 + ((v - least) * levels / (most - least).max(1)) as usize] as char,
+        })
+        .collect();
+
+    let mut ruler = vec![' '; width as usize + 20];
+    let mut free = 0;
+    for col in (0..width as usize).step_by(10) {
+        let label = column_start(col as isize, width, track_size).to_string();
+        // Long labels on huge tracks would run into each other, so skip those that do
+        if col + 1 < free {
+            continue;
+        }
+        for (i, c) in label.chars().enumerate() {
+            ruler[col + 1 + i] = c;
+        }
+        free = col + 2 + label.len();
+    }
+    format!(
+        "{}\n{}\nShading from '.' ({} visits) to '@' ({} visits); position 0 reached {} times\n",
+        frame(&[heat]),
+        ruler.into_iter().collect::<String>().trim_end(),
+        least,
+        most,
+        dial.zeros
+    )
+}
+
+/// Splits the steps `instruction` takes from `position` into whole laps, which step
+/// on every position, and the positions stepped on after the last whole lap, as at
+/// most two runs of neighbouring positions (two when they wrap past the end).
+fn steps_taken(
+    position: isize,
+    instruction: &Instruction,
+    track_size: isize,
+) -> (u64, Vec<RangeInclusive<isize>>) {
+    let (steps, lowest) = match *instruction {
+        Instruction::Right(rotation) => {
+            let steps = rotation.max(0);
+            (steps, (position + 1).rem_euclid(track_size))
+        }
+        Instruction::Left(rotation) => {
+            let steps = rotation.max(0);
+            (
+                steps,
+                (position - steps % track_size).rem_euclid(track_size),
+            )
+        }
+        Instruction::Jump(target) => {
+            let target = target.rem_euclid(track_size);
+            return (0, vec![target..=target]);
+        }
+    };
+    let laps = (steps / track_size) as u64;
+    let rest = steps % track_size;
+    let runs = if rest == 0 {
+        Vec::new()
+    } else if rest <= track_size - lowest {
+        vec![lowest..=lowest + (rest - 1)]
+    } else {
+        vec![
+            lowest..=track_size - 1,
+            0..=rest - 1 - (track_size - lowest),
+        ]
+    };
+    (laps, runs)
+}
+
+/// The column, out of `width`, that shows `position` on a track of `track_size`
+/// positions.
+fn column(position: isize, width: isize, track_size: isize) -> usize {
+    (position as i128 * width as i128 / track_size as i128) as usize
+}
+
+/// The first position shown in column `col`, out of `width`, on a track of
+/// `track_size` positions; `track_size` for the column after the last.
+fn column_start(col: isize, width: isize, track_size: isize) -> isize {
+    ((col as i128 * track_size as i128 + width as i128 - 1) / width as i128) as isize
+}
+
+/// Counts how many of `steps` single steps land on position 0, when the first step to
+/// reach 0 is step `towards_zero` (a full lap if that is 0, i.e. the move starts there).
+fn zeros_reached(steps: isize, towards_zero: isize, track_size: isize) -> usize {
+    let first = if towards_zero == 0 {
+        track_size
+    } else {
+        towards_zero
+    };
+    if steps < first {
+        0
+    } else {
+        ((steps - first) / track_size + 1) as usize
+    }
+}
+
+/// One line of the puzzle input: a move around the track.
+///
+/// Each instruction is an opcode followed by a number, e.g. `R5`, `CCW12` or `=25`.
+/// The opcodes are listed in [`OPCODES`]; new forms are added by giving them a
+/// variant and an entry there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    /// Turn right (clockwise, up) by the specified amount
+    Right(isize),
+    /// Turn left (anticlockwise, down) by the specified amount
+    Left(isize),
+    /// Move straight to the specified position, taken modulo the track size
+    Jump(isize),
+}
+
+/// An opcode's text and the instruction variant it builds from the number after it.
+pub type Opcode = (&'static str, fn(isize) -> Instruction);
+
+/// Every opcode [`Instruction`] parses.
+pub const OPCODES: &[Opcode] = &[
+    ("R", Instruction::Right),
+    ("L", Instruction::Left),
+    ("U", Instruction::Right),
+    ("D", Instruction::Left),
+    ("CW", Instruction::Right),
+    ("CCW", Instruction::Left),
+    ("=", Instruction::Jump),
+];
+
+impl Instruction {
+    /// Returns the position reached by applying the instruction at `position` on a
+    /// track of `track_size` positions.
+    pub fn apply(&self, position: isize, track_size: isize) -> isize {
+        match *self {
+            Instruction::Right(rotation) => (position + rotation).rem_euclid(track_size),
+            Instruction::Left(rotation) => (position - rotation).rem_euclid(track_size),
+            Instruction::Jump(target) => target.rem_euclid(track_size),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Writes the instruction in the input's format, with the shortest opcode.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Right(rotation) => write!(f, "R{}", rotation),
+            Instruction::Left(rotation) => write!(f, "L{}", rotation),
+            Instruction::Jump(target) => write!(f, "={}", target),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = String;
+
+    /// Parses an instruction from a string like "R5", "CCW3" or "=25".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The string is empty
+    /// - The opcode is not one of [`OPCODES`]
+    /// - The remaining characters cannot be parsed as an integer
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("Empty string cannot be parsed as Instruction".to_string());
+        }
+
+        // The longest matching opcode wins, so a short one never shadows a longer one
+        let opcode = OPCODES
+            .iter()
+            .filter(|(name, _)| s.starts_with(name))
+            .max_by_key(|(name, _)| name.len());
+        let Some((name, build)) = opcode else {
+            let end = s
+                .find(|c: char| c.is_ascii_digit() || c == '-' || c == '+')
+                .unwrap_or(s.len());
+            let opcode = &s[..end];
+            let names: Vec<&str> = OPCODES.iter().map(|(name, _)| *name).collect();
+            return Err(format!(
+                "Unknown opcode '{}' in '{}', expected one of {}",
+                opcode,
+                s,
+                names.join(", ")
+            ));
+        };
+        let amount = s[name.len()..]
+            .parse::<isize>()
+            .map_err(|e| format!("Failed to parse rotation amount: {}", e))?;
+        Ok(build(amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_parse_right() {
+        let turn = Instruction::from_str("R5").unwrap();
+        match turn {
+            Instruction::Right(5) => (),
+            _ => panic!("Expected Right(5)"),
+        }
+    }
+
+    #[test]
+    fn test_turn_parse_left() {
+        let turn = Instruction::from_str("L10").unwrap();
+        match turn {
+            Instruction::Left(10) => (),
+            _ => panic!("Expected Left(10)"),
+        }
+    }
+
+    #[test]
+    fn test_turn_parse_large_number() {
+        let turn = Instruction::from_str("R999").unwrap();
+        match turn {
+            Instruction::Right(999) => (),
+            _ => panic!("Expected Right(999)"),
+        }
+    }
+
+    #[test]
+    fn test_instruction_parse_other_opcodes() {
+        let parse = |s: &str| Instruction::from_str(s).unwrap();
+        assert_eq!(parse("U7"), Instruction::Right(7));
+        assert_eq!(parse("CW7"), Instruction::Right(7));
+        assert_eq!(parse("D7"), Instruction::Left(7));
+        assert_eq!(parse("CCW7"), Instruction::Left(7));
+        assert_eq!(parse("=25"), Instruction::Jump(25));
+        assert_eq!(parse("=-1"), Instruction::Jump(-1));
+        assert!(Instruction::from_str("C5")
+            .unwrap_err()
+            .starts_with("Unknown opcode 'C'"));
+        assert!(Instruction::from_str("5")
+            .unwrap_err()
+            .starts_with("Unknown opcode ''"));
+    }
+
+    #[test]
+    fn test_jumps() {
+        let instructions = vec![
+            Instruction::Jump(0),
+            Instruction::Jump(-1),
+            Instruction::Right(1),
+            Instruction::Jump(10),
+            Instruction::Left(210),
+        ];
+        // Lands on 0 after the first jump, the right turn and the left turn
+        assert_eq!(part1(&instructions, TRACK_SIZE, START_POSITION), 3);
+        // Also passes 0 twice more on the way left from 10
+        assert_eq!(part2(&instructions, TRACK_SIZE, START_POSITION), 5);
+        assert_eq!(
+            part2(&instructions, TRACK_SIZE, START_POSITION),
+            part2_stepwise(&instructions)
+        );
+    }
+
+    #[test]
+    fn test_turn_parse_empty_string() {
+        let result = Instruction::from_str("");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Empty string"));
+    }
+
+    #[test]
+    fn test_turn_parse_invalid_direction() {
+        let result = Instruction::from_str("X5");
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            "Unknown opcode 'X' in 'X5', expected one of R, L, U, D, CW, CCW, ="
+        );
+    }
+
+    #[test]
+    fn test_turn_parse_invalid_number() {
+        let result = Instruction::from_str("Rabc");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .contains("Failed to parse rotation amount"));
+    }
+
+    #[test]
+    fn test_turn_parse_missing_number() {
+        let result = Instruction::from_str("R");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_modulo_wrap_around() {
+        // Test that 99 + 2 wraps to 1
+        let position = 99_isize;
+        let new_position = (position + 2).rem_euclid(TRACK_SIZE);
+        assert_eq!(new_position, 1);
+    }
+
+    #[test]
+    fn test_negative_wrap_around() {
+        // Test that 1 - 2 wraps to 99
+        let position = 1_isize;
+        let new_position = (position - 2).rem_euclid(TRACK_SIZE);
+        assert_eq!(new_position, 99);
+    }
+
+    #[test]
+    fn test_part1_single_turn_hits_zero() {
+        let turns = vec![Instruction::Right(50)];
+        // Starting at 50, moving right 50 should land on 0
+        assert_eq!(part1(&turns, TRACK_SIZE, START_POSITION), 1);
+    }
+
+    #[test]
+    fn test_part1_wraps_correctly() {
+        let turns = vec![Instruction::Right(150)];
+        // Starting at 50, moving right 150 should wrap around
+        // (50 + 150) % 100 = 0, so should hit zero
+        assert_eq!(part1(&turns, TRACK_SIZE, START_POSITION), 1);
+    }
+
+    #[test]
+    fn test_part1_left_turn() {
+        let turns = vec![Instruction::Left(50)];
+        // Starting at 50, moving left 50 should land on 0
+        assert_eq!(part1(&turns, TRACK_SIZE, START_POSITION), 1);
+    }
+
+    #[test]
+    fn test_part2_single_step() {
+        let turns = vec![Instruction::Right(1)];
+        // Starting at 50, moving right 1 should land on 51
+        assert_eq!(part2(&turns, TRACK_SIZE, START_POSITION), 0);
+    }
+
+    #[test]
+    fn test_part2_crosses_zero() {
+        let turns = vec![Instruction::Right(50)];
+        // Starting at 50, moving right 50 steps should cross 0 once
+        assert_eq!(part2(&turns, TRACK_SIZE, START_POSITION), 1);
+    }
+
+    #[test]
+    fn test_part2_multiple_crosses() {
+        let turns = vec![Instruction::Right(250)];
+        // Starting at 50, moving right 250 steps should cross 0 at steps 50, 150 and 250
+        assert_eq!(part2(&turns, TRACK_SIZE, START_POSITION), 3);
+    }
+
+    /// The original step-by-step simulation of part 2.
+    fn part2_stepwise(turns: &[Instruction]) -> usize {
+        let mut position = START_POSITION;
+        let mut count = 0;
+        for turn in turns {
+            let (rotation, step) = match turn {
+                Instruction::Right(rotation) => (*rotation, 1),
+                Instruction::Left(rotation) => (*rotation, -1),
+                Instruction::Jump(_) => {
+                    position = turn.apply(position, TRACK_SIZE);
+                    count += usize::from(position == 0);
+                    continue;
+                }
+            };
+            for _ in 0..rotation {
+                position = (position + step).rem_euclid(TRACK_SIZE);
+                if position == 0 {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_part2_matches_stepwise() {
+        let edge_cases = [
+            vec![
+                Instruction::Left(50),
+                Instruction::Left(100),
+                Instruction::Right(100),
+            ],
+            vec![
+                Instruction::Left(50),
+                Instruction::Right(1),
+                Instruction::Left(1),
+                Instruction::Left(1),
+            ],
+            vec![
+                Instruction::Right(49),
+                Instruction::Right(1),
+                Instruction::Left(200),
+                Instruction::Right(0),
+            ],
+            vec![
+                Instruction::Left(-5),
+                Instruction::Right(-250),
+                Instruction::Left(351),
+            ],
+        ];
+        for turns in &edge_cases {
+            assert_eq!(
+                part2(turns, TRACK_SIZE, START_POSITION),
+                part2_stepwise(turns),
+                "{:?}",
+                turns
+            );
+        }
+
+        // A fixed pseudo-random walk covering every position and rotation size
+        let mut seed = 12345u64;
+        let turns: Vec<Instruction> = (0..2000)
+            .map(|_| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                let rotation = (seed >> 33) as isize % 1000;
+                if seed >> 63 == 0 {
+                    Instruction::Right(rotation)
+                } else {
+                    Instruction::Left(rotation)
+                }
+            })
+            .collect();
+        assert_eq!(
+            part2(&turns, TRACK_SIZE, START_POSITION),
+            part2_stepwise(&turns)
+        );
+    }
+
+    #[test]
+    fn test_part2_huge_rotation() {
+        // Would take billions of steps to simulate
+        let turns = vec![Instruction::Right(50 + 100 * 1_000_000_000)];
+        assert_eq!(part2(&turns, TRACK_SIZE, START_POSITION), 1_000_000_001);
+    }
+
+    #[test]
+    fn test_solve_example() {
+        let (part1, part2) = solve(include_str!("../example.txt")).unwrap();
+        assert_eq!(part1, Answer::Number(3));
+        assert_eq!(part2, Answer::Number(6));
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        let input = include_str!("../example.txt");
+        let stream = input.lines().map(Instruction::from_str);
+        assert_eq!(solve_stream(stream, TRACK_SIZE, START_POSITION), Ok((3, 6)));
+
+        // Generated lazily, so the instructions are never all in memory
+        let stream = (0..100_000).map(|i| Ok::<_, String>(Instruction::Right(i % 250)));
+        let instructions: Vec<Instruction> =
+            (0..100_000).map(|i| Instruction::Right(i % 250)).collect();
+        assert_eq!(
+            solve_stream(stream, TRACK_SIZE, START_POSITION),
+            Ok((
+                part1(&instructions, TRACK_SIZE, START_POSITION),
+                part2(&instructions, TRACK_SIZE, START_POSITION)
+            ))
+        );
+
+        let stream = ["R5", "X5", "R"].into_iter().map(Instruction::from_str);
+        assert!(solve_stream(stream, TRACK_SIZE, START_POSITION)
+            .unwrap_err()
+            .starts_with("Unknown opcode 'X'"));
+    }
+
+    #[test]
+    fn test_strips() {
+        let instructions = vec![
+            Instruction::Right(3),
+            Instruction::Left(2),
+            Instruction::Right(12),
+            Instruction::Jump(4),
+        ];
+        assert_eq!(
+            strips(&instructions, 10, 7),
+            "@.......~~     R3   7 -> 0    1 zero\n\
+             |.......@~     L2   0 -> 8\n\
+             @~~~~~~~~~    R12   8 -> 0    2 zeros\n\
+             |...@.....     =4   0 -> 4\n"
+        );
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let instructions = vec![Instruction::Right(23), Instruction::Left(1)];
+        // Two laps over everything, then 8, 9 and 0 once more, then 9 again
+        let map = heatmap(&instructions, 10, 7);
+        let lines: Vec<&str> = map.lines().collect();
+        assert_eq!(lines[1], "│+.......+@│");
+        assert_eq!(lines[3], " 0");
+        assert_eq!(
+            lines[4],
+            "Shading from '.' (2 visits) to '@' (4 visits); position 0 reached 3 times"
+        );
+    }
+
+    #[test]
+    fn test_scaled_visualisations() {
+        // Ten positions to a column: the first two columns are shaded although most of
+        // their positions were never stepped on
+        let map = heatmap(&[Instruction::Right(15)], 1000, 0);
+        let lines: Vec<&str> = map.lines().collect();
+        assert_eq!(lines[1], format!("│..{}│", " ".repeat(98)));
+        assert!(lines[3].starts_with(" 0         100       200"));
+        assert_eq!(
+            lines[4],
+            "Shading from '.' (1 visits) to '@' (1 visits); position 0 reached 0 times"
+        );
+
+        // Far too many positions to count one by one, or to multiply by the width
+        let size = 4_000_000_000_000_000_000;
+        let turns = [Instruction::Left(3), Instruction::Right(5)];
+        let map = heatmap(&turns, size, 1);
+        let lines: Vec<&str> = map.lines().collect();
+        assert_eq!(lines[1], format!("│.{}.│", " ".repeat(98)));
+        assert!(lines[4].ends_with("position 0 reached 2 times"));
+        assert_eq!(
+            strips(&turns, size, 1).lines().next(),
+            Some(format!("*{}@     L3   1 -> {}  1 zero", ".".repeat(98), size - 2).as_str())
+        );
+    }
+
+    #[test]
+    fn test_other_tracks() {
+        let turns = vec![
+            Instruction::Right(3),
+            Instruction::Left(7),
+            Instruction::Right(20),
+        ];
+        // A 10-position track from 7: the first turn ends on 0, the second starts
+        // there and stops short of a full lap, the third passes 0 at 10 and 20
+        assert_eq!(part1(&turns, 10, 7), 1);
+        assert_eq!(part2(&turns, 10, 7), 3);
+        // A single-position track is always at 0
+        assert_eq!(part1(&turns, 1, 0), 3);
+        assert_eq!(part2(&turns, 1, 0), 30);
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = |s: &str| -> Vec<String> { s.split_whitespace().map(str::to_string).collect() };
+        assert_eq!(
+            Day01::from_args(&args("--example")).unwrap(),
+            Day01::default()
+        );
+        assert_eq!(
+            Day01::from_args(&args("--track-size 10 --start -3")).unwrap(),
+            Day01 {
+                track_size: 10,
+                start: 7
+            }
+        );
+        // The default start of 50 wraps onto a short track just as an explicit one does
+        let short = Day01::from_args(&args("--track-size 10")).unwrap();
+        assert_eq!(
+            short,
+            Day01::from_args(&args("--track-size 10 --start 50")).unwrap()
+        );
+        assert_eq!(short.start, 0);
+        let turns = short.parse("R19\nL3\nR24").unwrap();
+        let explicit = Day01::from_args(&args("--track-size 10 --start 50")).unwrap();
+        assert_eq!(short.part2(&turns), explicit.part2(&turns));
+        assert!(Day01::from_args(&args("--track-size 0")).is_err());
+        assert!(Day01::from_args(&args("--start")).is_err());
+        assert!(Day01::from_args(&args("--start middle")).is_err());
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(TRACK_SIZE, 100);
+        assert_eq!(START_POSITION, 50);
+    }
+}
@@ -0,0 +1,1196 @@
+use aoclib::collections::RangeSet;
+use aoclib::math::factorize;
+use aoclib::progress::Progress;
+use aoclib::{Answer, Solver};
+use std::error::Error;
+use std::str::FromStr;
+
+/// Solver for the repeated-digit ID ranges puzzle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Day02 {
+    /// Sums each range on its own, so an ID in several overlapping ranges counts once
+    /// per range, as a literal reading of the puzzle does. By default overlapping and
+    /// adjacent ranges are merged first and every ID counts once.
+    pub keep_overlaps: bool,
+    /// Draws a progress bar on stderr while the parts are summed, as
+    /// [`sum_matching_with_progress`] does.
+    pub progress: bool,
+}
+
+impl Day02 {
+    /// Reads the options from command-line arguments: `--keep-overlaps` sets
+    /// [`Day02::keep_overlaps`] and `--progress` sets [`Day02::progress`].
+    pub fn from_args(args: &[String]) -> Self {
+        Day02 {
+            keep_overlaps: args.iter().any(|arg| arg == "--keep-overlaps"),
+            progress: args.iter().any(|arg| arg == "--progress"),
+        }
+    }
+
+    /// Sums the numbers in `ranges` that match `predicate`, with a progress bar if
+    /// [`Day02::progress`] is set.
+    pub fn sum_matching(&self, ranges: &[Range], predicate: &dyn DigitPredicate) -> u128 {
+        if self.progress {
+            sum_matching_with_progress(ranges, predicate)
+        } else {
+            sum_matching(ranges, predicate)
+        }
+    }
+
+    /// Returns the ranges to sum: merged with [`merge_ranges`] unless
+    /// [`Day02::keep_overlaps`] is set.
+    pub fn ranges(&self, ranges: &[Range]) -> Vec<Range> {
+        if self.keep_overlaps {
+            ranges.to_vec()
+        } else {
+            merge_ranges(ranges)
+        }
+    }
+}
+
+impl Solver for Day02 {
+    type Input = Vec<Range>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(aoclib::parse_delimited_str(input, ',')?)
+    }
+
+    fn part1(&self, ranges: &Self::Input) -> Answer {
+        answer(self.sum_matching(&self.ranges(ranges), &MirrorHalves))
+    }
+
+    fn part2(&self, ranges: &Self::Input) -> Answer {
+        answer(self.sum_matching(&self.ranges(ranges), &Repeating))
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        aoclib::examples!()
+    }
+}
+
+/// Parses the puzzle input and solves both parts.
+///
+/// # Errors
+///
+/// Returns an error if any comma-separated entry is not a valid range.
+pub fn solve(input: &str) -> Result<(Answer, Answer), Box<dyn Error>> {
+    Day02::default().solve(input)
+}
+
+/// Converts a sum to an answer, as text if it is too large for a numeric answer.
+fn answer(sum: u128) -> Answer {
+    i128::try_from(sum).map_or_else(|_| Answer::from(sum.to_string()), Answer::from)
+}
+
+/// Merges overlapping and adjacent ranges, returning disjoint ranges in increasing
+/// order. Empty ranges are dropped.
+pub fn merge_ranges(ranges: &[Range]) -> Vec<Range> {
+    let ranges: Vec<&Range> = ranges.iter().filter(|range| !range.is_empty()).collect();
+    // A RangeSet holds i64 half-open ranges, so wider IDs are merged by sorting
+    let to_i64 = |n: u128| i64::try_from(n).ok().filter(|&n| n < i64::MAX);
+    let Some(spans) = ranges
+        .iter()
+        .map(|range| Some(to_i64(range.start)?..to_i64(range.end)? + 1))
+        .collect::<Option<Vec<_>>>()
+    else {
+        let mut sorted: Vec<Range> = ranges.into_iter().copied().collect();
+        sorted.sort_by_key(|range| range.start);
+        let mut merged: Vec<Range> = Vec::new();
+        for range in sorted {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
+            }
+        }
+        return merged;
+    };
+    let set: RangeSet = spans.into_iter().collect();
+    set.ranges()
+        .iter()
+        .map(|range| Range {
+            start: range.start as u128,
+            end: (range.end - 1) as u128,
+        })
+        .collect()
+}
+
+/// Ranges shorter than this are summed number by number; longer ones are counted in
+/// closed form.
+const BRUTE_FORCE_LIMIT: u128 = 1_000;
+
+/// Part 1: Find numbers where splitting in half yields two equal parts.
+/// Example: 1221 splits into 12 and 21 (not equal), but 1111 splits into 11 and 11 (equal).
+///
+/// # Panics
+///
+/// Panics if the sum overflows `u128`.
+pub fn part1(ranges: &[Range]) -> u128 {
+    sum_matching(ranges, &MirrorHalves)
+}
+
+/// Part 2: Find numbers with any repeating pattern of equal-sized chunks.
+/// Example: 123123 has pattern "123" repeated twice, 11 has pattern "1" repeated twice.
+///
+/// # Panics
+///
+/// Panics if the sum overflows `u128`.
+pub fn part2(ranges: &[Range]) -> u128 {
+    sum_matching(ranges, &Repeating)
+}
+
+/// Sums the numbers in `ranges` that match `predicate`, using its closed form where
+/// it has one and a range is long enough for that to pay off.
+///
+/// The other ranges are scanned together with [`brute_force_sum`], so the scan is
+/// split across cores when the `parallel` feature is enabled.
+///
+/// # Panics
+///
+/// Panics if the sum overflows `u128`.
+pub fn sum_matching(ranges: &[Range], predicate: &dyn DigitPredicate) -> u128 {
+    let mut scanned = Vec::new();
+    let closed = ranges.iter().try_fold(0u128, |total, range| {
+        match range.closed_form_sum(predicate) {
+            Some(sum) => total.checked_add(sum),
+            None => {
+                scanned.push(*range);
+                Some(total)
+            }
+        }
+    });
+    closed
+        .and_then(|closed| closed.checked_add(brute_force_sum(&scanned, predicate)))
+        .expect("sum overflows u128")
+}
+
+/// How many numbers a scan checks between progress updates.
+const PROGRESS_BATCH: u128 = 1 << 16;
+
+/// Like [`sum_matching`], but draws a progress bar on stderr while it works.
+///
+/// The bar counts every number in `ranges`, including those summed in closed form,
+/// and its message names the range being scanned and how far through it the scan is,
+/// so a long brute-force scan can be told apart from a hung one.
+///
+/// # Panics
+///
+/// Panics if the sum overflows `u128`.
+pub fn sum_matching_with_progress(ranges: &[Range], predicate: &dyn DigitPredicate) -> u128 {
+    let units = |n: u128| u64::try_from(n).unwrap_or(u64::MAX);
+    let progress = Progress::new(predicate.name(), units(ranges.iter().map(Range::len).sum()));
+    let mut total = 0u128;
+    for (i, range) in ranges.iter().enumerate() {
+        progress.set_message(&format!("range {}/{}", i + 1, ranges.len()));
+        if let Some(sum) = range.closed_form_sum(predicate) {
+            total = total.checked_add(sum).expect("sum overflows u128");
+            progress.inc(units(range.len()));
+            continue;
+        }
+        let mut start = range.start;
+        while start <= range.end {
+            let end = range.end.min(start.saturating_add(PROGRESS_BATCH - 1));
+            let batch = Range { start, end };
+            total = total
+                .checked_add(brute_force_sum(&[batch], predicate))
+                .expect("sum overflows u128");
+            progress.set_message(&format!(
+                "range {}/{} {}%",
+                i + 1,
+                ranges.len(),
+                percent(end - range.start + 1, range.len())
+            ));
+            progress.inc(units(batch.len()));
+            match end.checked_add(1) {
+                Some(next) => start = next,
+                None => break,
+            }
+        }
+    }
+    progress.finish();
+    total
+}
+
+/// Returns `done` as a whole percentage of `total`, without overflowing on huge
+/// ranges.
+fn percent(done: u128, total: u128) -> u128 {
+    match done.checked_mul(100) {
+        Some(scaled) => scaled / total,
+        None => (done / (total / 100)).min(100),
+    }
+}
+
+/// A property of a number's decimal digits, such as the puzzle's mirror halves.
+///
+/// Implementors only have to look at the digits; [`DigitPredicate::matches`] and
+/// [`DigitPredicate::closed_form_sum`] can be overridden with faster arithmetic. Every
+/// predicate in [`PREDICATES`] can be chosen with `--predicate NAME`.
+pub trait DigitPredicate: Sync {
+    /// The name used to pick the predicate on the command line.
+    fn name(&self) -> &'static str;
+
+    /// Checks a number given its decimal digits as ASCII, most significant first.
+    fn matches_digits(&self, digits: &[u8]) -> bool;
+
+    /// Checks a number. The default formats it and calls
+    /// [`DigitPredicate::matches_digits`].
+    fn matches(&self, num: u128) -> bool {
+        self.matches_digits(num.to_string().as_bytes())
+    }
+
+    /// Sums the matching numbers in `range` without visiting them, if the predicate
+    /// has a formula for that. The default has none, so ranges are scanned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum overflows `u128`.
+    fn closed_form_sum(&self, _range: &Range) -> Option<u128> {
+        None
+    }
+}
+
+/// Numbers whose digits split into two equal halves, as in part 1; see
+/// [`has_mirror_halves`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MirrorHalves;
+
+impl DigitPredicate for MirrorHalves {
+    fn name(&self) -> &'static str {
+        "mirror"
+    }
+
+    fn matches_digits(&self, digits: &[u8]) -> bool {
+        let (first, second) = digits.split_at(digits.len() / 2);
+        digits != b"0" && digits.len().is_multiple_of(2) && first == second
+    }
+
+    fn matches(&self, num: u128) -> bool {
+        match usize::try_from(num) {
+            Ok(num) => has_mirror_halves(num),
+            Err(_) => self.matches_digits(num.to_string().as_bytes()),
+        }
+    }
+
+    /// A number with mirror halves repeats a block of half its length twice, so for
+    /// each length the matches form an arithmetic series.
+    fn closed_form_sum(&self, range: &Range) -> Option<u128> {
+        Some(mirror_halves_sum(range).expect("sum overflows u128"))
+    }
+}
+
+/// Numbers made of one block of digits repeated at least twice, as in part 2; see
+/// [`has_repeating_pattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeating;
+
+impl DigitPredicate for Repeating {
+    fn name(&self) -> &'static str {
+        "repeat"
+    }
+
+    fn matches_digits(&self, digits: &[u8]) -> bool {
+        digits != b"0"
+            && (1..=digits.len() / 2)
+                .filter(|chunk| digits.len().is_multiple_of(*chunk))
+                .any(|chunk| digits.chunks(chunk).all(|c| c == &digits[..chunk]))
+    }
+
+    fn matches(&self, num: u128) -> bool {
+        match usize::try_from(num) {
+            Ok(num) => has_repeating_pattern(num),
+            Err(_) => self.matches_digits(num.to_string().as_bytes()),
+        }
+    }
+
+    /// A number can repeat blocks of several sizes (1111 is both 1 x 4 and 11 x 2),
+    /// so the block sizes `L / m` of each length `L` are combined by
+    /// inclusion-exclusion over `m`, weighted by the Möbius function: the numbers with
+    /// period `L / p` for a prime `p` cover every repeating number, and the
+    /// intersections of those sets are again periodic sets.
+    fn closed_form_sum(&self, range: &Range) -> Option<u128> {
+        Some(repeating_sum(range).expect("sum overflows u128"))
+    }
+}
+
+/// Numbers that read the same backwards, such as 12321.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palindrome;
+
+impl DigitPredicate for Palindrome {
+    fn name(&self) -> &'static str {
+        "palindrome"
+    }
+
+    fn matches_digits(&self, digits: &[u8]) -> bool {
+        digits.iter().eq(digits.iter().rev())
+    }
+}
+
+/// Numbers whose digits never decrease from left to right, such as 11389.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonDecreasing;
+
+impl DigitPredicate for NonDecreasing {
+    fn name(&self) -> &'static str {
+        "nondecreasing"
+    }
+
+    fn matches_digits(&self, digits: &[u8]) -> bool {
+        digits.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+}
+
+/// Every predicate `--predicate` accepts.
+pub const PREDICATES: &[&dyn DigitPredicate] =
+    &[&MirrorHalves, &Repeating, &Palindrome, &NonDecreasing];
+
+/// Looks up a predicate in [`PREDICATES`] by name.
+pub fn predicate(name: &str) -> Option<&'static dyn DigitPredicate> {
+    PREDICATES.iter().copied().find(|p| p.name() == name)
+}
+
+/// Sums the numbers in `ranges` that match `predicate` by testing each one, splitting
+/// the work across the available cores when the `parallel` feature is enabled.
+///
+/// This is far slower than the closed-form sums behind [`part1`] and [`part2`], but
+/// checks them against the real input and works for any predicate.
+///
+/// # Panics
+///
+/// Panics if the sum overflows `u128`.
+pub fn brute_force_sum(ranges: &[Range], predicate: &dyn DigitPredicate) -> u128 {
+    #[cfg(feature = "parallel")]
+    return brute_force_sum_parallel(ranges, predicate);
+    #[cfg(not(feature = "parallel"))]
+    scan(ranges, predicate)
+}
+
+/// Like [`brute_force_sum`], but always splits the numbers between the available
+/// cores.
+///
+/// The ranges are cut into one contiguous share of numbers per thread, splitting wide
+/// ranges across several threads, so one huge range does not leave the other threads
+/// idle. The predicate is pure, so the partial sums simply add up. The workspace has
+/// no external dependencies, so this uses scoped `std` threads rather than a
+/// thread-pool crate.
+#[cfg(feature = "parallel")]
+pub fn brute_force_sum_parallel(ranges: &[Range], predicate: &dyn DigitPredicate) -> u128 {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = split_ranges(ranges, threads as u128)
+            .into_iter()
+            .map(|share| scope.spawn(move || scan(&share, predicate)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("scan thread panicked"))
+            .sum()
+    })
+}
+
+fn scan(ranges: &[Range], predicate: &dyn DigitPredicate) -> u128 {
+    ranges
+        .iter()
+        .flat_map(|range| range.start..=range.end)
+        .filter(|&n| predicate.matches(n))
+        .sum()
+}
+
+/// Cuts `ranges` into at most `parts` lists of sub-ranges holding nearly equal
+/// numbers of values between them.
+#[cfg(feature = "parallel")]
+fn split_ranges(ranges: &[Range], parts: u128) -> Vec<Vec<Range>> {
+    let total: u128 = ranges.iter().map(Range::len).sum();
+    let share = total.div_ceil(parts.max(1)).max(1);
+    let mut shares = vec![Vec::new()];
+    let mut room = share;
+    for range in ranges.iter().filter(|range| !range.is_empty()) {
+        let mut start = range.start;
+        loop {
+            if room == 0 {
+                shares.push(Vec::new());
+                room = share;
+            }
+            let take = (range.end - start).min(room - 1);
+            shares.last_mut().unwrap().push(Range {
+                start,
+                end: start + take,
+            });
+            room -= take + 1;
+            if start + take == range.end {
+                break;
+            }
+            start += take + 1;
+        }
+    }
+    shares
+}
+
+/// The most decimal digits an ID can have.
+const MAX_DIGITS: u32 = u128::MAX.ilog10() + 1;
+
+/// Sums the numbers in `range` with mirror halves, or returns `None` on overflow.
+fn mirror_halves_sum(range: &Range) -> Option<u128> {
+    (1..=MAX_DIGITS / 2).try_fold(0u128, |total, half| {
+        total.checked_add(sum_periodic(range, 2 * half, half)?)
+    })
+}
+
+/// Sums the numbers in `range` that repeat a block, or returns `None` on overflow.
+fn repeating_sum(range: &Range) -> Option<u128> {
+    let (mut added, mut removed) = (0u128, 0u128);
+    for len in 2..=MAX_DIGITS {
+        for m in (2..=len).filter(|m| len.is_multiple_of(*m)) {
+            match mobius(m) {
+                -1 => added = added.checked_add(sum_periodic(range, len, len / m)?)?,
+                1 => removed = removed.checked_add(sum_periodic(range, len, len / m)?)?,
+                _ => {}
+            }
+        }
+    }
+    added.checked_sub(removed)
+}
+
+/// Sums the `len`-digit numbers in `range` made of one `block`-digit block repeated,
+/// or returns `None` on overflow.
+///
+/// Such a number is the block times `(10^len - 1) / (10^block - 1)`, so the matches
+/// form an arithmetic series over the blocks that keep the number in range.
+fn sum_periodic(range: &Range, len: u32, block: u32) -> Option<u128> {
+    let base = 10u128.pow(block);
+    let repunit = (0..len / block).try_fold(0u128, |r, _| r.checked_mul(base)?.checked_add(1))?;
+    let lowest = (base / 10).max(range.start.div_ceil(repunit));
+    let highest = (base - 1).min(range.end / repunit);
+    if highest < lowest {
+        return Some(0);
+    }
+    // One of the count and the sum of the ends is even, so halve that one
+    let count = highest - lowest + 1;
+    let blocks = if count.is_multiple_of(2) {
+        (count / 2).checked_mul(lowest + highest)?
+    } else {
+        count.checked_mul((lowest + highest) / 2)?
+    };
+    blocks.checked_mul(repunit)
+}
+
+/// The Möbius function: 0 if `m` has a squared prime factor, otherwise 1 or -1 for
+/// an even or odd number of prime factors.
+fn mobius(m: u32) -> i32 {
+    let factors = factorize(m as u64);
+    if factors.iter().any(|&(_, exponent)| exponent > 1) {
+        0
+    } else if factors.len().is_multiple_of(2) {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Checks if a number has mirror halves (only works for even-length numbers).
+/// Example: 1221 -> 12 | 21 (false), 1111 -> 11 | 11 (true)
+pub fn has_mirror_halves(num: usize) -> bool {
+    if num == 0 {
+        return false;
+    }
+
+    let num_digits = num.ilog10() + 1;
+
+    // Only check numbers with even number of digits
+    if !num_digits.is_multiple_of(2) {
+        return false;
+    }
+
+    let half_digits = num_digits / 2;
+    let divisor = 10usize.pow(half_digits);
+
+    let first_half = num / divisor;
+    let second_half = num % divisor;
+
+    first_half == second_half
+}
+
+/// Checks if a number consists of repeating chunks of equal size.
+/// Example: 123123 has chunks [123, 123], 777 has chunks [7, 7, 7]
+pub fn has_repeating_pattern(num: usize) -> bool {
+    if num == 0 {
+        return false;
+    }
+
+    let num_digits = num.ilog10() + 1;
+
+    // Try all possible chunk sizes from 1 to half the number of digits
+    for chunk_size in 1..=num_digits / 2 {
+        // Skip chunk sizes that don't divide evenly
+        if !num_digits.is_multiple_of(chunk_size) {
+            continue;
+        }
+
+        let divisor = 10usize.pow(chunk_size);
+        let first_chunk = num % divisor;
+
+        // Check if all chunks match the first chunk
+        let mut temp = num / divisor;
+        let mut all_match = true;
+
+        while temp > 0 {
+            if temp % divisor != first_chunk {
+                all_match = false;
+                break;
+            }
+            temp /= divisor;
+        }
+
+        if all_match {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Represents a range of numbers to check (inclusive).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub start: u128,
+    pub end: u128,
+}
+
+impl Range {
+    /// Sums the numbers in the range that have mirror halves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum overflows `u128`.
+    pub fn sum_mirror_halves(&self) -> u128 {
+        self.sum_matching(&MirrorHalves)
+    }
+
+    /// Sums the numbers in the range that consist of a repeating pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum overflows `u128`.
+    pub fn sum_repeating(&self) -> u128 {
+        self.sum_matching(&Repeating)
+    }
+
+    /// Returns how many numbers the range holds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range covers every `u128`, one more than the count can hold.
+    pub fn len(&self) -> u128 {
+        if self.end < self.start {
+            0
+        } else {
+            self.end - self.start + 1
+        }
+    }
+
+    /// Returns true if the range holds no numbers.
+    pub fn is_empty(&self) -> bool {
+        self.end < self.start
+    }
+
+    /// Sums the numbers in the range that match `predicate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum overflows `u128`.
+    pub fn sum_matching(&self, predicate: &dyn DigitPredicate) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+        self.closed_form_sum(predicate)
+            .unwrap_or_else(|| brute_force_sum(std::slice::from_ref(self), predicate))
+    }
+
+    /// Sums the range with the closed form of `predicate`, if it has one and the range
+    /// is long enough for that to beat scanning.
+    fn closed_form_sum(&self, predicate: &dyn DigitPredicate) -> Option<u128> {
+        (self.len() >= BRUTE_FORCE_LIMIT)
+            .then(|| predicate.closed_form_sum(self))
+            .flatten()
+    }
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    /// Parses a range from format "start-end".
+    /// Example: "100-200" -> Range { start: 100, end: 200 }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('-').collect();
+
+        if parts.len() != 2 {
+            return Err(format!(
+                "Invalid range format: '{}'. Expected 'start-end'",
+                s
+            ));
+        }
+
+        let start = parts[0]
+            .parse()
+            .map_err(|_| format!("Invalid start value: '{}'", parts[0]))?;
+
+        let end = parts[1]
+            .parse()
+            .map_err(|_| format!("Invalid end value: '{}'", parts[1]))?;
+
+        Ok(Range { start, end })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_mirror_halves_basic() {
+        // Even length with matching halves
+        assert!(has_mirror_halves(1111));
+        assert!(has_mirror_halves(2222));
+
+        // Even length without matching halves
+        assert!(!has_mirror_halves(1001));
+        assert!(!has_mirror_halves(1221));
+        assert!(!has_mirror_halves(1234));
+
+        // Odd length (always false)
+        assert!(!has_mirror_halves(123));
+        assert!(!has_mirror_halves(1));
+        assert!(!has_mirror_halves(12345));
+    }
+
+    #[test]
+    fn test_has_mirror_halves_four_digits() {
+        assert!(has_mirror_halves(1212));
+        assert!(has_mirror_halves(9999));
+        assert!(!has_mirror_halves(0));
+        assert!(!has_mirror_halves(1234));
+        assert!(!has_mirror_halves(5678));
+    }
+
+    #[test]
+    fn test_has_mirror_halves_six_digits() {
+        assert!(has_mirror_halves(123123));
+        assert!(has_mirror_halves(999999));
+        assert!(!has_mirror_halves(123456));
+    }
+
+    #[test]
+    fn test_has_repeating_pattern_single_digit() {
+        // All single repeating digits
+        assert!(has_repeating_pattern(11));
+        assert!(has_repeating_pattern(222));
+        assert!(has_repeating_pattern(7777));
+
+        // Different digits
+        assert!(!has_repeating_pattern(12));
+        assert!(!has_repeating_pattern(123));
+    }
+
+    #[test]
+    fn test_has_repeating_pattern_two_digits() {
+        assert!(has_repeating_pattern(1212));
+        assert!(has_repeating_pattern(123123));
+        assert!(has_repeating_pattern(12341234));
+
+        assert!(!has_repeating_pattern(1234));
+        assert!(!has_repeating_pattern(123456));
+    }
+
+    #[test]
+    fn test_has_repeating_pattern_three_digits() {
+        assert!(has_repeating_pattern(123123));
+        assert!(has_repeating_pattern(999999));
+
+        assert!(!has_repeating_pattern(123456));
+    }
+
+    #[test]
+    fn test_has_repeating_pattern_edge_cases() {
+        // Single digit numbers don't have a pattern
+        assert!(!has_repeating_pattern(1));
+        assert!(!has_repeating_pattern(9));
+
+        // Numbers that look like they might have patterns but don't
+        assert!(!has_repeating_pattern(1213));
+        assert!(!has_repeating_pattern(12312));
+    }
+
+    #[test]
+    fn test_range_from_str_valid() {
+        assert_eq!(
+            Range::from_str("100-200").unwrap(),
+            Range {
+                start: 100,
+                end: 200
+            }
+        );
+
+        assert_eq!(
+            Range::from_str("1-10").unwrap(),
+            Range { start: 1, end: 10 }
+        );
+
+        // With whitespace
+        assert_eq!(
+            Range::from_str("  100-200  ").unwrap(),
+            Range {
+                start: 100,
+                end: 200
+            }
+        );
+
+        assert_eq!(
+            Range::from_str("100-200\n").unwrap(),
+            Range {
+                start: 100,
+                end: 200
+            }
+        );
+    }
+
+    #[test]
+    fn test_range_from_str_invalid() {
+        // No dash
+        assert!(Range::from_str("100200").is_err());
+
+        // Multiple dashes
+        assert!(Range::from_str("100-200-300").is_err());
+
+        // Invalid numbers
+        assert!(Range::from_str("abc-200").is_err());
+        assert!(Range::from_str("100-xyz").is_err());
+
+        // Empty
+        assert!(Range::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_part1_integration() {
+        let ranges = [
+            Range { start: 10, end: 20 },
+            Range {
+                start: 1111,
+                end: 1111,
+            },
+        ];
+
+        let sum = scan(&ranges, &MirrorHalves);
+
+        // Only 11 and 1111 should match
+        assert_eq!(sum, 11 + 1111);
+    }
+
+    #[test]
+    fn test_part2_integration() {
+        let ranges = [
+            Range { start: 11, end: 13 },
+            Range {
+                start: 1212,
+                end: 1212,
+            },
+        ];
+
+        let sum = scan(&ranges, &Repeating);
+
+        // 11 and 1212 should match (not 12 or 13)
+        assert_eq!(sum, 11 + 1212);
+    }
+
+    #[test]
+    fn test_solve_example() {
+        let (part1, part2) = solve(include_str!("../example.txt")).unwrap();
+        assert_eq!(part1, Answer::Number(1227775554));
+        assert_eq!(part2, Answer::Number(4174379265));
+    }
+
+    #[test]
+    fn test_closed_form_matches_brute_force() {
+        let ranges = [
+            Range { start: 0, end: 0 },
+            Range { start: 1, end: 9 },
+            Range {
+                start: 1,
+                end: 1111,
+            },
+            Range {
+                start: 95,
+                end: 115,
+            },
+            Range {
+                start: 998,
+                end: 1012,
+            },
+            Range {
+                start: 1,
+                end: 250_000,
+            },
+            Range {
+                start: 1112,
+                end: 123123,
+            },
+            Range {
+                start: 222220,
+                end: 222224,
+            },
+            Range { start: 5, end: 4 },
+        ];
+        for range in &ranges {
+            for predicate in [&MirrorHalves as &dyn DigitPredicate, &Repeating] {
+                assert_eq!(
+                    predicate.closed_form_sum(range),
+                    Some(scan(std::slice::from_ref(range), predicate)),
+                    "{} in {:?}",
+                    predicate.name(),
+                    range
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_digit_checks_match_arithmetic() {
+        for n in (0..20_000).chain([123123123, 1212121212, 1188511885, 999999999999]) {
+            let digits = n.to_string();
+            assert_eq!(
+                MirrorHalves.matches_digits(digits.as_bytes()),
+                has_mirror_halves(n),
+                "{}",
+                n
+            );
+            assert_eq!(
+                Repeating.matches_digits(digits.as_bytes()),
+                has_repeating_pattern(n),
+                "{}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_other_predicates() {
+        let matching = |predicate: &dyn DigitPredicate, range: Range| -> Vec<u128> {
+            (range.start..=range.end)
+                .filter(|&n| predicate.matches(n))
+                .collect()
+        };
+        assert_eq!(
+            matching(
+                &Palindrome,
+                Range {
+                    start: 95,
+                    end: 131
+                }
+            ),
+            vec![99, 101, 111, 121, 131]
+        );
+        assert_eq!(
+            matching(
+                &NonDecreasing,
+                Range {
+                    start: 108,
+                    end: 125
+                }
+            ),
+            vec![111, 112, 113, 114, 115, 116, 117, 118, 119, 122, 123, 124, 125]
+        );
+        // No closed form, so long ranges are scanned
+        let range = Range {
+            start: 1,
+            end: 5000,
+        };
+        assert_eq!(Palindrome.closed_form_sum(&range), None);
+        assert_eq!(
+            range.sum_matching(&Palindrome),
+            matching(&Palindrome, range).iter().sum()
+        );
+    }
+
+    #[test]
+    fn test_predicate_lookup() {
+        assert_eq!(predicate("mirror").unwrap().name(), "mirror");
+        assert_eq!(predicate("palindrome").unwrap().name(), "palindrome");
+        assert!(predicate("prime").is_none());
+        for p in PREDICATES {
+            assert_eq!(predicate(p.name()).unwrap().name(), p.name());
+        }
+    }
+
+    #[test]
+    fn test_sum_with_progress() {
+        // spans several batches, plus a range summed in closed form
+        let ranges = [
+            Range {
+                start: 1,
+                end: PROGRESS_BATCH * 2 + 5,
+            },
+            Range {
+                start: 10_000_000,
+                end: 20_000_000,
+            },
+        ];
+        for p in [&MirrorHalves as &dyn DigitPredicate, &Palindrome] {
+            assert_eq!(
+                sum_matching_with_progress(&ranges, p),
+                sum_matching(&ranges, p)
+            );
+        }
+    }
+
+    #[test]
+    fn test_beyond_usize() {
+        // 22 and 38 digits, past what a usize holds
+        let big: u128 = "1234567890123456789012".parse().unwrap();
+        let mirror: u128 = "1234567890123456789".repeat(2).parse().unwrap();
+        assert!(usize::try_from(big).is_err());
+        assert!(Repeating.matches("12".repeat(11).parse().unwrap()));
+        assert!(!MirrorHalves.matches(big));
+        assert!(MirrorHalves.matches(mirror));
+        assert!(Repeating.matches(mirror));
+
+        // Scanning across the usize boundary switches predicates without a gap
+        let edge = usize::MAX as u128;
+        let range = Range {
+            start: edge - 500,
+            end: edge + 500,
+        };
+        let expected: u128 = (range.start..=range.end)
+            .filter(|&n| Repeating.matches_digits(n.to_string().as_bytes()))
+            .sum();
+        assert_eq!(range.sum_repeating(), expected);
+
+        let (part1, part2) = solve(&format!("{}-{}", mirror - 5, mirror + 5)).unwrap();
+        assert_eq!(part1, Answer::Number(mirror as i128));
+        assert_eq!(part2, Answer::Number(mirror as i128));
+
+        // Answers past i128::MAX come back as text
+        let twos: u128 = "2".repeat(39).parse().unwrap();
+        let (part1, part2) = solve(&format!("{}-{}", twos - 5, twos + 5)).unwrap();
+        assert_eq!(part1, Answer::Number(0));
+        assert_eq!(part2, Answer::Text(twos.to_string()));
+
+        // Every 38-digit number with mirror halves adds up to more than u128 holds
+        let range = Range {
+            start: 10u128.pow(37),
+            end: u128::MAX,
+        };
+        assert_eq!(mirror_halves_sum(&range), None);
+    }
+
+    #[test]
+    fn test_brute_force_sum_matches_closed_form() {
+        let input = include_str!("../example.txt");
+        let ranges = Day02::default().parse(input).unwrap();
+        assert_eq!(brute_force_sum(&ranges, &MirrorHalves), part1(&ranges));
+        assert_eq!(brute_force_sum(&ranges, &Repeating), part2(&ranges));
+        assert_eq!(Range { start: 5, end: 4 }.len(), 0);
+        assert_eq!(Range { start: 4, end: 4 }.len(), 1);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_split_ranges() {
+        let ranges = [
+            Range { start: 1, end: 10 },
+            Range { start: 20, end: 19 },
+            Range {
+                start: 100,
+                end: 101,
+            },
+        ];
+        let shares = split_ranges(&ranges, 3);
+        assert_eq!(
+            shares,
+            vec![
+                vec![Range { start: 1, end: 4 }],
+                vec![Range { start: 5, end: 8 }],
+                vec![
+                    Range { start: 9, end: 10 },
+                    Range {
+                        start: 100,
+                        end: 101
+                    }
+                ],
+            ]
+        );
+        for parts in 1..15 {
+            let shares = split_ranges(&ranges, parts);
+            assert!(shares.len() as u128 <= parts);
+            let numbers: Vec<u128> = shares
+                .iter()
+                .flatten()
+                .flat_map(|r| r.start..=r.end)
+                .collect();
+            let expected: Vec<u128> = ranges.iter().flat_map(|r| r.start..=r.end).collect();
+            assert_eq!(numbers, expected);
+        }
+        assert_eq!(split_ranges(&[], 4), vec![Vec::<Range>::new()]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_fallback_scan_is_parallel() {
+        use std::collections::HashSet;
+        use std::sync::Mutex;
+        use std::thread::{self, ThreadId};
+
+        /// Odd numbers, with no closed form, remembering which threads checked them.
+        struct Odd(Mutex<HashSet<ThreadId>>);
+
+        impl DigitPredicate for Odd {
+            fn name(&self) -> &'static str {
+                "odd"
+            }
+
+            fn matches_digits(&self, digits: &[u8]) -> bool {
+                self.0.lock().unwrap().insert(thread::current().id());
+                digits.last().is_some_and(|d| d % 2 == 1)
+            }
+        }
+
+        let ranges = [
+            Range { start: 1, end: 10 },
+            Range {
+                start: 1,
+                end: 5000,
+            },
+        ];
+        let odd = Odd(Mutex::new(HashSet::new()));
+        assert_eq!(sum_matching(&ranges, &odd), 25 + 2500 * 2500);
+        assert_eq!(ranges[1].sum_matching(&odd), 2500 * 2500);
+        assert_eq!(sum_matching_with_progress(&ranges, &odd), 25 + 2500 * 2500);
+        // Every number was checked on a scan thread, never on the caller's
+        let checked_on = odd.0.into_inner().unwrap();
+        assert!(!checked_on.is_empty());
+        assert!(!checked_on.contains(&thread::current().id()));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let ranges = [
+            Range {
+                start: 1,
+                end: 300_000,
+            },
+            Range {
+                start: 998,
+                end: 1012,
+            },
+            Range {
+                start: 38593856,
+                end: 38593862,
+            },
+        ];
+        for predicate in PREDICATES {
+            assert_eq!(
+                brute_force_sum_parallel(&ranges, *predicate),
+                scan(&ranges, *predicate)
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        let ranges = [
+            Range { start: 30, end: 40 },
+            Range { start: 10, end: 20 },
+            Range { start: 15, end: 25 },
+            Range { start: 26, end: 28 },
+            Range { start: 9, end: 3 },
+        ];
+        assert_eq!(
+            merge_ranges(&ranges),
+            vec![Range { start: 10, end: 28 }, Range { start: 30, end: 40 }]
+        );
+        assert!(merge_ranges(&[]).is_empty());
+
+        // Too wide for a RangeSet, so merged by sorting instead
+        let wide = [
+            Range { start: 10, end: 20 },
+            Range {
+                start: 1,
+                end: u128::MAX - 1,
+            },
+            Range {
+                start: u128::MAX,
+                end: u128::MAX,
+            },
+        ];
+        assert_eq!(
+            merge_ranges(&wide),
+            vec![Range {
+                start: 1,
+                end: u128::MAX
+            }]
+        );
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        // 11, 22 and 33 are in both ranges
+        let input = "10-40,1-35";
+        let merged = Day02::default();
+        let kept = Day02::from_args(&["--keep-overlaps".to_string()]);
+        assert!(kept.keep_overlaps && !kept.progress);
+        assert_eq!(merged.solve(input).unwrap().0, Answer::from(66usize));
+        assert_eq!(kept.solve(input).unwrap().0, Answer::from(132usize));
+
+        // Without overlaps both read the input the same
+        let input = include_str!("../example.txt");
+        assert_eq!(merged.solve(input).unwrap(), kept.solve(input).unwrap());
+    }
+
+    #[test]
+    fn test_progress_option() {
+        let day = Day02::from_args(&["--progress".to_string()]);
+        assert!(day.progress && !day.keep_overlaps);
+        let input = include_str!("../example.txt");
+        assert_eq!(
+            day.solve(input).unwrap(),
+            Day02::default().solve(input).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(1, 3), 33);
+        assert_eq!(percent(3, 3), 100);
+        assert_eq!(percent(u128::MAX / 2, u128::MAX), 50);
+        assert_eq!(percent(u128::MAX, u128::MAX), 100);
+    }
+
+    #[test]
+    fn test_huge_ranges() {
+        // Far too many numbers to iterate: every 12-digit number with mirror halves
+        let range = Range {
+            start: 100_000_000_000,
+            end: 999_999_999_999,
+        };
+        let halves = (100_000u128..=999_999).sum::<u128>() * 1_000_001;
+        assert_eq!(range.sum_mirror_halves(), halves);
+        // Up to the largest IDs the closed form needs no scanning
+        let range = Range {
+            start: 10u128.pow(37),
+            end: 10u128.pow(37) + 10u128.pow(20),
+        };
+        assert!(range.sum_repeating() > range.start);
+    }
+
+    #[test]
+    fn test_comprehensive_small_numbers() {
+        // Test all two-digit numbers
+        let matching: Vec<usize> = (10..=99).filter(|&n| has_mirror_halves(n)).collect();
+
+        // Should be: 11, 22, 33, 44, 55, 66, 77, 88, 99
+        assert_eq!(matching.len(), 9);
+        assert!(matching.contains(&11));
+        assert!(matching.contains(&99));
+        assert!(!matching.contains(&12));
+    }
+}
@@ -0,0 +1,727 @@
+use aoclib::subsequence::select_max_subsequence;
+use aoclib::{Answer, Solver};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+use std::str::FromStr;
+
+/// Solver for the powerbank joltage puzzle.
+pub struct Day03;
+
+impl Solver for Day03 {
+    type Input = Vec<PowerBank>;
+
+    /// Parses one powerbank per line. Trailing whitespace is ignored and blank lines
+    /// are skipped; any other character that is not a digit is an error naming its
+    /// line and column.
+    fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn std::error::Error>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim_end()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(number, line)| {
+                line.parse()
+                    .map_err(|e| format!("Line {}: {}", number, e).into())
+            })
+            .collect()
+    }
+
+    fn part1(&self, powerbanks: &Self::Input) -> Answer {
+        part_1(powerbanks).into()
+    }
+
+    fn part2(&self, powerbanks: &Self::Input) -> Answer {
+        part_2(powerbanks).into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        aoclib::examples!()
+    }
+}
+
+/// Parses the puzzle input and solves both parts.
+///
+/// # Errors
+///
+/// Returns an error if any line is not a valid powerbank.
+pub fn solve(input: &str) -> Result<(Answer, Answer), Box<dyn std::error::Error>> {
+    Day03.solve(input)
+}
+
+/// Part 1: Find the largest 2-digit number that can be formed by selecting
+/// two digits in order from each powerbank, then sum all results.
+///
+/// Uses an O(n) greedy algorithm: for each digit, try forming a 2-digit number
+/// with the maximum digit seen so far, then update the maximum.
+///
+/// Example: For [9,8,7,6,5,4,3,2,1], we get 98 (9 and 8 in order).
+pub fn part_1(powerbanks: &[PowerBank]) -> usize {
+    sum_banks(powerbanks, |bank| find_largest_two_digit_number(&bank.bank))
+}
+
+/// Part 2: Find the largest 12-digit number that can be formed by selecting
+/// 12 digits in order from each powerbank, then sum all results.
+///
+/// Each bank's selection is made by [`find_largest_k_digit_number`].
+///
+/// Example: For [9,8,7,6,5,4,3,2,1,1,1,1,1,1,1], we get 987654321111.
+///
+/// The sum is a [`Joltage`], so it stays exact however many digits are selected.
+pub fn part_2(powerbanks: &[PowerBank]) -> Joltage {
+    sum_banks(powerbanks, |bank| {
+        find_largest_k_digit_number(&bank.bank, 12)
+    })
+}
+
+/// Sums `value` over every powerbank, spreading the banks over the available cores
+/// when the `parallel` feature is enabled.
+fn sum_banks<T, F>(powerbanks: &[PowerBank], value: F) -> T
+where
+    T: Sum + Send,
+    F: Fn(&PowerBank) -> T + Sync,
+{
+    #[cfg(feature = "parallel")]
+    return sum_banks_parallel(
+        powerbanks,
+        std::thread::available_parallelism().map_or(1, |n| n.get()),
+        value,
+    );
+    #[cfg(not(feature = "parallel"))]
+    powerbanks.iter().map(value).sum()
+}
+
+/// Sums `value` over every powerbank on `threads` threads.
+///
+/// Every bank is independent, so each thread sums one contiguous share of the banks
+/// and the shares are added in order, giving exactly the sequential result. The
+/// workspace has no external dependencies, so this uses scoped `std` threads rather
+/// than a thread-pool crate.
+#[cfg(feature = "parallel")]
+pub fn sum_banks_parallel<T, F>(powerbanks: &[PowerBank], threads: usize, value: F) -> T
+where
+    T: Sum + Send,
+    F: Fn(&PowerBank) -> T + Sync,
+{
+    let share = powerbanks.len().div_ceil(threads.max(1)).max(1);
+    let value = &value;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = powerbanks
+            .chunks(share)
+            .map(|banks| scope.spawn(move || banks.iter().map(value).sum::<T>()))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("powerbank thread panicked"))
+            .sum()
+    })
+}
+
+/// Finds the largest 2-digit number by selecting two digits in order.
+///
+/// Algorithm: Track the maximum first digit seen so far. For each digit,
+/// form a 2-digit number with the best first digit, keeping the maximum.
+///
+/// Time Complexity: O(n)
+/// Space Complexity: O(1)
+///
+/// # Examples
+/// ```
+/// use day03_2025::find_largest_two_digit_number;
+///
+/// assert_eq!(find_largest_two_digit_number(&[9, 8, 7]), 98);
+/// assert_eq!(find_largest_two_digit_number(&[8, 1, 9]), 89);
+/// assert_eq!(find_largest_two_digit_number(&[1, 2, 3, 4]), 34);
+/// ```
+pub fn find_largest_two_digit_number(digits: &[u8]) -> usize {
+    if digits.len() < 2 {
+        return 0;
+    }
+
+    let mut max_two_digit = 0;
+    let mut max_first_digit = 0;
+
+    for &digit in digits {
+        // Try forming a two-digit number with the best first digit we've seen
+        let two_digit = max_first_digit * 10 + digit as usize;
+        max_two_digit = max_two_digit.max(two_digit);
+
+        // Update the best first digit we've seen
+        max_first_digit = max_first_digit.max(digit as usize);
+    }
+
+    max_two_digit
+}
+
+/// Finds the largest k-digit number by selecting k digits in order.
+///
+/// Delegates to [`aoclib::subsequence::select_max_subsequence`], which uses a
+/// monotonic stack: dropping `n - k` digits, each digit pops the smaller digits before
+/// it off the stack while there are drops left. Among equal maxima the leftmost is
+/// used.
+///
+/// Time Complexity: O(n) where n is the length of digits, as each digit is pushed
+/// and popped at most once
+/// Space Complexity: O(n)
+///
+/// # Arguments
+/// * `digits` - The sequence of digits to select from
+/// * `k` - The number of digits to select
+///
+/// # Returns
+/// The largest k-digit number, or 0 if invalid input. It is a [`Joltage`] rather than
+/// a machine integer, since a 20-digit selection no longer fits in a `u64`.
+///
+/// # Examples
+/// ```
+/// use day03_2025::find_largest_k_digit_number;
+///
+/// // From [9,8,7,6,5,4,3,2,1,1,1,1,1,1,1], pick 12 digits
+/// assert_eq!(find_largest_k_digit_number(&[9,8,7,6,5,4,3,2,1,1,1,1,1,1,1], 12).to_string(), "987654321111");
+///
+/// // From [8,1,8,1,8,1,9,1,1,1,1,2,1,1,1], pick 12 digits
+/// // Picks the three 8s, then 9, then remaining digits
+/// assert_eq!(find_largest_k_digit_number(&[8,1,8,1,8,1,9,1,1,1,1,2,1,1,1], 12).to_string(), "888911112111");
+/// ```
+pub fn find_largest_k_digit_number(digits: &[u8], k: usize) -> Joltage {
+    // Too few digits to pick from selects nothing, which is zero
+    let picked = select_max_subsequence(digits, k, u8::cmp).unwrap_or_default();
+    let selected: Vec<u8> = picked.iter().map(|&i| digits[i]).collect();
+    Joltage::from_digits(&selected)
+}
+
+/// Finds the largest k-digit number by selecting k digits in order, with the
+/// original greedy scan.
+///
+/// Gives the same result as [`find_largest_k_digit_number`] in O(k * n) time rather
+/// than O(n). It is kept as a straightforward reference for differential tests.
+///
+/// Uses a greedy algorithm:
+/// 1. For each position i (0 to k-1), find the maximum digit in a valid range
+/// 2. The valid range ensures enough digits remain for positions i+1 to k-1
+/// 3. Among multiple occurrences of the max, choose the first (leftmost)
+///
+/// Time Complexity: O(k * n) where n is the length of digits
+/// Space Complexity: O(k)
+///
+/// # Arguments
+/// * `digits` - The sequence of digits to select from
+/// * `k` - The number of digits to select
+///
+/// # Returns
+/// The largest k-digit number, or 0 if invalid input. It is a [`Joltage`] rather than
+/// a machine integer, since a 20-digit selection no longer fits in a `u64`.
+///
+pub fn find_largest_k_digit_number_greedy(digits: &[u8], k: usize) -> Joltage {
+    if k == 0 || digits.is_empty() || k > digits.len() {
+        return Joltage::default();
+    }
+
+    let mut result = Vec::with_capacity(k);
+    let mut start = 0;
+
+    for position in 0..k {
+        // Calculate how many digits we still need after this position
+        let remaining = k - position - 1;
+
+        // We can search up to this index while leaving enough digits for later
+        let search_end = digits.len() - remaining;
+
+        // Find the maximum digit in the valid range
+        let max_digit = *digits[start..search_end]
+            .iter()
+            .max()
+            .expect("search range should not be empty");
+
+        // Find the first occurrence of the maximum digit
+        // (choosing first allows more options for later positions)
+        let max_idx = digits[start..search_end]
+            .iter()
+            .position(|&d| d == max_digit)
+            .expect("max digit should exist in range");
+
+        result.push(max_digit);
+        start = start + max_idx + 1;
+    }
+
+    Joltage::from_digits(&result)
+}
+
+/// A non-negative whole number of any length, kept as its decimal digits.
+///
+/// Selections of more than 19 digits overflow a `u64`, and their sums soon overflow
+/// a `u128`, so joltages are added digit by digit instead. A joltage converts to an
+/// [`Answer`] as a number when it fits and as text otherwise.
+///
+/// # Examples
+/// ```
+/// use day03_2025::Joltage;
+///
+/// let big = Joltage::from_digits(&[9; 25]);
+/// let sum = big.clone() + Joltage::from(1u128);
+/// assert_eq!(sum.to_string(), format!("1{}", "0".repeat(25)));
+/// assert_eq!(big.to_u128(), Some(10u128.pow(25) - 1));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Joltage {
+    /// Most significant first, without leading zeros, so zero has no digits.
+    digits: Vec<u8>,
+}
+
+impl Joltage {
+    /// Creates a joltage from its decimal digits, most significant first.
+    pub fn from_digits(digits: &[u8]) -> Self {
+        let first = digits.iter().position(|&d| d != 0).unwrap_or(digits.len());
+        Joltage {
+            digits: digits[first..].to_vec(),
+        }
+    }
+
+    /// Returns the decimal digits, most significant first; empty for zero.
+    pub fn digits(&self) -> &[u8] {
+        &self.digits
+    }
+
+    /// Returns the value as a `u128`, or `None` if it does not fit.
+    pub fn to_u128(&self) -> Option<u128> {
+        self.digits.iter().try_fold(0u128, |acc, &digit| {
+            acc.checked_mul(10)?.checked_add(u128::from(digit))
+        })
+    }
+}
+
+impl From<u128> for Joltage {
+    fn from(mut n: u128) -> Self {
+        let mut digits = Vec::new();
+        while n > 0 {
+            digits.push((n % 10) as u8);
+            n /= 10;
+        }
+        digits.reverse();
+        Joltage { digits }
+    }
+}
+
+impl fmt::Display for Joltage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.digits.is_empty() {
+            return write!(f, "0");
+        }
+        let text: String = self.digits.iter().map(|&d| char::from(b'0' + d)).collect();
+        f.pad(&text)
+    }
+}
+
+impl AddAssign<&Joltage> for Joltage {
+    fn add_assign(&mut self, other: &Joltage) {
+        if other.digits.len() > self.digits.len() {
+            let padding = other.digits.len() - self.digits.len();
+            self.digits.splice(0..0, std::iter::repeat_n(0, padding));
+        }
+        // Add from the least significant digit, in place, until nothing is left to add
+        let mut carry = 0;
+        let mut theirs = other.digits.iter().rev();
+        for digit in self.digits.iter_mut().rev() {
+            if carry == 0 && theirs.len() == 0 {
+                return;
+            }
+            let total = *digit + theirs.next().copied().unwrap_or(0) + carry;
+            *digit = total % 10;
+            carry = total / 10;
+        }
+        if carry > 0 {
+            self.digits.insert(0, carry);
+        }
+    }
+}
+
+impl Add for Joltage {
+    type Output = Joltage;
+
+    fn add(mut self, other: Joltage) -> Joltage {
+        self += &other;
+        self
+    }
+}
+
+impl Sum for Joltage {
+    fn sum<I: Iterator<Item = Joltage>>(iter: I) -> Self {
+        iter.fold(Joltage::default(), |mut total, joltage| {
+            total += &joltage;
+            total
+        })
+    }
+}
+
+impl From<Joltage> for Answer {
+    fn from(joltage: Joltage) -> Self {
+        match joltage.to_u128().and_then(|n| i128::try_from(n).ok()) {
+            Some(n) => Answer::Number(n),
+            None => Answer::Text(joltage.to_string()),
+        }
+    }
+}
+
+/// Represents a powerbank containing a sequence of digit batteries.
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub struct PowerBank {
+    pub bank: Vec<u8>,
+}
+
+impl FromStr for PowerBank {
+    type Err = String;
+
+    /// Parses a string of digits into a PowerBank.
+    ///
+    /// Every character must be a digit, so whitespace is rejected too; the error
+    /// names the 1-based column of the first offending character.
+    ///
+    /// # Examples
+    /// ```
+    /// use day03_2025::PowerBank;
+    ///
+    /// let bank: PowerBank = "123456".parse().unwrap();
+    /// assert_eq!(bank.bank, vec![1, 2, 3, 4, 5, 6]);
+    /// assert!("12a4".parse::<PowerBank>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bank = s
+            .chars()
+            .enumerate()
+            .map(|(i, ch)| match ch.to_digit(10) {
+                Some(digit) => Ok(digit as u8),
+                None => Err(format!(
+                    "Invalid battery {:?} at column {}, expected a digit",
+                    ch,
+                    i + 1
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(PowerBank { bank })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ===== Part 1 Tests =====
+
+    #[test]
+    fn test_part1_simple() {
+        assert_eq!(find_largest_two_digit_number(&[9, 8, 7]), 98);
+        assert_eq!(find_largest_two_digit_number(&[8, 1, 9]), 89);
+        assert_eq!(find_largest_two_digit_number(&[1, 2, 3, 4]), 34);
+    }
+
+    #[test]
+    fn test_part1_all_same() {
+        assert_eq!(find_largest_two_digit_number(&[5, 5, 5, 5]), 55);
+    }
+
+    #[test]
+    fn test_part1_descending() {
+        assert_eq!(
+            find_largest_two_digit_number(&[9, 8, 7, 6, 5, 4, 3, 2, 1]),
+            98
+        );
+    }
+
+    #[test]
+    fn test_part1_ascending() {
+        assert_eq!(
+            find_largest_two_digit_number(&[1, 2, 3, 4, 5, 6, 7, 8, 9]),
+            89
+        );
+    }
+
+    #[test]
+    fn test_part1_edge_cases() {
+        assert_eq!(find_largest_two_digit_number(&[1, 9]), 19);
+        assert_eq!(find_largest_two_digit_number(&[9, 1]), 91);
+        assert_eq!(find_largest_two_digit_number(&[]), 0);
+        assert_eq!(find_largest_two_digit_number(&[5]), 0);
+    }
+
+    // ===== Part 2 Tests =====
+
+    #[test]
+    fn test_part2_example_1() {
+        // Turn on everything except some 1s at the end
+        let bank = vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 1, 1, 1, 1, 1, 1];
+        assert_eq!(
+            find_largest_k_digit_number(&bank, 12),
+            Joltage::from(987654321111)
+        );
+    }
+
+    #[test]
+    fn test_part2_example_2() {
+        // Turn on everything except some 1s, keeping the 9 at the end
+        let bank = vec![8, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 9];
+        assert_eq!(
+            find_largest_k_digit_number(&bank, 12),
+            Joltage::from(811111111119)
+        );
+    }
+
+    #[test]
+    fn test_part2_example_3() {
+        // Skip 2, 3, 2 near the start to get larger digits later
+        let bank = vec![2, 3, 4, 2, 3, 4, 2, 3, 4, 2, 3, 4, 2, 7, 8];
+        assert_eq!(
+            find_largest_k_digit_number(&bank, 12),
+            Joltage::from(434234234278)
+        );
+    }
+
+    #[test]
+    fn test_part2_example_4() {
+        // Pick all three 8s, then continue with remaining digits
+        let bank = vec![8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1];
+        assert_eq!(
+            find_largest_k_digit_number(&bank, 12),
+            Joltage::from(888911112111)
+        );
+    }
+
+    #[test]
+    fn test_part2_all_examples_sum() {
+        let banks = [
+            vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 1, 1, 1, 1, 1, 1],
+            vec![8, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 9],
+            vec![2, 3, 4, 2, 3, 4, 2, 3, 4, 2, 3, 4, 2, 7, 8],
+            vec![8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1],
+        ];
+
+        let sum: Joltage = banks
+            .iter()
+            .map(|bank| find_largest_k_digit_number(bank, 12))
+            .sum();
+
+        assert_eq!(sum, Joltage::from(3121910778619));
+    }
+
+    #[test]
+    fn test_part2_k_equals_length() {
+        // When k equals array length, use all digits in order
+        let bank = vec![9, 8, 7, 6, 5];
+        assert_eq!(find_largest_k_digit_number(&bank, 5), Joltage::from(98765));
+    }
+
+    #[test]
+    fn test_part2_small_k() {
+        let bank = vec![9, 8, 7, 6, 5, 4, 3, 2, 1];
+        assert_eq!(find_largest_k_digit_number(&bank, 1), Joltage::from(9));
+        assert_eq!(find_largest_k_digit_number(&bank, 2), Joltage::from(98));
+        assert_eq!(find_largest_k_digit_number(&bank, 3), Joltage::from(987));
+    }
+
+    #[test]
+    fn test_part2_repeated_max_values() {
+        // Multiple 9s - should pick first occurrence each time
+        let bank = vec![9, 1, 9, 1, 9, 1, 1, 1];
+        assert_eq!(find_largest_k_digit_number(&bank, 5), Joltage::from(99911));
+    }
+
+    #[test]
+    fn test_part2_edge_cases() {
+        assert_eq!(find_largest_k_digit_number(&[], 5), Joltage::from(0));
+        assert_eq!(find_largest_k_digit_number(&[1, 2, 3], 0), Joltage::from(0));
+        assert_eq!(find_largest_k_digit_number(&[1, 2, 3], 5), Joltage::from(0));
+        // k > length
+    }
+
+    #[test]
+    fn test_part2_all_same_digits() {
+        let bank = vec![7, 7, 7, 7, 7, 7, 7];
+        assert_eq!(find_largest_k_digit_number(&bank, 4), Joltage::from(7777));
+    }
+
+    #[test]
+    fn test_part2_matches_greedy() {
+        // A fixed pseudo-random set of banks, lengths and k
+        let mut seed = 2025u64;
+        let mut next = |n: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+        for _ in 0..2000 {
+            let len = next(40) as usize;
+            let bank: Vec<u8> = (0..len).map(|_| next(10) as u8).collect();
+            let k = next(len as u64 + 2) as usize;
+            assert_eq!(
+                find_largest_k_digit_number(&bank, k),
+                find_largest_k_digit_number_greedy(&bank, k),
+                "{:?} k={}",
+                bank,
+                k
+            );
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let mut seed = 7u64;
+        let banks: Vec<PowerBank> = (0..500)
+            .map(|_| PowerBank {
+                bank: (0..60)
+                    .map(|_| {
+                        seed = seed
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        ((seed >> 33) % 10) as u8
+                    })
+                    .collect(),
+            })
+            .collect();
+        let part1 = |bank: &PowerBank| find_largest_two_digit_number(&bank.bank);
+        let part2 = |bank: &PowerBank| find_largest_k_digit_number(&bank.bank, 12);
+        for threads in [1, 3, 8, 1000] {
+            assert_eq!(
+                sum_banks_parallel(&banks, threads, part1),
+                banks.iter().map(part1).sum::<usize>()
+            );
+            assert_eq!(
+                sum_banks_parallel(&banks, threads, part2),
+                banks.iter().map(part2).sum::<Joltage>()
+            );
+        }
+        assert_eq!(sum_banks_parallel(&[], 4, part1), 0);
+    }
+
+    #[test]
+    fn test_part2_more_than_19_digits() {
+        // 25 digits overflow a u64
+        let bank: Vec<u8> = [1, 9, 2, 9, 3].repeat(8);
+        let expected = "9999999999392931929319293";
+        assert_eq!(find_largest_k_digit_number(&bank, 25).to_string(), expected);
+
+        // Sums carry across any number of digits
+        let nines = PowerBank { bank: vec![9; 40] };
+        let sum = part_2(&[nines.clone(), nines]);
+        assert_eq!(sum.to_string(), "1999999999998");
+        let huge = find_largest_k_digit_number(&[9; 40], 40) + Joltage::from(1);
+        assert_eq!(huge.to_string(), format!("1{}", "0".repeat(40)));
+        assert_eq!(huge.to_u128(), None);
+        assert_eq!(Answer::from(huge.clone()), Answer::Text(huge.to_string()));
+    }
+
+    #[test]
+    fn test_joltage() {
+        assert_eq!(Joltage::default().to_string(), "0");
+        assert_eq!(Joltage::from_digits(&[0, 0, 4, 2]), Joltage::from(42));
+        assert_eq!(Joltage::from(42).digits(), &[4, 2]);
+        assert_eq!(Joltage::from(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!(Answer::from(Joltage::from(99)), Answer::Number(99));
+        assert_eq!(format!("{:>4}", Joltage::from(7)), "   7");
+    }
+
+    // ===== PowerBank Parsing Tests =====
+
+    #[test]
+    fn test_powerbank_from_str() {
+        let bank: PowerBank = "123456789".parse().unwrap();
+        assert_eq!(bank.bank, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_powerbank_from_str_single_digit() {
+        let bank: PowerBank = "5".parse().unwrap();
+        assert_eq!(bank.bank, vec![5]);
+    }
+
+    #[test]
+    fn test_powerbank_from_str_empty() {
+        let bank: PowerBank = "".parse().unwrap();
+        assert_eq!(bank.bank, vec![]);
+    }
+
+    #[test]
+    fn test_powerbank_from_str_zeros() {
+        let bank: PowerBank = "1020304".parse().unwrap();
+        assert_eq!(bank.bank, vec![1, 0, 2, 0, 3, 0, 4]);
+    }
+
+    #[test]
+    fn test_powerbank_from_str_rejects_non_digits() {
+        let err = "12a4".parse::<PowerBank>().unwrap_err();
+        assert_eq!(err, "Invalid battery 'a' at column 3, expected a digit");
+        let err = "12 34".parse::<PowerBank>().unwrap_err();
+        assert_eq!(err, "Invalid battery ' ' at column 3, expected a digit");
+        assert!("123 ".parse::<PowerBank>().is_err());
+        assert!("-12".parse::<PowerBank>().is_err());
+    }
+
+    #[test]
+    fn test_parse_trailing_whitespace() {
+        let banks = Day03.parse("987 \n811\t\r\n").unwrap();
+        assert_eq!(banks, Day03.parse("987\n811").unwrap());
+    }
+
+    #[test]
+    fn test_parse_empty_lines() {
+        let banks = Day03.parse("\n987\n\n   \n811\n\n").unwrap();
+        assert_eq!(banks, Day03.parse("987\n811").unwrap());
+        assert!(Day03.parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error_names_line_and_column() {
+        let err = Day03.parse("987\n\n81x1\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 3: Invalid battery 'x' at column 3, expected a digit"
+        );
+    }
+
+    // ===== Integration Tests =====
+
+    #[test]
+    fn test_solve_example() {
+        let (part1, part2) = solve(include_str!("../example.txt")).unwrap();
+        assert_eq!(part1, Answer::Number(357));
+        assert_eq!(part2, Answer::Number(3121910778619));
+    }
+
+    #[test]
+    fn test_integration_part1() {
+        let banks = [
+            PowerBank {
+                bank: vec![9, 8, 7, 6, 5, 4, 3, 2, 1],
+            },
+            PowerBank {
+                bank: vec![8, 1, 1, 1, 1, 1, 1, 1, 9],
+            },
+        ];
+
+        let sum: usize = banks
+            .iter()
+            .map(|bank| find_largest_two_digit_number(&bank.bank))
+            .sum();
+
+        assert_eq!(sum, 98 + 89);
+    }
+
+    #[test]
+    fn test_integration_part2_small() {
+        let banks = [
+            PowerBank {
+                bank: vec![9, 8, 7],
+            },
+            PowerBank {
+                bank: vec![5, 4, 3],
+            },
+        ];
+
+        let sum: Joltage = banks
+            .iter()
+            .map(|bank| find_largest_k_digit_number(&bank.bank, 2))
+            .sum();
+
+        assert_eq!(sum, Joltage::from(98 + 54));
+    }
+}
@@ -0,0 +1,932 @@
+use aoclib::{Answer, Point2, Solver};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Solver for the paper-roll forklift access puzzle.
+///
+/// A roll is accessible when fewer than `threshold` of its neighbours hold rolls.
+/// The puzzle counts the eight surrounding cells against a threshold of 4
+/// ([`Day04::default`]); both can be changed from the command line to explore
+/// variants of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day04 {
+    /// A roll is accessible with fewer than this many neighbouring rolls.
+    pub threshold: usize,
+    /// Which cells count as neighbours.
+    pub connectivity: Connectivity,
+}
+
+impl Default for Day04 {
+    fn default() -> Self {
+        Day04 {
+            threshold: 4,
+            connectivity: Connectivity::Eight,
+        }
+    }
+}
+
+impl Day04 {
+    /// Reads the rules from command-line arguments, starting from the puzzle's:
+    ///
+    /// * `--threshold N` - rolls with fewer than `N` neighbours are accessible
+    /// * `--connectivity 4|8` - count only orthogonal neighbours, or diagonals too
+    ///
+    /// Other arguments are left for [`aoclib::run`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option is missing its value or the value is invalid.
+    pub fn from_args(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        let value = |name: &str| -> Result<Option<&String>, Box<dyn Error>> {
+            match args.iter().position(|arg| arg == name) {
+                Some(i) => {
+                    Ok(Some(args.get(i + 1).ok_or_else(|| {
+                        format!("Option '{}' requires a value", name)
+                    })?))
+                }
+                None => Ok(None),
+            }
+        };
+        let mut day = Day04::default();
+        if let Some(threshold) = value("--threshold")? {
+            day.threshold = threshold
+                .parse()
+                .map_err(|_| format!("Invalid threshold '{}'", threshold))?;
+        }
+        if let Some(connectivity) = value("--connectivity")? {
+            day.connectivity = connectivity.parse()?;
+        }
+        Ok(day)
+    }
+
+    /// Finds all accessible positions, in reading order.
+    pub fn find_accessible(&self, input: &Rolls) -> Vec<(isize, isize)> {
+        self.accessible_in_rows(input, 0..input.height)
+    }
+
+    /// Counts the accessible positions, scanning in parallel when the `parallel`
+    /// feature is enabled.
+    pub fn count_accessible(&self, input: &Rolls) -> usize {
+        #[cfg(feature = "parallel")]
+        return self.find_accessible_parallel(input).len();
+        #[cfg(not(feature = "parallel"))]
+        self.find_accessible(input).len()
+    }
+
+    /// Like [`Day04::find_accessible`], but splits the rows between the available
+    /// cores.
+    ///
+    /// Each cell's check only reads the grid, so every thread scans its own band of
+    /// rows and the bands are joined in order, giving exactly the sequential result.
+    /// The workspace has no external dependencies, so this uses scoped `std` threads
+    /// rather than a thread-pool crate.
+    #[cfg(feature = "parallel")]
+    pub fn find_accessible_parallel(&self, input: &Rolls) -> Vec<(isize, isize)> {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let band = input.height.div_ceil(threads).max(1);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..input.height)
+                .step_by(band)
+                .map(|start| {
+                    let rows = start..(start + band).min(input.height);
+                    scope.spawn(move || self.accessible_in_rows(input, rows))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("scan thread panicked"))
+                .collect()
+        })
+    }
+
+    /// Removes accessible positions round by round until none remain, returning how
+    /// many were removed in each round.
+    ///
+    /// Rather than rescanning every roll each round, this keeps each roll's neighbour
+    /// count and lowers it as rolls around it are removed. A roll becomes accessible
+    /// in the round after its count drops below the threshold, so each round only
+    /// visits the neighbours of the rolls just removed.
+    pub fn removal_rounds(&self, mut input: Rolls) -> Vec<usize> {
+        if self.threshold == 0 {
+            return Vec::new();
+        }
+        let mut counts: Vec<usize> = (0..input.cells.len())
+            .map(|i| {
+                let (row, col) = ((i / input.width) as isize, (i % input.width) as isize);
+                input.neighbors(row, col, self.connectivity)
+            })
+            .collect();
+        let mut rounds = Vec::new();
+        let mut frontier = self.find_accessible(&input);
+        while !frontier.is_empty() {
+            rounds.push(frontier.len());
+            for pos in &frontier {
+                input.remove(pos);
+            }
+            let mut next = Vec::new();
+            for &(row, col) in &frontier {
+                for n in input.adjacent(row, col, self.connectivity) {
+                    let i = n.y as usize * input.width + n.x as usize;
+                    if input.cells[i] {
+                        counts[i] -= 1;
+                        if counts[i] == self.threshold - 1 {
+                            next.push((n.y, n.x));
+                        }
+                    }
+                }
+            }
+            frontier = next;
+        }
+        rounds
+    }
+
+    /// Erodes the rolls as in [`Day04::removal_rounds`] and summarises each round.
+    pub fn stats(&self, input: &Rolls) -> ErosionStats {
+        ErosionStats {
+            initial: input.len(),
+            removed: self.removal_rounds(input.clone()),
+        }
+    }
+
+    /// Draws the erosion round by round on a terminal, pausing `delay` between
+    /// frames, and returns the number of rolls removed.
+    ///
+    /// Each frame redraws the grid in place using ANSI cursor control, with the rolls
+    /// about to be removed highlighted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn animate<W: Write>(
+        &self,
+        mut rolls: Rolls,
+        out: &mut W,
+        delay: Duration,
+    ) -> io::Result<usize> {
+        const CLEAR: &str = "\x1b[2J";
+        const HOME: &str = "\x1b[H";
+        const MARK: &str = "\x1b[7mx\x1b[0m";
+
+        write!(out, "{}", CLEAR)?;
+        let (mut round, mut removed) = (0, 0);
+        loop {
+            let accessible = self.find_accessible(&rolls);
+            let status = if accessible.is_empty() {
+                format!(
+                    "Done after {} rounds: {} removed, {} left",
+                    round,
+                    removed,
+                    rolls.len()
+                )
+            } else {
+                round += 1;
+                format!(
+                    "Round {}: removing {} of {}",
+                    round,
+                    accessible.len(),
+                    rolls.len()
+                )
+            };
+            // Clearing to the end of each line keeps longer earlier text from showing
+            writeln!(out, "{}{}\x1b[K", HOME, status)?;
+            write!(out, "{}", rolls.draw(&accessible, MARK))?;
+            out.flush()?;
+            if accessible.is_empty() {
+                return Ok(removed);
+            }
+            std::thread::sleep(delay);
+            for pos in &accessible {
+                rolls.remove(pos);
+            }
+            removed += accessible.len();
+        }
+    }
+
+    fn accessible_in_rows(&self, input: &Rolls, rows: Range<usize>) -> Vec<(isize, isize)> {
+        let cells = &input.cells[rows.start * input.width..rows.end * input.width];
+        cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &roll)| roll)
+            .map(|(i, _)| {
+                let i = rows.start * input.width + i;
+                ((i / input.width) as isize, (i % input.width) as isize)
+            })
+            .filter(|&(row, col)| input.neighbors(row, col, self.connectivity) < self.threshold)
+            .collect()
+    }
+}
+
+impl Solver for Day04 {
+    type Input = Rolls;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(parse_input(input.to_string()))
+    }
+
+    fn part1(&self, rolls: &Self::Input) -> Answer {
+        self.count_accessible(rolls).into()
+    }
+
+    fn part2(&self, rolls: &Self::Input) -> Answer {
+        self.removal_rounds(rolls.clone())
+            .iter()
+            .sum::<usize>()
+            .into()
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        aoclib::examples!()
+    }
+}
+
+/// How the rolls eroded round by round, as returned by [`Day04::stats`].
+///
+/// Displays as an aligned table; [`ErosionStats::to_csv`] gives the same rows for
+/// plotting. Both start with a round 0 holding the initial count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErosionStats {
+    /// The number of rolls before any were removed.
+    pub initial: usize,
+    /// The number of rolls removed in each round.
+    pub removed: Vec<usize>,
+}
+
+impl ErosionStats {
+    /// Returns the number of rounds that removed at least one roll.
+    pub fn rounds(&self) -> usize {
+        self.removed.len()
+    }
+
+    /// Iterates over `(round, removed, remaining)`, starting from round 0.
+    pub fn rows(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        let mut remaining = self.initial;
+        std::iter::once((0, 0, self.initial)).chain(self.removed.iter().enumerate().map(
+            move |(i, &removed)| {
+                remaining -= removed;
+                (i + 1, removed, remaining)
+            },
+        ))
+    }
+
+    /// Returns the rows as CSV with a `round,removed,remaining` header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("round,removed,remaining\n");
+        for (round, removed, remaining) in self.rows() {
+            csv += &format!("{},{},{}\n", round, removed, remaining);
+        }
+        csv
+    }
+}
+
+impl fmt::Display for ErosionStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Rounds: {}", self.rounds())?;
+        writeln!(
+            f,
+            "Removed: {}",
+            self.initial - self.rows().last().map_or(0, |r| r.2)
+        )?;
+        write!(f, "{:>5} {:>8} {:>9}", "Round", "Removed", "Remaining")?;
+        for (round, removed, remaining) in self.rows() {
+            write!(f, "\n{:>5} {:>8} {:>9}", round, removed, remaining)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the puzzle input and solves both parts with the puzzle's rules.
+///
+/// # Errors
+///
+/// Parsing the grid cannot fail, so this currently always succeeds; the `Result`
+/// matches the other days' `solve`.
+pub fn solve(input: &str) -> Result<(Answer, Answer), Box<dyn Error>> {
+    Day04::default().solve(input)
+}
+
+/// Which surrounding cells count as a roll's neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// The four orthogonally adjacent cells.
+    Four,
+    /// The eight surrounding cells, including diagonals.
+    Eight,
+}
+
+impl FromStr for Connectivity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "4" => Ok(Connectivity::Four),
+            "8" => Ok(Connectivity::Eight),
+            _ => Err(format!("Invalid connectivity '{}', expected 4 or 8", s)),
+        }
+    }
+}
+
+/// The paper rolls on the floor, stored densely as one flag per cell of the parsed
+/// grid so that counting neighbours needs no hashing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rolls {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+    count: usize,
+}
+
+impl Rolls {
+    /// Returns the number of rolls.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns whether there are no rolls.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns whether there is a roll at `(row, col)`; positions outside the grid
+    /// are empty.
+    pub fn contains(&self, &(row, col): &(isize, isize)) -> bool {
+        self.index(row, col).is_some_and(|i| self.cells[i])
+    }
+
+    /// Removes the roll at `(row, col)`, returning whether there was one.
+    pub fn remove(&mut self, &(row, col): &(isize, isize)) -> bool {
+        match self.index(row, col) {
+            Some(i) if self.cells[i] => {
+                self.cells[i] = false;
+                self.count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Iterates over the `(row, col)` positions of the rolls in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &roll)| roll)
+            .map(|(i, _)| ((i / self.width) as isize, (i % self.width) as isize))
+    }
+
+    /// Draws the grid with `@` for rolls and `.` for empty cells, showing the
+    /// `marked` positions as `x`.
+    pub fn render(&self, marked: &[(isize, isize)]) -> String {
+        self.draw(marked, "x")
+    }
+
+    fn draw(&self, marked: &[(isize, isize)], mark: &str) -> String {
+        let mut is_marked = vec![false; self.cells.len()];
+        for &(row, col) in marked {
+            if let Some(i) = self.index(row, col) {
+                is_marked[i] = true;
+            }
+        }
+        let mut picture = String::new();
+        for (i, &roll) in self.cells.iter().enumerate() {
+            match (is_marked[i], roll) {
+                (true, _) => picture.push_str(mark),
+                (false, true) => picture.push('@'),
+                (false, false) => picture.push('.'),
+            }
+            if (i + 1) % self.width == 0 {
+                picture.push('\n');
+            }
+        }
+        picture
+    }
+
+    /// Counts the rolls in the cells adjacent to `(row, col)`.
+    fn neighbors(&self, row: isize, col: isize, connectivity: Connectivity) -> usize {
+        self.adjacent(row, col, connectivity)
+            .filter(|n| self.cells[n.y as usize * self.width + n.x as usize])
+            .count()
+    }
+
+    /// Iterates over the in-bounds cells adjacent to `(row, col)`.
+    fn adjacent(
+        &self,
+        row: isize,
+        col: isize,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = Point2> {
+        let diagonals = connectivity == Connectivity::Eight;
+        Point2::new(col, row)
+            .neighbors_within(self.width, self.height)
+            .filter(move |n| diagonals || n.x == col || n.y == row)
+    }
+
+    fn index(&self, row: isize, col: isize) -> Option<usize> {
+        let (row, col) = (usize::try_from(row).ok()?, usize::try_from(col).ok()?);
+        (row < self.height && col < self.width).then_some(row * self.width + col)
+    }
+}
+
+/// Parses the input string into the grid of rolls, where '@' marks a roll.
+///
+/// The grid is as wide as the longest line; shorter lines are padded with empty
+/// cells.
+///
+/// # Arguments
+/// * `input` - A string containing a grid where '@' marks positions of interest
+///
+/// # Returns
+/// The dense [`Rolls`] grid, addressed by (row, column)
+pub fn parse_input(input: String) -> Rolls {
+    let lines: Vec<&str> = input.lines().collect();
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let mut cells = vec![false; width * lines.len()];
+    for (row, line) in lines.iter().enumerate() {
+        for (col, ch) in line.chars().enumerate() {
+            cells[row * width + col] = ch == '@';
+        }
+    }
+    Rolls {
+        width,
+        height: lines.len(),
+        count: cells.iter().filter(|&&roll| roll).count(),
+        cells,
+    }
+}
+
+/// Solves Part 1: Counts positions with fewer than 4 neighbors (accessible positions).
+pub fn part_1(input: &Rolls) -> usize {
+    Day04::default().count_accessible(input)
+}
+
+/// Solves Part 2: Repeatedly removes accessible positions until none remain,
+/// counting the total number of positions removed.
+pub fn part_2(input: Rolls) -> usize {
+    removal_rounds(input).iter().sum()
+}
+
+/// Removes accessible positions round by round with the puzzle's rules; see
+/// [`Day04::removal_rounds`].
+pub fn removal_rounds(input: Rolls) -> Vec<usize> {
+    Day04::default().removal_rounds(input)
+}
+
+/// Finds all "accessible" positions - those with fewer than 4 neighbors
+/// in the 8 surrounding cells (including diagonals).
+///
+/// # Arguments
+/// * `input` - The grid of rolls to check
+///
+/// # Returns
+/// A vector of positions, in reading order, that have fewer than 4 neighbors
+pub fn find_accessible(input: &Rolls) -> Vec<(isize, isize)> {
+    Day04::default().find_accessible(input)
+}
+
+/// Like [`find_accessible`], but splits the rows between the available cores; see
+/// [`Day04::find_accessible_parallel`].
+#[cfg(feature = "parallel")]
+pub fn find_accessible_parallel(input: &Rolls) -> Vec<(isize, isize)> {
+    Day04::default().find_accessible_parallel(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// The original sparse implementation, kept as a reference for the dense one.
+    fn find_accessible_hashed(input: &HashSet<(isize, isize)>) -> Vec<(isize, isize)> {
+        input
+            .iter()
+            .filter(|&&(row, col)| {
+                Point2::new(col, row)
+                    .neighbors8()
+                    .filter(|n| input.contains(&(n.y, n.x)))
+                    .count()
+                    < 4
+            })
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_input_basic() {
+        let input = "\
+.@.
+@.@
+.@.";
+        let rolls = parse_input(input.to_string());
+        assert_eq!(rolls.len(), 4);
+        assert!(rolls.contains(&(0, 1)));
+        assert!(rolls.contains(&(1, 0)));
+        assert!(rolls.contains(&(1, 2)));
+        assert!(rolls.contains(&(2, 1)));
+    }
+
+    #[test]
+    fn test_parse_input_empty() {
+        let input = "\
+...
+...
+...";
+        let rolls = parse_input(input.to_string());
+        assert_eq!(rolls.len(), 0);
+    }
+
+    #[test]
+    fn test_find_accessible_isolated() {
+        // Single isolated position
+        let rolls = parse_input("@".to_string());
+
+        let accessible = find_accessible(&rolls);
+        assert_eq!(
+            accessible.len(),
+            1,
+            "Isolated position should be accessible"
+        );
+    }
+
+    #[test]
+    fn test_find_accessible_cross_pattern() {
+        // Cross pattern: center has 4 neighbors
+        let input = "\
+.@.
+@@@
+.@.";
+        let rolls = parse_input(input.to_string());
+        let accessible = find_accessible(&rolls);
+
+        // Center has exactly 4 neighbors, so NOT accessible
+        // Only the 4 edge positions (with 1 neighbor each) are accessible
+        assert_eq!(accessible.len(), 4);
+        assert!(accessible.contains(&(0, 1)));
+        assert!(accessible.contains(&(1, 0)));
+        assert!(accessible.contains(&(1, 2)));
+        assert!(accessible.contains(&(2, 1)));
+    }
+
+    #[test]
+    fn test_find_accessible_dense_cluster() {
+        // 3x3 grid - center has 8 neighbors
+        let input = "\
+@@@
+@@@
+@@@";
+        let rolls = parse_input(input.to_string());
+        let accessible = find_accessible(&rolls);
+
+        // Only corner and edge positions have < 4 neighbors
+        // Corners: 3 neighbors each
+        // Edges: 5 neighbors each
+        // Center: 8 neighbors
+        assert_eq!(accessible.len(), 4, "Only corners have < 4 neighbors");
+    }
+
+    #[test]
+    fn test_part_1_example() {
+        let input = "\
+..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.";
+
+        let rolls = parse_input(input.to_string());
+        let accessible = find_accessible(&rolls);
+
+        assert_eq!(accessible.len(), 13, "Expected 13 accessible positions");
+    }
+
+    #[test]
+    fn test_part_1_example_total_count() {
+        let input = "\
+..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.";
+
+        let rolls = parse_input(input.to_string());
+        assert_eq!(rolls.len(), 71, "Should parse 71 @ symbols");
+    }
+
+    #[test]
+    fn test_solve_example() {
+        let (part1, part2) = solve(include_str!("../example.txt")).unwrap();
+        assert_eq!(part1, Answer::Number(13));
+        assert_eq!(part2, Answer::Number(43));
+    }
+
+    #[test]
+    fn test_part_2_removal_sequence() {
+        // Simple case where all positions are eventually removed
+        let input = "\
+@@@
+@@@
+@@@";
+        let rolls = parse_input(input.to_string());
+
+        let mut input_copy = rolls.clone();
+        let mut total_removed = 0;
+        let mut iterations = 0;
+
+        loop {
+            let acc = find_accessible(&input_copy);
+            if acc.is_empty() {
+                break;
+            }
+            total_removed += acc.len();
+            for pos in &acc {
+                input_copy.remove(pos);
+            }
+            iterations += 1;
+        }
+
+        assert_eq!(total_removed, 9, "All 9 positions should be removed");
+        assert!(iterations > 1, "Should take multiple iterations");
+    }
+
+    #[test]
+    fn test_part_2_single_position() {
+        let mut rolls = parse_input("@".to_string());
+
+        let mut total_removed = 0;
+        loop {
+            let acc = find_accessible(&rolls);
+            if acc.is_empty() {
+                break;
+            }
+            total_removed += acc.len();
+            for pos in &acc {
+                rolls.remove(pos);
+            }
+        }
+
+        assert_eq!(total_removed, 1);
+    }
+
+    #[test]
+    fn test_neighbor_count_boundary() {
+        // Test the < 4 boundary condition
+        // Position with exactly 3 neighbors: should be accessible
+        let input = "\
+.@.
+@@.
+...";
+        let rolls = parse_input(input.to_string());
+        let accessible = find_accessible(&rolls);
+
+        // All positions have < 4 neighbors
+        assert_eq!(accessible.len(), 3);
+    }
+
+    #[test]
+    fn test_dense_matches_hashed() {
+        let input = include_str!("../example.txt");
+        let mut rolls = parse_input(input.to_string());
+        let mut hashed: HashSet<(isize, isize)> = rolls.iter().collect();
+        assert_eq!(hashed.len(), rolls.len());
+        loop {
+            let mut dense = find_accessible(&rolls);
+            let mut sparse = find_accessible_hashed(&hashed);
+            dense.sort_unstable();
+            sparse.sort_unstable();
+            assert_eq!(dense, sparse);
+            if dense.is_empty() {
+                break;
+            }
+            for pos in &dense {
+                assert!(rolls.remove(pos));
+                hashed.remove(pos);
+            }
+        }
+        assert!(!rolls.remove(&(0, 0)));
+    }
+
+    #[test]
+    fn test_removal_rounds_match_rescan() {
+        let rolls = parse_input(include_str!("../example.txt").to_string());
+        let mut rescanned = Vec::new();
+        let mut remaining = rolls.clone();
+        loop {
+            let acc = find_accessible(&remaining);
+            if acc.is_empty() {
+                break;
+            }
+            rescanned.push(acc.len());
+            for pos in &acc {
+                remaining.remove(pos);
+            }
+        }
+        assert_eq!(removal_rounds(rolls), rescanned);
+        assert_eq!(rescanned.iter().sum::<usize>(), 43);
+
+        let full = parse_input("@@@\n@@@\n@@@".to_string());
+        assert_eq!(removal_rounds(full), vec![4, 4, 1]);
+        assert!(removal_rounds(parse_input(String::new())).is_empty());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let example = parse_input(include_str!("../example.txt").to_string());
+        assert_eq!(
+            find_accessible_parallel(&example),
+            find_accessible(&example)
+        );
+
+        // A large pseudo-random floor, so every thread gets a band
+        let mut state = 3u64;
+        let floor: String = (0..300)
+            .map(|_| {
+                let row: String = (0..200)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        if (state >> 33).is_multiple_of(3) {
+                            '.'
+                        } else {
+                            '@'
+                        }
+                    })
+                    .collect();
+                row + "\n"
+            })
+            .collect();
+        let rolls = parse_input(floor);
+        assert_eq!(find_accessible_parallel(&rolls), find_accessible(&rolls));
+        assert!(find_accessible_parallel(&parse_input(String::new())).is_empty());
+    }
+
+    #[test]
+    fn test_rule_variants() {
+        let example = parse_input(include_str!("../example.txt").to_string());
+        let orthogonal = Day04 {
+            threshold: 4,
+            connectivity: Connectivity::Four,
+        };
+        // With four neighbours at most, only fully surrounded rolls stay
+        let surrounded = example
+            .iter()
+            .filter(|&(row, col)| {
+                [
+                    (row - 1, col),
+                    (row + 1, col),
+                    (row, col - 1),
+                    (row, col + 1),
+                ]
+                .iter()
+                .all(|pos| example.contains(pos))
+            })
+            .count();
+        assert_eq!(
+            orthogonal.count_accessible(&example),
+            example.len() - surrounded
+        );
+
+        let strict = Day04 {
+            threshold: 1,
+            connectivity: Connectivity::Eight,
+        };
+        assert_eq!(
+            strict
+                .find_accessible(&parse_input("@.@\n...\n.@@".to_string()))
+                .len(),
+            2
+        );
+        let never = Day04 {
+            threshold: 0,
+            connectivity: Connectivity::Eight,
+        };
+        assert_eq!(never.count_accessible(&example), 0);
+        assert!(never.removal_rounds(example.clone()).is_empty());
+        let always = Day04 {
+            threshold: 9,
+            connectivity: Connectivity::Eight,
+        };
+        assert_eq!(always.removal_rounds(example.clone()), vec![example.len()]);
+    }
+
+    #[test]
+    fn test_rule_variants_erode_like_rescan() {
+        let example = parse_input(include_str!("../example.txt").to_string());
+        for connectivity in [Connectivity::Four, Connectivity::Eight] {
+            for threshold in 1..=5 {
+                let day = Day04 {
+                    threshold,
+                    connectivity,
+                };
+                let mut remaining = example.clone();
+                let mut rescanned = Vec::new();
+                loop {
+                    let acc = day.find_accessible(&remaining);
+                    if acc.is_empty() {
+                        break;
+                    }
+                    rescanned.push(acc.len());
+                    for pos in &acc {
+                        remaining.remove(pos);
+                    }
+                }
+                assert_eq!(day.removal_rounds(example.clone()), rescanned, "{:?}", day);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let rolls = parse_input("@@@\n@@@\n@@@".to_string());
+        let stats = Day04::default().stats(&rolls);
+        assert_eq!(stats.rounds(), 3);
+        assert_eq!(
+            stats.to_csv(),
+            "round,removed,remaining\n0,0,9\n1,4,5\n2,4,1\n3,1,0\n"
+        );
+        assert_eq!(
+            stats.to_string(),
+            "Rounds: 3\nRemoved: 9\nRound  Removed Remaining\n    0        0         9\n    \
+             1        4         5\n    2        4         1\n    3        1         0"
+        );
+
+        let example = parse_input(include_str!("../example.txt").to_string());
+        let stats = Day04::default().stats(&example);
+        assert_eq!(stats.removed.iter().sum::<usize>(), 43);
+        assert_eq!(
+            stats.rows().last(),
+            Some((stats.rounds(), stats.removed[stats.rounds() - 1], 28))
+        );
+        let empty = Day04::default().stats(&parse_input(String::new()));
+        assert_eq!(empty.to_csv(), "round,removed,remaining\n0,0,0\n");
+    }
+
+    #[test]
+    fn test_render_and_animate() {
+        let rolls = parse_input("@@.\n@@@\n..@".to_string());
+        let accessible = find_accessible(&rolls);
+        assert_eq!(rolls.render(&[]), "@@.\n@@@\n..@\n");
+        assert_eq!(rolls.render(&accessible), "x@.\nx@x\n..x\n");
+
+        let mut out = Vec::new();
+        let removed = Day04::default()
+            .animate(rolls, &mut out, Duration::ZERO)
+            .unwrap();
+        assert_eq!(removed, 6);
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("\x1b[2J\x1b[HRound 1: removing 4 of 6\x1b[K\n"));
+        assert!(text.contains("\x1b[7mx\x1b[0m@"));
+        assert!(text.contains("Round 2: removing 2 of 2"));
+        assert!(text.ends_with("Done after 2 rounds: 6 removed, 0 left\x1b[K\n...\n...\n...\n"));
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        assert_eq!(
+            Day04::from_args(&args("--example")).unwrap(),
+            Day04::default()
+        );
+        assert_eq!(
+            Day04::from_args(&args("--threshold 3 --connectivity 4 --json")).unwrap(),
+            Day04 {
+                threshold: 3,
+                connectivity: Connectivity::Four
+            }
+        );
+        assert!(Day04::from_args(&args("--threshold"))
+            .unwrap_err()
+            .to_string()
+            .contains("requires a value"));
+        assert!(Day04::from_args(&args("--threshold x")).is_err());
+        assert!(Day04::from_args(&args("--connectivity 6")).is_err());
+    }
+
+    #[test]
+    fn test_ragged_and_out_of_bounds() {
+        let rolls = parse_input("@@\n@\n@@@".to_string());
+        assert_eq!(rolls.len(), 6);
+        assert!(!rolls.contains(&(1, 1)));
+        assert!(!rolls.contains(&(-1, 0)));
+        assert!(!rolls.contains(&(0, 3)));
+        assert_eq!(
+            rolls.iter().collect::<Vec<_>>(),
+            vec![(0, 0), (0, 1), (1, 0), (2, 0), (2, 1), (2, 2)]
+        );
+        assert!(parse_input(String::new()).is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+//! Golden-answer regression checks for the day crates.
+//!
+//! Known-correct answers live in `answers.toml` at the workspace root. Every day listed
+//! there is solved against its committed `input.txt` and the answers are compared with
+//! the expected ones. Days linked into this crate through [`SOLVERS`] are solved
+//! in-process; any other day is run as a binary and its printed answers are used.
+
+use aoclib::Answer;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The expected answers for one day crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    /// Name of the day crate, e.g. `day01_2025`.
+    pub day: String,
+    /// Expected part 1 answer, if known.
+    pub part1: Option<String>,
+    /// Expected part 2 answer, if known.
+    pub part2: Option<String>,
+}
+
+/// A day crate's `solve` function: parses an input and returns both answers.
+pub type SolveFn = fn(&str) -> Result<(Answer, Answer), Box<dyn Error>>;
+
+/// Day crates solved in-process, keyed by crate name.
+pub const SOLVERS: &[(&str, SolveFn)] = &[
+    ("day01_2025", day01_2025::solve),
+    ("day02_2025", day02_2025::solve),
+    ("day03_2025", day03_2025::solve),
+    ("day04_2025", day04_2025::solve),
+];
+
+/// Returns the workspace root directory.
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("regression crate lives inside the workspace")
+        .to_path_buf()
+}
+
+/// Parses the contents of `answers.toml`.
+///
+/// Only the subset of TOML the answers file needs is supported: `[table]` headers,
+/// `part1`/`part2` keys with string or integer values, comments and blank lines.
+///
+/// # Errors
+///
+/// Returns an error naming the offending line if the file contains anything else.
+pub fn parse_answers(content: &str) -> Result<Vec<Expected>, String> {
+    let mut days: Vec<Expected> = Vec::new();
+
+    for (i, raw) in content.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            days.push(Expected {
+                day: name.trim().to_string(),
+                part1: None,
+                part2: None,
+            });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected 'key = value', got '{}'", i + 1, raw))?;
+        let value = value.trim().trim_matches('"').to_string();
+        let current = days
+            .last_mut()
+            .ok_or_else(|| format!("line {}: key outside of a [day] table", i + 1))?;
+
+        match key.trim() {
+            "part1" => current.part1 = Some(value),
+            "part2" => current.part2 = Some(value),
+            other => return Err(format!("line {}: unknown key '{}'", i + 1, other)),
+        }
+    }
+
+    Ok(days)
+}
+
+/// Reads and parses `answers.toml` from the workspace root.
+pub fn load_answers() -> Result<Vec<Expected>, Box<dyn Error>> {
+    let content = fs::read_to_string(workspace_root().join("answers.toml"))?;
+    Ok(parse_answers(&content)?)
+}
+
+/// Extracts the `Part N: answer` lines printed by a day binary.
+///
+/// Matching is case-insensitive, since older days print `part 1:`.
+pub fn parse_output(output: &str) -> (Option<String>, Option<String>) {
+    let mut part1 = None;
+    let mut part2 = None;
+
+    for line in output.lines() {
+        let lower = line.to_lowercase();
+        let answer = || line.split_once(':').map(|(_, a)| a.trim().to_string());
+        if lower.starts_with("part 1:") {
+            part1 = answer();
+        } else if lower.starts_with("part 2:") {
+            part2 = answer();
+        }
+    }
+
+    (part1, part2)
+}
+
+/// Solves a day against its `input.txt`, returning its answers as strings.
+///
+/// Days registered in [`SOLVERS`] are solved in-process; others fall back to
+/// [`run_day`]. Unsolved parts are reported as `None`.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or the day fails to solve it.
+pub fn solve_day(day: &str) -> Result<(Option<String>, Option<String>), Box<dyn Error>> {
+    let Some((_, solve)) = SOLVERS.iter().find(|(name, _)| *name == day) else {
+        return run_day(day);
+    };
+
+    let path = workspace_root().join(day).join("input.txt");
+    let input = fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read '{}': {}", path.display(), e))?;
+    let (part1, part2) = solve(&input).map_err(|e| format!("{} failed: {}", day, e))?;
+    Ok((answer_string(part1), answer_string(part2)))
+}
+
+/// Converts an answer to the string form used in `answers.toml`.
+fn answer_string(answer: Answer) -> Option<String> {
+    match answer {
+        Answer::Unsolved => None,
+        answer => Some(answer.to_string()),
+    }
+}
+
+/// Builds and runs a day crate in release mode from its own directory, returning the
+/// answers it prints.
+///
+/// # Errors
+///
+/// Returns an error if cargo cannot be started or the day exits unsuccessfully.
+pub fn run_day(day: &str) -> Result<(Option<String>, Option<String>), Box<dyn Error>> {
+    let root = workspace_root();
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .args(["run", "--quiet", "--release", "-p", day])
+        .current_dir(root.join(day))
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            day,
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+
+    Ok(parse_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let content = "\
+# comment
+[day01_2025]
+part1 = \"1078\"
+part2 = 6412  # trailing comment
+
+[day02_2025]
+part1 = \"abc\"
+";
+        let answers = parse_answers(content).unwrap();
+        assert_eq!(
+            answers,
+            vec![
+                Expected {
+                    day: "day01_2025".to_string(),
+                    part1: Some("1078".to_string()),
+                    part2: Some("6412".to_string()),
+                },
+                Expected {
+                    day: "day02_2025".to_string(),
+                    part1: Some("abc".to_string()),
+                    part2: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        assert!(parse_answers("part1 = \"1\"").is_err());
+        assert!(parse_answers("[day01_2025]\npart3 = \"1\"").is_err());
+        assert!(parse_answers("[day01_2025]\nnonsense").is_err());
+    }
+
+    #[test]
+    fn test_parse_output() {
+        let output = "part 1: 1078\nsome debug line\nPart 2: 6412\n";
+        assert_eq!(
+            parse_output(output),
+            (Some("1078".to_string()), Some("6412".to_string()))
+        );
+        assert_eq!(parse_output("nothing here"), (None, None));
+    }
+
+    #[test]
+    fn test_answer_string() {
+        assert_eq!(answer_string(Answer::Number(42)), Some("42".to_string()));
+        assert_eq!(
+            answer_string(Answer::Text("abc".into())),
+            Some("abc".to_string())
+        );
+        assert_eq!(answer_string(Answer::Unsolved), None);
+    }
+
+    #[test]
+    fn test_solvers_are_workspace_members() {
+        let manifest = fs::read_to_string(workspace_root().join("Cargo.toml")).unwrap();
+        for (day, _) in SOLVERS {
+            assert!(
+                manifest.contains(&format!("\"{}\"", day)),
+                "{} is not a workspace member",
+                day
+            );
+        }
+    }
+
+    #[test]
+    fn test_answers_file_lists_workspace_members() {
+        let manifest = fs::read_to_string(workspace_root().join("Cargo.toml")).unwrap();
+        for expected in load_answers().unwrap() {
+            assert!(
+                manifest.contains(&format!("\"{}\"", expected.day)),
+                "{} is not a workspace member",
+                expected.day
+            );
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use regression::{load_answers, solve_day};
+
+/// Solves every day listed in `answers.toml` and checks it still produces the known
+/// answers. All mismatches are collected so a single run reports every regression.
+#[test]
+fn test_all_days_match_known_answers() {
+    let mut failures = Vec::new();
+
+    for expected in load_answers().expect("answers.toml should parse") {
+        let (part1, part2) = match solve_day(&expected.day) {
+            Ok(answers) => answers,
+            Err(e) => {
+                failures.push(e.to_string());
+                continue;
+            }
+        };
+
+        for (part, want, got) in [(1, &expected.part1, part1), (2, &expected.part2, part2)] {
+            if let Some(want) = want {
+                if got.as_ref() != Some(want) {
+                    failures.push(format!(
+                        "{} part {}: expected {}, got {:?}",
+                        expected.day, part, want, got
+                    ));
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "regressions:\n{}", failures.join("\n"));
+}